@@ -230,8 +230,8 @@ fn test_real_command_execution() {
     // 使用标准方式
     let start = Instant::now();
     for _ in 0..iterations {
-        let mut cmd = Command::new(cmd_config.program());
-        cmd.args(cmd_config.args());
+        let mut cmd = Command::new(cmd_config.program_os());
+        cmd.args(cmd_config.args_os());
         if let Some(env_config) = cmd_config.env_config() {
             apply_env_config_standard(&mut cmd, env_config);
         }
@@ -242,8 +242,8 @@ fn test_real_command_execution() {
     // 使用优化方式
     let start = Instant::now();
     for _ in 0..iterations {
-        let mut cmd = Command::new(cmd_config.program());
-        cmd.args(cmd_config.args());
+        let mut cmd = Command::new(cmd_config.program_os());
+        cmd.args(cmd_config.args_os());
         if let Some(env_config) = cmd_config.env_config() {
             apply_env_config_optimized(&mut cmd, env_config);
         }
@@ -20,6 +20,8 @@ fn main() {
                     CommandError::SpawnFailed { .. } => "SpawnFailed",
                     CommandError::ExecutionFailed { .. } => "ExecutionFailed",
                     CommandError::Timeout { .. } => "Timeout",
+                    CommandError::UnexpectedExit { .. } => "UnexpectedExit",
+                    CommandError::ExitStatus { .. } => "ExitStatus",
                 }
             );
             println!("   错误详情: {}", e);
@@ -45,6 +47,8 @@ fn main() {
                         CommandError::SpawnFailed { .. } => "SpawnFailed",
                         CommandError::ExecutionFailed { .. } => "ExecutionFailed",
                         CommandError::Timeout { .. } => "Timeout",
+                        CommandError::UnexpectedExit { .. } => "UnexpectedExit",
+                        CommandError::ExitStatus { .. } => "ExitStatus",
                     }
                 );
                 println!("   错误详情: {}", e);
@@ -17,7 +17,6 @@ fn main() {
         println!("警告：未启用 iouring feature");
         println!("请使用：cargo run --example iouring_benchmark --features iouring\n");
         run_standard_benchmark();
-        return;
     }
 
     #[cfg(feature = "iouring")]
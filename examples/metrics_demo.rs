@@ -54,7 +54,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 失败的任务（命令不存在）
     for i in 0..3 {
-        let config = CommandConfig::new(&format!("nonexistent_command_{}", i), vec![]);
+        let config = CommandConfig::new(format!("nonexistent_command_{}", i), vec![]);
         let _ = pool.push_task(config); // 忽略提交错误
     }
 
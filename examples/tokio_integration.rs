@@ -21,10 +21,10 @@ impl TokioWithTimeoutExecutor {
 impl CommandExecutor for TokioWithTimeoutExecutor {
     fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
         self.rt.block_on(async {
-            let mut cmd = Command::new(config.program());
-            cmd.args(config.args());
+            let mut cmd = Command::new(config.program_os());
+            cmd.args(config.args_os());
 
-            if let Some(dir) = config.working_dir() {
+            if let Some(dir) = config.working_dir_path() {
                 cmd.current_dir(dir);
             }
 
@@ -53,7 +53,7 @@ fn main() -> Result<(), ExecuteError> {
     );
 
     // 使用自定义 Tokio 执行器
-    pool.start_with_executor(Duration::from_millis(50), executor);
+    pool.start_with_executor(executor);
 
     // 简单等待一段时间以便任务运行完成
     std::thread::sleep(Duration::from_secs(2));
@@ -0,0 +1,33 @@
+//! 亲和键到工作者的映射
+//!
+//! 同一亲和键的任务总是交给同一个工作线程或常驻工作进程，
+//! 使工作者可以按键保留预热状态（如已检出的仓库或已加载的模型）。
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// 计算亲和键对应的工作者序号（`0..workers`）
+///
+/// 映射在进程内稳定，只取决于键和工作者数量。
+pub(crate) fn worker_for_key(key: &str, workers: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() % workers.max(1) as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_for_key_is_stable_and_in_range() {
+        for workers in 1..8 {
+            for key in ["repo-a", "repo-b", "model-x", ""] {
+                let worker = worker_for_key(key, workers);
+                assert!(worker < workers);
+                assert_eq!(worker, worker_for_key(key, workers));
+            }
+        }
+        assert_eq!(worker_for_key("any", 0), 0);
+    }
+}
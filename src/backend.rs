@@ -1,17 +1,28 @@
+use std::collections::HashMap;
 use std::process::Output;
 use std::sync::Arc;
 
 use crate::config::CommandConfig;
 use crate::error::ExecuteError;
+use crate::hooks::{CommandRewriter, rewrite_config};
+use crate::report::ExecutionReport;
 use crate::semaphore::Semaphore;
 
 /// 执行后端 trait
 pub trait ExecutionBackend: Send + Sync {
     fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError>;
+
+    /// 执行命令并返回执行报告
+    ///
+    /// 默认实现调用 `execute`，报告中只包含输出。
+    fn execute_report(&self, config: &CommandConfig) -> Result<ExecutionReport, ExecuteError> {
+        self.execute(config).map(ExecutionReport::new)
+    }
 }
 
 /// 执行模式
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExecutionMode {
     #[default]
     Process,
@@ -21,6 +32,8 @@ pub enum ExecutionMode {
 
 /// 执行配置
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct ExecutionConfig {
     pub mode: ExecutionMode,
     pub workers: usize,
@@ -95,6 +108,11 @@ impl ExecutionBackend for GenericBackend {
         let _guard = self.semaphore.as_ref().map(|s| s.acquire_guard());
         crate::executor::execute_command(config)
     }
+
+    fn execute_report(&self, config: &CommandConfig) -> Result<ExecutionReport, ExecuteError> {
+        let _guard = self.semaphore.as_ref().map(|s| s.acquire_guard());
+        crate::executor::execute_with_report(config)
+    }
 }
 
 /// 后端工厂
@@ -109,3 +127,155 @@ impl BackendFactory {
         }
     }
 }
+
+/// 配额后端
+///
+/// 包装一个已有的后端，在其之上再增加一层并发限制。
+/// 被包装后端自身的并发限制（如父池的 `concurrency_limit`）依然生效，
+/// 因此多个配额后端共享同一个内部后端时会共同消耗其并发预算。
+///
+/// 由 [`CommandPool::sub_pool`](crate::CommandPool::sub_pool) 用于实现子池。
+pub struct QuotaBackend {
+    inner: Arc<dyn ExecutionBackend>,
+    semaphore: Semaphore,
+    limit: usize,
+}
+
+impl QuotaBackend {
+    /// 创建配额后端，最多同时执行 `limit` 个命令
+    pub fn new(inner: Arc<dyn ExecutionBackend>, limit: usize) -> Self {
+        Self {
+            inner,
+            semaphore: Semaphore::new(limit),
+            limit,
+        }
+    }
+
+    /// 获取配额上限
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl ExecutionBackend for QuotaBackend {
+    fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        // 先占用自身配额，再进入内部后端竞争父级预算，
+        // 避免子池在等待父级许可时占满父级的等待队列
+        let _guard = self.semaphore.acquire_guard();
+        self.inner.execute(config)
+    }
+
+    fn execute_report(&self, config: &CommandConfig) -> Result<ExecutionReport, ExecuteError> {
+        let _guard = self.semaphore.acquire_guard();
+        self.inner.execute_report(config)
+    }
+}
+
+/// 路由后端
+///
+/// 按任务通过 [`CommandConfig::with_backend`] 选择的名称把任务分派到已注册的后端，
+/// 未选择后端的任务交给默认后端。选择了未注册名称的任务返回
+/// [`ExecuteError::UnknownBackend`]。
+///
+/// 配合 [`CommandPoolBuilder::with_backend_limit`](crate::CommandPoolBuilder::with_backend_limit)
+/// 可以为每个后端单独限制并发。
+///
+/// # 示例
+///
+/// ```ignore
+/// use std::sync::Arc;
+/// use execute::{CommandPool, RoutingBackend};
+///
+/// let backend = RoutingBackend::new(process_backend).with_route("docker", docker_backend);
+/// let pool = CommandPool::builder()
+///     .with_workers(16)
+///     .with_backend(Arc::new(backend))
+///     .with_backend_limit("docker", 2)
+///     .build()?;
+/// ```
+pub struct RoutingBackend {
+    default: Arc<dyn ExecutionBackend>,
+    routes: HashMap<String, Arc<dyn ExecutionBackend>>,
+}
+
+impl RoutingBackend {
+    /// 创建只有默认后端的路由后端
+    pub fn new(default: Arc<dyn ExecutionBackend>) -> Self {
+        Self {
+            default,
+            routes: HashMap::new(),
+        }
+    }
+
+    /// 注册名为 `name` 的后端
+    pub fn with_route(mut self, name: &str, backend: Arc<dyn ExecutionBackend>) -> Self {
+        self.routes.insert(name.to_string(), backend);
+        self
+    }
+
+    /// 选择任务对应的后端
+    fn route(&self, config: &CommandConfig) -> Result<&Arc<dyn ExecutionBackend>, ExecuteError> {
+        match config.backend() {
+            None => Ok(&self.default),
+            Some(name) => self
+                .routes
+                .get(name)
+                .ok_or_else(|| ExecuteError::UnknownBackend(name.to_string())),
+        }
+    }
+}
+
+impl ExecutionBackend for RoutingBackend {
+    fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        self.route(config)?.execute(config)
+    }
+
+    fn execute_report(&self, config: &CommandConfig) -> Result<ExecutionReport, ExecuteError> {
+        self.route(config)?.execute_report(config)
+    }
+}
+
+/// 命令改写后端
+///
+/// 包装一个已有的后端，在交给内部后端执行之前依次应用命令改写器。
+///
+/// # 示例
+///
+/// ```ignore
+/// use std::sync::Arc;
+/// use execute::{ExecutionBackend, PrefixRewriter, RewritingBackend};
+///
+/// let backend = RewritingBackend::new(inner)
+///     .with_rewriter(Arc::new(PrefixRewriter::new("nice", ["-n", "19"])));
+/// ```
+pub struct RewritingBackend {
+    inner: Arc<dyn ExecutionBackend>,
+    rewriters: Vec<Arc<dyn CommandRewriter>>,
+}
+
+impl RewritingBackend {
+    /// 创建不带改写器的改写后端
+    pub fn new(inner: Arc<dyn ExecutionBackend>) -> Self {
+        Self {
+            inner,
+            rewriters: Vec::new(),
+        }
+    }
+
+    /// 添加命令改写器
+    pub fn with_rewriter(mut self, rewriter: Arc<dyn CommandRewriter>) -> Self {
+        self.rewriters.push(rewriter);
+        self
+    }
+}
+
+impl ExecutionBackend for RewritingBackend {
+    fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        self.inner.execute(&rewrite_config(config, &self.rewriters))
+    }
+
+    fn execute_report(&self, config: &CommandConfig) -> Result<ExecutionReport, ExecuteError> {
+        self.inner
+            .execute_report(&rewrite_config(config, &self.rewriters))
+    }
+}
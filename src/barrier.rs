@@ -0,0 +1,137 @@
+//! 任务完成屏障
+//!
+//! 基于命令池事件总线，等待接下来的若干个任务完成或指定的一组任务完成，
+//! 用于“这 20 个任务完成后再开始第二阶段”这类协调，无需轮询任务状态。
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use crate::events::PoolEvent;
+
+/// 屏障的完成条件
+enum Target {
+    /// 还需等待的完成数
+    Count(usize),
+    /// 尚未完成的任务 ID
+    Tasks(HashSet<u64>),
+}
+
+impl Target {
+    fn is_met(&self) -> bool {
+        match self {
+            Target::Count(remaining) => *remaining == 0,
+            Target::Tasks(pending) => pending.is_empty(),
+        }
+    }
+
+    fn record(&mut self, task_id: u64) {
+        match self {
+            Target::Count(remaining) => *remaining = remaining.saturating_sub(1),
+            Target::Tasks(pending) => {
+                pending.remove(&task_id);
+            }
+        }
+    }
+}
+
+struct BarrierState {
+    events: Receiver<PoolEvent>,
+    target: Target,
+    /// 事件总线已断开（命令池已被丢弃）
+    disconnected: bool,
+}
+
+/// 任务完成屏障句柄
+///
+/// 由 [`CommandPool::barrier`](crate::CommandPool::barrier) 或
+/// [`CommandPool::barrier_for`](crate::CommandPool::barrier_for) 创建。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, CommandPool};
+///
+/// let pool = CommandPool::new();
+/// pool.start_executor();
+///
+/// let phase1 = pool.barrier(20);
+/// for i in 0..20 {
+///     pool.push_task(CommandConfig::new("prepare.sh", vec![i.to_string()]))?;
+/// }
+/// phase1.wait();
+/// // 开始第二阶段
+/// ```
+pub struct BarrierHandle {
+    state: Mutex<BarrierState>,
+}
+
+impl BarrierHandle {
+    /// 等待接下来 `count` 个任务完成的屏障
+    pub(crate) fn count(events: Receiver<PoolEvent>, count: usize) -> Self {
+        Self::new(events, Target::Count(count))
+    }
+
+    /// 等待指定任务全部完成的屏障
+    pub(crate) fn tasks(events: Receiver<PoolEvent>, pending: HashSet<u64>) -> Self {
+        Self::new(events, Target::Tasks(pending))
+    }
+
+    fn new(events: Receiver<PoolEvent>, target: Target) -> Self {
+        Self {
+            state: Mutex::new(BarrierState {
+                events,
+                target,
+                disconnected: false,
+            }),
+        }
+    }
+
+    /// 阻塞等待屏障完成
+    ///
+    /// 命令池被丢弃导致事件流结束时也会返回。
+    pub fn wait(&self) {
+        let mut state = self.state.lock().unwrap();
+        while !state.target.is_met() && !state.disconnected {
+            match state.events.recv() {
+                Ok(event) => Self::handle(&mut state, event),
+                Err(_) => state.disconnected = true,
+            }
+        }
+    }
+
+    /// 在限定时间内等待屏障完成
+    ///
+    /// # 返回
+    ///
+    /// 屏障已完成返回 `true`，超时返回 `false`。
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock().unwrap();
+        while !state.target.is_met() && !state.disconnected {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match state.events.recv_timeout(remaining) {
+                Ok(event) => Self::handle(&mut state, event),
+                Err(RecvTimeoutError::Timeout) => return false,
+                Err(RecvTimeoutError::Disconnected) => state.disconnected = true,
+            }
+        }
+        state.target.is_met()
+    }
+
+    /// 检查屏障是否已完成（非阻塞）
+    pub fn is_complete(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        while let Ok(event) = state.events.try_recv() {
+            Self::handle(&mut state, event);
+        }
+        state.target.is_met()
+    }
+
+    fn handle(state: &mut BarrierState, event: PoolEvent) {
+        if let PoolEvent::TaskFinished { task_id, .. } = event {
+            state.target.record(task_id);
+        }
+    }
+}
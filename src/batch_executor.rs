@@ -13,6 +13,7 @@ use std::time::Duration;
 
 use crate::config::CommandConfig;
 use crate::error::ExecuteError;
+use crate::executor::OutputDrain;
 
 /// 批量执行结果
 pub struct BatchOutput {
@@ -43,6 +44,10 @@ pub struct BatchConfig {
     pub timeout: Option<Duration>,
     /// 是否分隔每个命令的输出
     pub separate_output: bool,
+    /// 是否按提交顺序合并各命令的输出
+    ///
+    /// 默认按临时文件名的字典序合并，超过 10 个命令时与提交顺序不一致。
+    pub ordered: bool,
 }
 
 impl Default for BatchConfig {
@@ -52,6 +57,7 @@ impl Default for BatchConfig {
             wait_all: true,
             timeout: None,
             separate_output: false,
+            ordered: false,
         }
     }
 }
@@ -105,15 +111,16 @@ pub fn execute_parallel_batch(
 
     let mut child = cmd.spawn().map_err(ExecuteError::Io)?;
 
-    // 处理超时
+    // 处理超时，等待期间持续读取输出，避免子进程因管道写满而阻塞
     let output = match batch_config.timeout {
         Some(timeout) => {
-            use wait_timeout::ChildExt;
+            use crate::child_wait::ChildExt;
+            let drain = OutputDrain::start(&mut child, None);
             match child
                 .wait_timeout(timeout)
                 .map_err(|e| ExecuteError::Io(std::io::Error::other(e)))?
             {
-                Some(_) => child.wait_with_output().map_err(ExecuteError::Io)?,
+                Some(status) => drain.finish(status).map_err(ExecuteError::Io)?,
                 None => {
                     let _ = child.kill();
                     let _ = child.wait();
@@ -175,8 +182,14 @@ fn build_parallel_script(configs: &[CommandConfig], batch_config: &BatchConfig)
 
         // 输出所有结果
         lines.push(String::new());
-        lines.push("for _f in $_batch_tmpdir/*.out; do".to_string());
-        lines.push("    cat \"$_f\" 2>/dev/null || true".to_string());
+        if batch_config.ordered {
+            let indices: Vec<String> = (0..configs.len()).map(|i| i.to_string()).collect();
+            lines.push(format!("for _i in {}; do", indices.join(" ")));
+            lines.push("    cat \"$_batch_tmpdir/$_i.out\" 2>/dev/null || true".to_string());
+        } else {
+            lines.push("for _f in $_batch_tmpdir/*.out; do".to_string());
+            lines.push("    cat \"$_f\" 2>/dev/null || true".to_string());
+        }
         lines.push("done".to_string());
     }
 
@@ -185,10 +198,10 @@ fn build_parallel_script(configs: &[CommandConfig], batch_config: &BatchConfig)
 
 /// 将命令配置转换为 shell 命令字符串
 fn format_command(config: &CommandConfig) -> String {
-    let mut parts = vec![shell_escape(&config.program)];
+    let mut parts = vec![shell_escape(&config.program.to_string_lossy())];
 
     for arg in &config.args {
-        parts.push(shell_escape(arg));
+        parts.push(shell_escape(&arg.to_string_lossy()));
     }
 
     parts.join(" ")
@@ -322,4 +335,20 @@ mod tests {
         assert!(stdout.contains("1"));
         assert!(stdout.contains("2"));
     }
+
+    #[test]
+    fn test_parallel_batch_ordered_output() {
+        let configs: Vec<_> = (0..12)
+            .map(|i| CommandConfig::new("echo", vec![i.to_string()]))
+            .collect();
+        let batch_config = BatchConfig {
+            ordered: true,
+            ..Default::default()
+        };
+
+        let result = execute_parallel_batch(&configs, &batch_config).unwrap();
+        let stdout = String::from_utf8_lossy(&result.stdout);
+        let expected: Vec<String> = (0..12).map(|i| i.to_string()).collect();
+        assert_eq!(stdout.lines().collect::<Vec<_>>(), expected);
+    }
 }
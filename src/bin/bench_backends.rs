@@ -0,0 +1,327 @@
+//! 执行后端对比基准
+//!
+//! 运行一个可配置的矩阵（执行模式 × 工作线程数 × 命令负载），
+//! 输出每个组合的往返延迟、吞吐量和 CPU 开销，帮助在
+//! `Process`、`Thread` 与 `ProcessPool` 之间基于数据做选择。
+//!
+//! # 用法
+//!
+//! ```bash
+//! cargo run --release --bin bench_backends -- \
+//!     --modes process,thread,process-pool \
+//!     --workers 1,4,8 \
+//!     --profiles noop,echo,sleep \
+//!     --tasks 200 \
+//!     --latency-samples 20
+//! ```
+
+use execute::{CommandConfig, CommandPool, ExecutionConfig, ExecutionMode};
+use std::process;
+use std::time::{Duration, Instant};
+
+/// 命令负载类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Profile {
+    /// 最小开销命令（`true`）
+    Noop,
+    /// 少量输出（`echo`）
+    Echo,
+    /// 较大输出（64 KB）
+    Output,
+    /// I/O 等待型命令（`sleep 0.01`）
+    Sleep,
+}
+
+impl Profile {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "noop" => Some(Profile::Noop),
+            "echo" => Some(Profile::Echo),
+            "output" => Some(Profile::Output),
+            "sleep" => Some(Profile::Sleep),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Profile::Noop => "noop",
+            Profile::Echo => "echo",
+            Profile::Output => "output",
+            Profile::Sleep => "sleep",
+        }
+    }
+
+    fn command(&self) -> CommandConfig {
+        match self {
+            Profile::Noop => CommandConfig::new("true", vec![]),
+            Profile::Echo => CommandConfig::new("echo", vec!["hello".to_string()]),
+            Profile::Output => CommandConfig::new(
+                "head",
+                vec![
+                    "-c".to_string(),
+                    "65536".to_string(),
+                    "/dev/zero".to_string(),
+                ],
+            ),
+            Profile::Sleep => CommandConfig::new("sleep", vec!["0.01".to_string()]),
+        }
+    }
+}
+
+/// 解析执行模式名称
+fn parse_mode(s: &str) -> Option<ExecutionMode> {
+    match s {
+        "process" => Some(ExecutionMode::Process),
+        "thread" => Some(ExecutionMode::Thread),
+        "process-pool" => Some(ExecutionMode::ProcessPool),
+        _ => None,
+    }
+}
+
+fn mode_name(mode: ExecutionMode) -> &'static str {
+    match mode {
+        ExecutionMode::Process => "process",
+        ExecutionMode::Thread => "thread",
+        ExecutionMode::ProcessPool => "process-pool",
+    }
+}
+
+/// 基准矩阵配置
+struct BenchOptions {
+    modes: Vec<ExecutionMode>,
+    workers: Vec<usize>,
+    profiles: Vec<Profile>,
+    tasks: usize,
+    latency_samples: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            modes: vec![
+                ExecutionMode::Process,
+                ExecutionMode::Thread,
+                ExecutionMode::ProcessPool,
+            ],
+            workers: vec![1, 4, 8],
+            profiles: vec![Profile::Noop, Profile::Echo, Profile::Sleep],
+            tasks: 200,
+            latency_samples: 20,
+        }
+    }
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: bench_backends [--modes process,thread,process-pool] [--workers 1,4,8] \
+         [--profiles noop,echo,output,sleep] [--tasks N] [--latency-samples N]"
+    );
+    process::exit(2);
+}
+
+/// 解析逗号分隔的列表
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Vec<T> {
+    value
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            parse(s.trim()).unwrap_or_else(|| {
+                eprintln!("invalid value: {}", s);
+                usage()
+            })
+        })
+        .collect()
+}
+
+fn parse_args() -> BenchOptions {
+    let mut opts = BenchOptions::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(flag) = args.next() {
+        let value = match flag.as_str() {
+            "-h" | "--help" => usage(),
+            _ => args.next().unwrap_or_else(|| usage()),
+        };
+        match flag.as_str() {
+            "--modes" => opts.modes = parse_list(&value, parse_mode),
+            "--workers" => {
+                opts.workers = parse_list(&value, |s| s.parse().ok().filter(|&n: &usize| n > 0))
+            }
+            "--profiles" => opts.profiles = parse_list(&value, Profile::parse),
+            "--tasks" => opts.tasks = value.parse().unwrap_or_else(|_| usage()),
+            "--latency-samples" => opts.latency_samples = value.parse().unwrap_or_else(|_| usage()),
+            _ => usage(),
+        }
+    }
+
+    opts
+}
+
+/// 进程 CPU 时间（自身 + 已回收子进程）
+#[derive(Debug, Clone, Copy, Default)]
+struct CpuTimes {
+    self_time: Duration,
+    children_time: Duration,
+}
+
+#[cfg(unix)]
+fn cpu_times() -> CpuTimes {
+    use nix::sys::resource::{UsageWho, getrusage};
+
+    fn total(who: UsageWho) -> Duration {
+        match getrusage(who) {
+            Ok(usage) => {
+                let user = usage.user_time();
+                let sys = usage.system_time();
+                let micros =
+                    (user.tv_sec() + sys.tv_sec()) * 1_000_000 + (user.tv_usec() + sys.tv_usec());
+                Duration::from_micros(micros.max(0) as u64)
+            }
+            Err(_) => Duration::ZERO,
+        }
+    }
+
+    CpuTimes {
+        self_time: total(UsageWho::RUSAGE_SELF),
+        children_time: total(UsageWho::RUSAGE_CHILDREN),
+    }
+}
+
+#[cfg(not(unix))]
+fn cpu_times() -> CpuTimes {
+    CpuTimes::default()
+}
+
+/// 单个矩阵单元的测量结果
+struct CellResult {
+    /// 单个任务从提交到拿到结果的往返时间（包括命令自身的执行时间）
+    round_trip_p50: Duration,
+    round_trip_p99: Duration,
+    throughput: f64,
+    /// 池自身（父进程）CPU 时间占墙钟时间的比例
+    self_cpu_ratio: f64,
+    /// 平均每个任务消耗的父进程 CPU 时间
+    self_cpu_per_task: Duration,
+    /// 平均每个任务消耗的子进程 CPU 时间
+    child_cpu_per_task: Duration,
+    failures: usize,
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx.min(sorted.len() - 1)]
+}
+
+/// 运行一个矩阵单元
+///
+/// 1. 往返延迟：队列为空时逐个提交任务，测量从提交到拿到结果的时间（包括命令执行时间）
+/// 2. 吞吐量：一次性提交 `tasks` 个任务，测量全部完成所需时间
+fn run_cell(
+    mode: ExecutionMode,
+    workers: usize,
+    profile: Profile,
+    opts: &BenchOptions,
+) -> CellResult {
+    let config = ExecutionConfig::new().with_mode(mode).with_workers(workers);
+    let pool = CommandPool::with_config(config);
+    pool.start_executor();
+
+    let command = profile.command();
+    let mut failures = 0;
+
+    // 预热一次，避免首次执行的冷启动影响测量
+    if let Ok(handle) = pool.push_task(command.clone()) {
+        let _ = handle.wait();
+    }
+
+    // 往返延迟
+    let mut latencies = Vec::with_capacity(opts.latency_samples);
+    for _ in 0..opts.latency_samples {
+        let start = Instant::now();
+        match pool.push_task(command.clone()).map(|h| h.wait()) {
+            Ok(Ok(_)) => latencies.push(start.elapsed()),
+            _ => failures += 1,
+        }
+    }
+    latencies.sort();
+
+    // 吞吐量与 CPU 开销
+    let cpu_before = cpu_times();
+    let start = Instant::now();
+    let handles: Vec<_> = (0..opts.tasks)
+        .filter_map(|_| pool.push_task(command.clone()).ok())
+        .collect();
+    failures += opts.tasks - handles.len();
+    for handle in handles {
+        if !matches!(handle.wait(), Ok(ref output) if output.status.success()) {
+            failures += 1;
+        }
+    }
+    let elapsed = start.elapsed();
+    let cpu_after = cpu_times();
+
+    let _ = pool.shutdown_with_timeout(Duration::from_secs(10));
+
+    let self_cpu = cpu_after.self_time.saturating_sub(cpu_before.self_time);
+    let child_cpu = cpu_after
+        .children_time
+        .saturating_sub(cpu_before.children_time);
+    let tasks = opts.tasks.max(1) as u32;
+
+    CellResult {
+        round_trip_p50: percentile(&latencies, 0.5),
+        round_trip_p99: percentile(&latencies, 0.99),
+        throughput: opts.tasks as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        self_cpu_ratio: self_cpu.as_secs_f64() / elapsed.as_secs_f64().max(f64::EPSILON),
+        self_cpu_per_task: self_cpu / tasks,
+        child_cpu_per_task: child_cpu / tasks,
+        failures,
+    }
+}
+
+fn main() {
+    let opts = parse_args();
+
+    println!(
+        "Backend comparison: {} tasks per cell, {} latency samples",
+        opts.tasks, opts.latency_samples
+    );
+    println!(
+        "{:<13} {:>7} {:<7} {:>10} {:>10} {:>12} {:>9} {:>12} {:>12} {:>6}",
+        "mode",
+        "workers",
+        "profile",
+        "rt p50",
+        "rt p99",
+        "tasks/s",
+        "cpu%",
+        "cpu/task",
+        "child/task",
+        "fail"
+    );
+
+    for &mode in &opts.modes {
+        for &workers in &opts.workers {
+            for &profile in &opts.profiles {
+                let r = run_cell(mode, workers, profile, &opts);
+                println!(
+                    "{:<13} {:>7} {:<7} {:>10.2?} {:>10.2?} {:>12.1} {:>8.1}% {:>12.2?} {:>12.2?} {:>6}",
+                    mode_name(mode),
+                    workers,
+                    profile.name(),
+                    r.round_trip_p50,
+                    r.round_trip_p99,
+                    r.throughput,
+                    r.self_cpu_ratio * 100.0,
+                    r.self_cpu_per_task,
+                    r.child_cpu_per_task,
+                    r.failures
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! 批量任务的累计资源预算
+//!
+//! 为一组任务声明总的墙钟时间或 CPU 时间预算以及整体截止时间，
+//! 由后台线程定期采样正在运行的任务，超出后取消组内尚未结束的任务。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::task_handle::{TaskHandle, TaskState};
+
+/// 批量任务的累计资源预算
+///
+/// 墙钟时间按组内每个任务的执行时长累加（两个并行运行 1 秒的任务共消耗 2 秒），
+/// CPU 时间按子进程的用户态和内核态时间累加。消耗通过定期采样得到，
+/// 精度取决于采样间隔；CPU 时间只统计直接启动的子进程，且仅在 Linux 上可用。
+///
+/// 截止时间是整组任务的完成期限，从设置预算时开始计时：到期后尚未开始的任务被取消，
+/// 正在运行的任务默认被终止，也可以通过 [`BatchBudget::with_kill_running`] 允许其运行完毕。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{BatchBudget, Duration};
+///
+/// let budget = BatchBudget::new()
+///     .with_wall_clock(Duration::from_secs(60))
+///     .with_cpu_time(Duration::from_secs(30))
+///     .with_deadline(Duration::from_secs(30 * 60));
+/// let stream = pool.submit_batch(configs)?.with_budget(budget);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchBudget {
+    wall_clock: Option<Duration>,
+    cpu_time: Option<Duration>,
+    deadline: Option<Duration>,
+    kill_running: bool,
+    check_interval: Duration,
+}
+
+impl BatchBudget {
+    /// 创建不设上限的预算
+    pub fn new() -> Self {
+        Self {
+            wall_clock: None,
+            cpu_time: None,
+            deadline: None,
+            kill_running: true,
+            check_interval: Duration::from_millis(20),
+        }
+    }
+
+    /// 设置累计墙钟时间上限
+    pub fn with_wall_clock(mut self, limit: Duration) -> Self {
+        self.wall_clock = Some(limit);
+        self
+    }
+
+    /// 设置累计 CPU 时间上限
+    pub fn with_cpu_time(mut self, limit: Duration) -> Self {
+        self.cpu_time = Some(limit);
+        self
+    }
+
+    /// 设置整组任务的截止时间
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// 设置到达截止时间时是否终止正在运行的任务（默认终止）
+    ///
+    /// 设为 false 时只取消尚未开始的任务，正在运行的任务正常完成。
+    pub fn with_kill_running(mut self, kill: bool) -> Self {
+        self.kill_running = kill;
+        self
+    }
+
+    /// 设置采样间隔（默认 20 毫秒）
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// 累计墙钟时间上限
+    pub fn wall_clock(&self) -> Option<Duration> {
+        self.wall_clock
+    }
+
+    /// 累计 CPU 时间上限
+    pub fn cpu_time(&self) -> Option<Duration> {
+        self.cpu_time
+    }
+
+    /// 整组任务的截止时间
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// 到达截止时间时是否终止正在运行的任务
+    pub fn kill_running(&self) -> bool {
+        self.kill_running
+    }
+
+    /// 检查累计消耗是否超出预算，返回超出的项描述
+    fn exceeded(&self, wall_clock: Duration, cpu_time: Duration) -> Option<String> {
+        if let Some(limit) = self.wall_clock
+            && wall_clock > limit
+        {
+            return Some(format!(
+                "wall-clock budget {:?} exceeded ({:?} used)",
+                limit, wall_clock
+            ));
+        }
+        if let Some(limit) = self.cpu_time
+            && cpu_time > limit
+        {
+            return Some(format!(
+                "cpu-time budget {:?} exceeded ({:?} used)",
+                limit, cpu_time
+            ));
+        }
+        None
+    }
+}
+
+impl Default for BatchBudget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 预算监控线程与任务组之间共享的状态
+pub(crate) struct BudgetMonitor {
+    /// 任务组已不再需要监控（完成流已被丢弃）
+    stopped: AtomicBool,
+    /// 超出预算的原因和因此被取消的任务
+    exceeded: Mutex<Option<(String, HashSet<u64>)>>,
+}
+
+impl BudgetMonitor {
+    /// 启动监控线程
+    pub(crate) fn spawn(budget: BatchBudget, handles: Vec<TaskHandle>) -> Arc<Self> {
+        let monitor = Arc::new(Self {
+            stopped: AtomicBool::new(false),
+            exceeded: Mutex::new(None),
+        });
+        let shared = Arc::clone(&monitor);
+        std::thread::spawn(move || shared.run(budget, handles));
+        monitor
+    }
+
+    /// 停止监控
+    pub(crate) fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
+    /// 任务因超出预算被取消时返回原因
+    pub(crate) fn exceeded_reason(&self, task_id: u64) -> Option<String> {
+        let exceeded = self.exceeded.lock().unwrap();
+        exceeded
+            .as_ref()
+            .filter(|(_, cancelled)| cancelled.contains(&task_id))
+            .map(|(reason, _)| reason.clone())
+    }
+
+    fn run(&self, budget: BatchBudget, handles: Vec<TaskHandle>) {
+        let mut wall_clock = Duration::ZERO;
+        // 每个子进程最近一次采样到的 CPU 时间，重试产生的多个子进程分别累计
+        let mut cpu_samples: HashMap<u32, Duration> = HashMap::new();
+        let started = Instant::now();
+        let mut last = started;
+
+        while !self.stopped.load(Ordering::Relaxed) {
+            std::thread::sleep(budget.check_interval);
+            let now = Instant::now();
+            let elapsed = now - last;
+            last = now;
+
+            let mut active = false;
+            for handle in &handles {
+                match handle.state() {
+                    TaskState::Queued => active = true,
+                    TaskState::Running { pid } => {
+                        active = true;
+                        wall_clock += elapsed;
+                        if let Some(pid) = pid
+                            && let Some(cpu) = process_cpu_time(pid)
+                        {
+                            cpu_samples.insert(pid, cpu);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let cpu_time = cpu_samples.values().sum();
+
+            if let Some(reason) = budget.exceeded(wall_clock, cpu_time) {
+                self.cancel_remaining(reason, &handles, true);
+                return;
+            }
+            if let Some(deadline) = budget.deadline
+                && now - started >= deadline
+            {
+                let reason = format!("batch deadline {:?} exceeded", deadline);
+                self.cancel_remaining(reason, &handles, budget.kill_running);
+                return;
+            }
+            if !active {
+                return;
+            }
+        }
+    }
+
+    /// 取消尚未结束的任务，`include_running` 为 false 时只取消尚未开始的任务
+    fn cancel_remaining(&self, reason: String, handles: &[TaskHandle], include_running: bool) {
+        #[cfg(feature = "logging")]
+        tracing::warn!(reason = %reason, "Batch budget exceeded, cancelling remaining tasks");
+
+        // 先记录再取消，保证调用方拿到取消结果时能查到原因
+        let pending: Vec<&TaskHandle> = handles
+            .iter()
+            .filter(|h| match h.state() {
+                TaskState::Queued => true,
+                TaskState::Running { .. } => include_running,
+                _ => false,
+            })
+            .collect();
+        *self.exceeded.lock().unwrap() = Some((reason, pending.iter().map(|h| h.id()).collect()));
+        for handle in pending {
+            let _ = handle.cancel();
+        }
+    }
+}
+
+/// 读取进程已消耗的 CPU 时间（用户态 + 内核态）
+///
+/// 在 Linux 上读取 /proc/[pid]/stat 的 utime 和 stime 字段。
+/// 在其他平台上返回 None。
+#[cfg(target_os = "linux")]
+fn process_cpu_time(pid: u32) -> Option<Duration> {
+    // /proc 中的时间以 USER_HZ 为单位，Linux 上固定为 100
+    const TICKS_PER_SECOND: u64 = 100;
+
+    let content = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // 进程名可能包含空格，从最后一个右括号之后开始解析；其后第一个字段为第 3 个字段
+    let fields: Vec<&str> = content.rsplit_once(')')?.1.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(Duration::from_millis(
+        (utime + stime) * 1000 / TICKS_PER_SECOND,
+    ))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_cpu_time(_pid: u32) -> Option<Duration> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_exceeded() {
+        let budget = BatchBudget::new().with_wall_clock(Duration::from_secs(1));
+        assert!(
+            budget
+                .exceeded(Duration::from_millis(900), Duration::ZERO)
+                .is_none()
+        );
+        assert!(
+            budget
+                .exceeded(Duration::from_millis(1100), Duration::ZERO)
+                .is_some()
+        );
+
+        let budget = BatchBudget::new().with_cpu_time(Duration::from_secs(1));
+        assert!(
+            budget
+                .exceeded(Duration::from_secs(10), Duration::ZERO)
+                .is_none()
+        );
+        assert!(
+            budget
+                .exceeded(Duration::ZERO, Duration::from_secs(2))
+                .is_some()
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_cpu_time_of_self() {
+        assert!(process_cpu_time(std::process::id()).is_some());
+        assert!(process_cpu_time(u32::MAX).is_none());
+    }
+}
@@ -0,0 +1,368 @@
+//! 子进程输出捕获
+//!
+//! 在后台线程中持续读取子进程的 stdout/stderr，写入共享缓冲区。
+//! 缓冲区支持完整保留或仅保留末尾 N 字节（环形缓冲），
+//! 读取过程中可随时查看最近的输出。时间线模式下，每个读到的输出块还会
+//! 连同相对启动时刻的偏移和来源流记录到共享时间线中。流式执行时，读到的输出还会按行
+//! 交给调用方的回调。
+
+use std::collections::VecDeque;
+use std::io::Read;
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+use crate::config::CaptureMode;
+use crate::report::{OutputChunk, OutputStream};
+
+/// 输出缓冲区
+///
+/// `Tail` 模式下超出容量的旧数据会从头部丢弃，内存占用严格有界。
+#[derive(Debug)]
+pub(crate) struct OutputBuffer {
+    data: VecDeque<u8>,
+    limit: Option<usize>,
+    /// 因容量限制被丢弃的字节数
+    dropped: usize,
+}
+
+impl OutputBuffer {
+    pub(crate) fn new(mode: CaptureMode) -> Self {
+        let limit = match mode {
+            CaptureMode::Full | CaptureMode::Timeline => None,
+            CaptureMode::Tail(bytes) => Some(bytes),
+        };
+        Self {
+            data: VecDeque::new(),
+            limit,
+            dropped: 0,
+        }
+    }
+
+    /// 追加数据，超出容量时丢弃最旧的数据
+    pub(crate) fn extend(&mut self, bytes: &[u8]) {
+        match self.limit {
+            None => self.data.extend(bytes),
+            Some(limit) => {
+                // 单次写入本身超过容量时只保留其末尾部分
+                let keep = &bytes[bytes.len().saturating_sub(limit)..];
+                self.dropped += bytes.len() - keep.len();
+                let overflow = (self.data.len() + keep.len()).saturating_sub(limit);
+                self.data.drain(..overflow);
+                self.dropped += overflow;
+                self.data.extend(keep);
+            }
+        }
+    }
+
+    /// 获取末尾最多 `n` 字节的副本
+    pub(crate) fn tail(&self, n: usize) -> Vec<u8> {
+        let from = self.data.len().saturating_sub(n);
+        self.data.range(from..).copied().collect()
+    }
+
+    /// 被丢弃的字节数
+    pub(crate) fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// 取出全部数据并清空缓冲区
+    pub(crate) fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.data).into()
+    }
+}
+
+/// 共享的输出缓冲区
+pub(crate) type SharedBuffer = Arc<Mutex<OutputBuffer>>;
+
+/// 创建共享缓冲区
+pub(crate) fn shared_buffer(mode: CaptureMode) -> SharedBuffer {
+    Arc::new(Mutex::new(OutputBuffer::new(mode)))
+}
+
+/// stdout 和 stderr 共用的输出时间线
+#[derive(Debug, Clone)]
+pub(crate) struct Timeline {
+    started: Instant,
+    chunks: Arc<Mutex<Vec<OutputChunk>>>,
+}
+
+impl Timeline {
+    /// 创建以 `started` 为零点的空时间线
+    pub(crate) fn new(started: Instant) -> Self {
+        Self {
+            started,
+            chunks: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// 记录一个输出块
+    ///
+    /// 偏移在持有锁时计算，时间线中的块始终按偏移递增排列。
+    fn record(&self, stream: OutputStream, data: &[u8]) {
+        let mut chunks = self.chunks.lock().unwrap_or_else(|e| e.into_inner());
+        chunks.push(OutputChunk {
+            offset: self.started.elapsed(),
+            stream,
+            data: data.to_vec(),
+        });
+    }
+
+    /// 取出全部输出块
+    fn take(&self) -> Vec<OutputChunk> {
+        std::mem::take(&mut *self.chunks.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// 逐行输出回调，参数为去掉行尾换行符的一行（非 UTF-8 字节按替换字符处理）
+pub(crate) type LineCallback = Box<dyn FnMut(&str) + Send>;
+
+/// 把输出块切分成行，交给回调
+pub(crate) struct LineSplitter {
+    pending: Vec<u8>,
+    callback: LineCallback,
+}
+
+impl LineSplitter {
+    pub(crate) fn new(callback: LineCallback) -> Self {
+        Self {
+            pending: Vec::new(),
+            callback,
+        }
+    }
+
+    /// 追加数据，对其中每个完整的行调用回调
+    pub(crate) fn feed(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+        let mut start = 0;
+        while let Some(end) = self.pending[start..].iter().position(|b| *b == b'\n') {
+            let line = &self.pending[start..start + end];
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            (self.callback)(&String::from_utf8_lossy(line));
+            start += end + 1;
+        }
+        self.pending.drain(..start);
+    }
+
+    /// 输出结束，把没有换行符结尾的最后一行交给回调
+    pub(crate) fn finish(mut self) {
+        if !self.pending.is_empty() {
+            let line = std::mem::take(&mut self.pending);
+            (self.callback)(&String::from_utf8_lossy(&line));
+        }
+    }
+}
+
+/// 在后台线程中持续读取管道输出到共享缓冲区
+///
+/// 提供时间线时，每次读到的数据同时以 `stream` 为来源记录到时间线；
+/// 提供逐行回调时，读到的数据同时按行交给回调。
+pub(crate) fn spawn_collector<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    buffer: SharedBuffer,
+    timeline: Option<(Timeline, OutputStream)>,
+    on_line: Option<LineCallback>,
+) -> Option<JoinHandle<()>> {
+    let mut pipe = pipe?;
+    Some(std::thread::spawn(move || {
+        let mut lines = on_line.map(LineSplitter::new);
+        let mut chunk = [0u8; 8192];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if let Some((timeline, stream)) = &timeline {
+                        timeline.record(*stream, &chunk[..n]);
+                    }
+                    if let Some(lines) = &mut lines {
+                        lines.feed(&chunk[..n]);
+                    }
+                    lock(&buffer).extend(&chunk[..n]);
+                }
+            }
+        }
+        if let Some(lines) = lines {
+            lines.finish();
+        }
+    }))
+}
+
+/// 收集器结束后得到的输出
+#[derive(Debug)]
+pub(crate) struct CapturedOutput {
+    pub(crate) stdout: Vec<u8>,
+    pub(crate) stderr: Vec<u8>,
+    /// 因容量限制丢弃的字节数
+    pub(crate) dropped: usize,
+    /// 时间线模式下按时间排列的输出块
+    pub(crate) timeline: Option<Vec<OutputChunk>>,
+}
+
+/// 子进程 stdout/stderr 的后台收集器
+#[derive(Debug)]
+pub(crate) struct OutputCollectors {
+    pub(crate) stdout: SharedBuffer,
+    pub(crate) stderr: SharedBuffer,
+    timeline: Option<Timeline>,
+    readers: Vec<JoinHandle<()>>,
+}
+
+impl OutputCollectors {
+    /// 取走子进程的输出管道并启动读取线程
+    ///
+    /// 已被取走的管道不再读取，对应的输出为空。时间线中的偏移以 `started`
+    /// （子进程启动时刻）为零点。
+    pub(crate) fn start(child: &mut Child, mode: CaptureMode, started: Instant) -> Self {
+        Self::start_with_lines(child, mode, started, None, None)
+    }
+
+    /// 与 [`start`](Self::start) 相同，读到的输出同时按行交给对应的回调
+    pub(crate) fn start_with_lines(
+        child: &mut Child,
+        mode: CaptureMode,
+        started: Instant,
+        on_stdout: Option<LineCallback>,
+        on_stderr: Option<LineCallback>,
+    ) -> Self {
+        let stdout = shared_buffer(mode);
+        let stderr = shared_buffer(mode);
+        let timeline = (mode == CaptureMode::Timeline).then(|| Timeline::new(started));
+        let tag = |stream| timeline.clone().map(|timeline| (timeline, stream));
+        let readers = [
+            spawn_collector(
+                child.stdout.take(),
+                Arc::clone(&stdout),
+                tag(OutputStream::Stdout),
+                on_stdout,
+            ),
+            spawn_collector(
+                child.stderr.take(),
+                Arc::clone(&stderr),
+                tag(OutputStream::Stderr),
+                on_stderr,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        Self {
+            stdout,
+            stderr,
+            timeline,
+            readers,
+        }
+    }
+
+    /// 从单个读取端收集输出，全部作为 stdout，stderr 为空
+    ///
+    /// 用于 stdout 和 stderr 合并到同一终端的伪终端执行。
+    #[cfg(all(unix, feature = "pty"))]
+    pub(crate) fn start_single<R: Read + Send + 'static>(
+        reader: R,
+        mode: CaptureMode,
+        started: Instant,
+    ) -> Self {
+        let stdout = shared_buffer(mode);
+        let timeline = (mode == CaptureMode::Timeline).then(|| Timeline::new(started));
+        let tagged = timeline
+            .clone()
+            .map(|timeline| (timeline, OutputStream::Stdout));
+        let readers = spawn_collector(Some(reader), Arc::clone(&stdout), tagged, None)
+            .into_iter()
+            .collect();
+        Self {
+            stdout,
+            stderr: shared_buffer(mode),
+            timeline,
+            readers,
+        }
+    }
+
+    /// 等待读取线程结束并取出捕获的输出
+    pub(crate) fn finish(self) -> CapturedOutput {
+        for reader in self.readers {
+            let _ = reader.join();
+        }
+        let mut stdout = lock(&self.stdout);
+        let mut stderr = lock(&self.stderr);
+        CapturedOutput {
+            dropped: stdout.dropped() + stderr.dropped(),
+            stdout: stdout.take(),
+            stderr: stderr.take(),
+            timeline: self.timeline.map(|timeline| timeline.take()),
+        }
+    }
+}
+
+/// 获取缓冲区锁（忽略中毒）
+pub(crate) fn lock(buffer: &SharedBuffer) -> std::sync::MutexGuard<'_, OutputBuffer> {
+    buffer.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_buffer_keeps_everything() {
+        let mut buffer = OutputBuffer::new(CaptureMode::Full);
+        buffer.extend(b"hello ");
+        buffer.extend(b"world");
+        assert_eq!(buffer.take(), b"hello world");
+        assert_eq!(buffer.dropped(), 0);
+    }
+
+    #[test]
+    fn test_tail_buffer_drops_oldest_bytes() {
+        let mut buffer = OutputBuffer::new(CaptureMode::Tail(5));
+        buffer.extend(b"abc");
+        buffer.extend(b"defg");
+        assert_eq!(buffer.tail(10), b"cdefg");
+        assert_eq!(buffer.dropped(), 2);
+    }
+
+    #[test]
+    fn test_tail_buffer_large_single_write() {
+        let mut buffer = OutputBuffer::new(CaptureMode::Tail(3));
+        buffer.extend(b"xy");
+        buffer.extend(b"0123456789");
+        assert_eq!(buffer.take(), b"789");
+        assert_eq!(buffer.dropped(), 9);
+    }
+
+    #[test]
+    fn test_line_splitter_joins_partial_lines() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&lines);
+        let mut splitter = LineSplitter::new(Box::new(move |line: &str| {
+            sink.lock().unwrap().push(line.to_string());
+        }));
+        splitter.feed(b"first\nsec");
+        splitter.feed(b"ond\r\n\nlast");
+        splitter.finish();
+        assert_eq!(*lines.lock().unwrap(), ["first", "second", "", "last"]);
+    }
+
+    #[test]
+    fn test_timeline_records_chunks_in_order() {
+        let timeline = Timeline::new(Instant::now());
+        timeline.record(OutputStream::Stdout, b"out");
+        timeline.record(OutputStream::Stderr, b"err");
+        let chunks = timeline.take();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].stream, OutputStream::Stdout);
+        assert_eq!(chunks[0].data, b"out");
+        assert_eq!(chunks[1].stream, OutputStream::Stderr);
+        assert!(chunks[0].offset <= chunks[1].offset);
+        assert!(timeline.take().is_empty());
+    }
+
+    #[test]
+    fn test_tail_reads_last_bytes() {
+        let mut buffer = OutputBuffer::new(CaptureMode::Full);
+        buffer.extend(b"0123456789");
+        assert_eq!(buffer.tail(4), b"6789");
+        assert_eq!(buffer.tail(100), b"0123456789");
+    }
+}
@@ -0,0 +1,384 @@
+//! 子进程退出等待
+//!
+//! 在 Linux 上由一个反应器线程通过 epoll 同时等待所有子进程的 pidfd：
+//! 子进程退出时反应器只唤醒等待该子进程的线程，等待线程在条件变量上带超时阻塞，
+//! 不轮询、不依赖 SIGCHLD 信号处理，也不会被其他子进程的退出唤醒。
+//! 每个子进程只打开一次 pidfd，多次带超时等待复用同一注册。
+//! 内核不支持 pidfd（Linux 5.3 之前）或在其他平台上时退化为 `wait-timeout`。
+//!
+//! 在 Linux 上，子进程退出后、回收之前通过 `waitid(WNOWAIT)` 读取其资源使用量，
+//! 回收仍由标准库完成，`Child` 的状态保持一致。
+
+use std::io;
+use std::process::{Child, ExitStatus};
+use std::time::Duration;
+
+use crate::report::ResourceUsage;
+
+/// 子进程的退出状态和回收前读取的资源使用量（无法读取时为 None）
+pub(crate) type Exited = (ExitStatus, Option<ResourceUsage>);
+
+/// 带超时等待子进程退出
+///
+/// 与 `wait_timeout::ChildExt` 接口一致，替换导入即可切换实现。
+pub(crate) trait ChildExt {
+    /// 等待子进程退出，超时返回 `Ok(None)`，子进程不会被终止
+    fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        Ok(self
+            .wait_timeout_with_usage(timeout)?
+            .map(|(status, _)| status))
+    }
+
+    /// 与 [`wait_timeout`](Self::wait_timeout) 相同，同时返回子进程的资源使用量
+    ///
+    /// 退化为 `wait-timeout` 或不在 Linux 上时资源使用量为 None。
+    fn wait_timeout_with_usage(&mut self, timeout: Duration) -> io::Result<Option<Exited>>;
+
+    /// 阻塞等待子进程退出，同时返回子进程的资源使用量
+    fn wait_with_usage(&mut self) -> io::Result<Exited>;
+}
+
+impl ChildExt for Child {
+    fn wait_timeout_with_usage(&mut self, timeout: Duration) -> io::Result<Option<Exited>> {
+        // 先检查是否已退出：已回收的子进程 PID 可能被复用，不能再为其打开 pidfd
+        if let Some(exited) = try_reap(self)? {
+            return Ok(Some(exited));
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(result) = reactor::wait_timeout(self, timeout) {
+            return result;
+        }
+
+        Ok(wait_timeout::ChildExt::wait_timeout(self, timeout)?.map(|status| (status, None)))
+    }
+
+    fn wait_with_usage(&mut self) -> io::Result<Exited> {
+        #[cfg(target_os = "linux")]
+        let usage = usage::read(self.id(), true);
+        #[cfg(not(target_os = "linux"))]
+        let usage = None;
+        Ok((self.wait()?, usage))
+    }
+}
+
+/// 子进程已退出时读取资源使用量并回收，尚未退出时返回 `Ok(None)`
+fn try_reap(child: &mut Child) -> io::Result<Option<Exited>> {
+    #[cfg(target_os = "linux")]
+    let usage = usage::read(child.id(), false);
+    #[cfg(not(target_os = "linux"))]
+    let usage = None;
+    Ok(child.try_wait()?.map(|status| (status, usage)))
+}
+
+#[cfg(target_os = "linux")]
+mod usage {
+    use nix::errno::Errno;
+    use nix::libc;
+
+    use crate::report::ResourceUsage;
+
+    /// 读取已退出、尚未回收的子进程的资源使用量，不回收子进程
+    ///
+    /// `block` 为 true 时等待子进程退出。子进程尚未退出、已被回收或读取失败时返回 None。
+    pub(super) fn read(pid: u32, block: bool) -> Option<ResourceUsage> {
+        let mut options = libc::WEXITED | libc::WNOWAIT;
+        if !block {
+            options |= libc::WNOHANG;
+        }
+        loop {
+            // SAFETY: siginfo_t 和 rusage 是纯数据结构，全零是合法的初始值
+            let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+            let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+            // glibc 的 waitid 不提供 rusage 参数，直接使用系统调用；
+            // WNOWAIT 使子进程保持可回收状态，之后由标准库回收
+            // SAFETY: 传入的指针在调用期间有效
+            let ret = unsafe {
+                libc::syscall(
+                    libc::SYS_waitid,
+                    libc::P_PID,
+                    pid as libc::id_t,
+                    &mut info as *mut libc::siginfo_t,
+                    options,
+                    &mut rusage as *mut libc::rusage,
+                )
+            };
+            if ret == -1 {
+                if Errno::last() == Errno::EINTR {
+                    continue;
+                }
+                return None;
+            }
+            // WNOHANG 且子进程尚未退出时 si_pid 为 0
+            // SAFETY: waitid 成功返回后 siginfo_t 已初始化
+            if unsafe { info.si_pid() } == 0 {
+                return None;
+            }
+            return Some(ResourceUsage::from_rusage(&rusage));
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod reactor {
+    use std::collections::HashMap;
+    use std::io;
+    use std::os::fd::{AsFd, FromRawFd, OwnedFd};
+    use std::process::Child;
+    use std::sync::{Arc, Condvar, Mutex, OnceLock};
+    use std::time::{Duration, Instant};
+
+    use nix::errno::Errno;
+    use nix::libc;
+    use nix::poll::PollTimeout;
+    use nix::sys::epoll::{Epoll, EpollCreateFlags, EpollEvent, EpollFlags};
+
+    use super::{Exited, try_reap};
+
+    /// 子进程退出通知，由反应器线程置位并唤醒等待线程
+    #[derive(Default)]
+    struct ExitSignal {
+        exited: Mutex<bool>,
+        cvar: Condvar,
+    }
+
+    /// 已注册的子进程
+    struct Watch {
+        /// epoll 事件携带的标识，区分同一 PID 先后的注册
+        token: u64,
+        /// 保持 pidfd 打开直到子进程退出
+        pidfd: OwnedFd,
+        signal: Arc<ExitSignal>,
+    }
+
+    /// 子进程退出反应器
+    ///
+    /// 一个后台线程通过 epoll 等待所有已注册子进程的 pidfd，子进程退出时唤醒对应的等待线程。
+    /// 同一子进程多次带超时等待时复用同一注册，子进程退出后注册自动移除。
+    struct Reactor {
+        epoll: Epoll,
+        watches: Mutex<HashMap<u32, Watch>>,
+        next_token: Mutex<u64>,
+    }
+
+    /// 进程内共享的反应器，无法创建 epoll 实例时为 None
+    static REACTOR: OnceLock<Option<Arc<Reactor>>> = OnceLock::new();
+
+    fn reactor() -> Option<&'static Arc<Reactor>> {
+        REACTOR
+            .get_or_init(|| {
+                let epoll = Epoll::new(EpollCreateFlags::EPOLL_CLOEXEC).ok()?;
+                let reactor = Arc::new(Reactor {
+                    epoll,
+                    watches: Mutex::new(HashMap::new()),
+                    next_token: Mutex::new(0),
+                });
+                let background = Arc::clone(&reactor);
+                std::thread::Builder::new()
+                    .name("execute-child-reactor".to_string())
+                    .spawn(move || background.run())
+                    .ok()?;
+                Some(reactor)
+            })
+            .as_ref()
+    }
+
+    /// 为子进程打开 pidfd，内核不支持时返回 None
+    fn open_pidfd(pid: u32) -> Option<OwnedFd> {
+        // SAFETY: pidfd_open 不访问调用方内存，成功时返回新的文件描述符
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        if fd < 0 {
+            return None;
+        }
+        // SAFETY: fd 是刚创建且未被其他对象持有的描述符
+        Some(unsafe { OwnedFd::from_raw_fd(fd as i32) })
+    }
+
+    impl Reactor {
+        /// 反应器线程主循环：等待 pidfd 可读（子进程已退出）后通知等待线程
+        fn run(&self) {
+            let mut events = [EpollEvent::empty(); 64];
+            loop {
+                let ready = match self.epoll.wait(&mut events, PollTimeout::NONE) {
+                    Ok(ready) => ready,
+                    Err(Errno::EINTR) => continue,
+                    Err(_) => return,
+                };
+                let mut watches = self.watches.lock().unwrap();
+                for event in &events[..ready] {
+                    let pid = (event.data() >> 32) as u32;
+                    if watches.get(&pid).is_some_and(|w| w.token == event.data()) {
+                        let watch = watches.remove(&pid).unwrap();
+                        let _ = self.epoll.delete(watch.pidfd.as_fd());
+                        *watch.signal.exited.lock().unwrap() = true;
+                        watch.signal.cvar.notify_all();
+                    }
+                }
+            }
+        }
+
+        /// 获取子进程的退出通知，尚未注册时打开 pidfd 并注册
+        ///
+        /// 调用方必须保证子进程尚未被回收（PID 未被复用）。内核不支持 pidfd 时返回 None。
+        fn watch(&self, pid: u32) -> Option<Arc<ExitSignal>> {
+            let mut watches = self.watches.lock().unwrap();
+            if let Some(watch) = watches.get(&pid) {
+                return Some(Arc::clone(&watch.signal));
+            }
+            let pidfd = open_pidfd(pid)?;
+            let token = {
+                let mut next = self.next_token.lock().unwrap();
+                *next = next.wrapping_add(1) & 0xffff_ffff;
+                (u64::from(pid) << 32) | *next
+            };
+            self.epoll
+                .add(pidfd.as_fd(), EpollEvent::new(EpollFlags::EPOLLIN, token))
+                .ok()?;
+            let signal = Arc::new(ExitSignal::default());
+            watches.insert(
+                pid,
+                Watch {
+                    token,
+                    pidfd,
+                    signal: Arc::clone(&signal),
+                },
+            );
+            Some(signal)
+        }
+
+        #[cfg(test)]
+        fn is_watched(&self, pid: u32) -> bool {
+            self.watches.lock().unwrap().contains_key(&pid)
+        }
+    }
+
+    /// 通过反应器等待子进程退出，反应器或 pidfd 不可用时返回 None
+    pub(super) fn wait_timeout(
+        child: &mut Child,
+        timeout: Duration,
+    ) -> Option<io::Result<Option<Exited>>> {
+        let reactor = reactor()?;
+        // 超时过大导致溢出时视为无限等待
+        let deadline = Instant::now().checked_add(timeout);
+
+        loop {
+            let signal = reactor.watch(child.id())?;
+            let exited = signal.exited.lock().unwrap();
+            // 先释放通知锁再回收，反应器线程持有注册表锁时会获取通知锁
+            drop(match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    signal
+                        .cvar
+                        .wait_timeout_while(exited, remaining, |exited| !*exited)
+                        .unwrap()
+                        .0
+                }
+                None => signal.cvar.wait_while(exited, |exited| !*exited).unwrap(),
+            });
+            match try_reap(child) {
+                Ok(Some(exited)) => return Some(Ok(Some(exited))),
+                Ok(None) if deadline.is_some_and(|d| Instant::now() >= d) => return Some(Ok(None)),
+                // 通知来自同一 PID 之前的注册，重新注册后继续等待
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub(super) fn is_watched(pid: u32) -> bool {
+        reactor().is_some_and(|reactor| reactor.is_watched(pid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use std::time::Instant;
+
+    #[test]
+    fn test_wait_timeout_returns_status_of_exited_child() {
+        let mut child = Command::new("sh").args(["-c", "exit 3"]).spawn().unwrap();
+        let status = child.wait_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        assert_eq!(status.code(), Some(3));
+    }
+
+    #[test]
+    fn test_wait_timeout_expires_for_running_child() {
+        let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+        let start = Instant::now();
+        assert!(
+            child
+                .wait_timeout(Duration::from_millis(50))
+                .unwrap()
+                .is_none()
+        );
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_secs(1));
+
+        child.kill().unwrap();
+        assert!(
+            child
+                .wait_timeout(Duration::from_secs(5))
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_wait_timeout_after_child_was_reaped() {
+        let mut child = Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        let status = child.wait_timeout(Duration::from_millis(10)).unwrap();
+        assert!(status.unwrap().success());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_reactor_reuses_registration_and_cleans_up() {
+        let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+        let pid = child.id();
+        for _ in 0..3 {
+            assert!(
+                child
+                    .wait_timeout(Duration::from_millis(10))
+                    .unwrap()
+                    .is_none()
+            );
+            assert!(reactor::is_watched(pid));
+        }
+
+        child.kill().unwrap();
+        assert!(
+            child
+                .wait_timeout(Duration::from_secs(5))
+                .unwrap()
+                .is_some()
+        );
+        // 反应器在子进程退出后移除注册
+        let start = Instant::now();
+        while reactor::is_watched(pid) {
+            assert!(start.elapsed() < Duration::from_secs(1));
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_wait_many_children_concurrently() {
+        let waiters: Vec<_> = (0..100)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    let delay = format!("0.{:02}", i % 20);
+                    let mut child = Command::new("sleep").arg(delay).spawn().unwrap();
+                    child.wait_timeout(Duration::from_secs(10)).unwrap()
+                })
+            })
+            .collect();
+        for waiter in waiters {
+            assert!(waiter.join().unwrap().unwrap().success());
+        }
+    }
+}
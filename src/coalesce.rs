@@ -0,0 +1,146 @@
+//! 相同任务的合并执行
+//!
+//! 带相同合并键的任务在首个任务等待或执行期间提交时不会再次入队，
+//! 而是登记为跟随者，首个任务完成后把结果广播给所有跟随者。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::mpsc::Sender;
+
+use crate::error::ExecuteError;
+use crate::task_handle::{TaskHandle, TaskResult, TaskState};
+
+/// 合并到首个任务的跟随者
+struct Follower {
+    handle: TaskHandle,
+    result_sender: Sender<TaskResult>,
+}
+
+/// 同一合并键下的任务组
+#[derive(Default)]
+struct CoalesceGroup {
+    /// 首个任务是否已开始执行
+    running: bool,
+    followers: Vec<Follower>,
+}
+
+/// 合并键到任务组的登记表
+#[derive(Default)]
+pub(crate) struct CoalesceTable {
+    groups: Mutex<HashMap<String, CoalesceGroup>>,
+}
+
+impl CoalesceTable {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// 尝试作为跟随者加入已有的任务组
+    ///
+    /// 返回 `true` 表示已加入，结果将由首个任务广播。
+    pub(crate) fn join(&self, key: &str, handle: &TaskHandle, sender: &Sender<TaskResult>) -> bool {
+        let mut groups = self.groups.lock().unwrap();
+        match groups.get_mut(key) {
+            Some(group) => {
+                Self::add_follower(group, handle, sender);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 加入已有的任务组，不存在时登记为首个任务
+    ///
+    /// 返回 `true` 表示已作为跟随者加入，`false` 表示成为首个任务，需要入队执行。
+    pub(crate) fn join_or_lead(
+        &self,
+        key: &str,
+        handle: &TaskHandle,
+        sender: &Sender<TaskResult>,
+    ) -> bool {
+        let mut groups = self.groups.lock().unwrap();
+        match groups.get_mut(key) {
+            Some(group) => {
+                Self::add_follower(group, handle, sender);
+                true
+            }
+            None => {
+                groups.insert(key.to_string(), CoalesceGroup::default());
+                false
+            }
+        }
+    }
+
+    fn add_follower(group: &mut CoalesceGroup, handle: &TaskHandle, sender: &Sender<TaskResult>) {
+        #[cfg(feature = "logging")]
+        tracing::debug!(task_id = handle.id(), "Task coalesced into pending task");
+
+        if group.running {
+            handle.set_state(TaskState::Running { pid: None });
+        }
+        group.followers.push(Follower {
+            handle: handle.clone(),
+            result_sender: sender.clone(),
+        });
+    }
+
+    /// 首个任务开始执行，同步更新跟随者状态
+    pub(crate) fn mark_running(&self, key: &str) {
+        let mut groups = self.groups.lock().unwrap();
+        if let Some(group) = groups.get_mut(key) {
+            group.running = true;
+            for follower in &group.followers {
+                if !follower.handle.is_cancelled() {
+                    follower.handle.set_state(TaskState::Running { pid: None });
+                }
+            }
+        }
+    }
+
+    /// 首个任务完成，把结果广播给所有跟随者并移除任务组
+    ///
+    /// 之后提交的同键任务会重新执行。已取消的跟随者收到取消错误。
+    /// 返回跟随者的任务 ID。
+    pub(crate) fn complete(&self, key: &str, leader: &TaskHandle, result: &TaskResult) -> Vec<u64> {
+        let Some(group) = self.groups.lock().unwrap().remove(key) else {
+            return Vec::new();
+        };
+        if group.followers.is_empty() {
+            return Vec::new();
+        }
+
+        #[cfg(feature = "logging")]
+        tracing::debug!(
+            task_id = leader.id(),
+            followers = group.followers.len(),
+            "Broadcasting coalesced result"
+        );
+
+        let report = leader.report_snapshot();
+        let state = match result {
+            Err(ExecuteError::Cancelled(_) | ExecuteError::Expired(_)) => TaskState::Cancelled,
+            Err(ExecuteError::Skipped(_)) => TaskState::Skipped,
+            _ => TaskState::Completed,
+        };
+        let mut ids = Vec::with_capacity(group.followers.len());
+        for follower in group.followers {
+            ids.push(follower.handle.id());
+            if follower.handle.is_cancelled() {
+                let _ = follower
+                    .result_sender
+                    .send(Err(ExecuteError::Cancelled(follower.handle.id())));
+                continue;
+            }
+            if let Some(report) = &report {
+                follower.handle.set_report(report.clone());
+            }
+            let copy = match result {
+                Ok(output) => Ok(output.clone()),
+                Err(e) => Err(e.duplicate()),
+            };
+            follower.handle.set_state(state.clone());
+            let _ = follower.result_sender.send(copy);
+        }
+        ids
+    }
+}
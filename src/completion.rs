@@ -0,0 +1,237 @@
+//! 任务完成流
+//!
+//! 批量提交任务后按完成顺序逐个取回结果；开启有序模式时按提交顺序交付，
+//! 先完成的任务结果在内部缓存，直到排在它前面的任务都已交付。
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+
+use crate::budget::{BatchBudget, BudgetMonitor};
+use crate::config::CommandConfig;
+use crate::error::{ExecuteError, SubmitError};
+use crate::events::{FinishStatus, PoolEvent};
+use crate::pool::CommandPool;
+use crate::task_handle::{TaskHandle, TaskResult};
+
+/// 任务完成流
+///
+/// 由 [`CommandPool::submit_batch`] 创建，迭代产出 `(提交序号, 结果)`。
+/// 默认按完成顺序产出，调用 [`CompletionStream::ordered`] 后按提交顺序产出。
+pub struct CompletionStream {
+    events: Receiver<PoolEvent>,
+    /// 任务 ID 到提交序号
+    indices: HashMap<u64, usize>,
+    handles: Vec<Option<TaskHandle>>,
+    ordered: bool,
+    /// 有序模式下已完成但尚未交付的结果
+    buffered: BTreeMap<usize, TaskResult>,
+    /// 有序模式下下一个要交付的序号
+    next: usize,
+    /// 事件总线已断开（命令池已被丢弃）
+    disconnected: bool,
+    /// 累计资源预算监控
+    budget: Option<Arc<BudgetMonitor>>,
+    /// 首个失败后取消其余任务
+    fail_fast: bool,
+    /// 快速失败模式下首个失败任务的序号
+    first_failure: Option<usize>,
+}
+
+impl CompletionStream {
+    fn new(events: Receiver<PoolEvent>, handles: Vec<TaskHandle>) -> Self {
+        let indices = handles
+            .iter()
+            .enumerate()
+            .map(|(index, handle)| (handle.id(), index))
+            .collect();
+        Self {
+            events,
+            indices,
+            handles: handles.into_iter().map(Some).collect(),
+            ordered: false,
+            buffered: BTreeMap::new(),
+            next: 0,
+            disconnected: false,
+            budget: None,
+            fail_fast: false,
+            first_failure: None,
+        }
+    }
+
+    /// 按提交顺序交付结果
+    ///
+    /// 执行仍然并行，乱序完成的结果在内部缓存。
+    pub fn ordered(mut self) -> Self {
+        self.ordered = true;
+        self
+    }
+
+    /// 为流中的任务设置累计资源预算和截止时间
+    ///
+    /// 组内已完成和正在运行的任务累计消耗超过预算或到达截止时间后，尚未结束的任务会被取消，
+    /// 其结果为 [`ExecuteError::BudgetExceeded`]。消耗和截止时间从调用本方法时开始计算。
+    pub fn with_budget(mut self, budget: BatchBudget) -> Self {
+        if let Some(previous) = self.budget.take() {
+            previous.stop();
+        }
+        let handles = self.handles.iter().flatten().cloned().collect();
+        self.budget = Some(BudgetMonitor::spawn(budget, handles));
+        self
+    }
+
+    /// 启用快速失败
+    ///
+    /// 首个失败的任务（非零退出、超时或执行错误）完成后，取消流中尚未结束的任务，
+    /// 它们的结果为 [`ExecuteError::Cancelled`]。失败按完成顺序判断，与是否有序交付无关。
+    pub fn fail_fast(mut self) -> Self {
+        self.fail_fast = true;
+        self
+    }
+
+    /// 快速失败模式下首个失败任务的提交序号
+    pub fn first_failure(&self) -> Option<usize> {
+        self.first_failure
+    }
+
+    /// 流中任务的句柄（按提交顺序，已交付的任务为 None）
+    pub fn handles(&self) -> impl Iterator<Item = Option<&TaskHandle>> {
+        self.handles.iter().map(Option::as_ref)
+    }
+
+    /// 尚未交付的结果数
+    pub fn remaining(&self) -> usize {
+        self.handles.iter().filter(|h| h.is_some()).count() + self.buffered.len()
+    }
+
+    /// 阻塞直到下一个任务完成，返回其序号和结果
+    fn next_completed(&mut self) -> Option<(usize, TaskResult)> {
+        while !self.indices.is_empty() {
+            if self.disconnected {
+                // 事件总线已断开，退化为按提交顺序逐个等待
+                let index = self.handles.iter().position(Option::is_some)?;
+                return Some((index, self.take(index)));
+            }
+            match self.events.recv() {
+                Ok(PoolEvent::TaskFinished { task_id, .. }) => {
+                    if let Some(&index) = self.indices.get(&task_id) {
+                        return Some((index, self.take(index)));
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => self.disconnected = true,
+            }
+        }
+        None
+    }
+
+    /// 取走任务结果
+    ///
+    /// 完成事件在结果发送前发布，这里的等待很短。
+    fn take(&mut self, index: usize) -> TaskResult {
+        let handle = self.handles[index].take().expect("task delivered twice");
+        self.indices.remove(&handle.id());
+        let result = self.resolve(handle.wait());
+        if self.fail_fast
+            && self.first_failure.is_none()
+            && FinishStatus::from_result(&result).is_failure()
+        {
+            self.first_failure = Some(index);
+            for handle in self.handles.iter().flatten() {
+                let _ = handle.cancel();
+            }
+        }
+        result
+    }
+
+    /// 将预算监控取消的任务结果转换为预算超出错误
+    fn resolve(&self, result: TaskResult) -> TaskResult {
+        match result {
+            Err(ExecuteError::Cancelled(task_id)) => {
+                match self
+                    .budget
+                    .as_ref()
+                    .and_then(|b| b.exceeded_reason(task_id))
+                {
+                    Some(reason) => Err(ExecuteError::BudgetExceeded(reason)),
+                    None => Err(ExecuteError::Cancelled(task_id)),
+                }
+            }
+            result => result,
+        }
+    }
+}
+
+impl Drop for CompletionStream {
+    fn drop(&mut self) {
+        if let Some(budget) = &self.budget {
+            budget.stop();
+        }
+    }
+}
+
+impl Iterator for CompletionStream {
+    type Item = (usize, TaskResult);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.ordered {
+            return self.next_completed();
+        }
+        loop {
+            if let Some(result) = self.buffered.remove(&self.next) {
+                let index = self.next;
+                self.next += 1;
+                return Some((index, result));
+            }
+            let (index, result) = self.next_completed()?;
+            self.buffered.insert(index, result);
+        }
+    }
+}
+
+impl CommandPool {
+    /// 批量提交任务，返回任务完成流
+    ///
+    /// 任务在共享工作线程上并行执行，通过返回的 [`CompletionStream`]
+    /// 逐个取回结果；需要按提交顺序处理输出时调用 [`CompletionStream::ordered`]。
+    ///
+    /// # 错误
+    ///
+    /// 任一任务提交失败时，已提交的任务会被取消并返回 [`SubmitError`]。
+    ///
+    /// # 示例
+    ///
+    /// ```ignore
+    /// use execute::{CommandConfig, CommandPool};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    ///
+    /// let configs = (0..10)
+    ///     .map(|i| CommandConfig::new("echo", vec![i.to_string()]))
+    ///     .collect::<Vec<_>>();
+    /// for (index, result) in pool.submit_batch(configs)?.ordered() {
+    ///     println!("{}: {:?}", index, result);
+    /// }
+    /// ```
+    pub fn submit_batch<I>(&self, configs: I) -> Result<CompletionStream, SubmitError>
+    where
+        I: IntoIterator<Item = CommandConfig>,
+    {
+        // 先订阅再提交，避免错过提交后立即完成的任务
+        let events = self.subscribe();
+        let mut handles = Vec::new();
+        for config in configs {
+            match self.push_task(config) {
+                Ok(handle) => handles.push(handle),
+                Err(e) => {
+                    for handle in &handles {
+                        let _ = handle.cancel();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(CompletionStream::new(events, handles))
+    }
+}
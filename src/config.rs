@@ -1,12 +1,25 @@
-use std::collections::HashMap;
-use std::time::Duration;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
-use crate::error::ConfigError;
+use crate::error::{ConfigError, ExecuteError};
+use crate::hooks::TimeoutHook;
 
 /// 重试策略
 ///
 /// 定义任务失败后的重试行为，包括最大重试次数和重试延迟策略。
 ///
+/// 通过 [`CommandConfig::with_retry`] 和 [`CommandConfig::with_retries`] 设置的重试遵循同一规则：
+/// 启动失败、超时等执行错误，以及按 [`CommandConfig::is_success`] 判断为失败的退出状态都会重试，
+/// 取消的任务不重试，用尽重试后返回最后一次的结果。只需重试执行错误时使用
+/// [`with_retry_failed_exit(false)`](Self::with_retry_failed_exit)。
+///
+/// 在命令池中，失败的任务按退避延迟重新放回队列，等待期间不占用工作线程。
+///
 /// # 示例
 ///
 /// ```ignore
@@ -27,6 +40,7 @@ use crate::error::ConfigError;
 /// );
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RetryPolicy {
     /// 最大重试次数（不包括初始尝试）
     ///
@@ -35,6 +49,13 @@ pub struct RetryPolicy {
 
     /// 重试延迟策略
     pub strategy: RetryStrategy,
+
+    /// 退出状态失败时是否重试
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "RetryPolicy::serde_default_retry_failed_exit")
+    )]
+    retry_failed_exit: bool,
 }
 
 impl RetryPolicy {
@@ -57,6 +78,47 @@ impl RetryPolicy {
         Self {
             max_attempts,
             strategy,
+            retry_failed_exit: true,
+        }
+    }
+
+    /// 反序列化时缺省的退出状态重试设置，与 [`new`](Self::new) 一致
+    #[cfg(feature = "serde")]
+    fn serde_default_retry_failed_exit() -> bool {
+        true
+    }
+
+    /// 设置退出状态失败时是否重试（默认为 true）
+    ///
+    /// # 示例
+    ///
+    /// ```ignore
+    /// use execute::config::{RetryPolicy, RetryStrategy};
+    /// use std::time::Duration;
+    ///
+    /// // 非零退出码表示确定的失败，只重试启动失败和超时
+    /// let policy = RetryPolicy::new(3, RetryStrategy::FixedInterval(Duration::from_secs(1)))
+    ///     .with_retry_failed_exit(false);
+    /// ```
+    pub fn with_retry_failed_exit(mut self, enabled: bool) -> Self {
+        self.retry_failed_exit = enabled;
+        self
+    }
+
+    /// 退出状态失败时是否重试
+    pub fn retry_failed_exit(&self) -> bool {
+        self.retry_failed_exit
+    }
+
+    /// 命令的执行结果是否需要重试
+    pub(crate) fn should_retry<E>(
+        &self,
+        config: &CommandConfig,
+        result: Result<&Output, E>,
+    ) -> bool {
+        match result {
+            Ok(output) => self.retry_failed_exit && !config.is_success(&output.status),
+            Err(_) => true,
         }
     }
 
@@ -101,6 +163,7 @@ impl RetryPolicy {
 ///
 /// 定义如何计算每次重试之间的延迟时间。
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RetryStrategy {
     /// 固定间隔重试
     ///
@@ -181,6 +244,92 @@ impl RetryStrategy {
     }
 }
 
+/// 触发命令池自动重试的失败类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RetryOn {
+    /// 进程以失败退出码结束（按任务的成功退出码规则判断）
+    NonZeroExit,
+    /// 执行超时
+    Timeout,
+    /// 其他执行错误（如启动失败、I/O 错误）；取消和跳过的任务从不重试
+    Error,
+}
+
+/// 命令池级别的自动重试策略
+///
+/// 通过 [`CommandPoolBuilder::with_retry_policy`](crate::CommandPoolBuilder::with_retry_policy)
+/// 设置后，失败的任务不会立即返回结果，而是按退避延迟重新放回队列，
+/// 直到成功或用完重试次数。等待重试期间任务不占用工作线程，
+/// 已尝试次数可以通过 [`TaskHandle::attempts`](crate::TaskHandle::attempts) 查询。
+/// 自身设置了 [`CommandConfig::with_retry`] 的任务只按自己的策略重试。
+///
+/// 默认只重试执行错误和超时，非零退出码需要通过 [`with_retry_on`](Self::with_retry_on) 启用。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandPool, PoolRetryPolicy, RetryOn, RetryStrategy};
+/// use std::time::Duration;
+///
+/// let policy = PoolRetryPolicy::new(
+///     3,
+///     RetryStrategy::FixedInterval(Duration::from_secs(1)),
+/// )
+/// .with_retry_on(&[RetryOn::Timeout, RetryOn::NonZeroExit]);
+/// let pool = CommandPool::builder().with_retry_policy(policy).build()?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolRetryPolicy {
+    /// 最大重试次数（不包括初始尝试）
+    pub max_attempts: usize,
+    /// 重试延迟策略
+    pub strategy: RetryStrategy,
+    /// 触发重试的失败类型
+    pub retry_on: Vec<RetryOn>,
+}
+
+impl PoolRetryPolicy {
+    /// 创建新的命令池重试策略，默认重试执行错误和超时
+    pub fn new(max_attempts: usize, strategy: RetryStrategy) -> Self {
+        Self {
+            max_attempts,
+            strategy,
+            retry_on: vec![RetryOn::Error, RetryOn::Timeout],
+        }
+    }
+
+    /// 设置触发重试的失败类型
+    pub fn with_retry_on(mut self, retry_on: &[RetryOn]) -> Self {
+        self.retry_on = retry_on.to_vec();
+        self
+    }
+
+    /// 计算第 `attempt` 次重试（从 1 开始）前的延迟
+    pub fn delay_for_attempt(&self, attempt: usize) -> Duration {
+        self.strategy.delay_for_attempt(attempt)
+    }
+
+    /// 任务结果是否属于需要重试的失败类型
+    pub(crate) fn should_retry(
+        &self,
+        config: &CommandConfig,
+        result: &Result<Output, ExecuteError>,
+    ) -> bool {
+        let kind = match result {
+            Ok(output) if config.is_success(&output.status) => return false,
+            Ok(_) | Err(ExecuteError::UnexpectedExit { .. } | ExecuteError::ExitStatus { .. }) => {
+                RetryOn::NonZeroExit
+            }
+            Err(ExecuteError::Timeout(_)) => RetryOn::Timeout,
+            Err(ExecuteError::Cancelled(_) | ExecuteError::Skipped(_)) => return false,
+            Err(_) => RetryOn::Error,
+        };
+        self.retry_on.contains(&kind)
+    }
+}
+
 /// 资源限制配置
 ///
 /// 用于限制命令执行时的资源使用，防止单个任务消耗过多资源。
@@ -195,6 +344,7 @@ impl RetryStrategy {
 ///     .with_max_memory(100 * 1024 * 1024); // 100 MB
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ResourceLimits {
     /// 最大输出大小（字节）
     ///
@@ -265,6 +415,25 @@ impl Default for ResourceLimits {
     }
 }
 
+/// 路径是否为可执行的普通文件
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || (path.extension().is_none() && path.with_extension("exe").is_file())
+}
+
+/// [`CommandConfig::with_retries`] 的默认首次重试间隔
+const RETRY_BACKOFF_INITIAL: Duration = Duration::from_millis(100);
+
+/// 重试退避的单次等待上限
+const RETRY_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
 /// CommandConfig 表示要执行的外部命令及其执行参数。
 ///
 /// 字段：
@@ -277,6 +446,9 @@ impl Default for ResourceLimits {
 /// - `timeout_config`: 可选的细粒度超时配置。
 /// - `env_config`: 可选的环境变量配置。
 ///
+/// 程序、参数和工作目录以 `OsString`/`PathBuf` 保存，可以包含非 UTF-8 的文件名；
+/// 构造方法同时接受 `&str`。
+///
 /// 示例（构造一个带超时的命令配置）：
 /// ```ignore
 /// use execute::CommandConfig;
@@ -286,42 +458,126 @@ impl Default for ResourceLimits {
 ///     .with_timeout(Duration::from_secs(2));
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default = "CommandConfig::serde_default"))]
 pub struct CommandConfig {
-    pub(crate) program: String,
-    pub(crate) args: Vec<String>,
-    pub(crate) working_dir: Option<String>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::os_string"))]
+    pub(crate) program: OsString,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_os::os_string_vec"))]
+    pub(crate) args: Vec<OsString>,
+    pub(crate) working_dir: Option<PathBuf>,
     pub(crate) timeout: Option<Duration>,
+    pub(crate) kill_grace: Option<Duration>,
+    pub(crate) kill_tree: bool,
     pub(crate) resource_limits: Option<ResourceLimits>,
     pub(crate) retry_policy: Option<RetryPolicy>,
     pub(crate) timeout_config: Option<TimeoutConfig>,
     pub(crate) env_config: Option<EnvConfig>,
+    /// 钩子是运行时对象，不参与序列化
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) timeout_hook: Option<TimeoutHookConfig>,
+    pub(crate) capture_mode: CaptureMode,
+    pub(crate) output_mode: OutputMode,
+    pub(crate) stdout_file: Option<OutputFile>,
+    pub(crate) stderr_file: Option<OutputFile>,
+    pub(crate) temp_workdir: Option<TempWorkdirConfig>,
+    pub(crate) artifacts: Option<ArtifactConfig>,
+    pub(crate) inputs: Option<InputConfig>,
+    pub(crate) checksums: bool,
+    pub(crate) flock: Option<PathBuf>,
+    pub(crate) singleton_key: Option<String>,
+    pub(crate) coalesce_key: Option<String>,
+    pub(crate) output_diff: Option<OutputDiffConfig>,
+    pub(crate) io_priority: Option<IoPriority>,
+    pub(crate) nice: Option<i32>,
+    pub(crate) cpu_affinity: Option<Vec<usize>>,
+    pub(crate) rlimits: Rlimits,
+    pub(crate) uid: Option<u32>,
+    pub(crate) gid: Option<u32>,
+    pub(crate) umask: Option<u32>,
+    pub(crate) detach_session: bool,
+    pub(crate) affinity_key: Option<String>,
+    pub(crate) serial_key: Option<String>,
+    pub(crate) concurrency_key: Option<String>,
+    pub(crate) priority: u8,
+    pub(crate) start_at: Option<SystemTime>,
+    pub(crate) queue_ttl: Option<Duration>,
+    pub(crate) backend: Option<String>,
+    pub(crate) force_color: bool,
+    pub(crate) stdin: Option<Vec<u8>>,
+    pub(crate) success_codes: Option<Vec<i32>>,
+    pub(crate) allow_failure: bool,
+    pub(crate) check_exit: bool,
+    pub(crate) labels: BTreeMap<String, String>,
 }
 
 impl CommandConfig {
     /// # 创建一个CommandConfig结构体
     ///
     /// # 参数
-    /// - `program`: 执行的命令
-    /// - `args`: 命令参数列表
+    /// - `program`: 执行的命令（`&str`、`OsString`、`PathBuf` 等）
+    /// - `args`: 命令参数列表，非 UTF-8 参数通过 [`with_args`](Self::with_args) 添加
     ///
     /// # 示例
     /// ```ignore
     /// let cfg = CommandConfig::new("echo", vec!["hello".to_string()]);
     /// println!("program = {}", cfg.program());
     /// ```
-    pub fn new(program: &str, args: Vec<String>) -> Self {
+    pub fn new(program: impl Into<OsString>, args: Vec<String>) -> Self {
         Self {
-            program: program.to_string(),
-            args,
+            program: program.into(),
+            args: args.into_iter().map(OsString::from).collect(),
             working_dir: None,
             timeout: Some(Duration::from_secs(10)),
+            kill_grace: None,
+            kill_tree: false,
             resource_limits: None,
             retry_policy: None,
             timeout_config: None,
             env_config: None,
+            timeout_hook: None,
+            capture_mode: CaptureMode::Full,
+            output_mode: OutputMode::Capture,
+            stdout_file: None,
+            stderr_file: None,
+            temp_workdir: None,
+            artifacts: None,
+            inputs: None,
+            checksums: false,
+            flock: None,
+            singleton_key: None,
+            coalesce_key: None,
+            output_diff: None,
+            io_priority: None,
+            nice: None,
+            cpu_affinity: None,
+            rlimits: Rlimits::default(),
+            uid: None,
+            gid: None,
+            umask: None,
+            detach_session: false,
+            affinity_key: None,
+            serial_key: None,
+            concurrency_key: None,
+            priority: 0,
+            start_at: None,
+            queue_ttl: None,
+            backend: None,
+            force_color: false,
+            stdin: None,
+            success_codes: None,
+            allow_failure: false,
+            check_exit: false,
+            labels: BTreeMap::new(),
         }
     }
 
+    /// 反序列化时缺省字段的取值，与 [`new`](Self::new) 的默认值一致
+    #[cfg(feature = "serde")]
+    fn serde_default() -> Self {
+        Self::new("", Vec::new())
+    }
+
     /// # 设置任务的工作目录
     ///
     /// 将命令的工作目录设置为给定路径，返回修改后的 `CommandConfig`，便于链式调用。
@@ -337,8 +593,42 @@ impl CommandConfig {
     ///     .with_working_dir("/tmp");
     /// assert_eq!(cmd.working_dir().unwrap(), "/tmp".to_string());
     /// ```
-    pub fn with_working_dir(mut self, dir: &str) -> Self {
-        self.working_dir = Some(dir.to_string());
+    pub fn with_working_dir(self, dir: &str) -> Self {
+        self.with_working_dir_path(dir)
+    }
+
+    /// # 以路径设置任务的工作目录
+    ///
+    /// 与 [`with_working_dir`](Self::with_working_dir) 相同，但接受 `PathBuf`、`&Path`、`OsString` 等，
+    /// 路径可以不是 UTF-8。
+    pub fn with_working_dir_path(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(dir.into());
+        self
+    }
+
+    /// # 追加一个参数
+    ///
+    /// 接受 `&str`、`String`、`OsString`、`&Path` 等，参数可以不是 UTF-8。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    /// use std::path::Path;
+    ///
+    /// let cmd = CommandConfig::new("cat", vec![]).with_arg(Path::new("data.bin"));
+    /// ```
+    pub fn with_arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// # 追加多个参数
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
         self
     }
 
@@ -363,21 +653,181 @@ impl CommandConfig {
         self
     }
 
+    /// # 设置超时终止的宽限期
+    ///
+    /// 超时后先向子进程发送 SIGTERM，给它 `grace` 时长清理资源，
+    /// 宽限期结束仍未退出时再发送 SIGKILL。未设置时超时立即发送 SIGKILL。
+    /// 非 Unix 平台没有可供清理的终止信号，超时始终立即终止子进程。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    /// use std::time::Duration;
+    ///
+    /// let cmd = CommandConfig::new("server", vec![])
+    ///     .with_timeout(Duration::from_secs(30))
+    ///     .with_kill_grace(Duration::from_secs(5));
+    /// ```
+    pub fn with_kill_grace(mut self, grace: Duration) -> Self {
+        self.kill_grace = Some(grace);
+        self
+    }
+
+    /// # 获取超时终止的宽限期
+    pub fn kill_grace(&self) -> Option<Duration> {
+        self.kill_grace
+    }
+
+    /// # 超时时终止整棵进程树
+    ///
+    /// 启用后子进程在 Unix 上作为新进程组的组长启动，超时时信号发送给整个进程组
+    /// 以及子进程的所有后代进程（见 [`process_util::kill_tree`](crate::process_util::kill_tree)），
+    /// `sh -c "sleep 100"` 之类由子进程再启动的进程不会在超时后继续运行。
+    /// Windows 上子进程启动时加入作业对象，超时时终止整个作业。
+    ///
+    /// 新进程组不接收终端发给前台进程组的信号（如 Ctrl-C），因此默认不启用。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    /// use std::time::Duration;
+    ///
+    /// let cmd = CommandConfig::new("sh", vec!["-c".to_string(), "make -j8".to_string()])
+    ///     .with_timeout(Duration::from_secs(600))
+    ///     .with_kill_tree(true);
+    /// ```
+    pub fn with_kill_tree(mut self, enabled: bool) -> Self {
+        self.kill_tree = enabled;
+        self
+    }
+
+    /// # 超时时是否终止整棵进程树
+    pub fn kill_tree(&self) -> bool {
+        self.kill_tree
+    }
+
     /// # 获取程序名
-    pub fn program(&self) -> &str {
-        &self.program
+    ///
+    /// 非 UTF-8 字符替换为 U+FFFD，需要原样的程序名时使用 [`program_os`](Self::program_os)。
+    pub fn program(&self) -> Cow<'_, str> {
+        self.program.to_string_lossy()
     }
 
     /// # 获取命令参数
-    pub fn args(&self) -> &[String] {
-        &self.args
+    ///
+    /// 非 UTF-8 字符替换为 U+FFFD，需要原样的参数时使用 [`args_os`](Self::args_os)。
+    pub fn args(&self) -> Vec<String> {
+        self.args
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
     }
 
     /// # 获取工作目录
+    ///
+    /// 路径不是合法 UTF-8 时返回 `None`，此时使用 [`working_dir_path`](Self::working_dir_path)。
     pub fn working_dir(&self) -> Option<&str> {
+        self.working_dir.as_deref().and_then(Path::to_str)
+    }
+
+    /// # 获取原样的程序名
+    pub fn program_os(&self) -> &OsStr {
+        &self.program
+    }
+
+    /// # 获取原样的命令参数
+    pub fn args_os(&self) -> &[OsString] {
+        &self.args
+    }
+
+    /// # 获取工作目录路径
+    pub fn working_dir_path(&self) -> Option<&Path> {
         self.working_dir.as_deref()
     }
 
+    /// # 检查命令能否启动
+    ///
+    /// 在提交到命令池之前检查工作目录存在，并且程序能在 `PATH` 中找到
+    /// （或路径指向可执行文件），避免拼错的程序名在工作线程中才失败。
+    /// 查找使用子进程将看到的 `PATH`，即考虑环境变量配置中的修改。
+    ///
+    /// 只检查启动前可以确定的条件，通过检查不保证程序执行成功。
+    ///
+    /// # 错误
+    /// - [`ExecuteError::WorkingDirInvalid`]：工作目录不存在或不是目录
+    /// - [`ExecuteError::ProgramNotFound`]：程序不在 `PATH` 中或路径不存在
+    /// - [`ExecuteError::PermissionDenied`]：程序存在但不是可执行文件
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("gti", vec!["status".to_string()]);
+    /// assert!(cmd.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), ExecuteError> {
+        if let Some(dir) = &self.working_dir
+            && !dir.is_dir()
+        {
+            return Err(ExecuteError::WorkingDirInvalid { dir: dir.clone() });
+        }
+        self.resolve_program().map(|_| ())
+    }
+
+    /// # 解析程序的完整路径
+    ///
+    /// 程序名包含路径分隔符时按路径检查（相对路径相对于工作目录），
+    /// 否则依次在 `PATH` 的各个目录中查找。错误与 [`validate`](Self::validate) 相同。
+    pub fn resolve_program(&self) -> Result<PathBuf, ExecuteError> {
+        let program = Path::new(&self.program);
+        let not_found = || ExecuteError::ProgramNotFound {
+            program: self.program().into_owned(),
+        };
+
+        if program.components().count() > 1 || program.is_absolute() {
+            let path = match &self.working_dir {
+                Some(dir) => dir.join(program),
+                None => program.to_path_buf(),
+            };
+            if !path.exists() {
+                return Err(not_found());
+            }
+            if !is_executable(&path) {
+                return Err(ExecuteError::PermissionDenied {
+                    program: self.program().into_owned(),
+                });
+            }
+            return Ok(path);
+        }
+
+        let search_path = self.child_path().ok_or_else(not_found)?;
+        std::env::split_paths(&search_path)
+            .map(|dir| dir.join(program))
+            .find(|candidate| is_executable(candidate))
+            .ok_or_else(not_found)
+    }
+
+    /// 子进程启动时的 `PATH`：环境变量配置中的设置优先，其次继承父进程
+    fn child_path(&self) -> Option<OsString> {
+        match self.env_config() {
+            Some(env) => match env.vars().get("PATH") {
+                Some(value) => value.as_ref().map(OsString::from),
+                None if env.inherit_parent() => std::env::var_os("PATH"),
+                None => None,
+            },
+            None => std::env::var_os("PATH"),
+        }
+    }
+
+    /// 用于日志和错误信息的完整命令行，程序和参数以空格分隔
+    pub(crate) fn command_line_lossy(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     /// # 获取超时时间
     pub fn timeout(&self) -> Option<Duration> {
         self.timeout
@@ -409,86 +859,1013 @@ impl CommandConfig {
         self.resource_limits.as_ref()
     }
 
-    /// # 设置重试策略
+    /// # 设置 I/O 优先级
     ///
-    /// 为该命令设置失败后的重试策略。
+    /// 在 Linux 上于子进程执行命令前调用 `ioprio_set`，效果等同于 `ionice -c <class> -n <level>`，
+    /// 避免 tar、rsync、备份这类磁盘密集的批处理命令影响主机上交互任务的 I/O 延迟。
+    /// 设置失败（例如无权限使用实时类）时命令启动失败。其他平台上忽略该设置。
     ///
     /// # 参数
-    /// - `policy`: 重试策略配置
+    /// - `class`: 调度类
+    /// - `level`: 类内优先级，0 最高、7 最低，超过 7 按 7 处理；空闲类忽略该值
     ///
     /// # 示例
     /// ```ignore
-    /// use execute::{CommandConfig, RetryPolicy, RetryStrategy};
-    /// use std::time::Duration;
+    /// use execute::{CommandConfig, IoPriorityClass};
     ///
-    /// let policy = RetryPolicy::new(3, RetryStrategy::FixedInterval(Duration::from_secs(1)));
-    /// let cmd = CommandConfig::new("curl", vec!["https://example.com".to_string()])
-    ///     .with_retry(policy);
+    /// let cmd = CommandConfig::new("tar", vec!["czf".to_string(), "backup.tgz".to_string(), "/srv".to_string()])
+    ///     .with_io_priority(IoPriorityClass::BestEffort, 7);
     /// ```
-    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
-        self.retry_policy = Some(policy);
+    pub fn with_io_priority(mut self, class: IoPriorityClass, level: u8) -> Self {
+        self.io_priority = Some(IoPriority {
+            class,
+            level: level.min(7),
+        });
         self
     }
 
-    /// # 获取重试策略
-    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
-        self.retry_policy.as_ref()
+    /// # 获取 I/O 优先级
+    pub fn io_priority(&self) -> Option<IoPriority> {
+        self.io_priority
+    }
+
+    /// # 设置调度优先级
+    ///
+    /// 在 Unix 上于子进程执行命令前调用 `setpriority`，效果等同于 `nice -n <value>`，
+    /// 使批处理任务让出 CPU 给对延迟敏感的任务。取值范围 -20（最高）到 19（最低），
+    /// 超出范围按边界处理；负值通常需要特权，设置失败时命令启动失败。
+    ///
+    /// 在 Windows 上映射为进程优先级类：15 及以上为 `IDLE`，1 到 14 为 `BELOW_NORMAL`，
+    /// 0 为 `NORMAL`，-1 到 -10 为 `ABOVE_NORMAL`，更低为 `HIGH`。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("make", vec!["-j8".to_string()])
+    ///     .with_nice(10);
+    /// ```
+    pub fn with_nice(mut self, nice: i32) -> Self {
+        self.nice = Some(nice.clamp(-20, 19));
+        self
+    }
+
+    /// # 获取调度优先级
+    pub fn nice(&self) -> Option<i32> {
+        self.nice
+    }
+
+    /// # 设置 CPU 亲和性
+    ///
+    /// 在 Linux 上于子进程执行命令前调用 `sched_setaffinity`，将子进程（及其后代）
+    /// 固定在 `cpus` 列出的 CPU 核心上，效果等同于 `taskset -c`。
+    /// 核心编号超出系统支持范围时启动失败；其他平台忽略此设置。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("encode", vec!["input.mp4".to_string()])
+    ///     .with_cpu_affinity(&[2, 3]);
+    /// ```
+    pub fn with_cpu_affinity(mut self, cpus: &[usize]) -> Self {
+        self.cpu_affinity = Some(cpus.to_vec());
+        self
+    }
+
+    /// # 获取 CPU 亲和性
+    pub fn cpu_affinity(&self) -> Option<&[usize]> {
+        self.cpu_affinity.as_deref()
+    }
+
+    /// # 设置内存硬上限
+    ///
+    /// 在 Unix 上于子进程执行命令前以 `setrlimit(RLIMIT_AS)` 限制虚拟地址空间，
+    /// 超出时内存分配失败，由子进程自行处理（通常异常退出）。
+    /// 与 [`ResourceLimits::max_memory`] 的轮询监控不同，该上限由内核强制执行。
+    /// 其他平台上忽略该设置。
+    ///
+    /// # 参数
+    /// - `bytes`: 地址空间上限（字节）
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("python3", vec!["untrusted.py".to_string()])
+    ///     .with_max_memory(256 * 1024 * 1024);
+    /// ```
+    pub fn with_max_memory(mut self, bytes: u64) -> Self {
+        self.rlimits.max_memory = Some(bytes);
+        self
+    }
+
+    /// # 设置 CPU 时间硬上限
+    ///
+    /// 在 Unix 上以 `setrlimit(RLIMIT_CPU)` 限制子进程消耗的 CPU 时间，
+    /// 超出时内核发送 `SIGXCPU` 终止子进程。上限按整秒向上取整，最少 1 秒。
+    /// 与超时不同，睡眠或等待 I/O 的时间不计入。其他平台上忽略该设置。
+    pub fn with_max_cpu_time(mut self, limit: Duration) -> Self {
+        self.rlimits.max_cpu_time = Some(limit);
+        self
+    }
+
+    /// # 设置打开文件数硬上限
+    ///
+    /// 在 Unix 上以 `setrlimit(RLIMIT_NOFILE)` 限制子进程可同时打开的文件描述符数量。
+    /// 其他平台上忽略该设置。
+    pub fn with_max_open_files(mut self, count: u64) -> Self {
+        self.rlimits.max_open_files = Some(count);
+        self
+    }
+
+    /// # 获取子进程资源上限
+    pub fn rlimits(&self) -> Rlimits {
+        self.rlimits
+    }
+
+    /// # 以指定用户身份运行
+    ///
+    /// 在 Unix 上于子进程执行命令前调用 `setuid`，用于以 root 运行的守护进程按任务降低权限。
+    /// 需要同时切换用户组时配合 [`with_gid`](Self::with_gid) 使用。
+    /// 切换失败（例如当前进程无权限）时命令启动失败。其他平台上忽略该设置。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("whoami", vec![])
+    ///     .with_uid(65534)
+    ///     .with_gid(65534);
+    /// ```
+    pub fn with_uid(mut self, uid: u32) -> Self {
+        self.uid = Some(uid);
+        self
+    }
+
+    /// # 以指定用户组身份运行
+    ///
+    /// 在 Unix 上于子进程执行命令前调用 `setgid`。其他平台上忽略该设置。
+    pub fn with_gid(mut self, gid: u32) -> Self {
+        self.gid = Some(gid);
+        self
+    }
+
+    /// # 获取运行用户 ID
+    pub fn uid(&self) -> Option<u32> {
+        self.uid
+    }
+
+    /// # 获取运行用户组 ID
+    pub fn gid(&self) -> Option<u32> {
+        self.gid
+    }
+
+    /// # 设置文件模式掩码
+    ///
+    /// 在 Unix 上于子进程执行命令前调用 `umask`，决定子进程新建文件和目录的默认权限，
+    /// 只取低 9 位权限位；其他平台忽略此设置。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// // 新建文件仅属主可读写
+    /// let cmd = CommandConfig::new("backup.sh", vec![]).with_umask(0o077);
+    /// ```
+    pub fn with_umask(mut self, umask: u32) -> Self {
+        self.umask = Some(umask & 0o777);
+        self
+    }
+
+    /// # 获取文件模式掩码
+    pub fn umask(&self) -> Option<u32> {
+        self.umask
+    }
+
+    /// # 在新会话中运行
+    ///
+    /// 在 Unix 上于子进程执行命令前调用 `setsid`，子进程成为新会话和新进程组的组长，
+    /// 脱离控制终端，不再收到终端挂断和 Ctrl-C 等信号，适合启动守护进程类的命令。
+    /// 新会话同时是新进程组，[`with_kill_tree`](Self::with_kill_tree) 照常生效。
+    /// 其他平台忽略此设置。
+    pub fn detach_session(mut self) -> Self {
+        self.detach_session = true;
+        self
+    }
+
+    /// # 是否在新会话中运行
+    pub fn is_session_detached(&self) -> bool {
+        self.detach_session
+    }
+
+    /// # 强制彩色输出
+    ///
+    /// 许多工具检测到输出是管道时会去掉颜色。启用后为子进程设置约定的环境变量
+    /// （`FORCE_COLOR=1`、`CLICOLOR_FORCE=1`，以及 `TERM=xterm-256color`），
+    /// 使捕获的输出保留 ANSI 颜色，便于在网页界面或终端中渲染。
+    /// 环境变量配置中显式设置或清除的同名变量优先。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("cargo", vec!["build".to_string()]).with_force_color(true);
+    /// ```
+    pub fn with_force_color(mut self, enabled: bool) -> Self {
+        self.force_color = enabled;
+        self
+    }
+
+    /// # 是否强制彩色输出
+    pub fn force_color(&self) -> bool {
+        self.force_color
+    }
+
+    /// # 设置写入 stdin 的内容
+    ///
+    /// 子进程启动后在后台线程中把 `input` 写入其 stdin，写完后关闭 stdin，
+    /// 子进程读到 EOF。子进程不读取全部输入就退出时，剩余输入被丢弃。
+    /// 未设置时子进程继承当前进程的 stdin。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("sort", vec![]).with_stdin(b"b\na\n".to_vec());
+    /// ```
+    pub fn with_stdin(mut self, input: Vec<u8>) -> Self {
+        self.stdin = Some(input);
+        self
+    }
+
+    /// # 设置写入 stdin 的文本
+    ///
+    /// 与 [`with_stdin`](Self::with_stdin) 相同，接受字符串。
+    pub fn with_stdin_str(self, input: &str) -> Self {
+        self.with_stdin(input.as_bytes().to_vec())
+    }
+
+    /// # 获取写入 stdin 的内容
+    pub fn stdin(&self) -> Option<&[u8]> {
+        self.stdin.as_deref()
+    }
+
+    /// # 设置视为成功的退出码
+    ///
+    /// 子进程的退出码不在 `codes` 中时（包括被信号终止），执行返回
+    /// [`ExecuteError::UnexpectedExit`] 而不是携带失败状态的 `Output`，并按重试策略重试。
+    /// 未设置时保持默认行为：任何退出码都作为 `Output` 正常返回。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// // grep 未匹配时退出码为 1，同样视为成功
+    /// let cmd = CommandConfig::new("grep", vec!["pattern".to_string(), "file".to_string()])
+    ///     .with_success_codes(&[0, 1]);
+    /// ```
+    pub fn with_success_codes(mut self, codes: &[i32]) -> Self {
+        self.success_codes = Some(codes.to_vec());
+        self
+    }
+
+    /// # 获取视为成功的退出码
+    pub fn success_codes(&self) -> Option<&[i32]> {
+        self.success_codes.as_deref()
+    }
+
+    /// # 把失败的退出状态作为错误返回
+    ///
+    /// 启用后，退出状态按 [`is_success`](Self::is_success) 判断为失败时，执行返回
+    /// [`ExecuteError::ExitStatus`] 而不是携带失败状态的 `Output`，调用方可以直接用 `?`
+    /// 传播。设置了 [`with_success_codes`](Self::with_success_codes) 时仍返回
+    /// [`ExecuteError::UnexpectedExit`]；[`allow_failure`](Self::allow_failure) 优先。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{CommandConfig, ExecuteError, execute_with_report};
+    ///
+    /// let cmd = CommandConfig::new("false", vec![]).with_check_exit(true);
+    /// assert!(matches!(
+    ///     execute_with_report(&cmd),
+    ///     Err(ExecuteError::ExitStatus { code: Some(1), .. })
+    /// ));
+    /// ```
+    pub fn with_check_exit(mut self, enabled: bool) -> Self {
+        self.check_exit = enabled;
+        self
+    }
+
+    /// # 是否把失败的退出状态作为错误返回
+    pub fn is_exit_checked(&self) -> bool {
+        self.check_exit
+    }
+
+    /// # 允许任务失败
+    ///
+    /// 任何退出状态都视为成功：忽略 [`with_success_codes`](Self::with_success_codes)，
+    /// 且管道中该阶段以非零退出码结束时不会中断管道。
+    pub fn allow_failure(mut self) -> Self {
+        self.allow_failure = true;
+        self
+    }
+
+    /// # 是否允许任务失败
+    pub fn is_failure_allowed(&self) -> bool {
+        self.allow_failure
+    }
+
+    /// # 按配置判断退出状态是否成功
+    ///
+    /// 依次考虑 [`allow_failure`](Self::allow_failure) 与
+    /// [`with_success_codes`](Self::with_success_codes)，都未设置时等同于 `status.success()`。
+    pub fn is_success(&self, status: &ExitStatus) -> bool {
+        if self.allow_failure {
+            return true;
+        }
+        match &self.success_codes {
+            Some(codes) => status.code().is_some_and(|code| codes.contains(&code)),
+            None => status.success(),
+        }
+    }
+
+    /// 按退出码规则检查输出，仅在设置了 `success_codes` 或启用了退出状态检查时才可能返回错误
+    pub(crate) fn check_exit(&self, output: Output) -> Result<Output, ExecuteError> {
+        if self.is_success(&output.status) {
+            return Ok(output);
+        }
+        if self.success_codes.is_some() {
+            return Err(ExecuteError::UnexpectedExit {
+                output: Box::new(output),
+            });
+        }
+        if self.check_exit {
+            return Err(ExecuteError::ExitStatus {
+                code: output.status.code(),
+                stdout: output.stdout,
+                stderr: output.stderr,
+            });
+        }
+        Ok(output)
+    }
+
+    /// # 设置重试策略
+    ///
+    /// 为该命令设置失败后的重试策略，重试规则见 [`RetryPolicy`]。
+    ///
+    /// # 参数
+    /// - `policy`: 重试策略配置
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{CommandConfig, RetryPolicy, RetryStrategy};
+    /// use std::time::Duration;
+    ///
+    /// let policy = RetryPolicy::new(3, RetryStrategy::FixedInterval(Duration::from_secs(1)));
+    /// let cmd = CommandConfig::new("curl", vec!["https://example.com".to_string()])
+    ///     .with_retry(policy);
+    /// ```
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// # 设置重试次数
+    ///
+    /// 命令失败或超时时自动重新执行，最多重试 `retries` 次（不含首次执行），
+    /// 重试规则与 [`with_retry`](Self::with_retry) 相同，见 [`RetryPolicy`]。
+    /// 未通过 [`with_retry_backoff`](Self::with_retry_backoff) 设置退避时，
+    /// 重试间隔从 100 毫秒开始按指数增长。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    /// use std::time::Duration;
+    ///
+    /// let cmd = CommandConfig::new("curl", vec!["https://example.com".to_string()])
+    ///     .with_retries(3)
+    ///     .with_retry_backoff(Duration::from_millis(500));
+    /// ```
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        let policy = self.retry_policy_mut();
+        policy.max_attempts = retries;
+        self
+    }
+
+    /// # 设置重试退避
+    ///
+    /// 第一次重试前等待 `initial`，之后每次翻倍，单次等待不超过 60 秒
+    /// （`initial` 更大时以 `initial` 为上限）。需要配合
+    /// [`with_retries`](Self::with_retries) 设置重试次数。
+    pub fn with_retry_backoff(mut self, initial: Duration) -> Self {
+        self.retry_policy_mut().strategy = RetryStrategy::ExponentialBackoff {
+            initial,
+            max: initial.max(RETRY_BACKOFF_MAX),
+            multiplier: 2.0,
+        };
+        self
+    }
+
+    /// # 获取重试策略
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    /// 获取重试策略，未设置时创建不重试、使用默认退避的策略
+    fn retry_policy_mut(&mut self) -> &mut RetryPolicy {
+        self.retry_policy.get_or_insert_with(|| {
+            RetryPolicy::new(
+                0,
+                RetryStrategy::ExponentialBackoff {
+                    initial: RETRY_BACKOFF_INITIAL,
+                    max: RETRY_BACKOFF_MAX,
+                    multiplier: 2.0,
+                },
+            )
+        })
+    }
+
+    /// # 设置细粒度超时配置
+    ///
+    /// 为该命令设置分离的启动超时和执行超时。
+    /// 这提供了比单一超时更精确的控制。
+    ///
+    /// # 参数
+    /// - `config`: 超时配置
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{CommandConfig, TimeoutConfig};
+    /// use std::time::Duration;
+    ///
+    /// let timeout_config = TimeoutConfig::new()
+    ///     .with_spawn_timeout(Duration::from_secs(5))
+    ///     .with_execution_timeout(Duration::from_secs(30));
+    /// let cmd = CommandConfig::new("sleep", vec!["10".to_string()])
+    ///     .with_timeouts(timeout_config);
+    /// ```
+    pub fn with_timeouts(mut self, config: TimeoutConfig) -> Self {
+        self.timeout_config = Some(config);
+        self
+    }
+
+    /// # 获取超时配置
+    pub fn timeout_config(&self) -> Option<&TimeoutConfig> {
+        self.timeout_config.as_ref()
+    }
+
+    /// # 设置环境变量配置
+    ///
+    /// 为该命令设置环境变量配置，包括设置、清除环境变量和控制继承行为。
+    ///
+    /// # 参数
+    /// - `env`: 环境变量配置
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{CommandConfig, EnvConfig};
+    ///
+    /// let env = EnvConfig::new()
+    ///     .set("PATH", "/usr/local/bin:/usr/bin")
+    ///     .set("HOME", "/home/user");
+    /// let cmd = CommandConfig::new("ls", vec!["-la".to_string()])
+    ///     .with_env(env);
+    /// ```
+    pub fn with_env(mut self, env: EnvConfig) -> Self {
+        self.env_config = Some(env);
+        self
+    }
+
+    /// # 获取环境变量配置
+    pub fn env_config(&self) -> Option<&EnvConfig> {
+        self.env_config.as_ref()
+    }
+
+    /// # 设置超时延长钩子
+    ///
+    /// 在超时即将触发时调用钩子，由钩子决定是否延长。
+    /// 仅在设置了 `timeout` 时生效。
+    ///
+    /// # 参数
+    /// - `config`: 超时延长配置
+    pub fn with_timeout_hook(mut self, config: TimeoutHookConfig) -> Self {
+        self.timeout_hook = Some(config);
+        self
+    }
+
+    /// # 获取超时延长配置
+    pub fn timeout_hook(&self) -> Option<&TimeoutHookConfig> {
+        self.timeout_hook.as_ref()
+    }
+
+    /// # 设置输出捕获模式
+    ///
+    /// 默认完整保留 stdout/stderr。使用 `CaptureMode::Tail(n)` 时
+    /// 每个输出流只保留最后 `n` 字节，适合只关心失败任务日志末尾的场景。
+    /// 使用 `CaptureMode::Timeline` 时额外记录每个输出块出现的时间，
+    /// 便于排查执行缓慢或卡住的命令。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{CaptureMode, CommandConfig};
+    ///
+    /// let cmd = CommandConfig::new("make", vec!["all".to_string()])
+    ///     .with_capture_mode(CaptureMode::Tail(64 * 1024));
+    /// ```
+    pub fn with_capture_mode(mut self, mode: CaptureMode) -> Self {
+        self.capture_mode = mode;
+        self
+    }
+
+    /// # 获取输出捕获模式
+    pub fn capture_mode(&self) -> CaptureMode {
+        self.capture_mode
+    }
+
+    /// # 设置输出去向
+    ///
+    /// 默认通过管道捕获 stdout/stderr。长时间运行且输出量大的命令可以改为
+    /// 继承父进程的输出、丢弃输出或追加写入日志文件，避免在内存中累积不会读取的输出。
+    /// 非捕获模式下返回的 stdout/stderr 为空，[`with_capture_mode`](Self::with_capture_mode)
+    /// 不再生效。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{CommandConfig, OutputMode};
+    ///
+    /// let cmd = CommandConfig::new("make", vec!["all".to_string()])
+    ///     .with_output_mode(OutputMode::ToFile("/var/log/build.log".into()));
+    /// ```
+    pub fn with_output_mode(mut self, mode: OutputMode) -> Self {
+        self.output_mode = mode;
+        self
+    }
+
+    /// # 获取输出去向
+    pub fn output_mode(&self) -> &OutputMode {
+        &self.output_mode
+    }
+
+    /// # 将 stdout 重定向到文件
+    ///
+    /// 子进程直接写入文件而不经过管道，进程结束或被终止后日志仍保留在磁盘上。
+    /// `append` 为 true 时追加写入，否则截断已有内容；文件不存在时创建。
+    /// 对 stdout 优先于 [`with_output_mode`](Self::with_output_mode)，返回的 stdout 为空。
+    /// stdout 和 stderr 指向同一路径时共用一个文件句柄，两路输出按写入顺序交错。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("long-job", vec![])
+    ///     .with_stdout_file("/var/log/job.out", true)
+    ///     .with_stderr_file("/var/log/job.err", true);
+    /// ```
+    pub fn with_stdout_file(mut self, path: impl Into<PathBuf>, append: bool) -> Self {
+        self.stdout_file = Some(OutputFile::new(path, append));
+        self
+    }
+
+    /// # 将 stderr 重定向到文件
+    ///
+    /// 与 [`with_stdout_file`](Self::with_stdout_file) 相同，作用于 stderr。
+    pub fn with_stderr_file(mut self, path: impl Into<PathBuf>, append: bool) -> Self {
+        self.stderr_file = Some(OutputFile::new(path, append));
+        self
+    }
+
+    /// # 获取 stdout 重定向的文件
+    pub fn stdout_file(&self) -> Option<&OutputFile> {
+        self.stdout_file.as_ref()
+    }
+
+    /// # 获取 stderr 重定向的文件
+    pub fn stderr_file(&self) -> Option<&OutputFile> {
+        self.stderr_file.as_ref()
+    }
+
+    /// # 使用托管的临时工作目录
+    ///
+    /// 执行前创建一个唯一的临时目录作为命令的工作目录，执行结束后删除。
+    /// 目录路径会记录在 `ExecutionReport::temp_workdir` 中。
+    /// 设置后会覆盖 `with_working_dir` 指定的目录。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("git", vec!["clone".to_string(), url])
+    ///     .with_temp_workdir();
+    /// ```
+    pub fn with_temp_workdir(self) -> Self {
+        self.with_temp_workdir_config(TempWorkdirConfig::new())
+    }
+
+    /// # 使用指定配置的托管临时工作目录
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{CommandConfig, TempWorkdirConfig};
+    ///
+    /// // 任务失败时保留目录，便于排查
+    /// let cmd = CommandConfig::new("make", vec![])
+    ///     .with_temp_workdir_config(TempWorkdirConfig::new().with_keep_on_failure(true));
+    /// ```
+    pub fn with_temp_workdir_config(mut self, config: TempWorkdirConfig) -> Self {
+        self.temp_workdir = Some(config);
+        self
+    }
+
+    /// # 获取临时工作目录配置
+    pub fn temp_workdir(&self) -> Option<&TempWorkdirConfig> {
+        self.temp_workdir.as_ref()
+    }
+
+    /// # 声明输出产物
+    ///
+    /// 命令结束后按模式在工作目录中查找产物文件，复制或移动到目标目录，
+    /// 并在 `ExecutionReport` 中记录收集到的产物及未找到的模式。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{ArtifactConfig, CommandConfig};
+    ///
+    /// let cmd = CommandConfig::new("make", vec!["dist".to_string()])
+    ///     .with_temp_workdir()
+    ///     .with_artifacts(
+    ///         ArtifactConfig::new()
+    ///             .with_pattern("dist/*.tar.gz")
+    ///             .with_pattern("report.xml")
+    ///             .copy_to("/srv/artifacts/build-42"),
+    ///     );
+    /// ```
+    pub fn with_artifacts(mut self, artifacts: ArtifactConfig) -> Self {
+        self.artifacts = Some(artifacts);
+        self
+    }
+
+    /// # 获取产物声明
+    pub fn artifacts(&self) -> Option<&ArtifactConfig> {
+        self.artifacts.as_ref()
+    }
+
+    /// # 声明输入文件
+    ///
+    /// 在启动子进程之前把输入文件写入工作目录，使任务可以携带自己的数据，
+    /// 而不依赖执行主机上已存在的本地路径。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{CommandConfig, InputConfig};
+    ///
+    /// let cmd = CommandConfig::new("python3", vec!["job.py".to_string()])
+    ///     .with_temp_workdir()
+    ///     .with_inputs(
+    ///         InputConfig::new()
+    ///             .with_bytes("job.py", script_bytes)
+    ///             .with_file("data/input.csv", "/shared/input.csv"),
+    ///     );
+    /// ```
+    pub fn with_inputs(mut self, inputs: InputConfig) -> Self {
+        self.inputs = Some(inputs);
+        self
+    }
+
+    /// # 获取输入文件声明
+    pub fn inputs(&self) -> Option<&InputConfig> {
+        self.inputs.as_ref()
+    }
+
+    /// # 启用输出校验和
+    ///
+    /// 启用后在 `ExecutionReport` 中记录捕获的 stdout 和收集到的产物文件的 SHA-256，
+    /// 供下游系统校验和去重，无需重新读取输出。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::{CommandConfig, execute_with_report};
+    ///
+    /// let cmd = CommandConfig::new("echo", vec!["hi".to_string()]).with_checksums(true);
+    /// let report = execute_with_report(&cmd)?;
+    /// println!("stdout sha256 = {:?}", report.stdout_sha256);
+    /// ```
+    pub fn with_checksums(mut self, enabled: bool) -> Self {
+        self.checksums = enabled;
+        self
+    }
+
+    /// # 是否启用输出校验和
+    pub fn checksums(&self) -> bool {
+        self.checksums
+    }
+
+    /// # 设置跨进程文件锁
+    ///
+    /// 启动子进程之前对指定文件加排他的咨询锁（不存在时创建），命令结束后释放。
+    /// 锁在多个独立进程乃至共享文件系统的多台主机之间生效，
+    /// 可用于串行化同一命令的执行，而不仅限于单个命令池内部。
+    ///
+    /// 加锁会一直阻塞直到获得锁，等待时间不计入命令超时。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("backup.sh", vec![])
+    ///     .with_flock("/var/lock/backup.lock");
+    /// ```
+    pub fn with_flock(mut self, path: impl Into<PathBuf>) -> Self {
+        self.flock = Some(path.into());
+        self
+    }
+
+    /// # 获取文件锁路径
+    pub fn flock(&self) -> Option<&Path> {
+        self.flock.as_deref()
+    }
+
+    /// # 设置单例键
+    ///
+    /// 命令池执行该任务时，如果池中（包括父池和子池）已有相同键的任务正在运行，
+    /// 则不再执行，任务状态变为 [`TaskState::Skipped`](crate::TaskState::Skipped)，
+    /// 结果为 [`ExecuteError::Skipped`](crate::ExecuteError::Skipped)。
+    /// 可防止周期性任务（如备份）在上一次运行超出间隔时重叠执行。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("backup.sh", vec![]).with_singleton_key("nightly-backup");
+    /// ```
+    pub fn with_singleton_key(mut self, key: &str) -> Self {
+        self.singleton_key = Some(key.to_string());
+        self
+    }
+
+    /// # 获取单例键
+    pub fn singleton_key(&self) -> Option<&str> {
+        self.singleton_key.as_deref()
+    }
+
+    /// # 设置亲和键
+    ///
+    /// 命令池把相同亲和键的任务总是分派给同一个工作线程，
+    /// [`ProcessPool::execute_with_affinity`](crate::ProcessPool::execute_with_affinity)
+    /// 按同样的方式选择常驻工作进程。适合工作者按键保留预热状态的场景，
+    /// 如已检出的仓库或已加载的模型。
+    ///
+    /// 键到工作线程的映射由键的哈希和工作线程数决定；目标工作线程忙碌时，
+    /// 任务在队列中等待该线程，即使其他线程空闲。由于工作线程逐个执行任务，
+    /// 相同亲和键的任务不会并发执行（看门狗替补线程在被替换的线程结束前不取亲和键任务），
+    /// 不同键的任务仍可在其他线程上同时执行，可用于按仓库串行执行 git 命令等场景。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("git", vec!["pull".to_string()])
+    ///     .with_working_dir("/srv/checkouts/repo-a")
+    ///     .with_affinity_key("repo-a");
+    /// ```
+    pub fn with_affinity_key(mut self, key: &str) -> Self {
+        self.affinity_key = Some(key.to_string());
+        self
+    }
+
+    /// # 获取亲和键
+    pub fn affinity_key(&self) -> Option<&str> {
+        self.affinity_key.as_deref()
+    }
+
+    /// # 设置串行键
+    ///
+    /// 命令池保证相同串行键的任务严格逐个执行，并按提交顺序执行；
+    /// 不同键以及没有串行键的任务照常并发。适合“同一客户的所有命令按顺序执行”
+    /// 这类按实体排序的需求。
+    ///
+    /// 同键的前一个任务结束（包括失败、超时和取消）后，下一个任务才会出队。
+    /// 串行键只在单个命令池内生效，不跨父池和子池。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("sync-customer.sh", vec!["42".to_string()])
+    ///     .with_serial_key("customer-42");
+    /// ```
+    pub fn with_serial_key(mut self, key: &str) -> Self {
+        self.serial_key = Some(key.to_string());
+        self
+    }
+
+    /// # 获取串行键
+    pub fn serial_key(&self) -> Option<&str> {
+        self.serial_key.as_deref()
+    }
+
+    /// # 设置并发键
+    ///
+    /// 命令池通过 [`CommandPoolBuilder::with_concurrency_key_limit`](crate::CommandPoolBuilder::with_concurrency_key_limit)
+    /// 限制同一并发键的任务同时执行的数量，例如同一主机上最多 2 个命令，
+    /// 而命令池整体仍按工作线程数并发。达到上限时工作线程跳过这些任务，继续执行其他任务。
+    /// 没有配置上限的键不受限制。上限为 1 时相当于不保证顺序的串行键。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("pg_dump", vec!["orders".to_string()])
+    ///     .with_concurrency_key("host:db1");
+    /// ```
+    pub fn with_concurrency_key(mut self, key: &str) -> Self {
+        self.concurrency_key = Some(key.to_string());
+        self
+    }
+
+    /// # 获取并发键
+    pub fn concurrency_key(&self) -> Option<&str> {
+        self.concurrency_key.as_deref()
+    }
+
+    /// # 设置调度优先级
+    ///
+    /// 命令池优先取出优先级高的排队任务，数值越大越先执行，默认 0；
+    /// 相同优先级的任务按提交顺序执行。优先级只影响出队顺序，不会中断正在执行的任务，
+    /// 也会改变同一串行键下不同优先级任务的先后。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// // 交互请求排在批量任务之前
+    /// let cmd = CommandConfig::new("render-preview", vec![]).with_priority(200);
+    /// ```
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// # 获取调度优先级
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+
+    /// # 延迟执行
+    ///
+    /// 命令池在 `delay` 之后（从调用时起计算）才会取出该任务，在此之前任务留在队列中，
+    /// 不占用工作线程。等同于 `with_start_at(SystemTime::now() + delay)`。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    /// use std::time::Duration;
+    ///
+    /// let cmd = CommandConfig::new("send-reminder", vec![])
+    ///     .with_start_after(Duration::from_secs(300));
+    /// ```
+    pub fn with_start_after(self, delay: Duration) -> Self {
+        self.with_start_at(SystemTime::now() + delay)
+    }
+
+    /// # 在指定时间执行
+    ///
+    /// 命令池在 `at` 到达之前不会取出该任务；`at` 已经过去时任务照常排队。
+    /// 命令池关闭时尚未到期的任务不再执行。
+    pub fn with_start_at(mut self, at: SystemTime) -> Self {
+        self.start_at = Some(at);
+        self
+    }
+
+    /// # 获取计划执行时间
+    pub fn start_at(&self) -> Option<SystemTime> {
+        self.start_at
+    }
+
+    /// 距离计划执行时间的剩余时长，已到期或未设置时返回 None
+    pub(crate) fn start_delay(&self, now: SystemTime) -> Option<Duration> {
+        self.start_at
+            .and_then(|at| at.duration_since(now).ok())
+            .filter(|delay| !delay.is_zero())
+    }
+
+    /// # 设置队列存活时间
+    ///
+    /// 任务在队列中等待超过 `ttl` 后，命令池取出它时不再执行，结果为
+    /// [`ExecuteError::Expired`](crate::ExecuteError::Expired)。等待时间从提交时起计算；
+    /// 设置了计划执行时间的任务从计划时间起计算。适用于过时即无意义的任务，如健康检查。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    /// use std::time::Duration;
+    ///
+    /// let cmd = CommandConfig::new("curl", vec!["-f".to_string(), "http://localhost/health".to_string()])
+    ///     .with_queue_ttl(Duration::from_secs(30));
+    /// ```
+    pub fn with_queue_ttl(mut self, ttl: Duration) -> Self {
+        self.queue_ttl = Some(ttl);
+        self
+    }
+
+    /// # 获取队列存活时间
+    pub fn queue_ttl(&self) -> Option<Duration> {
+        self.queue_ttl
+    }
+
+    /// # 选择执行后端
+    ///
+    /// 由 [`RoutingBackend`](crate::RoutingBackend) 按名称把任务分派到对应的后端；
+    /// 命令池按 [`CommandPoolBuilder::with_backend_limit`](crate::CommandPoolBuilder::with_backend_limit)
+    /// 限制同一后端同时执行的任务数。未选择后端的任务使用默认后端，不受后端限制。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("build.sh", vec![]).with_backend("docker");
+    /// ```
+    pub fn with_backend(mut self, name: &str) -> Self {
+        self.backend = Some(name.to_string());
+        self
+    }
+
+    /// # 获取选择的执行后端名称
+    pub fn backend(&self) -> Option<&str> {
+        self.backend.as_deref()
+    }
+
+    /// # 设置合并键
+    ///
+    /// 提交到命令池时，如果已有相同合并键的任务在等待或执行，则不再重复执行，
+    /// 而是等待该任务完成并收到相同的结果。适合缓存刷新这类由多个生产者
+    /// 同时触发、结果可以共享的命令。
+    ///
+    /// 首个任务完成后再提交的同键任务会重新执行。首个任务被取消时，
+    /// 合并到它的任务同样收到取消错误。
+    ///
+    /// # 示例
+    /// ```ignore
+    /// use execute::CommandConfig;
+    ///
+    /// let cmd = CommandConfig::new("refresh-cache.sh", vec![]).with_coalesce_key("cache");
+    /// ```
+    pub fn with_coalesce_key(mut self, key: &str) -> Self {
+        self.coalesce_key = Some(key.to_string());
+        self
+    }
+
+    /// # 获取合并键
+    pub fn coalesce_key(&self) -> Option<&str> {
+        self.coalesce_key.as_deref()
     }
 
-    /// # 设置细粒度超时配置
-    ///
-    /// 为该命令设置分离的启动超时和执行超时。
-    /// 这提供了比单一超时更精确的控制。
+    /// # 添加标签
     ///
-    /// # 参数
-    /// - `config`: 超时配置
+    /// 标签只用于展示和排查问题（如 [`CommandPool::snapshot`](crate::CommandPool::snapshot)），
+    /// 不影响执行和调度。同名标签后设置的值覆盖先设置的值。
     ///
     /// # 示例
     /// ```ignore
-    /// use execute::{CommandConfig, TimeoutConfig};
-    /// use std::time::Duration;
+    /// use execute::CommandConfig;
     ///
-    /// let timeout_config = TimeoutConfig::new()
-    ///     .with_spawn_timeout(Duration::from_secs(5))
-    ///     .with_execution_timeout(Duration::from_secs(30));
-    /// let cmd = CommandConfig::new("sleep", vec!["10".to_string()])
-    ///     .with_timeouts(timeout_config);
+    /// let cmd = CommandConfig::new("git", vec!["fetch".to_string()])
+    ///     .with_label("repo", "execute")
+    ///     .with_label("trigger", "webhook");
     /// ```
-    pub fn with_timeouts(mut self, config: TimeoutConfig) -> Self {
-        self.timeout_config = Some(config);
+    pub fn with_label(mut self, key: &str, value: &str) -> Self {
+        self.labels.insert(key.to_string(), value.to_string());
         self
     }
 
-    /// # 获取超时配置
-    pub fn timeout_config(&self) -> Option<&TimeoutConfig> {
-        self.timeout_config.as_ref()
+    /// # 获取标签
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
     }
 
-    /// # 设置环境变量配置
+    /// # 启用输出对比
     ///
-    /// 为该命令设置环境变量配置，包括设置、清除环境变量和控制继承行为。
+    /// 命令池按键保留同一任务上一次运行的 stdout 哈希（需要差异时同时保留内容），
+    /// 并在新结果的 `ExecutionReport::output_change` 中标记输出是否发生变化。
+    /// 适合周期性检查类任务，只在输出变化时触发后续处理。
     ///
-    /// # 参数
-    /// - `env`: 环境变量配置
+    /// 只有经由命令池执行并成功获得输出的任务才会参与对比。
     ///
     /// # 示例
     /// ```ignore
-    /// use execute::{CommandConfig, EnvConfig};
+    /// use execute::{CommandConfig, OutputDiffConfig};
     ///
-    /// let env = EnvConfig::new()
-    ///     .set("PATH", "/usr/local/bin:/usr/bin")
-    ///     .set("HOME", "/home/user");
-    /// let cmd = CommandConfig::new("ls", vec!["-la".to_string()])
-    ///     .with_env(env);
+    /// let cmd = CommandConfig::new("df", vec!["-h".to_string()])
+    ///     .with_output_diff(OutputDiffConfig::new("disk-usage").with_unified_diff(true));
     /// ```
-    pub fn with_env(mut self, env: EnvConfig) -> Self {
-        self.env_config = Some(env);
+    pub fn with_output_diff(mut self, diff: OutputDiffConfig) -> Self {
+        self.output_diff = Some(diff);
         self
     }
 
-    /// # 获取环境变量配置
-    pub fn env_config(&self) -> Option<&EnvConfig> {
-        self.env_config.as_ref()
+    /// # 获取输出对比配置
+    pub fn output_diff(&self) -> Option<&OutputDiffConfig> {
+        self.output_diff.as_ref()
     }
 }
 
@@ -504,8 +1881,6 @@ pub struct PoolConfig {
     pub queue_capacity: Option<usize>,
     /// 默认超时时间
     pub default_timeout: Option<Duration>,
-    /// 轮询间隔
-    pub poll_interval: Duration,
 }
 
 impl PoolConfig {
@@ -520,7 +1895,6 @@ impl PoolConfig {
     /// let config = PoolConfig::builder()
     ///     .thread_count(4)
     ///     .queue_capacity(100)
-    ///     .poll_interval(Duration::from_millis(100))
     ///     .build()
     ///     .unwrap();
     /// ```
@@ -544,7 +1918,6 @@ impl PoolConfig {
 ///     .thread_count(4)
 ///     .queue_capacity(100)
 ///     .default_timeout(Duration::from_secs(30))
-///     .poll_interval(Duration::from_millis(100))
 ///     .build()
 ///     .unwrap();
 /// ```
@@ -553,7 +1926,6 @@ pub struct PoolConfigBuilder {
     thread_count: Option<usize>,
     queue_capacity: Option<usize>,
     default_timeout: Option<Duration>,
-    poll_interval: Option<Duration>,
 }
 
 impl PoolConfigBuilder {
@@ -620,26 +1992,6 @@ impl PoolConfigBuilder {
         self
     }
 
-    /// 设置轮询间隔
-    ///
-    /// # 参数
-    ///
-    /// * `interval` - 轮询间隔，必须为正数
-    ///
-    /// # 示例
-    ///
-    /// ```ignore
-    /// use execute::config::PoolConfigBuilder;
-    /// use std::time::Duration;
-    ///
-    /// let builder = PoolConfigBuilder::new()
-    ///     .poll_interval(Duration::from_millis(100));
-    /// ```
-    pub fn poll_interval(mut self, interval: Duration) -> Self {
-        self.poll_interval = Some(interval);
-        self
-    }
-
     /// 构建并验证配置
     ///
     /// 验证所有配置参数，如果有任何参数无效则返回错误。
@@ -656,7 +2008,6 @@ impl PoolConfigBuilder {
     /// * `ConfigError::ThreadCountExceedsLimit` - 线程数超过系统限制
     /// * `ConfigError::InvalidQueueCapacity` - 队列容量 < 1
     /// * `ConfigError::InvalidTimeout` - 超时时间 <= 0
-    /// * `ConfigError::InvalidPollInterval` - 轮询间隔 <= 0
     ///
     /// # 示例
     ///
@@ -704,21 +2055,115 @@ impl PoolConfigBuilder {
             return Err(ConfigError::InvalidTimeout(timeout));
         }
 
-        // 验证轮询间隔
-        let poll_interval = self.poll_interval.unwrap_or(Duration::from_millis(100));
-        if poll_interval.is_zero() {
-            return Err(ConfigError::InvalidPollInterval(poll_interval));
-        }
-
         Ok(PoolConfig {
             thread_count,
             queue_capacity: self.queue_capacity,
             default_timeout: self.default_timeout,
-            poll_interval,
         })
     }
 }
 
+/// 卡住工作线程看门狗配置
+///
+/// 工作线程执行单个任务的时间超过任务超时（包括超时钩子允许的延长）
+/// 再加上宽限期时视为卡住，例如阻塞在不可中断的系统调用中，或自定义执行器没有返回。
+/// 设置 `max_runtime` 后，运行超过该时长的任务无论超时如何都视为卡住。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandPool, WatchdogConfig};
+/// use std::time::Duration;
+///
+/// let pool = CommandPool::builder()
+///     .with_watchdog(
+///         WatchdogConfig::new(Duration::from_secs(30))
+///             .with_max_runtime(Duration::from_secs(3600))
+///             .with_replacement(true),
+///     )
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WatchdogConfig {
+    /// 超过任务超时多久后视为卡住
+    pub grace: Duration,
+    /// 任务运行多久后无论超时如何都视为卡住（None 表示只按超时判断）
+    pub max_runtime: Option<Duration>,
+    /// 检查间隔
+    pub check_interval: Duration,
+    /// 发现卡住的工作线程时启动替补工作线程，恢复命令池的处理能力
+    pub replace_stuck: bool,
+}
+
+impl WatchdogConfig {
+    /// 创建看门狗配置
+    ///
+    /// 默认每秒检查一次，只按任务超时判断，不启动替补工作线程。
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            grace,
+            max_runtime: None,
+            check_interval: Duration::from_secs(1),
+            replace_stuck: false,
+        }
+    }
+
+    /// 设置任务允许的最长运行时间，未设置超时的任务也按此检测
+    pub fn with_max_runtime(mut self, max_runtime: Duration) -> Self {
+        self.max_runtime = Some(max_runtime);
+        self
+    }
+
+    /// 设置检查间隔
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// 设置是否为卡住的工作线程启动替补
+    ///
+    /// 替补线程沿用被替换线程的序号（亲和键路由不变），被替换的线程在
+    /// 当前任务最终结束后退出。被替换的线程结束之前，替补线程只执行没有亲和键的任务，
+    /// 相同亲和键的任务仍不会并发执行。
+    pub fn with_replacement(mut self, enabled: bool) -> Self {
+        self.replace_stuck = enabled;
+        self
+    }
+
+    /// 任务被视为卡住之前允许的执行时间
+    pub(crate) fn threshold(&self, config: &CommandConfig) -> Option<Duration> {
+        let timeout = config
+            .timeout
+            .or_else(|| config.timeout_config().and_then(|t| t.execution_timeout));
+        let by_timeout = timeout.map(|timeout| {
+            let per_attempt = timeout
+                + config
+                    .timeout_hook()
+                    .map_or(Duration::ZERO, |hook| hook.max_extension);
+            per_attempt + self.grace
+        });
+        match (by_timeout, self.max_runtime) {
+            (Some(limit), Some(max)) => Some(limit.min(max)),
+            (limit, max) => limit.or(max),
+        }
+    }
+}
+
+/// 关闭模式
+///
+/// 决定 [`CommandPool::shutdown_with_mode`](crate::CommandPool::shutdown_with_mode)
+/// 如何处理队列中和正在执行的任务。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownMode {
+    /// 停止接受新任务，执行完队列中的所有任务后关闭
+    Drain,
+    /// 停止接受新任务，取消队列中的任务，等待正在执行的任务完成
+    Finish,
+    /// 停止接受新任务，取消队列中的任务，并终止正在执行的子进程
+    Abort,
+}
+
 /// 关闭配置
 ///
 /// 配置命令池的优雅关闭行为。
@@ -824,6 +2269,7 @@ pub enum ShutdownState {
 ///     .with_execution_timeout(Duration::from_secs(30));
 /// ```
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeoutConfig {
     /// 命令启动超时
     ///
@@ -905,6 +2351,394 @@ impl TimeoutConfig {
     }
 }
 
+/// 输出捕获模式
+///
+/// 控制每个任务的 stdout/stderr 在内存中保留多少。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CaptureMode {
+    /// 完整保留所有输出
+    #[default]
+    Full,
+    /// 每个输出流只保留最后 N 字节（环形缓冲区）
+    Tail(usize),
+    /// 完整保留输出，并按到达顺序记录每个输出块的时间和来源流
+    ///
+    /// 合并后的时间线见 [`ExecutionReport::timeline`](crate::ExecutionReport::timeline)。
+    Timeline,
+}
+
+/// 子进程 stdout/stderr 的去向
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutputMode {
+    /// 通过管道捕获，按 [`CaptureMode`] 保留在内存中
+    #[default]
+    Capture,
+    /// 继承父进程的 stdout/stderr，直接输出到终端
+    Inherit,
+    /// 丢弃输出（重定向到空设备）
+    Discard,
+    /// stdout 和 stderr 追加写入同一个文件，文件不存在时创建
+    ToFile(PathBuf),
+}
+
+/// stdout 或 stderr 重定向的目标文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutputFile {
+    /// 文件路径，相对路径相对于当前进程的工作目录
+    pub path: PathBuf,
+    /// 追加写入（false 表示打开时截断）
+    pub append: bool,
+}
+
+impl OutputFile {
+    /// 创建重定向目标
+    pub fn new(path: impl Into<PathBuf>, append: bool) -> Self {
+        Self {
+            path: path.into(),
+            append,
+        }
+    }
+
+    /// 打开文件，不存在时创建
+    pub(crate) fn open(&self) -> std::io::Result<std::fs::File> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(self.append)
+            .truncate(!self.append)
+            .open(&self.path)
+    }
+}
+
+/// 托管临时工作目录配置
+///
+/// 控制临时目录的创建位置和任务结束后的清理行为。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TempWorkdirConfig {
+    /// 任务失败（非零退出、超时或执行错误）时保留目录
+    pub keep_on_failure: bool,
+    /// 在此目录下创建临时目录（None 表示系统临时目录）
+    pub base_dir: Option<PathBuf>,
+}
+
+impl TempWorkdirConfig {
+    /// 创建默认配置：在系统临时目录下创建，结束后总是删除
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置任务失败时是否保留目录
+    pub fn with_keep_on_failure(mut self, keep: bool) -> Self {
+        self.keep_on_failure = keep;
+        self
+    }
+
+    /// 设置创建临时目录的父目录
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+}
+
+/// 产物收集方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ArtifactAction {
+    /// 复制到目标目录，保留工作目录中的原文件
+    #[default]
+    Copy,
+    /// 移动到目标目录
+    Move,
+}
+
+/// 输出产物声明
+///
+/// 模式相对于命令的工作目录，支持 `*`、`?` 和 `**`。
+/// 未设置目标目录时只在报告中记录产物的路径和大小，不移动文件。
+/// 与临时工作目录一起使用时应设置目标目录，否则产物会随目录一同删除。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArtifactConfig {
+    /// 产物文件模式
+    pub patterns: Vec<String>,
+    /// 目标目录（保留产物的相对路径结构）
+    pub destination: Option<PathBuf>,
+    /// 收集方式
+    pub action: ArtifactAction,
+}
+
+impl ArtifactConfig {
+    /// 创建空的产物声明
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加产物模式
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.to_string());
+        self
+    }
+
+    /// 将产物复制到目标目录
+    pub fn copy_to(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.destination = Some(dir.into());
+        self.action = ArtifactAction::Copy;
+        self
+    }
+
+    /// 将产物移动到目标目录
+    pub fn move_to(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.destination = Some(dir.into());
+        self.action = ArtifactAction::Move;
+        self
+    }
+}
+
+/// I/O 调度类（对应 `ionice -c`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IoPriorityClass {
+    /// 实时类，优先于其他所有 I/O（需要 CAP_SYS_ADMIN）
+    RealTime,
+    /// 尽力而为类，普通进程的默认类
+    BestEffort,
+    /// 空闲类，仅在磁盘空闲时获得 I/O
+    Idle,
+}
+
+/// 子进程的 I/O 优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IoPriority {
+    /// 调度类
+    pub class: IoPriorityClass,
+    /// 类内优先级（0-7，0 最高）
+    pub level: u8,
+}
+
+/// 子进程的硬性资源上限
+///
+/// 在 Unix 上于子进程执行命令前通过 `setrlimit` 设置软、硬上限，子进程无法再提高。
+/// 未设置的项保持继承自父进程的值。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rlimits {
+    /// 地址空间上限（字节，`RLIMIT_AS`）
+    pub max_memory: Option<u64>,
+    /// CPU 时间上限（`RLIMIT_CPU`，按整秒向上取整）
+    pub max_cpu_time: Option<Duration>,
+    /// 打开文件数上限（`RLIMIT_NOFILE`）
+    pub max_open_files: Option<u64>,
+}
+
+impl Rlimits {
+    /// 是否设置了任意上限
+    pub fn is_empty(&self) -> bool {
+        self.max_memory.is_none() && self.max_cpu_time.is_none() && self.max_open_files.is_none()
+    }
+
+    /// CPU 时间上限的秒数（向上取整，最少 1 秒）
+    pub(crate) fn cpu_seconds(&self) -> Option<u64> {
+        self.max_cpu_time.map(|limit| {
+            let secs = limit.as_secs() + u64::from(limit.subsec_nanos() > 0);
+            secs.max(1)
+        })
+    }
+}
+
+/// 输出对比配置
+///
+/// 键标识“同一个任务”：相同键的多次运行之间比较 stdout。
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutputDiffConfig {
+    /// 任务的稳定键
+    pub key: String,
+    /// 输出变化时是否生成统一格式差异（需要保留上一次的完整输出）
+    pub unified_diff: bool,
+    /// 统一格式差异的上下文行数
+    pub context_lines: usize,
+}
+
+impl OutputDiffConfig {
+    /// 创建只比较哈希的配置
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            unified_diff: false,
+            context_lines: 3,
+        }
+    }
+
+    /// 设置输出变化时是否生成统一格式差异
+    pub fn with_unified_diff(mut self, enabled: bool) -> Self {
+        self.unified_diff = enabled;
+        self
+    }
+
+    /// 设置统一格式差异的上下文行数
+    pub fn with_context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+}
+
+/// 输入文件内容来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputSource {
+    /// 直接写入的字节内容
+    Bytes(Vec<u8>),
+    /// 从本地文件复制
+    File(PathBuf),
+}
+
+/// 需要写入工作目录的输入文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputFile {
+    /// 相对于工作目录的目标路径
+    pub path: PathBuf,
+    /// 内容来源
+    pub source: InputSource,
+}
+
+/// 输入文件声明
+///
+/// 目标路径必须是工作目录内的相对路径，不能包含 `..`。
+/// 设置 `cleanup` 后，任务结束时删除已写入的输入文件（临时工作目录会整体删除，无需设置）。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputConfig {
+    /// 输入文件列表
+    pub files: Vec<InputFile>,
+    /// 任务结束后删除写入的输入文件
+    pub cleanup: bool,
+}
+
+impl InputConfig {
+    /// 创建空的输入声明
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加以字节内容写入的输入文件
+    pub fn with_bytes(mut self, path: impl Into<PathBuf>, data: impl Into<Vec<u8>>) -> Self {
+        self.files.push(InputFile {
+            path: path.into(),
+            source: InputSource::Bytes(data.into()),
+        });
+        self
+    }
+
+    /// 添加从本地文件复制的输入文件
+    pub fn with_file(mut self, path: impl Into<PathBuf>, source: impl Into<PathBuf>) -> Self {
+        self.files.push(InputFile {
+            path: path.into(),
+            source: InputSource::File(source.into()),
+        });
+        self
+    }
+
+    /// 设置任务结束后是否删除输入文件
+    pub fn with_cleanup(mut self, cleanup: bool) -> Self {
+        self.cleanup = cleanup;
+        self
+    }
+}
+
+/// 超时延长配置
+///
+/// 为命令注册一个 [`TimeoutHook`]，在超时前 `lead_time` 时调用。
+/// 钩子可以授予延长，所有延长的总和不超过 `max_extension`，
+/// 从而保证任务的最长运行时间依然有界。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, TimeoutHookConfig};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let cmd = CommandConfig::new("./import.sh", vec![])
+///     .with_timeout(Duration::from_secs(60))
+///     .with_timeout_hook(
+///         TimeoutHookConfig::new(Arc::new(MyHook))
+///             .with_lead_time(Duration::from_secs(5))
+///             .with_max_extension(Duration::from_secs(120)),
+///     );
+/// ```
+#[derive(Clone)]
+pub struct TimeoutHookConfig {
+    /// 超时钩子
+    pub(crate) hook: Arc<dyn TimeoutHook>,
+    /// 在超时前多久调用钩子
+    pub(crate) lead_time: Duration,
+    /// 允许延长的总时长上限
+    pub(crate) max_extension: Duration,
+}
+
+impl TimeoutHookConfig {
+    /// 创建超时延长配置
+    ///
+    /// 默认在超时前 1 秒调用钩子，总延长上限为 60 秒。
+    pub fn new(hook: Arc<dyn TimeoutHook>) -> Self {
+        Self {
+            hook,
+            lead_time: Duration::from_secs(1),
+            max_extension: Duration::from_secs(60),
+        }
+    }
+
+    /// 设置在超时前多久调用钩子
+    pub fn with_lead_time(mut self, lead_time: Duration) -> Self {
+        self.lead_time = lead_time;
+        self
+    }
+
+    /// 设置允许延长的总时长上限
+    pub fn with_max_extension(mut self, max_extension: Duration) -> Self {
+        self.max_extension = max_extension;
+        self
+    }
+
+    /// 获取钩子
+    pub fn hook(&self) -> &Arc<dyn TimeoutHook> {
+        &self.hook
+    }
+
+    /// 获取提前调用时间
+    pub fn lead_time(&self) -> Duration {
+        self.lead_time
+    }
+
+    /// 获取延长上限
+    pub fn max_extension(&self) -> Duration {
+        self.max_extension
+    }
+}
+
+impl std::fmt::Debug for TimeoutHookConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimeoutHookConfig")
+            .field("lead_time", &self.lead_time)
+            .field("max_extension", &self.max_extension)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for TimeoutHookConfig {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.hook, &other.hook)
+            && self.lead_time == other.lead_time
+            && self.max_extension == other.max_extension
+    }
+}
+
 /// 环境变量配置
 ///
 /// 用于配置命令执行时的环境变量。
@@ -930,6 +2764,7 @@ impl TimeoutConfig {
 ///     .set("PATH", "/usr/bin");
 /// ```
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnvConfig {
     /// 环境变量映射
     ///
@@ -1075,6 +2910,107 @@ impl Default for EnvConfig {
     }
 }
 
+/// 命令池级别的任务默认值
+///
+/// 通过 [`CommandPoolBuilder::with_task_defaults`](crate::CommandPoolBuilder::with_task_defaults)
+/// 配置，提交到命令池的每个任务都会应用这些默认值，任务自身的设置优先：
+/// - `env`: 基础环境变量，任务未设置（或未清除）同名变量时生效
+/// - `working_dir`: 基础工作目录，任务未设置工作目录时生效
+/// - `path_prepend`: 添加到 `PATH` 前面的目录，作用于任务最终的 `PATH`
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandPool, TaskDefaults};
+///
+/// let pool = CommandPool::builder()
+///     .with_task_defaults(
+///         TaskDefaults::new()
+///             .with_env("RUST_LOG", "info")
+///             .with_working_dir("/srv/jobs")
+///             .with_path_prepend("/opt/tools/bin"),
+///     )
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaskDefaults {
+    /// 基础环境变量
+    pub env: HashMap<String, String>,
+    /// 基础工作目录
+    pub working_dir: Option<PathBuf>,
+    /// 添加到 `PATH` 前面的目录（按顺序）
+    pub path_prepend: Vec<String>,
+}
+
+impl TaskDefaults {
+    /// 创建空的默认值
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置基础环境变量
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    /// 设置基础工作目录
+    pub fn with_working_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(dir.into());
+        self
+    }
+
+    /// 在 `PATH` 前面添加目录
+    pub fn with_path_prepend(mut self, dir: &str) -> Self {
+        self.path_prepend.push(dir.to_string());
+        self
+    }
+
+    /// 是否未设置任何默认值
+    pub fn is_empty(&self) -> bool {
+        self.env.is_empty() && self.working_dir.is_none() && self.path_prepend.is_empty()
+    }
+
+    /// 将默认值应用到任务配置
+    pub(crate) fn apply(&self, config: &mut CommandConfig) {
+        if config.working_dir.is_none() {
+            config.working_dir = self.working_dir.clone();
+        }
+        if self.env.is_empty() && self.path_prepend.is_empty() {
+            return;
+        }
+
+        let mut env = config.env_config.take().unwrap_or_default();
+        for (key, value) in &self.env {
+            env.vars
+                .entry(key.clone())
+                .or_insert_with(|| Some(value.clone()));
+        }
+
+        if !self.path_prepend.is_empty() {
+            let base = match env.vars.get("PATH") {
+                Some(path) => path.clone(),
+                None if env.inherit_parent => std::env::var("PATH").ok(),
+                None => None,
+            };
+            let mut paths: Vec<std::path::PathBuf> =
+                self.path_prepend.iter().map(Into::into).collect();
+            if let Some(base) = base {
+                paths.extend(std::env::split_paths(&base));
+            }
+            if let Ok(joined) = std::env::join_paths(paths) {
+                env.vars.insert(
+                    "PATH".to_string(),
+                    Some(joined.to_string_lossy().into_owned()),
+                );
+            }
+        }
+
+        config.env_config = Some(env);
+    }
+}
+
 /// 获取系统线程限制
 ///
 /// 尝试从系统获取最大线程数限制。
@@ -0,0 +1,227 @@
+//! 带依赖关系的任务图
+//!
+//! 任务只有在所有前置任务成功后才提交到命令池，互不依赖的任务并行执行；
+//! 前置任务失败时，依赖它的任务（以及间接依赖的任务）不再执行。
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::config::CommandConfig;
+use crate::error::{ExecuteError, SubmitError};
+use crate::events::PoolEvent;
+use crate::pool::CommandPool;
+use crate::task_handle::{TaskHandle, TaskResult};
+
+/// 任务图中的节点标识
+///
+/// 由 [`TaskGraph::add`] 或 [`TaskGraph::add_after`] 返回，
+/// [`CommandPool::run_graph`] 的结果按 [`NodeId::index`] 排列。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// 节点在任务图中的序号（按添加顺序，从 0 开始）
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+struct GraphNode {
+    config: CommandConfig,
+    dependencies: Vec<usize>,
+}
+
+/// 任务图
+///
+/// 节点只能依赖已经添加的节点，因此任务图不会出现环。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, CommandPool, TaskGraph};
+///
+/// let mut graph = TaskGraph::new();
+/// let fetch = graph.add(CommandConfig::new("git", vec!["fetch".to_string()]));
+/// let build = graph.add_after(CommandConfig::new("make", vec![]), &[fetch]);
+/// let lint = graph.add_after(CommandConfig::new("make", vec!["lint".to_string()]), &[fetch]);
+/// graph.add_after(CommandConfig::new("make", vec!["package".to_string()]), &[build, lint]);
+///
+/// let pool = CommandPool::new();
+/// pool.start_executor();
+/// let results = pool.run_graph(graph).unwrap();
+/// ```
+#[derive(Default)]
+pub struct TaskGraph {
+    nodes: Vec<GraphNode>,
+}
+
+impl TaskGraph {
+    /// 创建空的任务图
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加没有前置任务的节点
+    pub fn add(&mut self, config: CommandConfig) -> NodeId {
+        self.add_after(config, &[])
+    }
+
+    /// 添加在 `dependencies` 全部成功后才执行的节点
+    ///
+    /// # Panics
+    ///
+    /// 依赖的节点不属于本任务图时 panic
+    pub fn add_after(&mut self, config: CommandConfig, dependencies: &[NodeId]) -> NodeId {
+        let id = self.nodes.len();
+        assert!(
+            dependencies.iter().all(|dep| dep.0 < id),
+            "dependency does not belong to this graph"
+        );
+        let mut dependencies: Vec<usize> = dependencies.iter().map(|dep| dep.0).collect();
+        dependencies.sort_unstable();
+        dependencies.dedup();
+        self.nodes.push(GraphNode {
+            config,
+            dependencies,
+        });
+        NodeId(id)
+    }
+
+    /// 节点数
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// 是否没有节点
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// 运行中的任务图状态
+struct GraphRun {
+    configs: Vec<Option<CommandConfig>>,
+    /// 每个节点尚未成功的前置任务数
+    pending: Vec<usize>,
+    dependents: Vec<Vec<usize>>,
+    results: Vec<Option<TaskResult>>,
+    ready: VecDeque<usize>,
+}
+
+impl GraphRun {
+    fn new(graph: TaskGraph) -> Self {
+        let count = graph.nodes.len();
+        let mut dependents = vec![Vec::new(); count];
+        let mut pending = Vec::with_capacity(count);
+        let mut configs = Vec::with_capacity(count);
+        let mut ready = VecDeque::new();
+        for (index, node) in graph.nodes.into_iter().enumerate() {
+            for &dep in &node.dependencies {
+                dependents[dep].push(index);
+            }
+            if node.dependencies.is_empty() {
+                ready.push_back(index);
+            }
+            pending.push(node.dependencies.len());
+            configs.push(Some(node.config));
+        }
+        Self {
+            configs,
+            pending,
+            dependents,
+            results: (0..count).map(|_| None).collect(),
+            ready,
+        }
+    }
+
+    /// 记录节点结果，成功时释放后继节点，失败时使所有后继节点失败
+    fn settle(&mut self, node: usize, succeeded: bool, result: TaskResult) {
+        self.results[node] = Some(result);
+        let mut failed = VecDeque::new();
+        for &next in &self.dependents[node] {
+            if succeeded {
+                self.pending[next] -= 1;
+                if self.pending[next] == 0 && self.results[next].is_none() {
+                    self.ready.push_back(next);
+                }
+            } else {
+                failed.push_back((next, node));
+            }
+        }
+        while let Some((next, cause)) = failed.pop_front() {
+            if self.results[next].is_some() {
+                continue;
+            }
+            self.configs[next] = None;
+            self.results[next] = Some(Err(ExecuteError::DependencyFailed(cause)));
+            failed.extend(self.dependents[next].iter().map(|&after| (after, next)));
+        }
+    }
+}
+
+impl CommandPool {
+    /// 按依赖关系执行任务图，阻塞直到所有节点结束
+    ///
+    /// 前置任务全部成功（按各自的成功退出码规则判断）后才提交节点，
+    /// 互不依赖的节点在共享工作线程上并行执行。前置任务失败、超时或被取消时，
+    /// 依赖它的节点不会执行，结果为 [`ExecuteError::DependencyFailed`]。
+    /// 返回的结果按节点添加顺序排列。
+    ///
+    /// 需要先启动执行器，否则会一直等待。
+    ///
+    /// # 错误
+    ///
+    /// 任一节点提交失败（如命令池正在关闭）时，取消已提交的任务并返回 [`SubmitError`]。
+    pub fn run_graph(&self, graph: TaskGraph) -> Result<Vec<TaskResult>, SubmitError> {
+        // 先订阅再提交，避免错过完成事件
+        let events = self.subscribe();
+        let mut run = GraphRun::new(graph);
+        let mut running: HashMap<u64, (usize, TaskHandle)> = HashMap::new();
+
+        loop {
+            while let Some(node) = run.ready.pop_front() {
+                let config = run.configs[node].clone().expect("node submitted twice");
+                match self.push_task(config) {
+                    Ok(handle) => {
+                        running.insert(handle.id(), (node, handle));
+                    }
+                    Err(e) => {
+                        for (_, handle) in running.values() {
+                            let _ = handle.cancel();
+                        }
+                        for (_, handle) in running.values() {
+                            let _ = handle.wait();
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+
+            if running.is_empty() {
+                break;
+            }
+
+            // 事件总线断开时（命令池已被丢弃）逐个等待剩余任务
+            let task_id = match events.recv() {
+                Ok(PoolEvent::TaskFinished { task_id, .. }) => task_id,
+                Ok(_) => continue,
+                Err(_) => *running.keys().next().unwrap(),
+            };
+            let Some((node, handle)) = running.remove(&task_id) else {
+                continue;
+            };
+            // 完成事件在结果发送前发布，这里的等待很短
+            let result = handle.wait();
+            let succeeded = match (&result, &run.configs[node]) {
+                (Ok(output), Some(config)) => config.is_success(&output.status),
+                _ => false,
+            };
+            run.settle(node, succeeded, result);
+        }
+
+        Ok(run
+            .results
+            .into_iter()
+            .map(|result| result.expect("every node settles"))
+            .collect())
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
@@ -33,6 +33,207 @@ pub enum ExecuteError {
     /// 包含任务 ID。
     #[error("task {0} was cancelled")]
     Cancelled(u64),
+
+    /// 任务被跳过
+    ///
+    /// 当单例任务执行时已有相同键的任务正在运行时返回。
+    /// 包含单例键。
+    #[error("task skipped: singleton key {0:?} is already running")]
+    Skipped(String),
+
+    /// 超出批量预算
+    ///
+    /// 当任务所在批次的累计资源消耗超过预算、任务因此被取消时返回。
+    /// 包含超出的预算项描述。
+    #[error("batch budget exceeded: {0}")]
+    BudgetExceeded(String),
+
+    /// 找不到要执行的程序
+    ///
+    /// 启动子进程时程序不存在（不在 `PATH` 中或路径错误）时返回。
+    #[error("program not found: {program}")]
+    ProgramNotFound {
+        /// 程序名或路径
+        program: String,
+    },
+
+    /// 无权执行程序
+    ///
+    /// 程序存在但不可执行（缺少执行权限或为目录等）时返回。
+    #[error("permission denied executing {program}")]
+    PermissionDenied {
+        /// 程序名或路径
+        program: String,
+    },
+
+    /// 工作目录无效
+    ///
+    /// 配置的工作目录不存在或不是目录时返回。
+    #[error("invalid working directory: {}", dir.display())]
+    WorkingDirInvalid {
+        /// 配置的工作目录
+        dir: PathBuf,
+    },
+
+    /// Pipeline 阶段失败
+    ///
+    /// 当 pipeline 的某个阶段启动失败、读写失败或以非零状态退出时返回，
+    /// 后续阶段不再执行。
+    #[error("pipeline stage {index} ({program}) failed: {source}")]
+    PipelineStage {
+        /// 失败阶段的序号（从 0 开始）
+        index: usize,
+        /// 失败阶段的程序
+        program: String,
+        /// 阶段的错误
+        source: Box<ExecuteError>,
+        /// 已运行阶段的输出（按阶段顺序）；阶段以非零状态退出时包含该阶段的输出
+        outputs: Vec<std::process::Output>,
+    },
+
+    /// 子进程以不被接受的退出状态结束
+    ///
+    /// 仅在通过 [`CommandConfig::with_success_codes`](crate::CommandConfig::with_success_codes)
+    /// 设置了视为成功的退出码时返回，携带子进程的完整输出。
+    #[error("command exited with unexpected status: {}", output.status)]
+    UnexpectedExit {
+        /// 子进程的输出
+        output: Box<std::process::Output>,
+    },
+
+    /// 子进程以失败的退出状态结束
+    ///
+    /// 仅在通过 [`CommandConfig::with_check_exit`](crate::CommandConfig::with_check_exit)
+    /// 或 [`execute_checked`](crate::execute_checked) 启用退出状态检查时返回。
+    #[error("command exited with {}", describe_exit_code(*code))]
+    ExitStatus {
+        /// 退出码，被信号终止时为 None
+        code: Option<i32>,
+        /// 捕获的 stdout
+        stdout: Vec<u8>,
+        /// 捕获的 stderr
+        stderr: Vec<u8>,
+    },
+
+    /// 任务选择的后端不存在
+    ///
+    /// 当 [`RoutingBackend`](crate::RoutingBackend) 中没有注册任务选择的后端名称时返回。
+    #[error("no backend registered as {0:?}")]
+    UnknownBackend(String),
+
+    /// 依赖的任务没有成功
+    ///
+    /// 任务图中前置任务失败、超时或被取消时，依赖它的任务不再执行并返回此错误。
+    /// 包含失败的前置任务在任务图中的序号。
+    #[error("dependency {0} did not succeed")]
+    DependencyFailed(usize),
+
+    /// 任务在队列中等待超过存活时间
+    ///
+    /// 通过 [`CommandConfig::with_queue_ttl`](crate::CommandConfig::with_queue_ttl)
+    /// 设置存活时间的任务出队时已过期，不再执行。包含设置的存活时间。
+    #[error("task expired after waiting longer than {0:?} in queue")]
+    Expired(Duration),
+}
+
+impl ExecuteError {
+    /// 复制错误，`Io` 错误保留种类和消息
+    ///
+    /// 用于将同一结果发送给多个任务句柄。
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            ExecuteError::Io(e) => ExecuteError::Io(std::io::Error::new(e.kind(), e.to_string())),
+            ExecuteError::Timeout(timeout) => ExecuteError::Timeout(*timeout),
+            ExecuteError::Child(msg) => ExecuteError::Child(msg.clone()),
+            ExecuteError::Cancelled(task_id) => ExecuteError::Cancelled(*task_id),
+            ExecuteError::Skipped(key) => ExecuteError::Skipped(key.clone()),
+            ExecuteError::BudgetExceeded(reason) => ExecuteError::BudgetExceeded(reason.clone()),
+            ExecuteError::ProgramNotFound { program } => ExecuteError::ProgramNotFound {
+                program: program.clone(),
+            },
+            ExecuteError::PermissionDenied { program } => ExecuteError::PermissionDenied {
+                program: program.clone(),
+            },
+            ExecuteError::WorkingDirInvalid { dir } => {
+                ExecuteError::WorkingDirInvalid { dir: dir.clone() }
+            }
+            ExecuteError::PipelineStage {
+                index,
+                program,
+                source,
+                outputs,
+            } => ExecuteError::PipelineStage {
+                index: *index,
+                program: program.clone(),
+                source: Box::new(source.duplicate()),
+                outputs: outputs.clone(),
+            },
+            ExecuteError::UnexpectedExit { output } => ExecuteError::UnexpectedExit {
+                output: output.clone(),
+            },
+            ExecuteError::ExitStatus {
+                code,
+                stdout,
+                stderr,
+            } => ExecuteError::ExitStatus {
+                code: *code,
+                stdout: stdout.clone(),
+                stderr: stderr.clone(),
+            },
+            ExecuteError::UnknownBackend(name) => ExecuteError::UnknownBackend(name.clone()),
+            ExecuteError::DependencyFailed(node) => ExecuteError::DependencyFailed(*node),
+            ExecuteError::Expired(ttl) => ExecuteError::Expired(*ttl),
+        }
+    }
+
+    /// 对启动子进程失败的 IO 错误分类
+    ///
+    /// 工作目录无效优先于程序不存在判断，因为两者都会以 `NotFound` 报告。
+    /// 无法归类的错误保留为 [`ExecuteError::Io`]。
+    pub(crate) fn spawn_failed(
+        error: std::io::Error,
+        program: &std::ffi::OsStr,
+        working_dir: Option<&Path>,
+    ) -> Self {
+        if let Some(dir) = working_dir
+            && !dir.is_dir()
+        {
+            return ExecuteError::WorkingDirInvalid {
+                dir: dir.to_path_buf(),
+            };
+        }
+        match error.kind() {
+            std::io::ErrorKind::NotFound => ExecuteError::ProgramNotFound {
+                program: program.to_string_lossy().into_owned(),
+            },
+            std::io::ErrorKind::PermissionDenied => ExecuteError::PermissionDenied {
+                program: program.to_string_lossy().into_owned(),
+            },
+            _ => ExecuteError::Io(error),
+        }
+    }
+
+    /// 附加任务上下文
+    pub fn with_context(self, context: ErrorContext) -> TaskError {
+        TaskError {
+            context: Box::new(context),
+            source: self,
+        }
+    }
+
+    /// 是否为重试也无法恢复的错误
+    ///
+    /// 程序不存在、无权执行、工作目录无效和后端不存在在配置修正之前每次都会失败，
+    /// 重试逻辑可据此跳过重试。
+    pub fn is_permanent(&self) -> bool {
+        matches!(
+            self,
+            ExecuteError::ProgramNotFound { .. }
+                | ExecuteError::PermissionDenied { .. }
+                | ExecuteError::WorkingDirInvalid { .. }
+                | ExecuteError::UnknownBackend(_)
+        )
+    }
 }
 
 /// 错误上下文，包含命令执行失败时的详细信息
@@ -81,6 +282,15 @@ impl ErrorContext {
         }
     }
 
+    /// 根据命令配置创建错误上下文
+    ///
+    /// 命令字符串为程序和参数的摘要，过长时截断，避免数百个参数淹没错误信息。
+    /// 未设置工作目录时记录为 `.`。
+    pub fn from_config(task_id: u64, config: &crate::config::CommandConfig) -> Self {
+        let working_dir = config.working_dir_path().unwrap_or(Path::new("."));
+        Self::new(task_id, &command_summary(config), working_dir)
+    }
+
     /// 设置工作线程 ID
     pub fn with_worker_id(mut self, worker_id: usize) -> Self {
         self.worker_id = Some(worker_id);
@@ -88,6 +298,19 @@ impl ErrorContext {
     }
 }
 
+/// 错误上下文中命令字符串的最大长度（字符数）
+const COMMAND_SUMMARY_CHARS: usize = 256;
+
+/// 生成程序和参数的摘要
+fn command_summary(config: &crate::config::CommandConfig) -> String {
+    let full = config.command_line_lossy();
+    if full.chars().count() <= COMMAND_SUMMARY_CHARS {
+        return full;
+    }
+    let truncated: String = full.chars().take(COMMAND_SUMMARY_CHARS).collect();
+    format!("{}... ({} args)", truncated, config.args_os().len())
+}
+
 impl std::fmt::Display for ErrorContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -105,6 +328,28 @@ impl std::fmt::Display for ErrorContext {
     }
 }
 
+/// 带任务上下文的执行错误
+///
+/// 将 [`ExecuteError`] 与出错任务的 ID、命令摘要和工作目录绑定，
+/// 从排队了大量命令的命令池中返回的错误可以直接指明是哪个命令失败。
+/// 由 [`TaskHandle::wait_with_context`](crate::TaskHandle::wait_with_context) 返回，
+/// 也可以通过 [`ExecuteError::with_context`] 自行构造。
+#[derive(Error, Debug)]
+#[error("{source} [{context}]")]
+pub struct TaskError {
+    /// 出错任务的上下文
+    pub context: Box<ErrorContext>,
+    /// 原始错误
+    pub source: ExecuteError,
+}
+
+impl TaskError {
+    /// 出错任务的 ID
+    pub fn task_id(&self) -> u64 {
+        self.context.task_id
+    }
+}
+
 /// 命令执行错误，包含详细的上下文信息
 #[derive(Error, Debug)]
 pub enum CommandError {
@@ -130,6 +375,25 @@ pub enum CommandError {
         #[source]
         source: std::io::Error,
     },
+
+    #[error("Unexpected exit status: {context}, status: {}", output.status)]
+    UnexpectedExit {
+        context: ErrorContext,
+        output: Box<std::process::Output>,
+    },
+
+    #[error("Exit status check failed: {context}, {error}")]
+    ExitStatus {
+        context: ErrorContext,
+        error: Box<ExecuteError>,
+    },
+}
+
+fn describe_exit_code(code: Option<i32>) -> String {
+    match code {
+        Some(code) => format!("exit code {code}"),
+        None => "no exit code (terminated by signal)".to_string(),
+    }
 }
 
 impl CommandError {
@@ -152,6 +416,53 @@ impl CommandError {
                     format!("Task {} was cancelled", task_id),
                 ),
             },
+            ExecuteError::Skipped(key) => CommandError::ExecutionFailed {
+                context,
+                source: std::io::Error::new(
+                    std::io::ErrorKind::WouldBlock,
+                    format!("Singleton key {:?} is already running", key),
+                ),
+            },
+            ExecuteError::BudgetExceeded(reason) => CommandError::ExecutionFailed {
+                context,
+                source: std::io::Error::new(
+                    std::io::ErrorKind::Interrupted,
+                    format!("Batch budget exceeded: {}", reason),
+                ),
+            },
+            error @ (ExecuteError::ProgramNotFound { .. }
+            | ExecuteError::PermissionDenied { .. }
+            | ExecuteError::WorkingDirInvalid { .. }) => {
+                let kind = match &error {
+                    ExecuteError::PermissionDenied { .. } => std::io::ErrorKind::PermissionDenied,
+                    _ => std::io::ErrorKind::NotFound,
+                };
+                CommandError::SpawnFailed {
+                    context,
+                    source: std::io::Error::new(kind, error.to_string()),
+                }
+            }
+            error @ ExecuteError::PipelineStage { .. } => CommandError::ExecutionFailed {
+                context,
+                source: std::io::Error::other(error.to_string()),
+            },
+            ExecuteError::UnexpectedExit { output } => {
+                CommandError::UnexpectedExit { context, output }
+            }
+            error @ ExecuteError::ExitStatus { .. } => CommandError::ExitStatus {
+                context,
+                error: Box::new(error),
+            },
+            error @ ExecuteError::UnknownBackend(_) => CommandError::ExecutionFailed {
+                context,
+                source: std::io::Error::new(std::io::ErrorKind::NotFound, error.to_string()),
+            },
+            error @ (ExecuteError::DependencyFailed(_) | ExecuteError::Expired(_)) => {
+                CommandError::ExecutionFailed {
+                    context,
+                    source: std::io::Error::new(std::io::ErrorKind::Interrupted, error.to_string()),
+                }
+            }
         }
     }
 }
@@ -183,19 +494,24 @@ pub enum ConfigError {
     #[error("Invalid timeout: {0:?}, must be positive")]
     InvalidTimeout(Duration),
 
-    /// 无效的轮询间隔
-    ///
-    /// 当轮询间隔为零或负数时返回此错误。
-    /// 轮询间隔必须为正数。
-    #[error("Invalid poll interval: {0:?}, must be positive")]
-    InvalidPollInterval(Duration),
-
     /// 线程数超过系统限制
     ///
     /// 当请求的线程数超过系统允许的最大线程数时返回此错误。
     /// 第一个参数是请求的线程数，第二个参数是系统限制。
     #[error("Thread count {0} exceeds system limit {1}")]
     ThreadCountExceedsLimit(usize, usize),
+
+    /// 无效的 cron 表达式
+    ///
+    /// 第一个参数是表达式，第二个参数是具体原因。
+    #[error("Invalid cron expression {0:?}: {1}")]
+    InvalidCronExpression(String, String),
+
+    /// 进程级共享命令池已经创建
+    ///
+    /// `init_global_pool` 只能在第一次使用共享命令池之前调用一次。
+    #[error("Global pool already initialized")]
+    GlobalPoolInitialized,
 }
 
 /// 关闭错误类型
@@ -238,6 +554,13 @@ pub enum SubmitError {
     /// 当命令池已完全停止时尝试提交任务会返回此错误。
     #[error("Pool is stopped")]
     Stopped,
+
+    /// 写入持久化任务日志失败
+    ///
+    /// 任务没有入队。需要启用 `persistence` feature。
+    #[cfg(feature = "persistence")]
+    #[error("Failed to write task journal: {0}")]
+    Journal(std::io::Error),
 }
 
 /// 取消错误类型
@@ -0,0 +1,200 @@
+//! 命令池事件总线
+//!
+//! 以结构化事件的形式发布任务和工作线程的生命周期，
+//! 多个观察者（界面、指标、持久化等）可以各自订阅同一事件流。
+
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+use crate::error::ExecuteError;
+use crate::task_handle::TaskResult;
+
+/// 命令池事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolEvent {
+    /// 任务已加入队列
+    TaskQueued {
+        /// 任务 ID
+        task_id: u64,
+        /// 执行的程序
+        command: String,
+    },
+    /// 任务启动了子进程
+    ///
+    /// 每启动一个子进程发布一次，带重试的任务每次尝试都会发布。
+    TaskStarted {
+        /// 任务 ID
+        task_id: u64,
+        /// 子进程 ID
+        pid: u32,
+    },
+    /// 任务已结束
+    TaskFinished {
+        /// 任务 ID
+        task_id: u64,
+        /// 结束状态
+        status: FinishStatus,
+        /// 从开始执行到结束的时长（未执行的任务为零）
+        duration: Duration,
+    },
+    /// 任务执行失败，已按命令池重试策略重新入队
+    ///
+    /// 由 [`CommandPoolBuilder::with_retry_policy`](crate::CommandPoolBuilder::with_retry_policy)
+    /// 启用，任务在 `delay` 之后再次执行，最终结束时仍只发布一次 `TaskFinished`。
+    TaskRetrying {
+        /// 任务 ID
+        task_id: u64,
+        /// 已执行的次数
+        attempt: u32,
+        /// 重新执行前的延迟
+        delay: Duration,
+    },
+    /// 工作线程已启动
+    WorkerStarted {
+        /// 工作线程序号
+        worker: usize,
+    },
+    /// 工作线程已退出
+    WorkerStopped {
+        /// 工作线程序号
+        worker: usize,
+    },
+    /// 工作线程执行单个任务的时间远超任务超时
+    ///
+    /// 由 [`CommandPoolBuilder::with_watchdog`](crate::CommandPoolBuilder::with_watchdog)
+    /// 启用的看门狗发布，每个卡住的任务发布一次。
+    WorkerStuck {
+        /// 工作线程序号
+        worker: usize,
+        /// 正在执行的任务 ID
+        task_id: u64,
+        /// 任务已执行的时长
+        elapsed: Duration,
+        /// 是否已启动替补工作线程
+        replaced: bool,
+    },
+    /// 队列长度达到高水位
+    ///
+    /// 在队列长度首次达到
+    /// [`CommandPoolBuilder::with_queue_high_watermark`](crate::CommandPoolBuilder::with_queue_high_watermark)
+    /// 设定的阈值时发布，队列回落到阈值以下后才会再次发布。
+    QueueHighWatermark {
+        /// 当前队列长度
+        depth: usize,
+    },
+}
+
+/// 任务结束状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FinishStatus {
+    /// 进程以零退出码结束
+    Success,
+    /// 进程以非零退出码结束或被信号终止
+    Failed {
+        /// 退出码（被信号终止时为 None）
+        exit_code: Option<i32>,
+    },
+    /// 执行超时
+    TimedOut,
+    /// 任务被取消
+    Cancelled,
+    /// 任务被跳过（同键单例任务正在运行）
+    Skipped,
+    /// 任务在队列中等待超过存活时间，没有执行
+    Expired,
+    /// 其他执行错误
+    Error(String),
+}
+
+impl FinishStatus {
+    /// 根据任务结果确定结束状态
+    pub(crate) fn from_result(result: &TaskResult) -> Self {
+        match result {
+            Ok(output) if output.status.success() => FinishStatus::Success,
+            Ok(output) => FinishStatus::Failed {
+                exit_code: output.status.code(),
+            },
+            Err(ExecuteError::UnexpectedExit { output }) => FinishStatus::Failed {
+                exit_code: output.status.code(),
+            },
+            Err(ExecuteError::ExitStatus { code, .. }) => FinishStatus::Failed { exit_code: *code },
+            Err(ExecuteError::Timeout(_)) => FinishStatus::TimedOut,
+            Err(ExecuteError::Cancelled(_)) => FinishStatus::Cancelled,
+            Err(ExecuteError::Skipped(_)) => FinishStatus::Skipped,
+            Err(ExecuteError::Expired(_)) => FinishStatus::Expired,
+            Err(e) => FinishStatus::Error(e.to_string()),
+        }
+    }
+
+    /// 是否为失败结果（非零退出、超时或执行错误；取消、跳过和过期不算失败）
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            FinishStatus::Failed { .. } | FinishStatus::TimedOut | FinishStatus::Error(_)
+        )
+    }
+}
+
+/// 事件总线，向所有订阅者广播事件
+#[derive(Default)]
+pub(crate) struct EventBus {
+    subscribers: Mutex<Vec<Sender<PoolEvent>>>,
+}
+
+impl EventBus {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// 新增订阅者
+    pub(crate) fn subscribe(&self) -> Receiver<PoolEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// 是否有订阅者，无订阅者时可跳过构造事件
+    pub(crate) fn has_subscribers(&self) -> bool {
+        !self.subscribers.lock().unwrap().is_empty()
+    }
+
+    /// 发布事件，移除已断开的订阅者
+    pub(crate) fn emit(&self, event: PoolEvent) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|sender| sender.send(event.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emit_reaches_all_subscribers() {
+        let bus = EventBus::new();
+        let a = bus.subscribe();
+        let b = bus.subscribe();
+
+        bus.emit(PoolEvent::WorkerStarted { worker: 0 });
+        assert_eq!(
+            a.try_recv().unwrap(),
+            PoolEvent::WorkerStarted { worker: 0 }
+        );
+        assert_eq!(
+            b.try_recv().unwrap(),
+            PoolEvent::WorkerStarted { worker: 0 }
+        );
+    }
+
+    #[test]
+    fn test_dropped_subscriber_is_removed() {
+        let bus = EventBus::new();
+        let a = bus.subscribe();
+        drop(bus.subscribe());
+
+        bus.emit(PoolEvent::WorkerStopped { worker: 1 });
+        assert_eq!(bus.subscribers.lock().unwrap().len(), 1);
+        assert!(a.try_recv().is_ok());
+    }
+}
@@ -1,14 +1,58 @@
 #![cfg_attr(not(feature = "logging"), allow(dead_code))]
 
+use std::cell::RefCell;
 use std::io::Read;
-use std::process::{Command, Output, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output, Stdio};
 use std::sync::Arc;
-use std::time::Instant;
+use std::thread::JoinHandle;
+use std::time::{Instant, SystemTime};
 
+use crate::capture::{CapturedOutput, OutputCollectors};
+use crate::checksum;
+use crate::config::OutputMode;
 use crate::error::{CommandError, ErrorContext};
 use crate::hooks::{ExecutionContext, ExecutionHook, HookTaskResult};
+use crate::report::{ExecutionReport, ExecutionTiming, OutputChunk, ResourceUsage};
+use crate::running_task::RunningTask;
+use crate::workspace::{self, TempWorkdir};
 use crate::{CommandConfig, ExecuteError};
 
+/// 子进程启动观察者，参数为子进程 PID
+type SpawnObserver = Box<dyn Fn(u32)>;
+
+thread_local! {
+    /// 当前线程上的子进程启动观察者
+    static SPAWN_OBSERVER: RefCell<Option<SpawnObserver>> = const { RefCell::new(None) };
+}
+
+/// 在设置了子进程启动观察者的情况下执行 `f`
+///
+/// `f` 在当前线程内每启动一个子进程，都会以其 PID 调用一次观察者。
+/// 命令池借此在任务启动时记录 PID 并发布事件，而无需改变后端接口。
+pub(crate) fn with_spawn_observer<R>(observer: impl Fn(u32) + 'static, f: impl FnOnce() -> R) -> R {
+    let previous = SPAWN_OBSERVER.with(|slot| slot.replace(Some(Box::new(observer))));
+    // 即使 f 发生 panic 也要恢复之前的观察者
+    struct Restore(Option<SpawnObserver>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SPAWN_OBSERVER.with(|slot| *slot.borrow_mut() = previous);
+        }
+    }
+    let _restore = Restore(previous);
+    f()
+}
+
+/// 通知当前线程的观察者子进程已启动
+pub(crate) fn notify_spawned(pid: u32) {
+    SPAWN_OBSERVER.with(|slot| {
+        if let Some(observer) = slot.borrow().as_ref() {
+            observer(pid);
+        }
+    });
+}
+
 /// 日志宏：在 logging feature 启用时使用 tracing，否则不记录
 #[cfg(feature = "logging")]
 macro_rules! log_warn {
@@ -230,6 +274,362 @@ pub fn apply_env_config(cmd: &mut Command, env_config: &crate::config::EnvConfig
     crate::env_optimizer::apply_env_config_optimized(cmd, env_config);
 }
 
+/// 强制彩色输出时设置的环境变量
+const FORCE_COLOR_ENV: [(&str, &str); 3] = [
+    ("FORCE_COLOR", "1"),
+    ("CLICOLOR_FORCE", "1"),
+    ("TERM", "xterm-256color"),
+];
+
+/// 为启用强制彩色输出的命令设置环境变量
+///
+/// 环境变量配置中已经设置或清除的变量保持不变。
+pub(crate) fn apply_force_color(cmd: &mut Command, config: &CommandConfig) {
+    if !config.force_color() {
+        return;
+    }
+    let explicit = config.env_config().map(|env| env.vars());
+    for (key, value) in FORCE_COLOR_ENV {
+        if !explicit.is_some_and(|vars| vars.contains_key(key)) {
+            cmd.env(key, value);
+        }
+    }
+}
+
+/// 终止超时的子进程并回收
+///
+/// 配置了宽限期时先发送 SIGTERM，宽限期内未退出再发送 SIGKILL；
+/// 启用了进程树终止时信号发送给整棵进程树。
+#[cfg(unix)]
+pub(crate) fn terminate_child(child: &mut std::process::Child, config: &CommandConfig) {
+    use crate::child_wait::ChildExt;
+    use nix::sys::signal::{Signal, kill};
+    use nix::unistd::Pid;
+
+    if config.kill_tree() {
+        let (signal, grace) = match config.kill_grace() {
+            Some(grace) => (Signal::SIGTERM, grace),
+            None => (Signal::SIGKILL, std::time::Duration::ZERO),
+        };
+        let _ = crate::process_util::kill_tree(child.id(), signal, grace);
+    } else if let Some(grace) = config.kill_grace()
+        && kill(Pid::from_raw(child.id() as i32), Signal::SIGTERM).is_ok()
+        && matches!(child.wait_timeout(grace), Ok(Some(_)))
+    {
+        return;
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// 终止超时的子进程并回收
+///
+/// 启用了进程树终止时结束子进程启动时加入的作业对象，后代进程一并终止。
+#[cfg(not(unix))]
+pub(crate) fn terminate_child(child: &mut std::process::Child, config: &CommandConfig) {
+    if config.kill_tree() {
+        use crate::process_util::Signal;
+        let _ =
+            crate::process_util::kill_tree(child.id(), Signal::SIGKILL, std::time::Duration::ZERO);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// 配置了 stdin 内容时将子进程的 stdin 连接到管道
+fn apply_stdin(cmd: &mut Command, config: &CommandConfig) {
+    if config.stdin().is_some() {
+        cmd.stdin(Stdio::piped());
+    }
+}
+
+/// 按输出去向设置子进程的 stdout 和 stderr
+///
+/// 写入文件时打开文件失败返回错误。
+fn apply_output_mode(cmd: &mut Command, config: &CommandConfig) -> std::io::Result<()> {
+    match config.output_mode() {
+        OutputMode::Capture => {
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        }
+        OutputMode::Inherit => {
+            cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+        }
+        OutputMode::Discard => {
+            cmd.stdout(Stdio::null()).stderr(Stdio::null());
+        }
+        OutputMode::ToFile(path) => {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            cmd.stderr(file.try_clone()?).stdout(file);
+        }
+    }
+
+    // 单独重定向的流覆盖输出去向；两路指向同一文件时共用句柄，避免相互覆盖
+    let stdout_file = config
+        .stdout_file()
+        .map(|target| target.open())
+        .transpose()?;
+    match config.stderr_file() {
+        Some(target)
+            if config
+                .stdout_file()
+                .is_some_and(|out| out.path == target.path) =>
+        {
+            let file = stdout_file.as_ref().expect("stdout file opened");
+            cmd.stderr(file.try_clone()?);
+        }
+        Some(target) => {
+            cmd.stderr(target.open()?);
+        }
+        None => {}
+    }
+    if let Some(file) = stdout_file {
+        cmd.stdout(file);
+    }
+    Ok(())
+}
+
+/// 在后台线程中把配置的内容写入子进程的 stdin，写完后关闭
+///
+/// 子进程提前退出导致的写入失败被忽略。
+fn feed_stdin(child: &mut std::process::Child, config: &CommandConfig) {
+    let Some(input) = config.stdin() else {
+        return;
+    };
+    let Some(mut stdin) = child.stdin.take() else {
+        return;
+    };
+    let input = input.to_vec();
+    std::thread::spawn(move || {
+        use std::io::Write;
+        let _ = stdin.write_all(&input);
+    });
+}
+
+/// 在子进程 exec 之前设置 I/O 优先级
+#[cfg(target_os = "linux")]
+fn apply_io_priority(cmd: &mut Command, config: &CommandConfig) {
+    use crate::config::IoPriorityClass;
+    use nix::libc;
+    use std::os::unix::process::CommandExt;
+
+    // linux/ioprio.h
+    const IOPRIO_CLASS_SHIFT: u32 = 13;
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+    let Some(priority) = config.io_priority() else {
+        return;
+    };
+    let class: u32 = match priority.class {
+        IoPriorityClass::RealTime => 1,
+        IoPriorityClass::BestEffort => 2,
+        IoPriorityClass::Idle => 3,
+    };
+    let ioprio = (class << IOPRIO_CLASS_SHIFT) | u32::from(priority.level);
+
+    // SAFETY: 闭包只执行一次 ioprio_set 系统调用，不分配内存，在 fork 后调用是安全的
+    unsafe {
+        cmd.pre_exec(move || {
+            if libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, ioprio) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_io_priority(_cmd: &mut Command, _config: &CommandConfig) {}
+
+/// 在子进程 exec 之前设置调度优先级
+#[cfg(unix)]
+pub(crate) fn apply_nice(cmd: &mut Command, config: &CommandConfig) {
+    use nix::libc;
+    use std::os::unix::process::CommandExt;
+
+    let Some(nice) = config.nice() else {
+        return;
+    };
+
+    // SAFETY: 闭包只执行一次 setpriority 系统调用，不分配内存，在 fork 后调用是安全的
+    unsafe {
+        cmd.pre_exec(move || {
+            if libc::setpriority(libc::PRIO_PROCESS as _, 0, nice) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+/// 按调度优先级设置子进程的优先级类
+#[cfg(windows)]
+pub(crate) fn apply_nice(cmd: &mut Command, config: &CommandConfig) {
+    use std::os::windows::process::CommandExt;
+
+    // winbase.h
+    const IDLE_PRIORITY_CLASS: u32 = 0x0000_0040;
+    const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+    const NORMAL_PRIORITY_CLASS: u32 = 0x0000_0020;
+    const ABOVE_NORMAL_PRIORITY_CLASS: u32 = 0x0000_8000;
+    const HIGH_PRIORITY_CLASS: u32 = 0x0000_0080;
+
+    let Some(nice) = config.nice() else {
+        return;
+    };
+    let class = match nice {
+        15.. => IDLE_PRIORITY_CLASS,
+        1..=14 => BELOW_NORMAL_PRIORITY_CLASS,
+        0 => NORMAL_PRIORITY_CLASS,
+        -10..=-1 => ABOVE_NORMAL_PRIORITY_CLASS,
+        _ => HIGH_PRIORITY_CLASS,
+    };
+    cmd.creation_flags(class);
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn apply_nice(_cmd: &mut Command, _config: &CommandConfig) {}
+
+/// 在子进程 exec 之前设置文件模式掩码
+#[cfg(unix)]
+pub(crate) fn apply_umask(cmd: &mut Command, config: &CommandConfig) {
+    use nix::sys::stat::{Mode, umask};
+    use std::os::unix::process::CommandExt;
+
+    let Some(mask) = config.umask() else {
+        return;
+    };
+    let mode = Mode::from_bits_truncate(mask as nix::libc::mode_t);
+
+    // SAFETY: 闭包只执行一次 umask 系统调用，不分配内存，在 fork 后调用是安全的
+    unsafe {
+        cmd.pre_exec(move || {
+            umask(mode);
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn apply_umask(_cmd: &mut Command, _config: &CommandConfig) {}
+
+/// 在子进程 exec 之前设置 CPU 亲和性
+#[cfg(target_os = "linux")]
+pub(crate) fn apply_cpu_affinity(cmd: &mut Command, config: &CommandConfig) {
+    use nix::sched::{CpuSet, sched_setaffinity};
+    use nix::unistd::Pid;
+    use std::os::unix::process::CommandExt;
+
+    let Some(cpus) = config.cpu_affinity() else {
+        return;
+    };
+    // CpuSet 在 fork 之前构造，超出范围的核心编号留到子进程中报告
+    let mut set = CpuSet::new();
+    let valid = cpus.iter().all(|&cpu| set.set(cpu).is_ok());
+
+    // SAFETY: 闭包只执行一次 sched_setaffinity 系统调用，不分配内存，在 fork 后调用是安全的
+    unsafe {
+        cmd.pre_exec(move || {
+            if !valid {
+                return Err(std::io::Error::from_raw_os_error(nix::libc::EINVAL));
+            }
+            sched_setaffinity(Pid::from_raw(0), &set)?;
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn apply_cpu_affinity(_cmd: &mut Command, _config: &CommandConfig) {}
+
+/// 在子进程 exec 之前通过 `setrlimit` 设置资源上限
+#[cfg(unix)]
+pub(crate) fn apply_rlimits(cmd: &mut Command, config: &CommandConfig) {
+    use nix::sys::resource::{Resource, setrlimit};
+    use std::os::unix::process::CommandExt;
+
+    let rlimits = config.rlimits();
+    if rlimits.is_empty() {
+        return;
+    }
+    let limits = [
+        (Resource::RLIMIT_AS, rlimits.max_memory),
+        (Resource::RLIMIT_CPU, rlimits.cpu_seconds()),
+        (Resource::RLIMIT_NOFILE, rlimits.max_open_files),
+    ];
+
+    // SAFETY: 闭包只执行 setrlimit 系统调用，不分配内存，在 fork 后调用是安全的
+    unsafe {
+        cmd.pre_exec(move || {
+            for (resource, limit) in limits {
+                if let Some(limit) = limit {
+                    setrlimit(resource, limit, limit)?;
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn apply_rlimits(_cmd: &mut Command, _config: &CommandConfig) {}
+
+/// 设置子进程的运行用户和用户组
+#[cfg(unix)]
+pub(crate) fn apply_user(cmd: &mut Command, config: &CommandConfig) {
+    use std::os::unix::process::CommandExt;
+
+    if let Some(gid) = config.gid() {
+        cmd.gid(gid);
+    }
+    if let Some(uid) = config.uid() {
+        cmd.uid(uid);
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn apply_user(_cmd: &mut Command, _config: &CommandConfig) {}
+
+/// 按配置让子进程成为新会话或新进程组的组长
+///
+/// `setsid` 要求调用者不是进程组组长，因此脱离会话时不再单独设置进程组。
+#[cfg(unix)]
+pub(crate) fn apply_process_group(cmd: &mut Command, config: &CommandConfig) {
+    use std::os::unix::process::CommandExt;
+
+    if config.is_session_detached() {
+        // SAFETY: 闭包只执行一次 setsid 系统调用，不分配内存，在 fork 后调用是安全的
+        unsafe {
+            cmd.pre_exec(|| {
+                nix::unistd::setsid()?;
+                Ok(())
+            });
+        }
+    } else if config.kill_tree() {
+        cmd.process_group(0);
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn apply_process_group(_cmd: &mut Command, _config: &CommandConfig) {}
+
+/// 启用了进程树终止时把刚启动的子进程加入作业对象
+///
+/// Windows 上没有进程组，由作业对象收拢子进程及其后代进程，超时时一次性终止。
+#[cfg(windows)]
+fn attach_kill_tree_job(child: &std::process::Child, config: &CommandConfig) {
+    if config.kill_tree()
+        && let Err(_e) = crate::process_util::attach_job(child)
+    {
+        log_warn!(pid = child.id(), error = %_e, "Failed to attach child to job object");
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_kill_tree_job(_child: &std::process::Child, _config: &CommandConfig) {}
+
 /// 命令执行器 trait
 ///
 /// 抽象命令执行的接口，支持不同的运行时实现（std::process、tokio、async-std 等）。
@@ -254,52 +654,440 @@ impl CommandExecutor for StdCommandExecutor {
 /// 内部函数，用于启动子进程并处理超时。使用 wait-timeout crate 在同一线程中进行超时等待，
 /// 避免为每个任务生成额外的等待线程，提高性能和降低系统开销。
 pub(crate) fn execute_command(config: &CommandConfig) -> Result<Output, ExecuteError> {
-    // 启动子进程，重定向 stdout 和 stderr
+    execute_with_report(config).map(|report| report.output)
+}
+
+/// 执行命令，失败的退出状态作为错误返回
+///
+/// 与启用了 [`CommandConfig::with_check_exit`] 的 [`execute_with_report`] 相同，只返回输出：
+/// 退出状态失败时返回携带退出码和输出的 [`ExecuteError::ExitStatus`]。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, execute_checked};
+///
+/// let output = execute_checked(&CommandConfig::new("git", vec!["rev-parse".into(), "HEAD".into()]))?;
+/// let head = String::from_utf8_lossy(&output.stdout);
+/// ```
+pub fn execute_checked(config: &CommandConfig) -> Result<Output, ExecuteError> {
+    if config.is_exit_checked() {
+        return execute_command(config);
+    }
+    execute_command(&config.clone().with_check_exit(true))
+}
+
+/// 执行命令并返回执行报告
+///
+/// 与命令池后端使用相同的执行流程，额外返回执行过程中的元数据，
+/// 如托管临时工作目录的路径。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, execute_with_report};
+///
+/// let config = CommandConfig::new("pwd", vec![]).with_temp_workdir();
+/// let report = execute_with_report(&config)?;
+/// assert!(report.temp_workdir.is_some());
+/// ```
+pub fn execute_with_report(config: &CommandConfig) -> Result<ExecutionReport, ExecuteError> {
+    // 文件锁在整个执行期间持有，函数返回时释放
+    let _flock = match config.flock() {
+        Some(path) => {
+            log_debug!(command = %config.program(), lock = %path.display(), "Acquiring file lock");
+            Some(workspace::lock_file(path)?)
+        }
+        None => None,
+    };
+
+    let temp_workdir = config.temp_workdir().map(TempWorkdir::create).transpose()?;
+
+    let cwd = temp_workdir.as_ref().map(|dir| dir.path());
+    // 输入文件和产物相对的目录：临时目录、配置的工作目录或当前目录
+    let workdir_root = || -> std::io::Result<PathBuf> {
+        cwd.map(Path::to_path_buf)
+            .or_else(|| config.working_dir.clone())
+            .map_or_else(std::env::current_dir, Ok)
+    };
+
+    let staged = match config.inputs() {
+        Some(inputs) => Some(workspace::stage_inputs(&workdir_root()?, inputs)?),
+        None => None,
+    };
+
+    // 产物需要在临时目录清理之前收集
+    let started_at = SystemTime::now();
+    let started = Instant::now();
+    let result = run_command(config, cwd).and_then(|mut report| {
+        report.timing = Some(ExecutionTiming::direct(started_at, started.elapsed()));
+        if let Some(artifacts) = config.artifacts() {
+            let (collected, missing) = workspace::collect_artifacts(&workdir_root()?, artifacts)?;
+            if !missing.is_empty() {
+                log_warn!(
+                    command = %config.program(),
+                    missing = ?missing,
+                    "Declared artifacts not found"
+                );
+            }
+            report.artifacts = collected;
+            report.missing_artifacts = missing;
+        }
+        if config.checksums() {
+            report.stdout_sha256 = Some(checksum::sha256_hex(&report.output.stdout));
+            for artifact in &mut report.artifacts {
+                artifact.sha256 = Some(checksum::sha256_file(&artifact.path)?);
+            }
+        }
+        Ok(report)
+    });
+
+    if let Some(staged) = staged
+        && config.inputs().is_some_and(|inputs| inputs.cleanup)
+    {
+        staged.cleanup();
+    }
+
+    let success = matches!(&result, Ok(report) if config.is_success(&report.output.status));
+    let temp_path = temp_workdir.map(|dir| dir.finish(success));
+
+    let mut report = result?;
+    report.temp_workdir = temp_path;
+    report.output = config.check_exit(report.output)?;
+    Ok(report)
+}
+
+/// 启动子进程后立即返回，不等待其完成
+///
+/// 启动与等待分离：调用方可以先做其他工作，再决定等待、轮询或终止任务。
+/// 返回的 [`RunningTask`] 等待时使用配置中的超时、超时钩子和输出捕获模式，
+/// 超时从启动时开始计算。子进程的 stdin 连接到管道，可通过
+/// [`RunningTask::stdin_writer`] 写入；配置了 [`CommandConfig::with_stdin`] 时
+/// 自动写入配置的内容，不再提供写入端。
+///
+/// 仅应用命令本身的配置（参数、工作目录、环境变量）；临时工作目录、输入文件、
+/// 产物收集和文件锁属于完整执行流程，请使用 [`execute_with_report`]。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, spawn};
+///
+/// let task = spawn(&CommandConfig::new("sleep", vec!["1".to_string()]))?;
+/// // ... 执行其他工作 ...
+/// let output = task.wait()?;
+/// ```
+pub fn spawn(config: &CommandConfig) -> Result<RunningTask, ExecuteError> {
+    let mut cmd = build_command(config, None)?;
+    cmd.stdin(Stdio::piped());
+    let started = Instant::now();
+    let mut child = spawn_child(&mut cmd, config, None)?;
+    feed_stdin(&mut child, config);
+    notify_spawned(child.id());
+    Ok(RunningTask::new(child, config.clone(), started))
+}
+
+/// 执行命令，边执行边把每一行输出交给回调
+///
+/// stdout 和 stderr 各由一个后台线程读取，读到完整的一行（去掉行尾的 `\n` 或 `\r\n`）
+/// 就调用对应的回调；没有换行符结尾的最后一行在输出结束时交给回调。
+/// 非 UTF-8 字节按替换字符处理。两个回调在不同线程中调用，同一个回调按输出顺序调用。
+///
+/// 输出总是通过管道读取，配置的输出去向和输出文件不生效；返回的 [`Output`]
+/// 按配置的捕获模式保留输出。超时和超时钩子与 [`execute_with_report`] 相同。
+/// 与 [`spawn`] 一样只应用命令本身的配置，不处理临时工作目录、输入文件、
+/// 产物收集和文件锁。
+///
+/// # 错误
+///
+/// 启动失败、超时或退出码检查失败时返回错误，此前读到的行已经交给回调。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, execute_streaming};
+///
+/// let config = CommandConfig::new("cargo", vec!["build".to_string()]);
+/// let output = execute_streaming(
+///     &config,
+///     |line| println!("{line}"),
+///     |line| eprintln!("{line}"),
+/// )?;
+/// ```
+pub fn execute_streaming<O, E>(
+    config: &CommandConfig,
+    on_stdout_line: O,
+    on_stderr_line: E,
+) -> Result<Output, ExecuteError>
+where
+    O: FnMut(&str) + Send + 'static,
+    E: FnMut(&str) + Send + 'static,
+{
+    let mut cmd = build_command(config, None)?;
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let start = Instant::now();
+    let mut child = spawn_child(&mut cmd, config, None)?;
+    feed_stdin(&mut child, config);
+    notify_spawned(child.id());
+
+    let collectors = OutputCollectors::start_with_lines(
+        &mut child,
+        config.capture_mode,
+        start,
+        Some(Box::new(on_stdout_line)),
+        Some(Box::new(on_stderr_line)),
+    );
+    let status = wait_for_exit(&mut child, config, start, &collectors)?;
+    let output = finish_output(status, collectors, config);
+    config.check_exit(output)
+}
+
+/// 根据配置构建子进程命令，stdout 和 stderr 按输出去向重定向
+///
+/// `cwd` 指定时覆盖配置中的工作目录。
+fn build_command(config: &CommandConfig, cwd: Option<&Path>) -> Result<Command, ExecuteError> {
+    let mut cmd = build_command_without_group(config, cwd)?;
+    apply_process_group(&mut cmd, config);
+    Ok(cmd)
+}
+
+/// 与 [`build_command`] 相同，但不设置进程组和会话
+///
+/// 供需要自行建立会话的调用方使用（如伪终端执行），`setsid` 要求子进程不是进程组组长。
+pub(crate) fn build_command_without_group(
+    config: &CommandConfig,
+    cwd: Option<&Path>,
+) -> Result<Command, ExecuteError> {
     let mut cmd = Command::new(&config.program);
     cmd.args(&config.args);
-    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
-    if let Some(dir) = &config.working_dir {
-        cmd.current_dir(dir);
+    apply_output_mode(&mut cmd, config)?;
+    match (cwd, &config.working_dir) {
+        (Some(dir), _) => {
+            cmd.current_dir(dir);
+        }
+        (None, Some(dir)) => {
+            cmd.current_dir(dir);
+        }
+        (None, None) => {}
     }
 
     // 应用环境变量配置
     if let Some(env_config) = config.env_config() {
         apply_env_config(&mut cmd, env_config);
     }
+    apply_force_color(&mut cmd, config);
+    apply_io_priority(&mut cmd, config);
+    apply_nice(&mut cmd, config);
+    apply_cpu_affinity(&mut cmd, config);
+    apply_rlimits(&mut cmd, config);
+    apply_user(&mut cmd, config);
+    apply_umask(&mut cmd, config);
+    apply_stdin(&mut cmd, config);
+
+    Ok(cmd)
+}
 
-    let mut child = cmd.spawn()?;
+/// 启动子进程，启动失败时按程序和工作目录对错误分类
+pub(crate) fn spawn_child(
+    cmd: &mut Command,
+    config: &CommandConfig,
+    cwd: Option<&Path>,
+) -> Result<std::process::Child, ExecuteError> {
+    let child = cmd.spawn().map_err(|e| {
+        let dir = cwd.or(config.working_dir.as_deref());
+        ExecuteError::spawn_failed(e, &config.program, dir)
+    })?;
+    attach_kill_tree_job(&child, config);
+    Ok(child)
+}
+
+/// 启动子进程并等待其完成
+///
+/// 返回的报告只包含输出和输出时间线，其余元数据由调用方填充。
+fn run_command(
+    config: &CommandConfig,
+    cwd: Option<&Path>,
+) -> Result<ExecutionReport, ExecuteError> {
+    // 启动子进程，重定向 stdout 和 stderr
+    let mut cmd = build_command(config, cwd)?;
+    let start = Instant::now();
+    let mut child = spawn_child(&mut cmd, config, cwd)?;
+    feed_stdin(&mut child, config);
+    notify_spawned(child.id());
+
+    // 后台读取线程边执行边读取输出，等待线程在回收子进程前读取资源使用量
+    wait_with_collectors(child, config, start)
+}
+
+/// 超时钩子可见的最近输出字节数
+const RECENT_OUTPUT_BYTES: usize = 4096;
+
+/// 通过后台读取线程收集输出的等待流程
+///
+/// 在需要边执行边读取输出时使用：
+/// - `CaptureMode::Tail` 只保留末尾输出
+/// - `CaptureMode::Timeline` 额外记录输出时间线
+/// - 设置了超时时，等待期间持续读取管道，避免子进程因管道写满而阻塞
+/// - 配置了超时钩子时，在超时前 `lead_time` 调用钩子，钩子可以授予延长，
+///   所有延长之和不超过 `max_extension`。钩子 panic 视为不延长。
+fn wait_with_collectors(
+    mut child: std::process::Child,
+    config: &CommandConfig,
+    start: Instant,
+) -> Result<ExecutionReport, ExecuteError> {
+    let collectors = OutputCollectors::start(&mut child, config.capture_mode, start);
+    let (status, usage) = wait_for_exit_with_usage(&mut child, config, start, &collectors)?;
+    let (output, timeline) = finish_capture(status, collectors, config);
+    let mut report = ExecutionReport::new(output);
+    report.timeline = timeline;
+    report.resource_usage = usage;
+    Ok(report)
+}
+
+/// 按配置的超时和超时钩子等待子进程退出
+///
+/// 超时后子进程被终止并返回 [`ExecuteError::Timeout`]，超时从 `start` 开始计算。
+pub(crate) fn wait_for_exit(
+    child: &mut std::process::Child,
+    config: &CommandConfig,
+    start: Instant,
+    collectors: &OutputCollectors,
+) -> Result<ExitStatus, ExecuteError> {
+    wait_for_exit_with_usage(child, config, start, collectors).map(|(status, _)| status)
+}
+
+/// 与 [`wait_for_exit`] 相同，同时返回子进程的资源使用量
+fn wait_for_exit_with_usage(
+    child: &mut std::process::Child,
+    config: &CommandConfig,
+    start: Instant,
+    collectors: &OutputCollectors,
+) -> Result<(ExitStatus, Option<ResourceUsage>), ExecuteError> {
+    use crate::capture::lock;
+    use crate::child_wait::ChildExt;
+    use crate::hooks::{TimeoutContext, TimeoutDecision};
+    use std::time::Duration;
 
-    // 根据是否设置超时进行等待处理 | Handle waiting based on timeout configuration
     match config.timeout {
+        None => Ok(child.wait_with_usage()?),
         Some(timeout) => {
-            // 使用 wait-timeout 在当前线程中等待，不产生额外线程
-            // Use wait-timeout for in-thread waiting without spawning extra threads
-            use wait_timeout::ChildExt;
-            match child
-                .wait_timeout(timeout)
-                .map_err(|e| ExecuteError::Io(std::io::Error::other(e)))?
-            {
-                Some(_) => {
-                    // 子进程在超时前正常退出，收集输出 | Child exited within timeout; collect output
-                    let output = child.wait_with_output()?;
-                    Ok(output)
+            let hook_config = config.timeout_hook();
+            let mut deadline = timeout;
+            let mut extended = Duration::ZERO;
+            let mut extensions = 0u32;
+            let mut next_check = hook_config
+                .map(|h| deadline.saturating_sub(h.lead_time))
+                .unwrap_or(deadline);
+            let mut hook_active = hook_config.is_some();
+
+            loop {
+                let remaining_extension = hook_config
+                    .map(|h| h.max_extension.saturating_sub(extended))
+                    .unwrap_or(Duration::ZERO);
+                // 钩子拒绝延长或额度用尽后不再调用钩子，直接等到截止时间
+                let wake_at = if !hook_active || remaining_extension.is_zero() {
+                    deadline
+                } else {
+                    next_check.min(deadline)
+                };
+
+                let wait_for = wake_at.saturating_sub(start.elapsed());
+                if let Some(exited) = child
+                    .wait_timeout_with_usage(wait_for)
+                    .map_err(|e| ExecuteError::Io(std::io::Error::other(e)))?
+                {
+                    return Ok(exited);
                 }
-                None => {
-                    // 超时：尝试杀死子进程 | Timeout: attempt to kill the child process
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    Err(ExecuteError::Timeout(timeout))
+
+                let Some(hook_config) = hook_config.filter(|_| wake_at < deadline) else {
+                    // 超时：杀死子进程，不等待读取线程——
+                    // 孙进程可能仍持有管道，读取线程会在其退出后自行结束
+                    terminate_child(child, config);
+                    return Err(ExecuteError::Timeout(deadline));
+                };
+
+                let ctx = TimeoutContext {
+                    pid: child.id(),
+                    command: config.program().into_owned(),
+                    elapsed: start.elapsed(),
+                    timeout: deadline,
+                    extensions,
+                    remaining_extension,
+                    recent_stdout: lock(&collectors.stdout).tail(RECENT_OUTPUT_BYTES),
+                    recent_stderr: lock(&collectors.stderr).tail(RECENT_OUTPUT_BYTES),
+                };
+                let hook = Arc::clone(&hook_config.hook);
+                let decision = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    hook.on_timeout_imminent(&ctx)
+                })) {
+                    Ok(decision) => decision,
+                    Err(_) => {
+                        log_error!(command = %config.program(), "Timeout hook panicked");
+                        TimeoutDecision::Proceed
+                    }
+                };
+
+                match decision {
+                    TimeoutDecision::Extend(requested) if !requested.is_zero() => {
+                        let granted = requested.min(remaining_extension);
+                        deadline += granted;
+                        extended += granted;
+                        extensions += 1;
+                        // 下次检查不早于本次授予的时长之后，避免短延长导致钩子被反复调用
+                        next_check = deadline
+                            .saturating_sub(hook_config.lead_time)
+                            .max(start.elapsed() + granted);
+                        log_info!(
+                            command = %config.program(),
+                            granted_ms = granted.as_millis(),
+                            timeout_ms = deadline.as_millis(),
+                            "Timeout extended by hook"
+                        );
+                    }
+                    _ => hook_active = false,
                 }
             }
         }
-        None => {
-            // 无超时限制，直接等待子进程完成 | No timeout: wait and collect without limit
-            let output = child.wait_with_output()?;
-            Ok(output)
-        }
     }
 }
 
+/// 等待读取线程结束并组装输出
+pub(crate) fn finish_output(
+    status: ExitStatus,
+    collectors: OutputCollectors,
+    config: &CommandConfig,
+) -> Output {
+    finish_capture(status, collectors, config).0
+}
+
+/// 等待读取线程结束，组装输出并取出时间线（仅时间线模式下存在）
+#[cfg_attr(not(feature = "logging"), allow(unused_variables))]
+fn finish_capture(
+    status: ExitStatus,
+    collectors: OutputCollectors,
+    config: &CommandConfig,
+) -> (Output, Option<Vec<OutputChunk>>) {
+    let CapturedOutput {
+        stdout,
+        stderr,
+        dropped,
+        timeline,
+    } = collectors.finish();
+    if dropped > 0 {
+        log_debug!(
+            command = %config.program(),
+            dropped_bytes = dropped,
+            "Output exceeded tail capture size, oldest bytes discarded"
+        );
+    }
+    let output = Output {
+        status,
+        stdout,
+        stderr,
+    };
+    (output, timeline)
+}
+
 /// 执行命令并返回带有丰富错误上下文的结果
 ///
 /// 此函数提供了增强的错误处理，包含完整的执行上下文信息。
@@ -329,31 +1117,30 @@ pub fn execute_command_with_context(
     let start_time = Instant::now();
 
     // 构建完整的命令字符串用于错误上下文
-    let command_str = format!("{} {}", config.program(), config.args().join(" "));
-    let working_dir = std::path::Path::new(config.working_dir().unwrap_or("."));
+    let command_str = config.command_line_lossy();
+    let working_dir = config.working_dir_path().unwrap_or(Path::new("."));
 
     // 创建错误上下文
     let create_context = || ErrorContext::new(task_id, &command_str, working_dir);
 
     // 启动子进程
-    let mut cmd = Command::new(&config.program);
-    cmd.args(&config.args);
-    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
-    if let Some(dir) = &config.working_dir {
-        cmd.current_dir(dir);
-    }
-
-    // 应用环境变量配置
-    if let Some(env_config) = config.env_config() {
-        apply_env_config(&mut cmd, env_config);
-    }
+    let mut cmd = build_command(config, None).map_err(|e| match e {
+        ExecuteError::Io(source) => CommandError::SpawnFailed {
+            context: create_context(),
+            source,
+        },
+        other => CommandError::from_execute_error(other, create_context()),
+    })?;
 
     let mut child = cmd.spawn().map_err(|e| CommandError::SpawnFailed {
         context: create_context(),
         source: e,
     })?;
+    attach_kill_tree_job(&child, config);
 
+    feed_stdin(&mut child, config);
     let pid = child.id();
+    notify_spawned(pid);
 
     // 如果配置了内存限制，启动内存监控线程
     let memory_monitor_handle = if let Some(limits) = config.resource_limits() {
@@ -370,50 +1157,29 @@ pub fn execute_command_with_context(
     };
 
     // 根据是否设置超时进行等待处理
+    let limit = config
+        .resource_limits()
+        .and_then(|limits| limits.max_output_size);
+    let drain = OutputDrain::start(&mut child, limit);
     let result = match config.timeout {
         Some(timeout) => {
-            use wait_timeout::ChildExt;
+            use crate::child_wait::ChildExt;
             match child
                 .wait_timeout(timeout)
                 .map_err(|e| CommandError::ExecutionFailed {
                     context: create_context(),
                     source: std::io::Error::other(e),
                 })? {
-                Some(_) => {
-                    // 子进程在超时前正常退出
-                    // 如果配置了输出大小限制，使用 LimitedReader 读取输出
-                    if let Some(limits) = config.resource_limits() {
-                        if limits.max_output_size.is_some() {
-                            // 使用 LimitedReader 读取输出
-                            read_output_with_limit(&mut child, limits.max_output_size).map_err(
-                                |e| CommandError::ExecutionFailed {
-                                    context: create_context(),
-                                    source: e,
-                                },
-                            )
-                        } else {
-                            // 无输出限制，直接读取
-                            child
-                                .wait_with_output()
-                                .map_err(|e| CommandError::ExecutionFailed {
-                                    context: create_context(),
-                                    source: e,
-                                })
-                        }
-                    } else {
-                        // 无资源限制，直接读取
-                        child
-                            .wait_with_output()
-                            .map_err(|e| CommandError::ExecutionFailed {
-                                context: create_context(),
-                                source: e,
-                            })
-                    }
-                }
+                // 子进程在超时前正常退出
+                Some(status) => drain
+                    .finish(status)
+                    .map_err(|e| CommandError::ExecutionFailed {
+                        context: create_context(),
+                        source: e,
+                    }),
                 None => {
                     // 超时：尝试杀死子进程
-                    let _ = child.kill();
-                    let _ = child.wait();
+                    terminate_child(&mut child, config);
                     Err(CommandError::Timeout {
                         context: create_context(),
                         configured_timeout: timeout,
@@ -422,36 +1188,14 @@ pub fn execute_command_with_context(
                 }
             }
         }
-        None => {
-            // 无超时限制
-            if let Some(limits) = config.resource_limits() {
-                if limits.max_output_size.is_some() {
-                    // 使用 LimitedReader 读取输出
-                    read_output_with_limit(&mut child, limits.max_output_size).map_err(|e| {
-                        CommandError::ExecutionFailed {
-                            context: create_context(),
-                            source: e,
-                        }
-                    })
-                } else {
-                    // 无输出限制，直接读取
-                    child
-                        .wait_with_output()
-                        .map_err(|e| CommandError::ExecutionFailed {
-                            context: create_context(),
-                            source: e,
-                        })
-                }
-            } else {
-                // 无资源限制，直接读取
-                child
-                    .wait_with_output()
-                    .map_err(|e| CommandError::ExecutionFailed {
-                        context: create_context(),
-                        source: e,
-                    })
-            }
-        }
+        // 无超时限制
+        None => child
+            .wait()
+            .and_then(|status| drain.finish(status))
+            .map_err(|e| CommandError::ExecutionFailed {
+                context: create_context(),
+                source: e,
+            }),
     };
 
     // 等待内存监控线程结束
@@ -459,47 +1203,62 @@ pub fn execute_command_with_context(
         let _ = handle.join();
     }
 
-    result
+    result.and_then(|output| {
+        config
+            .check_exit(output)
+            .map_err(|error| CommandError::from_execute_error(error, create_context()))
+    })
 }
 
-/// 使用 LimitedReader 读取子进程输出
+/// 在后台线程中读取子进程 stdout 和 stderr 的读取器
 ///
-/// # 参数
-///
-/// * `child` - 子进程
-/// * `max_output_size` - 最大输出大小（字节）
-///
-/// # 返回
-///
-/// 成功时返回命令输出
-fn read_output_with_limit(
-    child: &mut std::process::Child,
-    max_output_size: Option<usize>,
-) -> std::io::Result<Output> {
-    use std::io::Read;
-
-    // 等待进程完成
-    let status = child.wait()?;
-
-    // 读取 stdout
-    let mut stdout = Vec::new();
-    if let Some(mut stdout_pipe) = child.stdout.take() {
-        let mut limited_reader = LimitedReader::new(&mut stdout_pipe, max_output_size);
-        limited_reader.read_to_end(&mut stdout)?;
+/// 必须在等待子进程之前启动：子进程写满管道缓冲区后会阻塞在写入上，
+/// 先等待退出再读取输出会一直等到超时。超过输出大小限制的部分被丢弃，
+/// 但管道仍然读到结束，子进程不会因此阻塞。
+pub(crate) struct OutputDrain {
+    stdout: Option<JoinHandle<std::io::Result<Vec<u8>>>>,
+    stderr: Option<JoinHandle<std::io::Result<Vec<u8>>>>,
+}
+
+impl OutputDrain {
+    /// 取走子进程的输出管道并开始读取，`limit` 限制每一路保留的字节数
+    pub(crate) fn start(child: &mut std::process::Child, limit: Option<usize>) -> Self {
+        Self {
+            stdout: drain_pipe(child.stdout.take(), limit),
+            stderr: drain_pipe(child.stderr.take(), limit),
+        }
     }
 
-    // 读取 stderr
-    let mut stderr = Vec::new();
-    if let Some(mut stderr_pipe) = child.stderr.take() {
-        let mut limited_reader = LimitedReader::new(&mut stderr_pipe, max_output_size);
-        limited_reader.read_to_end(&mut stderr)?;
+    /// 等待读取线程结束，与退出状态组装为输出
+    ///
+    /// 只应在子进程退出后调用；超时终止子进程时直接丢弃即可，
+    /// 孙进程可能仍持有管道，读取线程会在其退出后自行结束。
+    pub(crate) fn finish(self, status: ExitStatus) -> std::io::Result<Output> {
+        let join = |handle: Option<JoinHandle<std::io::Result<Vec<u8>>>>| {
+            handle.map_or(Ok(Vec::new()), |handle| {
+                handle.join().unwrap_or_else(|_| Ok(Vec::new()))
+            })
+        };
+        Ok(Output {
+            status,
+            stdout: join(self.stdout)?,
+            stderr: join(self.stderr)?,
+        })
     }
+}
 
-    Ok(Output {
-        status,
-        stdout,
-        stderr,
-    })
+/// 读取管道到结束，最多保留 `limit` 字节
+fn drain_pipe<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    limit: Option<usize>,
+) -> Option<JoinHandle<std::io::Result<Vec<u8>>>> {
+    let mut pipe = pipe?;
+    Some(std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        LimitedReader::new(&mut pipe, limit).read_to_end(&mut buf)?;
+        std::io::copy(&mut pipe, &mut std::io::sink())?;
+        Ok(buf)
+    }))
 }
 
 /// 执行命令并支持分离的超时控制
@@ -540,8 +1299,8 @@ pub fn execute_with_timeouts(config: &CommandConfig, task_id: u64) -> Result<Out
     let start_time = Instant::now();
 
     // 构建完整的命令字符串用于错误上下文
-    let command_str = format!("{} {}", config.program(), config.args().join(" "));
-    let working_dir = std::path::Path::new(config.working_dir().unwrap_or("."));
+    let command_str = config.command_line_lossy();
+    let working_dir = config.working_dir_path().unwrap_or(Path::new("."));
 
     // 创建错误上下文
     let create_context = || ErrorContext::new(task_id, &command_str, working_dir);
@@ -556,17 +1315,13 @@ pub fn execute_with_timeouts(config: &CommandConfig, task_id: u64) -> Result<Out
     };
 
     // 构建命令
-    let mut cmd = Command::new(&config.program);
-    cmd.args(&config.args);
-    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
-    if let Some(dir) = &config.working_dir {
-        cmd.current_dir(dir);
-    }
-
-    // 应用环境变量配置
-    if let Some(env_config) = config.env_config() {
-        apply_env_config(&mut cmd, env_config);
-    }
+    let mut cmd = build_command(config, None).map_err(|e| match e {
+        ExecuteError::Io(source) => CommandError::SpawnFailed {
+            context: create_context(),
+            source,
+        },
+        other => CommandError::from_execute_error(other, create_context()),
+    })?;
 
     // 处理启动超时
     let spawn_start = Instant::now();
@@ -578,6 +1333,7 @@ pub fn execute_with_timeouts(config: &CommandConfig, task_id: u64) -> Result<Out
             context: create_context(),
             source: e,
         })?;
+        attach_kill_tree_job(&child, config);
 
         let spawn_duration = spawn_start.elapsed();
         if spawn_duration > spawn_timeout {
@@ -591,8 +1347,7 @@ pub fn execute_with_timeouts(config: &CommandConfig, task_id: u64) -> Result<Out
 
             // 尝试终止刚启动的进程
             let mut child_mut = child;
-            let _ = child_mut.kill();
-            let _ = child_mut.wait();
+            terminate_child(&mut child_mut, config);
 
             return Err(CommandError::Timeout {
                 context: create_context(),
@@ -604,13 +1359,17 @@ pub fn execute_with_timeouts(config: &CommandConfig, task_id: u64) -> Result<Out
         child
     } else {
         // 无启动超时限制
-        cmd.spawn().map_err(|e| CommandError::SpawnFailed {
+        let child = cmd.spawn().map_err(|e| CommandError::SpawnFailed {
             context: create_context(),
             source: e,
-        })?
+        })?;
+        attach_kill_tree_job(&child, config);
+        child
     };
 
+    feed_stdin(&mut child, config);
     let pid = child.id();
+    notify_spawned(pid);
 
     // 如果配置了内存限制，启动内存监控线程
     let memory_monitor_handle = if let Some(limits) = config.resource_limits() {
@@ -627,86 +1386,52 @@ pub fn execute_with_timeouts(config: &CommandConfig, task_id: u64) -> Result<Out
     };
 
     // 处理执行超时
-    let result = if let Some(execution_timeout) = timeout_config.execution_timeout() {
-        use wait_timeout::ChildExt;
-        match child
-            .wait_timeout(execution_timeout)
-            .map_err(|e| CommandError::ExecutionFailed {
-                context: create_context(),
-                source: std::io::Error::other(e),
+    let limit = config
+        .resource_limits()
+        .and_then(|limits| limits.max_output_size);
+    let drain = OutputDrain::start(&mut child, limit);
+    let result = match timeout_config.execution_timeout() {
+        Some(execution_timeout) => {
+            use crate::child_wait::ChildExt;
+            match child.wait_timeout(execution_timeout).map_err(|e| {
+                CommandError::ExecutionFailed {
+                    context: create_context(),
+                    source: std::io::Error::other(e),
+                }
             })? {
-            Some(_) => {
                 // 子进程在超时前正常退出
-                // 如果配置了输出大小限制，使用 LimitedReader 读取输出
-                if let Some(limits) = config.resource_limits() {
-                    if limits.max_output_size.is_some() {
-                        read_output_with_limit(&mut child, limits.max_output_size).map_err(|e| {
-                            CommandError::ExecutionFailed {
-                                context: create_context(),
-                                source: e,
-                            }
-                        })
-                    } else {
-                        child
-                            .wait_with_output()
-                            .map_err(|e| CommandError::ExecutionFailed {
-                                context: create_context(),
-                                source: e,
-                            })
-                    }
-                } else {
-                    child
-                        .wait_with_output()
-                        .map_err(|e| CommandError::ExecutionFailed {
-                            context: create_context(),
-                            source: e,
-                        })
-                }
-            }
-            None => {
-                // 执行超时：尝试杀死子进程
-                log_warn!(
-                    task_id = task_id,
-                    execution_timeout_ms = execution_timeout.as_millis(),
-                    actual_duration_ms = start_time.elapsed().as_millis(),
-                    "Command execution exceeded timeout"
-                );
-
-                let _ = child.kill();
-                let _ = child.wait();
-                Err(CommandError::Timeout {
-                    context: create_context(),
-                    configured_timeout: execution_timeout,
-                    actual_duration: start_time.elapsed(),
-                })
-            }
-        }
-    } else {
-        // 无执行超时限制
-        if let Some(limits) = config.resource_limits() {
-            if limits.max_output_size.is_some() {
-                read_output_with_limit(&mut child, limits.max_output_size).map_err(|e| {
-                    CommandError::ExecutionFailed {
-                        context: create_context(),
-                        source: e,
-                    }
-                })
-            } else {
-                child
-                    .wait_with_output()
+                Some(status) => drain
+                    .finish(status)
                     .map_err(|e| CommandError::ExecutionFailed {
                         context: create_context(),
                         source: e,
+                    }),
+                None => {
+                    // 执行超时：尝试杀死子进程
+                    log_warn!(
+                        task_id = task_id,
+                        execution_timeout_ms = execution_timeout.as_millis(),
+                        actual_duration_ms = start_time.elapsed().as_millis(),
+                        "Command execution exceeded timeout"
+                    );
+
+                    terminate_child(&mut child, config);
+                    Err(CommandError::Timeout {
+                        context: create_context(),
+                        configured_timeout: execution_timeout,
+                        actual_duration: start_time.elapsed(),
                     })
+                }
             }
-        } else {
-            child
-                .wait_with_output()
-                .map_err(|e| CommandError::ExecutionFailed {
-                    context: create_context(),
-                    source: e,
-                })
         }
+        // 无超时限制
+        None => child
+            .wait()
+            .and_then(|status| drain.finish(status))
+            .map_err(|e| CommandError::ExecutionFailed {
+                context: create_context(),
+                source: e,
+            }),
     };
 
     // 等待内存监控线程结束
@@ -714,7 +1439,11 @@ pub fn execute_with_timeouts(config: &CommandConfig, task_id: u64) -> Result<Out
         let _ = handle.join();
     }
 
-    result
+    result.and_then(|output| {
+        config
+            .check_exit(output)
+            .map_err(|error| CommandError::from_execute_error(error, create_context()))
+    })
 }
 
 /// 执行命令并支持重试
@@ -766,7 +1495,7 @@ pub fn execute_with_retry(config: &CommandConfig, task_id: u64) -> Result<Output
         if attempt == 0 {
             log_debug!(
                 task_id = task_id,
-                command = format!("{} {}", config.program(), config.args().join(" ")),
+                command = config.command_line_lossy(),
                 "Executing command (initial attempt)"
             );
         } else {
@@ -774,7 +1503,7 @@ pub fn execute_with_retry(config: &CommandConfig, task_id: u64) -> Result<Output
                 task_id = task_id,
                 attempt = attempt,
                 max_attempts = retry_policy.max_attempts,
-                command = format!("{} {}", config.program(), config.args().join(" ")),
+                command = config.command_line_lossy(),
                 "Retrying command after failure"
             );
         }
@@ -789,6 +1518,22 @@ pub fn execute_with_retry(config: &CommandConfig, task_id: u64) -> Result<Output
         };
 
         match execution_result {
+            Ok(output)
+                if attempt + 1 < max_attempts
+                    && retry_policy.should_retry(config, Ok::<_, ()>(&output)) =>
+            {
+                // 退出状态失败且策略要求重试
+                attempt += 1;
+                log_warn!(
+                    task_id = task_id,
+                    attempt = attempt,
+                    max_attempts = max_attempts,
+                    status = %output.status,
+                    "Command exited with failure status"
+                );
+                let delay = retry_policy.delay_for_attempt(attempt);
+                std::thread::sleep(delay);
+            }
             Ok(output) => {
                 // 成功，记录日志并返回
                 if attempt > 0 {
@@ -879,7 +1624,7 @@ pub fn execute_task_with_hooks(
     hooks: &[Arc<dyn ExecutionHook>],
 ) -> Result<Output, CommandError> {
     // 构建完整的命令字符串
-    let command_str = format!("{} {}", config.program(), config.args().join(" "));
+    let command_str = config.command_line_lossy();
 
     // 创建执行上下文
     let ctx = ExecutionContext::new(task_id, command_str, worker_id);
@@ -0,0 +1,144 @@
+//! 流式命令构建入口
+//!
+//! [`Execute::cmd`] 是构建并运行命令的一站式入口，覆盖 [`CommandConfig`] 的常用选项，
+//! 可以直接提交到命令池、在当前线程执行或只启动不等待。
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Output;
+use std::time::Duration;
+
+use crate::config::{CommandConfig, RetryPolicy};
+use crate::error::{ExecuteError, SubmitError};
+use crate::executor::{execute_command, spawn};
+use crate::pool::CommandPool;
+use crate::running_task::RunningTask;
+use crate::task_handle::TaskHandle;
+
+/// 流式构建入口
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandPool, Duration, Execute};
+///
+/// let pool = CommandPool::new();
+/// pool.start_executor();
+///
+/// let handle = Execute::cmd("ffmpeg")
+///     .arg("-i")
+///     .arg("input.mp4")
+///     .cwd("/tmp/videos")
+///     .timeout(Duration::from_secs(60))
+///     .submit(&pool)?;
+/// let output = handle.wait()?;
+/// ```
+pub struct Execute;
+
+impl Execute {
+    /// 开始构建执行 `program` 的命令
+    pub fn cmd(program: impl Into<OsString>) -> CommandBuilder {
+        CommandBuilder {
+            config: CommandConfig::new(program, Vec::new()),
+        }
+    }
+}
+
+/// 流式命令构建器
+///
+/// 由 [`Execute::cmd`] 创建。未覆盖的选项可通过 [`CommandBuilder::configure`]
+/// 直接修改底层的 [`CommandConfig`]。
+#[derive(Debug, Clone)]
+pub struct CommandBuilder {
+    config: CommandConfig,
+}
+
+impl CommandBuilder {
+    /// 追加一个参数
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.config.args.push(arg.into());
+        self
+    }
+
+    /// 追加多个参数
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.config.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// 设置工作目录
+    pub fn cwd(mut self, dir: impl AsRef<Path>) -> Self {
+        self.config.working_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// 设置环境变量（在继承的父进程环境之上）
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let env = self.config.env_config.take().unwrap_or_default();
+        self.config.env_config = Some(env.set(key, value));
+        self
+    }
+
+    /// 设置超时（默认 10 秒）
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// 取消超时限制
+    pub fn no_timeout(mut self) -> Self {
+        self.config.timeout = None;
+        self
+    }
+
+    /// 设置重试策略
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.config.retry_policy = Some(policy);
+        self
+    }
+
+    /// 直接修改底层配置，用于构建器未覆盖的选项
+    ///
+    /// ```ignore
+    /// let builder = Execute::cmd("make").configure(|c| c.with_checksums(true));
+    /// ```
+    pub fn configure(mut self, f: impl FnOnce(CommandConfig) -> CommandConfig) -> Self {
+        self.config = f(self.config);
+        self
+    }
+
+    /// 底层配置
+    pub fn config(&self) -> &CommandConfig {
+        &self.config
+    }
+
+    /// 构建命令配置
+    pub fn build(self) -> CommandConfig {
+        self.config
+    }
+
+    /// 提交到命令池，返回任务句柄
+    pub fn submit(self, pool: &CommandPool) -> Result<TaskHandle, SubmitError> {
+        pool.push_task(self.config)
+    }
+
+    /// 在当前线程执行并等待完成
+    pub fn run(self) -> Result<Output, ExecuteError> {
+        execute_command(&self.config)
+    }
+
+    /// 启动后立即返回，见 [`spawn`](crate::spawn)
+    pub fn spawn(self) -> Result<RunningTask, ExecuteError> {
+        spawn(&self.config)
+    }
+}
+
+impl From<CommandBuilder> for CommandConfig {
+    fn from(builder: CommandBuilder) -> Self {
+        builder.config
+    }
+}
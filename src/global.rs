@@ -0,0 +1,61 @@
+//! 进程级共享命令池
+//!
+//! 基于本库构建的多个库各自创建命令池时，每个命令池都有自己的工作线程；
+//! 通过 [`global_pool`] 共享同一个命令池，整个进程只有一组工作线程。
+
+use std::sync::OnceLock;
+
+use crate::backend::ExecutionConfig;
+use crate::error::ConfigError;
+use crate::pool::CommandPool;
+
+static GLOBAL_POOL: OnceLock<CommandPool> = OnceLock::new();
+
+/// 返回进程级共享命令池，首次调用时使用默认配置创建并启动执行器
+///
+/// 需要自定义配置时，应在任何代码调用 `global_pool` 之前调用 [`init_global_pool`]。
+/// 共享命令池在进程退出前一直存在，不应对其调用 `shutdown`。
+///
+/// # 示例
+///
+/// ```no_run
+/// use execute::{CommandConfig, global_pool};
+///
+/// let handle = global_pool()
+///     .push_task(CommandConfig::new("echo", vec!["hi".to_string()]))
+///     .unwrap();
+/// handle.wait().unwrap();
+/// ```
+pub fn global_pool() -> &'static CommandPool {
+    GLOBAL_POOL.get_or_init(|| start(ExecutionConfig::default()))
+}
+
+/// 使用指定配置创建进程级共享命令池并启动执行器
+///
+/// 只能成功调用一次，且必须早于第一次 [`global_pool`] 调用。
+///
+/// # 错误
+///
+/// * `ConfigError::InvalidThreadCount` - 工作线程数为 0
+/// * `ConfigError::GlobalPoolInitialized` - 共享命令池已经创建
+pub fn init_global_pool(config: ExecutionConfig) -> Result<&'static CommandPool, ConfigError> {
+    if config.workers < 1 {
+        return Err(ConfigError::InvalidThreadCount(config.workers));
+    }
+    let mut created = false;
+    let pool = GLOBAL_POOL.get_or_init(|| {
+        created = true;
+        start(config)
+    });
+    if created {
+        Ok(pool)
+    } else {
+        Err(ConfigError::GlobalPoolInitialized)
+    }
+}
+
+fn start(config: ExecutionConfig) -> CommandPool {
+    let pool = CommandPool::with_config(config);
+    pool.start_executor();
+    pool
+}
@@ -1,5 +1,9 @@
+use std::ffi::OsString;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use crate::config::CommandConfig;
+
 /// 执行上下文，包含任务执行前的上下文信息
 #[derive(Debug, Clone)]
 pub struct ExecutionContext {
@@ -117,6 +121,185 @@ pub trait ExecutionHook: Send + Sync {
     fn after_execute(&self, ctx: &ExecutionContext, result: &HookTaskResult);
 }
 
+/// 超时即将触发时的上下文信息
+///
+/// 传递给 [`TimeoutHook::on_timeout_imminent`]，用于判断任务是否仍在正常推进。
+#[derive(Debug, Clone)]
+pub struct TimeoutContext {
+    /// 子进程 ID
+    pub pid: u32,
+    /// 正在执行的命令
+    pub command: String,
+    /// 已执行时长
+    pub elapsed: Duration,
+    /// 当前生效的超时时间（包含已授予的延长）
+    pub timeout: Duration,
+    /// 已授予的延长次数
+    pub extensions: u32,
+    /// 剩余可授予的延长时长
+    pub remaining_extension: Duration,
+    /// 最近的标准输出（末尾若干字节）
+    pub recent_stdout: Vec<u8>,
+    /// 最近的标准错误（末尾若干字节）
+    pub recent_stderr: Vec<u8>,
+}
+
+/// 超时钩子的决策
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutDecision {
+    /// 不延长，按原计划在超时后终止进程
+    Proceed,
+    /// 延长指定时长（会被限制在剩余可延长额度内）
+    Extend(Duration),
+}
+
+/// 超时即将触发钩子 trait
+///
+/// 在任务超时前的一小段时间内调用，可以检查任务进度（如最近输出），
+/// 并决定授予有限的延长时间或让终止流程继续。
+/// 适用于运行时长取决于数据量、难以预先确定上限的任务。
+///
+/// # 示例
+///
+/// ```rust
+/// use std::time::Duration;
+/// use execute::{TimeoutContext, TimeoutDecision, TimeoutHook};
+///
+/// /// 只要还有输出就延长 5 秒
+/// struct ProgressHook;
+///
+/// impl TimeoutHook for ProgressHook {
+///     fn on_timeout_imminent(&self, ctx: &TimeoutContext) -> TimeoutDecision {
+///         if ctx.recent_stdout.ends_with(b"progress\n") {
+///             TimeoutDecision::Extend(Duration::from_secs(5))
+///         } else {
+///             TimeoutDecision::Proceed
+///         }
+///     }
+/// }
+/// ```
+pub trait TimeoutHook: Send + Sync {
+    /// 在超时即将触发时调用，返回是否延长
+    fn on_timeout_imminent(&self, ctx: &TimeoutContext) -> TimeoutDecision;
+}
+
+/// 命令行：程序及其参数
+///
+/// 传递给 [`CommandRewriter::rewrite`] 供修改。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandLine {
+    /// 可执行程序
+    pub program: OsString,
+    /// 参数列表
+    pub args: Vec<OsString>,
+}
+
+impl CommandLine {
+    /// 用包装命令包裹当前命令行
+    ///
+    /// 例如对 `make all` 调用 `wrap("nice", ["-n", "19"])` 得到 `nice -n 19 make all`。
+    pub fn wrap<P, I, S>(&mut self, program: P, args: I)
+    where
+        P: Into<OsString>,
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        let mut wrapped: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        wrapped.push(std::mem::replace(&mut self.program, program.into()));
+        wrapped.append(&mut self.args);
+        self.args = wrapped;
+    }
+}
+
+/// 命令改写器 trait
+///
+/// 在启动子进程之前调用，可以包裹或修改命令行，例如统一加上
+/// `nice -n 19`、`timeout 300`、`stdbuf -oL` 或公司内部的包装脚本。
+/// 把这类策略集中配置在命令池或后端上，而不必写进每个提交的配置。
+///
+/// 多个改写器按注册顺序依次应用，后注册的包裹在最外层。
+///
+/// # 示例
+///
+/// ```rust
+/// use execute::{CommandLine, CommandRewriter};
+///
+/// /// 所有命令以行缓冲方式输出
+/// struct LineBuffered;
+///
+/// impl CommandRewriter for LineBuffered {
+///     fn rewrite(&self, command: &mut CommandLine) {
+///         command.wrap("stdbuf", ["-oL"]);
+///     }
+/// }
+/// ```
+pub trait CommandRewriter: Send + Sync {
+    /// 改写命令行
+    fn rewrite(&self, command: &mut CommandLine);
+}
+
+/// 为命令加上固定前缀的改写器
+///
+/// # 示例
+///
+/// ```rust
+/// use execute::{CommandLine, CommandRewriter, PrefixRewriter};
+///
+/// let nice = PrefixRewriter::new("nice", ["-n", "19"]);
+/// let mut command = CommandLine {
+///     program: "make".into(),
+///     args: vec!["all".into()],
+/// };
+/// nice.rewrite(&mut command);
+/// assert_eq!(command.program, "nice");
+/// assert_eq!(command.args, ["-n", "19", "make", "all"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrefixRewriter {
+    program: OsString,
+    args: Vec<OsString>,
+}
+
+impl PrefixRewriter {
+    /// 创建前缀改写器
+    pub fn new<P, I, S>(program: P, args: I) -> Self
+    where
+        P: Into<OsString>,
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl CommandRewriter for PrefixRewriter {
+    fn rewrite(&self, command: &mut CommandLine) {
+        command.wrap(self.program.clone(), self.args.iter().cloned());
+    }
+}
+
+/// 依次应用改写器，返回改写后的配置
+pub(crate) fn rewrite_config(
+    config: &CommandConfig,
+    rewriters: &[Arc<dyn CommandRewriter>],
+) -> CommandConfig {
+    let mut command = CommandLine {
+        program: config.program.clone(),
+        args: config.args.clone(),
+    };
+    for rewriter in rewriters {
+        rewriter.rewrite(&mut command);
+    }
+
+    let mut rewritten = config.clone();
+    rewritten.program = command.program;
+    rewritten.args = command.args;
+    rewritten
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -11,14 +11,16 @@
 
 // use std::os::unix::process::ExitStatusExt;
 use std::process::{Child, ExitStatus, Output};
-use std::time::Duration;
 
 use io_uring::{IoUring, opcode, types};
 use slab::Slab;
 
 use crate::config::CommandConfig;
 use crate::error::ExecuteError;
-use crate::executor::execute_command;
+use crate::executor::{
+    apply_cpu_affinity, apply_force_color, apply_nice, apply_process_group, apply_rlimits,
+    apply_umask, apply_user, execute_command, terminate_child,
+};
 
 /// io_uring 执行器
 ///
@@ -113,7 +115,7 @@ impl IoUringExecutor {
         };
 
         // 等待进程退出
-        let status = self.async_wait(&mut child, config.timeout)?;
+        let status = self.async_wait(&mut child, config)?;
 
         Ok(Output {
             status,
@@ -150,8 +152,17 @@ impl IoUringExecutor {
                 }
             }
         }
-
-        cmd.spawn().map_err(ExecuteError::Io)
+        apply_force_color(&mut cmd, config);
+        apply_nice(&mut cmd, config);
+        apply_cpu_affinity(&mut cmd, config);
+        apply_rlimits(&mut cmd, config);
+        apply_user(&mut cmd, config);
+        apply_process_group(&mut cmd, config);
+        apply_umask(&mut cmd, config);
+
+        cmd.spawn().map_err(|e| {
+            ExecuteError::spawn_failed(e, &config.program, config.working_dir.as_deref())
+        })
     }
 
     /// 异步读取管道数据
@@ -292,28 +303,26 @@ impl IoUringExecutor {
 
     /// 异步等待进程退出
     ///
-    /// 使用 io_uring 的 poll 操作等待进程退出
+    /// 使用 io_uring 的 poll 操作等待进程退出，超时按配置终止子进程
     fn async_wait(
         &mut self,
         child: &mut Child,
-        timeout: Option<Duration>,
+        config: &CommandConfig,
     ) -> Result<ExitStatus, ExecuteError> {
         #[allow(unused_imports)]
         use std::os::fd::AsRawFd;
 
-        // 使用 wait-timeout 作为后备方案
-        // 完整的 io_uring 实现需要使用 pidfd_open (Linux 5.3+)
-        match timeout {
+        // 通过 pidfd 等待子进程退出（Linux 5.3+，更早的内核退化为 wait-timeout）
+        match config.timeout {
             Some(t) => {
-                use wait_timeout::ChildExt;
+                use crate::child_wait::ChildExt;
                 match child
                     .wait_timeout(t)
                     .map_err(|e| ExecuteError::Io(std::io::Error::other(e)))?
                 {
                     Some(status) => Ok(status),
                     None => {
-                        let _ = child.kill();
-                        let _ = child.wait();
+                        terminate_child(child, config);
                         Err(ExecuteError::Timeout(t))
                     }
                 }
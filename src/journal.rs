@@ -0,0 +1,240 @@
+//! 持久化任务日志
+//!
+//! 以追加写入的 JSON Lines 文件记录提交到命令池的任务和任务的确认，
+//! 进程崩溃后重新打开日志即可找回尚未确认的任务并重新提交（至少执行一次）。
+//!
+//! 每行是一条记录：`{"op":"enqueue","seq":N,"config":{..}}` 或 `{"op":"ack","seq":N}`。
+//! 打开日志时丢弃已确认的记录并压缩文件；崩溃时写了一半的最后一行会被忽略。
+
+#![cfg(feature = "persistence")]
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config::CommandConfig;
+
+/// 日志中的一条记录
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Entry {
+    Enqueue {
+        seq: u64,
+        config: Box<CommandConfig>,
+    },
+    Ack {
+        seq: u64,
+    },
+}
+
+struct JournalState {
+    file: File,
+    /// 下一条入队记录的序号
+    next_seq: u64,
+    /// 上一次运行遗留、尚未重新提交的任务（按写入顺序）
+    recovered: Vec<(u64, CommandConfig)>,
+    /// 本次运行中尚未确认的任务：任务 ID 到（记录序号，是否为重新提交的遗留任务）
+    live: HashMap<u64, (u64, bool)>,
+}
+
+/// 持久化任务日志
+///
+/// 通过 [`CommandPool::with_journal`](crate::CommandPool::with_journal) 交给命令池后，
+/// 每个提交的任务在入队前写入日志并同步到磁盘；任务结束后自动确认，
+/// 或在 [`with_manual_ack`](Self::with_manual_ack) 模式下由调用方通过
+/// [`CommandPool::acknowledge`](crate::CommandPool::acknowledge) 确认。
+///
+/// 重新启动后调用 [`CommandPool::replay_journal`](crate::CommandPool::replay_journal)
+/// 重新提交上次没有确认的任务，包括崩溃时正在执行的任务，因此命令应当可以安全地重复执行。
+/// 超时钩子等运行时对象不会写入日志。
+///
+/// 需要启用 `persistence` feature。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, CommandPool, TaskJournal};
+///
+/// let journal = TaskJournal::open("/var/lib/myapp/tasks.journal")?;
+/// let pool = CommandPool::new().with_journal(journal);
+/// pool.start_executor();
+///
+/// // 重新执行上次崩溃时没有完成的任务
+/// let recovered = pool.replay_journal()?;
+///
+/// pool.push_task(CommandConfig::new("backup", vec![]))?;
+/// ```
+pub struct TaskJournal {
+    path: PathBuf,
+    manual_ack: bool,
+    state: Mutex<JournalState>,
+}
+
+impl TaskJournal {
+    /// 打开（不存在时创建）日志文件，读取上次运行遗留的未确认任务
+    ///
+    /// 打开时会把日志压缩为只包含未确认任务的新文件。
+    ///
+    /// # 错误
+    ///
+    /// 读取、解析或重写日志文件失败时返回 I/O 错误；
+    /// 只有最后一行不完整（写入时崩溃）时会被忽略。
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let (recovered, next_seq) = read_pending(&path)?;
+
+        // 先写临时文件再替换，压缩过程中崩溃不会丢失记录
+        let compacted = path.with_extension("compact");
+        {
+            let mut file = File::create(&compacted)?;
+            for (seq, config) in &recovered {
+                write_entry(
+                    &mut file,
+                    &Entry::Enqueue {
+                        seq: *seq,
+                        config: Box::new(config.clone()),
+                    },
+                )?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(&compacted, &path)?;
+
+        let file = OpenOptions::new().append(true).open(&path)?;
+        Ok(Self {
+            path,
+            manual_ack: false,
+            state: Mutex::new(JournalState {
+                file,
+                next_seq,
+                recovered,
+                live: HashMap::new(),
+            }),
+        })
+    }
+
+    /// 任务结束后不自动确认，由调用方处理完结果后调用
+    /// [`CommandPool::acknowledge`](crate::CommandPool::acknowledge)
+    pub fn with_manual_ack(mut self) -> Self {
+        self.manual_ack = true;
+        self
+    }
+
+    /// 日志文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 尚未确认的任务数（包括尚未重新提交的遗留任务）
+    pub fn pending(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.recovered.len() + state.live.len()
+    }
+
+    pub(crate) fn manual_ack(&self) -> bool {
+        self.manual_ack
+    }
+
+    /// 记录新提交的任务
+    pub(crate) fn record(&self, task_id: u64, config: &CommandConfig) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let seq = state.next_seq;
+        write_entry(
+            &mut state.file,
+            &Entry::Enqueue {
+                seq,
+                config: Box::new(config.clone()),
+            },
+        )?;
+        state.file.sync_data()?;
+        state.next_seq += 1;
+        state.live.insert(task_id, (seq, false));
+        Ok(())
+    }
+
+    /// 取出上一次运行遗留的任务，返回（记录序号，配置）
+    pub(crate) fn take_recovered(&self) -> Vec<(u64, CommandConfig)> {
+        std::mem::take(&mut self.state.lock().unwrap().recovered)
+    }
+
+    /// 把重新提交的遗留任务关联到新的任务 ID，沿用原记录
+    pub(crate) fn adopt(&self, task_id: u64, seq: u64) {
+        self.state.lock().unwrap().live.insert(task_id, (seq, true));
+    }
+
+    /// 把未能重新提交的遗留任务放回，留待下次重放
+    pub(crate) fn restore(&self, seq: u64, config: CommandConfig) {
+        self.state.lock().unwrap().recovered.push((seq, config));
+    }
+
+    /// 撤销被命令池拒绝的任务：新记录写入确认，遗留任务保留原记录
+    pub(crate) fn reject(&self, task_id: u64) -> io::Result<()> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some((_, true)) = state.live.get(&task_id) {
+                state.live.remove(&task_id);
+                return Ok(());
+            }
+        }
+        self.acknowledge(task_id).map(|_| ())
+    }
+
+    /// 确认任务，返回任务是否在日志中
+    pub(crate) fn acknowledge(&self, task_id: u64) -> io::Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        let Some((seq, _)) = state.live.remove(&task_id) else {
+            return Ok(false);
+        };
+        write_entry(&mut state.file, &Entry::Ack { seq })?;
+        state.file.sync_data()?;
+        Ok(true)
+    }
+}
+
+/// 读取日志，返回未确认的任务（按写入顺序）和下一个可用序号
+fn read_pending(path: &Path) -> io::Result<(Vec<(u64, CommandConfig)>, u64)> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), 1)),
+        Err(e) => return Err(e),
+    };
+
+    let lines = BufReader::new(file)
+        .lines()
+        .collect::<io::Result<Vec<String>>>()?;
+    let mut pending: Vec<(u64, CommandConfig)> = Vec::new();
+    let mut acked = HashSet::new();
+    let mut next_seq = 1;
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Entry>(line) {
+            Ok(Entry::Enqueue { seq, config }) => {
+                next_seq = next_seq.max(seq + 1);
+                pending.push((seq, *config));
+            }
+            Ok(Entry::Ack { seq }) => {
+                acked.insert(seq);
+            }
+            // 最后一行可能是崩溃时写了一半的记录
+            Err(_) if index + 1 == lines.len() => {}
+            Err(e) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("corrupt journal entry on line {}: {e}", index + 1),
+                ));
+            }
+        }
+    }
+    pending.retain(|(seq, _)| !acked.contains(seq));
+    Ok((pending, next_seq))
+}
+
+fn write_entry(file: &mut File, entry: &Entry) -> io::Result<()> {
+    let mut line = serde_json::to_vec(entry).map_err(io::Error::other)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
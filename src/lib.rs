@@ -21,13 +21,28 @@
 //! pool.shutdown().unwrap();
 //! ```
 //!
+//! 也可以通过流式入口 [`Execute::cmd`] 一步构建并提交命令：
+//!
+//! ```rust,no_run
+//! use execute::{CommandPool, Duration, Execute};
+//!
+//! let pool = CommandPool::new();
+//! pool.start_executor();
+//!
+//! let handle = Execute::cmd("echo")
+//!     .arg("Hello, World!")
+//!     .timeout(Duration::from_secs(5))
+//!     .submit(&pool)
+//!     .unwrap();
+//! let output = handle.wait().unwrap();
+//! ```
+//!
 //! ## 主要特性
 //!
 //! ### 核心功能
-//! - **多线程安全的任务队列**：`CommandPool`（基于 `Mutex<VecDeque>`）
-//! - **无锁队列变体**：`CommandPoolSeg`（基于 `crossbeam_queue::SegQueue`）
+//! - **多线程安全的任务队列**：`CommandPool`（基于 `Mutex` 保护的优先级队列）
 //! - **可扩展执行器接口**：`CommandExecutor`（可集成 tokio / async-std）
-//! - **子进程超时与安全等待**：使用 `wait-timeout` 避免额外等待线程
+//! - **子进程超时与安全等待**：Linux 上基于 pidfd 等待子进程退出（其他平台使用 `wait-timeout`），避免额外等待线程
 //! - **线程池、并发限制**（信号量）和多种执行模式
 //! - **执行器停止机制**：优雅关闭执行器线程
 //! - **队列大小限制**：支持有界队列，防止内存无限增长
@@ -71,6 +86,9 @@
 //! | `minimal` | 无 | 仅核心功能 | ❌ |
 //! | `full` | 全部 | 启用所有功能 | ❌ |
 //! | `iouring` | `io-uring`, `slab` | io_uring 异步 I/O（Linux 5.1+） | ❌ |
+//! | `serde` | `serde` | 命令、管道和执行配置的序列化 | ❌ |
+//! | `persistence` | `serde`, `serde_json` | 持久化任务日志，崩溃后重放未完成的任务 | ❌ |
+//! | `pty` | `nix/term` | 在伪终端中执行命令（仅 Unix） | ❌ |
 //!
 //! ## 示例程序
 //!
@@ -96,12 +114,24 @@
 // 在 docs.rs 上显示 feature 标志
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+mod affinity;
 mod backend;
+mod barrier;
 mod batch_executor;
+mod budget;
+mod capture;
+mod checksum;
+mod child_wait;
+mod coalesce;
+mod completion;
 mod config;
+mod dag;
 mod env_optimizer;
 mod error;
+mod events;
 mod executor;
+mod fluent;
+mod global;
 #[cfg(feature = "health")]
 #[cfg_attr(docsrs, doc(cfg(feature = "health")))]
 mod health;
@@ -109,22 +139,49 @@ mod hooks;
 #[cfg(feature = "iouring")]
 #[cfg_attr(docsrs, doc(cfg(feature = "iouring")))]
 mod iouring_executor;
+#[cfg(feature = "persistence")]
+#[cfg_attr(docsrs, doc(cfg(feature = "persistence")))]
+mod journal;
 #[cfg(feature = "logging")]
 #[cfg_attr(docsrs, doc(cfg(feature = "logging")))]
 mod logging;
 #[cfg(feature = "metrics")]
 #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
 mod metrics;
+mod output_diff;
+mod output_lines;
 #[cfg(feature = "pipeline")]
 #[cfg_attr(docsrs, doc(cfg(feature = "pipeline")))]
 mod pipeline;
 mod pool;
+mod pool_builder;
 pub mod prelude;
 mod process_pool;
+pub mod process_util;
+#[cfg(all(unix, feature = "pty"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "pty"))))]
+mod pty;
+mod rate_limit;
+mod report;
+mod reservation;
+mod running_task;
+mod scheduler;
+mod scope;
 mod semaphore;
+#[cfg(feature = "serde")]
+mod serde_os;
+mod session;
+mod sink;
+mod snapshot;
+mod stats;
 mod task_handle;
+mod task_queue;
 mod task_status;
+pub mod testing;
 mod warm_pool;
+mod watchdog;
+mod worker_slots;
+mod workspace;
 mod zombie_reaper;
 
 // Re-export 标准库类型（在公共 API 中使用）
@@ -134,42 +191,80 @@ pub use std::time::Duration;
 // Re-export 外部库类型（在公共 API 中使用）
 pub use thiserror::Error;
 
-pub use backend::{ExecutionBackend, ExecutionConfig, ExecutionMode};
+pub use backend::{
+    ExecutionBackend, ExecutionConfig, ExecutionMode, QuotaBackend, RewritingBackend,
+    RoutingBackend,
+};
+pub use barrier::BarrierHandle;
 pub use batch_executor::{
     BatchConfig, BatchOutput, IndividualOutput, execute_batch_detailed, execute_parallel_batch,
     execute_sequential_batch,
 };
+pub use budget::BatchBudget;
+pub use completion::CompletionStream;
 pub use config::{
-    CommandConfig, EnvConfig, PoolConfig, PoolConfigBuilder, ResourceLimits, RetryPolicy,
-    RetryStrategy, ShutdownConfig, TimeoutConfig,
+    ArtifactAction, ArtifactConfig, CaptureMode, CommandConfig, EnvConfig, InputConfig, InputFile,
+    InputSource, IoPriority, IoPriorityClass, OutputDiffConfig, OutputFile, OutputMode, PoolConfig,
+    PoolConfigBuilder, PoolRetryPolicy, ResourceLimits, RetryOn, RetryPolicy, RetryStrategy,
+    Rlimits, ShutdownConfig, ShutdownMode, TaskDefaults, TempWorkdirConfig, TimeoutConfig,
+    TimeoutHookConfig, WatchdogConfig,
 };
+pub use dag::{NodeId, TaskGraph};
 pub use env_optimizer::{EnvCache, EnvOptimizer, apply_env_config_optimized};
 pub use error::{
     CancelError, CommandError, ConfigError, ErrorContext, ExecuteError, ShutdownError, SubmitError,
+    TaskError,
 };
+pub use events::{FinishStatus, PoolEvent};
 pub use executor::{
-    CommandExecutor, StdCommandExecutor, apply_env_config, execute_command_with_context,
-    execute_task_with_hooks, execute_with_retry, execute_with_timeouts,
+    CommandExecutor, StdCommandExecutor, apply_env_config, execute_checked,
+    execute_command_with_context, execute_streaming, execute_task_with_hooks, execute_with_report,
+    execute_with_retry, execute_with_timeouts, spawn,
 };
+pub use fluent::{CommandBuilder, Execute};
+pub use global::{global_pool, init_global_pool};
 #[cfg(feature = "health")]
 #[cfg_attr(docsrs, doc(cfg(feature = "health")))]
 pub use health::{HealthCheck, HealthDetails, HealthStatus};
-pub use hooks::{ExecutionContext, ExecutionHook, HookTaskResult};
+pub use hooks::{
+    CommandLine, CommandRewriter, ExecutionContext, ExecutionHook, HookTaskResult, PrefixRewriter,
+    TimeoutContext, TimeoutDecision, TimeoutHook,
+};
 #[cfg(feature = "iouring")]
 #[cfg_attr(docsrs, doc(cfg(feature = "iouring")))]
 pub use iouring_executor::{IoUringExecutor, execute_batch_iouring};
+#[cfg(feature = "persistence")]
+#[cfg_attr(docsrs, doc(cfg(feature = "persistence")))]
+pub use journal::TaskJournal;
 #[cfg(feature = "logging")]
 #[cfg_attr(docsrs, doc(cfg(feature = "logging")))]
 pub use logging::{LogConfig, LogFormat, LogLevel, LogTarget};
 #[cfg(feature = "metrics")]
 #[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
 pub use metrics::{Metrics, MetricsSnapshot};
+pub use output_lines::{OutputLine, OutputLines, spawn_lines};
 #[cfg(feature = "pipeline")]
 #[cfg_attr(docsrs, doc(cfg(feature = "pipeline")))]
 pub use pipeline::{Pipeline, PipelineExecutor, PipelineStage};
 pub use pool::{CommandPool, TaskItem};
+pub use pool_builder::CommandPoolBuilder;
 pub use process_pool::ProcessPool;
+#[cfg(all(unix, feature = "pty"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "pty"))))]
+pub use pty::{PtyBackend, PtySize, execute_pty, execute_pty_with_size};
+pub use report::{
+    Artifact, ExecutionReport, ExecutionTiming, OutputChange, OutputChunk, OutputStream,
+    ResourceUsage,
+};
+pub use reservation::Reservation;
+pub use running_task::RunningTask;
+pub use scheduler::{BlackoutAction, BlackoutWindows, CronSchedule, RecurringHandle};
+pub use scope::TaskScope;
 pub use semaphore::{Semaphore, SemaphoreGuard};
+pub use session::Session;
+pub use sink::ResultSink;
+pub use snapshot::{InFlightTask, QueuedTask};
+pub use stats::PoolStats;
 pub use task_handle::{CancellationToken, TaskHandle, TaskResult, TaskState, TaskWithResult};
 pub use task_status::{TaskIdGenerator, TaskStatus, TaskStatusTracker};
 pub use warm_pool::{WarmExecutor, WarmProcessPool};
@@ -29,7 +29,7 @@ fn main() -> Result<(), execute::ExecuteError> {
     command_pool.start_executor();
 
     let pool_clone = command_pool.clone();
-    thread::spawn(move || {
+    let producer = thread::spawn(move || {
         println!("[producer] 向池中推入第一个任务");
         let task1 = CommandConfig::new("echo", vec!["第一次任务执行".to_string()]);
         let _ = pool_clone.push_task(task1);
@@ -51,7 +51,8 @@ fn main() -> Result<(), execute::ExecuteError> {
     });
 
     println!("[main] 等待所有任务执行完毕...");
-    thread::sleep(Duration::from_secs(15));
+    let _ = producer.join();
+    command_pool.wait_idle();
     println!("[main] 程序结束");
     Ok(())
 }
@@ -113,24 +114,15 @@ fn run_worker_mode() -> Result<(), execute::ExecuteError> {
                 .spawn()
                 .and_then(|mut child| {
                     let timeout = Duration::from_secs(timeout_secs);
+                    // 等待期间在后台读取输出，避免子进程因管道写满而阻塞
+                    let out_reader = drain(child.stdout.take());
+                    let err_reader = drain(child.stderr.take());
                     match child.wait_timeout(timeout) {
-                        Ok(Some(status)) => {
-                            let mut out = Vec::new();
-                            let mut err = Vec::new();
-                            if let Some(mut stdout) = child.stdout.take() {
-                                use std::io::Read;
-                                let _ = stdout.read_to_end(&mut out);
-                            }
-                            if let Some(mut stderr) = child.stderr.take() {
-                                use std::io::Read;
-                                let _ = stderr.read_to_end(&mut err);
-                            }
-                            Ok(std::process::Output {
-                                status,
-                                stdout: out,
-                                stderr: err,
-                            })
-                        }
+                        Ok(Some(status)) => Ok(std::process::Output {
+                            status,
+                            stdout: out_reader.join().unwrap_or_default(),
+                            stderr: err_reader.join().unwrap_or_default(),
+                        }),
                         Ok(None) => {
                             let _ = child.kill();
                             Err(std::io::Error::new(
@@ -172,3 +164,14 @@ fn run_worker_mode() -> Result<(), execute::ExecuteError> {
 
     Ok(())
 }
+
+/// 在后台线程中读取管道到结束
+fn drain<R: io::Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
@@ -36,6 +36,7 @@ pub struct Metrics {
     pub(crate) tasks_completed: Arc<AtomicU64>,
     pub(crate) tasks_failed: Arc<AtomicU64>,
     pub(crate) tasks_cancelled: Arc<AtomicU64>,
+    pub(crate) workers_stuck: Arc<AtomicU64>,
 
     // 当前状态
     pub(crate) tasks_queued: Arc<AtomicUsize>,
@@ -65,6 +66,7 @@ impl Metrics {
             tasks_completed: Arc::new(AtomicU64::new(0)),
             tasks_failed: Arc::new(AtomicU64::new(0)),
             tasks_cancelled: Arc::new(AtomicU64::new(0)),
+            workers_stuck: Arc::new(AtomicU64::new(0)),
             tasks_queued: Arc::new(AtomicUsize::new(0)),
             tasks_running: Arc::new(AtomicUsize::new(0)),
             execution_stats: Arc::new(RwLock::new(ExecutionStats::new())),
@@ -193,6 +195,21 @@ impl Metrics {
         self.tasks_queued.fetch_sub(1, Ordering::Relaxed);
     }
 
+    /// 记录看门狗发现的卡住工作线程
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use execute::Metrics;
+    ///
+    /// let metrics = Metrics::new();
+    /// metrics.record_worker_stuck();
+    /// assert_eq!(metrics.snapshot().workers_stuck, 1);
+    /// ```
+    pub fn record_worker_stuck(&self) {
+        self.workers_stuck.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// 获取指标快照
     ///
     /// 返回当前时刻的指标快照，包括所有计数器和统计信息。
@@ -241,6 +258,7 @@ impl Metrics {
             tasks_completed: completed,
             tasks_failed: failed,
             tasks_cancelled: self.tasks_cancelled.load(Ordering::Relaxed),
+            workers_stuck: self.workers_stuck.load(Ordering::Relaxed),
             tasks_queued: self.tasks_queued.load(Ordering::Relaxed),
             tasks_running: self.tasks_running.load(Ordering::Relaxed),
             success_rate,
@@ -349,6 +367,7 @@ impl Clone for ExecutionStats {
 /// * `tasks_completed` - 已成功完成的任务总数
 /// * `tasks_failed` - 失败的任务总数
 /// * `tasks_cancelled` - 被取消的任务总数
+/// * `workers_stuck` - 看门狗发现的卡住工作线程次数
 /// * `tasks_queued` - 当前队列中的任务数
 /// * `tasks_running` - 当前正在执行的任务数
 /// * `success_rate` - 成功率（0.0 - 1.0）
@@ -386,6 +405,7 @@ pub struct MetricsSnapshot {
     pub tasks_completed: u64,
     pub tasks_failed: u64,
     pub tasks_cancelled: u64,
+    pub workers_stuck: u64,
     pub tasks_queued: usize,
     pub tasks_running: usize,
     pub success_rate: f64,
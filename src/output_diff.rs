@@ -0,0 +1,278 @@
+//! 同一任务多次运行之间的输出对比
+//!
+//! 命令池按 [`OutputDiffConfig`] 的键保存上一次运行的 stdout 哈希，
+//! 需要差异时同时保存内容，并生成统一格式差异。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::checksum::sha256_hex;
+use crate::config::{CommandConfig, OutputDiffConfig};
+use crate::report::{ExecutionReport, OutputChange};
+
+/// 去掉公共前后缀后允许的 LCS 表最大单元数，超过时不生成差异
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// 上一次运行的输出
+struct PreviousOutput {
+    sha256: String,
+    content: Option<Vec<u8>>,
+}
+
+/// 按任务键保存的输出历史（与子池共享）
+#[derive(Default)]
+pub(crate) struct OutputHistory {
+    entries: Mutex<HashMap<String, PreviousOutput>>,
+}
+
+impl OutputHistory {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// 与上一次运行比较并记录本次输出
+    ///
+    /// 未启用输出对比的任务不做任何处理。
+    pub(crate) fn record(&self, config: &CommandConfig, report: &mut ExecutionReport) {
+        let Some(diff_config) = config.output_diff() else {
+            return;
+        };
+        let stdout = &report.output.stdout;
+        let sha256 = sha256_hex(stdout);
+
+        let mut entries = self.entries.lock().unwrap();
+        let change = match entries.get(&diff_config.key) {
+            None => OutputChange::First,
+            Some(previous) if previous.sha256 == sha256 => OutputChange::Unchanged,
+            Some(previous) => OutputChange::Changed {
+                diff: previous
+                    .content
+                    .as_deref()
+                    .and_then(|old| unified_diff(old, stdout, diff_config)),
+            },
+        };
+        entries.insert(
+            diff_config.key.clone(),
+            PreviousOutput {
+                sha256,
+                content: diff_config.unified_diff.then(|| stdout.clone()),
+            },
+        );
+        drop(entries);
+
+        report.output_change = Some(change);
+    }
+}
+
+/// 差异中的一行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line<'a> {
+    Equal(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// 生成统一格式差异
+///
+/// 输出不是 UTF-8 或过大时返回 None。
+fn unified_diff(old: &[u8], new: &[u8], config: &OutputDiffConfig) -> Option<String> {
+    if !config.unified_diff {
+        return None;
+    }
+    let old = std::str::from_utf8(old).ok()?;
+    let new = std::str::from_utf8(new).ok()?;
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let lines = diff_lines(&old_lines, &new_lines)?;
+    Some(format_hunks(&lines, config.context_lines))
+}
+
+/// 基于最长公共子序列计算逐行差异
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Option<Vec<Line<'a>>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let (n, m) = (old_mid.len(), new_mid.len());
+    if (n + 1).saturating_mul(m + 1) > MAX_DIFF_CELLS {
+        return None;
+    }
+
+    // lcs[i][j] 为 old_mid[i..] 与 new_mid[j..] 的 LCS 长度
+    let width = m + 1;
+    let mut lcs = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * width + j] = if old_mid[i] == new_mid[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<Line<'a>> = old[..prefix].iter().map(|l| Line::Equal(l)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_mid[i] == new_mid[j] {
+            lines.push(Line::Equal(old_mid[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            lines.push(Line::Removed(old_mid[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new_mid[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old_mid[i..].iter().map(|l| Line::Removed(l)));
+    lines.extend(new_mid[j..].iter().map(|l| Line::Added(l)));
+    lines.extend(old[old.len() - suffix..].iter().map(|l| Line::Equal(l)));
+    Some(lines)
+}
+
+/// 将逐行差异按上下文行数分组为 `@@ -a,b +c,d @@` 块
+fn format_hunks(lines: &[Line<'_>], context: usize) -> String {
+    let changes: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Line::Equal(_)))
+        .map(|(index, _)| index)
+        .collect();
+
+    // 间隔不超过两倍上下文的变化合并到同一块
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &index in &changes {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    let mut out = String::from("--- previous\n+++ current\n");
+    for (start, end) in ranges {
+        // 块之前的行号（从 1 开始）
+        let (mut old_line, mut new_line) = (1, 1);
+        for line in &lines[..start] {
+            match line {
+                Line::Equal(_) => {
+                    old_line += 1;
+                    new_line += 1;
+                }
+                Line::Removed(_) => old_line += 1,
+                Line::Added(_) => new_line += 1,
+            }
+        }
+        let hunk = &lines[start..end];
+        let old_count = hunk.iter().filter(|l| !matches!(l, Line::Added(_))).count();
+        let new_count = hunk
+            .iter()
+            .filter(|l| !matches!(l, Line::Removed(_)))
+            .count();
+        // 与 diff -u 一致，空范围的起始行号为其前一行
+        if old_count == 0 {
+            old_line -= 1;
+        }
+        if new_count == 0 {
+            new_line -= 1;
+        }
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_line, old_count, new_line, new_count
+        ));
+        for line in hunk {
+            let (prefix, text) = match line {
+                Line::Equal(text) => (' ', text),
+                Line::Removed(text) => ('-', text),
+                Line::Added(text) => ('+', text),
+            };
+            out.push(prefix);
+            out.push_str(text);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> Option<String> {
+        let config = OutputDiffConfig::new("k").with_unified_diff(true);
+        unified_diff(old.as_bytes(), new.as_bytes(), &config)
+    }
+
+    #[test]
+    fn test_unified_diff_single_change() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let new = "a\nb\nc\nd\nE\nf\ng\nh\n";
+        assert_eq!(
+            diff(old, new).unwrap(),
+            "--- previous\n+++ current\n@@ -2,7 +2,7 @@\n b\n c\n d\n-e\n+E\n f\n g\n h\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("{i}\n")).collect();
+        let new: String = (1..=20)
+            .map(|i| match i {
+                2 => "two\n".to_string(),
+                19 => "nineteen\n".to_string(),
+                _ => format!("{i}\n"),
+            })
+            .collect();
+        let text = diff(&old, &new).unwrap();
+        assert_eq!(text.matches("@@ ").count(), 2);
+        assert!(text.contains("@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n"));
+        assert!(text.contains("-19\n+nineteen\n 20\n"));
+    }
+
+    #[test]
+    fn test_unified_diff_insert_into_empty() {
+        assert_eq!(
+            diff("", "x\n").unwrap(),
+            "--- previous\n+++ current\n@@ -0,0 +1,1 @@\n+x\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_rejects_binary() {
+        let config = OutputDiffConfig::new("k").with_unified_diff(true);
+        assert_eq!(unified_diff(&[0xff, 0xfe], b"x", &config), None);
+    }
+
+    #[test]
+    fn test_record_tracks_changes_per_key() {
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::{ExitStatus, Output};
+
+        let history = OutputHistory::new();
+        let config =
+            CommandConfig::new("true", vec![]).with_output_diff(OutputDiffConfig::new("k"));
+        let run = |stdout: &str| {
+            let mut report = ExecutionReport::new(Output {
+                status: ExitStatus::from_raw(0),
+                stdout: stdout.as_bytes().to_vec(),
+                stderr: Vec::new(),
+            });
+            history.record(&config, &mut report);
+            report.output_change.unwrap()
+        };
+
+        assert_eq!(run("1\n"), OutputChange::First);
+        assert_eq!(run("1\n"), OutputChange::Unchanged);
+        // 未启用差异时不保留内容
+        assert_eq!(run("2\n"), OutputChange::Changed { diff: None });
+    }
+}
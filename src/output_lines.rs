@@ -0,0 +1,174 @@
+//! 逐行读取子进程输出
+//!
+//! stdout 和 stderr 各由一个后台线程按行读取，读到的行连同来源流和时间偏移
+//! 送入同一个通道，调用方按到达顺序迭代。
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::ExitStatus;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+use crate::config::CommandConfig;
+use crate::error::ExecuteError;
+use crate::executor::spawn;
+use crate::report::OutputStream;
+use crate::running_task::RunningTask;
+
+/// 子进程输出的一行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine {
+    /// 读到该行时相对于子进程启动时刻的偏移（单调时钟）
+    pub offset: Duration,
+    /// 来源流
+    pub stream: OutputStream,
+    /// 行内容，不含行尾的 `\n` 或 `\r\n`，非 UTF-8 字节按替换字符处理
+    pub text: String,
+}
+
+/// 按到达顺序迭代子进程 stdout 和 stderr 的行
+///
+/// 由 [`spawn_lines`] 或 [`RunningTask::into_lines`] 创建。两路输出都结束后迭代结束，
+/// 之后调用 [`wait`](Self::wait) 获取退出状态。同一路输出中的行保持顺序，
+/// 两路之间按读取线程读到的先后排列。
+///
+/// 配置了超时时，超过超时（从启动时开始计算）仍未结束的子进程被终止，
+/// 迭代产生一个 [`io::ErrorKind::TimedOut`] 错误后结束。读取管道出错时产生对应的
+/// I/O 错误，另一路输出继续迭代。
+///
+/// 丢弃 `OutputLines` 会终止仍在运行的子进程。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, OutputStream, spawn_lines};
+///
+/// let mut lines = spawn_lines(&CommandConfig::new("tail", vec!["-f".into(), "app.log".into()]))?;
+/// for line in &mut lines {
+///     let line = line?;
+///     if line.stream == OutputStream::Stderr {
+///         eprintln!("[{:?}] {}", line.offset, line.text);
+///     }
+/// }
+/// let status = lines.wait()?;
+/// ```
+#[derive(Debug)]
+pub struct OutputLines {
+    task: RunningTask,
+    receiver: Receiver<io::Result<OutputLine>>,
+    /// 配置的超时
+    timeout: Option<Duration>,
+    started: Instant,
+    /// 迭代中因超时终止了子进程
+    timed_out: bool,
+}
+
+impl OutputLines {
+    pub(crate) fn new(mut task: RunningTask) -> Self {
+        let started = task.started();
+        let timeout = task.config().timeout;
+        // 不取走 stdin 时关闭它，与等待时的行为一致
+        drop(task.stdin_writer());
+        let (stdout, stderr) = task.take_output_readers();
+        let (sender, receiver) = mpsc::channel();
+        spawn_line_reader(stdout, OutputStream::Stdout, started, sender.clone());
+        spawn_line_reader(stderr, OutputStream::Stderr, started, sender);
+        Self {
+            task,
+            receiver,
+            timeout,
+            started,
+            timed_out: false,
+        }
+    }
+
+    /// 子进程 ID
+    pub fn pid(&self) -> u32 {
+        self.task.pid()
+    }
+
+    /// 等待子进程退出并返回退出状态
+    ///
+    /// 尚未迭代的行被丢弃。迭代中已因超时终止子进程时返回 [`ExecuteError::Timeout`]；
+    /// 配置了 [`with_success_codes`](crate::CommandConfig::with_success_codes)
+    /// 且退出码不在其中时返回 [`ExecuteError::UnexpectedExit`]。
+    pub fn wait(self) -> Result<ExitStatus, ExecuteError> {
+        if self.timed_out {
+            return Err(ExecuteError::Timeout(
+                self.timeout.expect("timed out without a timeout"),
+            ));
+        }
+        self.task.wait().map(|output| output.status)
+    }
+}
+
+impl Iterator for OutputLines {
+    type Item = io::Result<OutputLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.timed_out {
+            return None;
+        }
+        let Some(timeout) = self.timeout else {
+            return self.receiver.recv().ok();
+        };
+        match self
+            .receiver
+            .recv_timeout(timeout.saturating_sub(self.started.elapsed()))
+        {
+            Ok(line) => Some(line),
+            Err(RecvTimeoutError::Disconnected) => None,
+            Err(RecvTimeoutError::Timeout) => {
+                self.timed_out = true;
+                let _ = self.task.kill();
+                Some(Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("command timed out after {timeout:?}"),
+                )))
+            }
+        }
+    }
+}
+
+/// 启动子进程，按行迭代其输出
+///
+/// 等价于 `spawn(config)?.into_lines()`，参见 [`spawn`] 和 [`OutputLines`]。
+pub fn spawn_lines(config: &CommandConfig) -> Result<OutputLines, ExecuteError> {
+    spawn(config).map(RunningTask::into_lines)
+}
+
+/// 在后台线程中按行读取管道，读到的行送入通道，管道结束或出错后退出
+fn spawn_line_reader<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    stream: OutputStream,
+    started: Instant,
+    sender: Sender<io::Result<OutputLine>>,
+) {
+    let Some(pipe) = pipe else {
+        return;
+    };
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let line = match reader.read_until(b'\n', &mut buf) {
+                Ok(0) => break,
+                Ok(_) => {
+                    let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+                    let line = line.strip_suffix(b"\r").unwrap_or(line);
+                    Ok(OutputLine {
+                        offset: started.elapsed(),
+                        stream,
+                        text: String::from_utf8_lossy(line).into_owned(),
+                    })
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => Err(e),
+            };
+            let failed = line.is_err();
+            if sender.send(line).is_err() || failed {
+                break;
+            }
+        }
+    });
+}
@@ -9,6 +9,7 @@ use crate::error::ExecuteError;
 ///
 /// 表示 pipeline 中的一个命令阶段
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PipelineStage {
     /// 命令配置
     pub config: CommandConfig,
@@ -36,6 +37,7 @@ impl PipelineStage {
 ///
 /// 用于构建命令 pipeline，支持链式调用
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pipeline {
     stages: Vec<PipelineStage>,
 }
@@ -77,14 +79,7 @@ impl Pipeline {
     pub fn to_command_string(&self) -> String {
         self.stages
             .iter()
-            .map(|s| {
-                let args = s.config.args.join(" ");
-                if args.is_empty() {
-                    s.config.program.clone()
-                } else {
-                    format!("{} {}", s.config.program, args)
-                }
-            })
+            .map(|s| s.config.command_line_lossy())
             .collect::<Vec<_>>()
             .join(" | ")
     }
@@ -104,68 +99,96 @@ pub struct PipelineExecutor;
 impl PipelineExecutor {
     /// 执行 pipeline
     ///
-    /// 依次执行每个阶段的命令，将前一个阶段的 stdout 作为下一个阶段的 stdin
+    /// 依次执行每个阶段的命令，将前一个阶段的 stdout 作为下一个阶段的 stdin。
+    ///
+    /// # 错误
+    ///
+    /// 任一阶段启动失败、读写失败或退出状态不被接受时返回
+    /// [`ExecuteError::PipelineStage`]，其中包含失败阶段的序号、程序和已运行阶段的输出。
+    /// 退出状态按阶段配置的 [`CommandConfig::is_success`](crate::CommandConfig::is_success)
+    /// 判断，设置了 [`allow_failure`](crate::CommandConfig::allow_failure) 的阶段不会中断管道。
     pub fn execute(pipeline: &Pipeline) -> Result<Output, ExecuteError> {
         if pipeline.is_empty() {
             return Err(ExecuteError::Io(std::io::Error::other("pipeline is empty")));
         }
 
-        let stages = pipeline.stages();
-        let mut last_output: Option<Output> = None;
-
-        for (i, stage) in stages.iter().enumerate() {
-            let is_first = i == 0;
-            let _is_last = i == stages.len() - 1;
+        let mut outputs: Vec<Output> = Vec::with_capacity(pipeline.len());
 
-            // 构建命令
-            let mut cmd = std::process::Command::new(&stage.config.program);
-            cmd.args(&stage.config.args);
+        for (index, stage) in pipeline.stages().iter().enumerate() {
+            // 忽略输入的阶段和第一个阶段不接收前一个阶段的输出
+            let input = if stage.ignore_input {
+                None
+            } else {
+                outputs.last().map(|output| output.stdout.as_slice())
+            };
 
-            // 设置工作目录
-            if let Some(ref dir) = stage.config.working_dir {
-                cmd.current_dir(dir);
+            let result = Self::run_stage(stage, input).and_then(|output| {
+                if stage.config.is_success(&output.status) {
+                    Ok(output)
+                } else {
+                    let error = ExecuteError::Child(format!("exited with {}", output.status));
+                    outputs.push(output);
+                    Err(error)
+                }
+            });
+
+            match result {
+                Ok(output) => outputs.push(output),
+                Err(error) => {
+                    return Err(ExecuteError::PipelineStage {
+                        index,
+                        program: stage.config.program().into_owned(),
+                        source: Box::new(error),
+                        outputs,
+                    });
+                }
             }
+        }
 
-            // 如果不是第一个阶段，且不是忽略输入的阶段，将前一个输出作为输入
-            if !is_first && !stage.ignore_input && last_output.is_some() {
-                cmd.stdin(std::process::Stdio::piped());
-            }
+        // 返回最后一个阶段的输出
+        Ok(outputs.pop().expect("pipeline has at least one stage"))
+    }
 
-            // 捕获输出（除了最后一个阶段可选）
-            cmd.stdout(std::process::Stdio::piped());
-            cmd.stderr(std::process::Stdio::piped());
-
-            // 启动进程
-            let mut child = cmd.spawn().map_err(ExecuteError::Io)?;
-
-            // 如果不是第一个阶段，写入前一个阶段的输出
-            if !is_first
-                && !stage.ignore_input
-                && let Some(ref prev_output) = last_output
-                && let Some(mut stdin) = child.stdin.take()
-            {
-                use std::io::Write;
-                stdin
-                    .write_all(&prev_output.stdout)
-                    .map_err(ExecuteError::Io)?;
-                // 必须关闭 stdin，否则子进程会一直等待输入
-                drop(stdin);
-            }
+    /// 执行单个阶段，`input` 写入阶段的 stdin
+    fn run_stage(stage: &PipelineStage, input: Option<&[u8]>) -> Result<Output, ExecuteError> {
+        // 构建命令
+        let mut cmd = std::process::Command::new(&stage.config.program);
+        cmd.args(&stage.config.args);
 
-            // 等待进程完成
-            let output = child.wait_with_output().map_err(ExecuteError::Io)?;
+        // 设置工作目录
+        if let Some(ref dir) = stage.config.working_dir {
+            cmd.current_dir(dir);
+        }
 
-            // 检查是否成功
-            if !output.status.success() {
-                return Ok(output);
-            }
+        if input.is_some() {
+            cmd.stdin(std::process::Stdio::piped());
+        }
 
-            last_output = Some(output);
+        // 捕获输出
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
+
+        // 启动进程
+        let mut child = cmd.spawn().map_err(|e| {
+            ExecuteError::spawn_failed(
+                e,
+                &stage.config.program,
+                stage.config.working_dir.as_deref(),
+            )
+        })?;
+
+        // 写入前一个阶段的输出
+        if let Some(input) = input
+            && let Some(mut stdin) = child.stdin.take()
+        {
+            use std::io::Write;
+            stdin.write_all(input).map_err(ExecuteError::Io)?;
+            // 必须关闭 stdin，否则子进程会一直等待输入
+            drop(stdin);
         }
 
-        // 返回最后一个阶段的输出
-        last_output
-            .ok_or_else(|| ExecuteError::Io(std::io::Error::other("pipeline execution failed")))
+        // 等待进程完成
+        child.wait_with_output().map_err(ExecuteError::Io)
     }
 
     /// 异步执行 pipeline（在单独线程中）
@@ -254,6 +277,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pipeline_executor_reports_failing_stage() {
+        let pipeline = Pipeline::new()
+            .pipe(CommandConfig::new("echo", vec!["hello".to_string()]))
+            .pipe(CommandConfig::new(
+                "sh",
+                vec!["-c".to_string(), "cat; echo oops >&2; exit 3".to_string()],
+            ))
+            .pipe(CommandConfig::new("cat", vec![]));
+
+        match PipelineExecutor::execute(&pipeline) {
+            Err(ExecuteError::PipelineStage {
+                index,
+                program,
+                source,
+                outputs,
+            }) => {
+                assert_eq!(index, 1);
+                assert_eq!(program, "sh");
+                assert!(matches!(*source, ExecuteError::Child(_)));
+                assert_eq!(outputs.len(), 2);
+                assert_eq!(outputs[0].stdout, b"hello\n");
+                assert_eq!(outputs[1].stdout, b"hello\n");
+                assert_eq!(outputs[1].stderr, b"oops\n");
+                assert_eq!(outputs[1].status.code(), Some(3));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn pipeline_executor_reports_spawn_failure_stage() {
+        let pipeline = Pipeline::new()
+            .pipe(CommandConfig::new("echo", vec!["hello".to_string()]))
+            .pipe(CommandConfig::new("nonexistent_pipeline_stage", vec![]));
+
+        match PipelineExecutor::execute(&pipeline) {
+            Err(ExecuteError::PipelineStage {
+                index,
+                source,
+                outputs,
+                ..
+            }) => {
+                assert_eq!(index, 1);
+                assert!(matches!(*source, ExecuteError::ProgramNotFound { .. }));
+                assert_eq!(outputs.len(), 1);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
     #[test]
     fn pipeline_executor_async() {
         let pipeline = Pipeline::new().pipe(CommandConfig::new("echo", vec!["async".to_string()]));
@@ -1,21 +1,44 @@
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, Condvar, Mutex};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::thread::{self, JoinHandle};
-#[cfg(feature = "health")]
-use std::time::SystemTime;
-use std::time::{Duration, Instant};
-
-use crate::backend::{BackendFactory, ExecutionBackend, ExecutionConfig, ExecutionMode};
-use crate::config::{CommandConfig, ShutdownConfig};
-use crate::error::{ExecuteError, ShutdownError, SubmitError};
-use crate::executor::CommandExecutor;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::affinity::worker_for_key;
+use crate::backend::{
+    BackendFactory, ExecutionBackend, ExecutionConfig, ExecutionMode, QuotaBackend,
+};
+use crate::barrier::BarrierHandle;
+use crate::coalesce::CoalesceTable;
+use crate::config::{
+    CommandConfig, PoolRetryPolicy, ShutdownConfig, ShutdownMode, TaskDefaults, WatchdogConfig,
+};
+use crate::error::{ConfigError, ExecuteError, ShutdownError, SubmitError};
+use crate::events::{EventBus, FinishStatus, PoolEvent};
+use crate::executor::{CommandExecutor, with_spawn_observer};
 #[cfg(feature = "health")]
 use crate::health::{HealthCheck, HealthDetails, HealthStatus};
-use crate::hooks::ExecutionHook;
+use crate::hooks::{CommandRewriter, ExecutionHook, rewrite_config};
+#[cfg(feature = "persistence")]
+use crate::journal::TaskJournal;
 #[cfg(feature = "metrics")]
 use crate::metrics::Metrics;
+use crate::output_diff::OutputHistory;
+use crate::pool_builder::CommandPoolBuilder;
+use crate::rate_limit::RateLimiter;
+use crate::report::{ExecutionReport, ExecutionTiming};
+use crate::reservation::Reservation;
+use crate::scheduler::{BlackoutAction, BlackoutWindows, CronSchedule, RecurringHandle};
+use crate::sink::ResultSink;
+use crate::snapshot::{InFlight, InFlightTask, QueuedTask};
+use crate::stats::{PoolStats, StatsCounters};
 use crate::task_handle::{TaskHandle, TaskResult, TaskState};
+use crate::task_queue::TaskQueue;
+use crate::task_status::{TaskIdGenerator, TaskStatus, TaskStatusTracker};
+use crate::watchdog::{ActivityGuard, WorkerActivity};
+use crate::worker_slots::{BusyGuard, SlotGuard, WorkerSlots};
 use crate::zombie_reaper::ZombieReaper;
 
 /// 任务项，包含配置和句柄
@@ -30,6 +53,146 @@ pub struct TaskItem {
     pub result_sender: std::sync::mpsc::Sender<TaskResult>,
 }
 
+/// 入队时占用队列空位的方式
+enum Admission<'a> {
+    /// 队列满时等待到截止时间（None 表示一直等待）
+    Wait(Option<Instant>),
+    /// 使用预留的空位，入队时从剩余数中扣除
+    Reserved(&'a mut usize),
+}
+
+/// 任务结束回调，参数为任务 ID 和任务结果
+type TaskCallback = Arc<dyn Fn(u64, &TaskResult) + Send + Sync>;
+
+/// 启动工作线程的函数，参数为序号、代号和启动延迟
+type WorkerSpawner =
+    Arc<dyn Fn(&CommandPool, usize, u64, Duration) -> JoinHandle<()> + Send + Sync>;
+
+/// 通过 `on_task_complete` / `on_task_failed` 注册的回调
+#[derive(Default)]
+struct TaskCallbacks {
+    complete: Vec<TaskCallback>,
+    failed: Vec<TaskCallback>,
+}
+
+/// 单例任务键的占用守卫，丢弃时释放键
+struct SingletonGuard {
+    key: String,
+    singletons: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Drop for SingletonGuard {
+    fn drop(&mut self) {
+        if let Ok(mut singletons) = self.singletons.lock() {
+            singletons.remove(&self.key);
+        }
+    }
+}
+
+/// 分派时占用的串行键、后端名额和并发键名额，丢弃时释放并唤醒等待的工作线程
+struct DispatchGuard {
+    serial_key: Option<String>,
+    backend: Option<String>,
+    concurrency_key: Option<String>,
+    tasks: Arc<(Mutex<TaskQueue>, Condvar)>,
+    serial_keys: Arc<Mutex<HashSet<String>>>,
+    backend_slots: Arc<Mutex<HashMap<String, usize>>>,
+    key_slots: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+/// 释放一个名额，计数归零时移除该项
+fn release_slot(slots: &Mutex<HashMap<String, usize>>, name: &str) {
+    if let Ok(mut slots) = slots.lock()
+        && let Some(count) = slots.get_mut(name)
+    {
+        *count -= 1;
+        if *count == 0 {
+            slots.remove(name);
+        }
+    }
+}
+
+/// 任务本次执行前在队列中等待的时间
+///
+/// 从提交时开始计算；计划执行或等待重试的任务从计划时间开始计算。
+fn queue_wait(item: &TaskItem) -> Duration {
+    let waited = item.handle.submitted_at().elapsed();
+    match item.config.start_at() {
+        Some(at) => waited.min(SystemTime::now().duration_since(at).unwrap_or_default()),
+        None => waited,
+    }
+}
+
+impl Drop for DispatchGuard {
+    fn drop(&mut self) {
+        // 与出队相同，先持有队列锁再修改占用状态，避免工作线程错过唤醒
+        let (lock, cvar) = &*self.tasks;
+        let _tasks = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(key) = &self.serial_key
+            && let Ok(mut keys) = self.serial_keys.lock()
+        {
+            keys.remove(key);
+        }
+        if let Some(name) = &self.backend {
+            release_slot(&self.backend_slots, name);
+        }
+        if let Some(key) = &self.concurrency_key {
+            release_slot(&self.key_slots, key);
+        }
+        cvar.notify_all();
+    }
+}
+
+/// 命令池级别的调度选项
+///
+/// 通过 [`CommandPoolBuilder`] 的 `with_*` 方法设置，子池继承父池的选项。
+#[derive(Debug, Clone)]
+pub(crate) struct PoolOptions {
+    /// 工作线程启动间隔（None 表示同时启动所有工作线程）
+    pub(crate) worker_start_interval: Option<Duration>,
+    /// 应用到每个任务的默认环境变量和工作目录
+    pub(crate) task_defaults: TaskDefaults,
+    /// 队列高水位阈值（达到时发布 `QueueHighWatermark` 事件）
+    pub(crate) queue_high_watermark: Option<usize>,
+    /// 首个任务失败后停止分派并取消其余任务
+    pub(crate) fail_fast: bool,
+    /// 按后端名称限制同时执行的任务数
+    pub(crate) backend_limits: HashMap<String, usize>,
+    /// 按并发键限制同时执行的任务数
+    pub(crate) concurrency_key_limits: HashMap<String, usize>,
+    /// 卡住工作线程看门狗（None 表示不检测）
+    pub(crate) watchdog: Option<WatchdogConfig>,
+    /// 命令池级别的自动重试策略（None 表示不重试）
+    pub(crate) retry_policy: Option<PoolRetryPolicy>,
+    /// 每秒最多启动的任务数（None 表示不限制）
+    pub(crate) rate_limit: Option<u32>,
+    /// 工作线程名称前缀，线程命名为 `<前缀>-<序号>`
+    pub(crate) worker_name_prefix: String,
+    /// 最少空闲工作线程数（None 表示执行器启动时启动全部工作线程）
+    pub(crate) min_idle_workers: Option<usize>,
+    /// 多出的工作线程空闲多久后退出（None 表示不退出）
+    pub(crate) idle_timeout: Option<Duration>,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self {
+            worker_start_interval: None,
+            task_defaults: TaskDefaults::default(),
+            queue_high_watermark: None,
+            fail_fast: false,
+            backend_limits: HashMap::new(),
+            concurrency_key_limits: HashMap::new(),
+            watchdog: None,
+            retry_policy: None,
+            rate_limit: None,
+            worker_name_prefix: "execute-worker".to_string(),
+            min_idle_workers: None,
+            idle_timeout: None,
+        }
+    }
+}
+
 /// 命令池，支持多线程和多进程两种执行模式
 ///
 /// `CommandPool` 是主要的任务调度器，负责任务的提交、调度和生命周期管理。
@@ -68,9 +231,11 @@ pub struct TaskItem {
 /// - 默认情况下队列为无界队列，如需限制大小可使用 `with_config_and_limit`
 pub struct CommandPool {
     /// 任务队列和条件变量（用于线程同步）
-    tasks: Arc<(Mutex<VecDeque<TaskItem>>, Condvar)>,
+    tasks: Arc<(Mutex<TaskQueue>, Condvar)>,
     /// 执行配置（线程数、执行模式等）
     config: ExecutionConfig,
+    /// 调度选项（重试、限流、看门狗等）
+    options: PoolOptions,
     /// 执行后端（决定使用线程还是进程执行）
     backend: Arc<dyn ExecutionBackend>,
     /// 运行状态标志
@@ -83,7 +248,9 @@ pub struct CommandPool {
     #[cfg(feature = "metrics")]
     metrics: Metrics,
     /// 任务 ID 生成器
-    task_id_counter: Arc<AtomicU64>,
+    task_ids: Arc<TaskIdGenerator>,
+    /// 任务状态（与克隆共享，子池独立记录）
+    task_statuses: TaskStatusTracker,
     /// 关闭标志
     shutdown_flag: Arc<AtomicBool>,
     /// 关闭配置（超时时间、是否强制终止等）
@@ -93,6 +260,55 @@ pub struct CommandPool {
     zombie_reaper: Option<ZombieReaper>,
     /// 执行钩子（用于性能分析、监控等）
     hooks: Vec<Arc<dyn ExecutionHook>>,
+    /// 命令改写器（启动子进程前依次应用）
+    rewriters: Vec<Arc<dyn CommandRewriter>>,
+    /// 池名称（子池创建时指定）
+    name: Option<String>,
+    /// 正在运行的单例任务键（与子池共享）
+    singletons: Arc<Mutex<HashSet<String>>>,
+    /// 等待或执行中的可合并任务
+    coalesced: Arc<CoalesceTable>,
+    /// 去重键到持有该键的任务 ID，提交过程中为 None（子池独立记录）
+    dedup_keys: Arc<Mutex<HashMap<String, Option<u64>>>>,
+    /// 正在执行的串行键（仅在持有队列锁时访问）
+    serial_keys: Arc<Mutex<HashSet<String>>>,
+    /// 各受限后端正在执行的任务数（仅在持有队列锁时访问）
+    backend_slots: Arc<Mutex<HashMap<String, usize>>>,
+    /// 各并发键正在执行的任务数
+    key_slots: Arc<Mutex<HashMap<String, usize>>>,
+    /// 任务启动速率限制（与子池不共享）
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// 快速失败模式下触发停止的首个失败任务
+    first_failure: Arc<Mutex<Option<u64>>>,
+    /// 正在执行的任务（快速失败触发或中止关闭时取消）
+    in_flight: Arc<Mutex<HashMap<u64, InFlight>>>,
+    /// 已入队但尚未发送结果的任务数，归零时唤醒 `wait_idle`
+    outstanding: Arc<(Mutex<usize>, Condvar)>,
+    /// 按排空模式关闭时，等待队列排空期间拒绝新任务
+    draining: Arc<AtomicBool>,
+    /// 按任务键保存的上一次输出（与子池共享）
+    output_history: Arc<OutputHistory>,
+    /// 任务结束回调（与克隆共享，子池不继承）
+    callbacks: Arc<RwLock<TaskCallbacks>>,
+    /// 任务结果接收端（子池不继承）
+    result_sink: Option<Arc<dyn ResultSink>>,
+    /// 持久化任务日志（子池不继承）
+    #[cfg(feature = "persistence")]
+    journal: Option<Arc<TaskJournal>>,
+    /// 运行统计（与克隆共享，子池独立统计）
+    stats: Arc<StatsCounters>,
+    /// 事件总线
+    events: Arc<EventBus>,
+    /// 队列长度是否处于高水位之上（用于边沿触发高水位事件）
+    above_watermark: Arc<AtomicBool>,
+    /// 各工作线程正在执行的任务（供看门狗检查）
+    activity: Arc<WorkerActivity>,
+    /// 各序号工作线程的存活情况和忙碌的工作线程数
+    worker_slots: Arc<Mutex<WorkerSlots>>,
+    /// 当前执行器启动工作线程的方式，用于按需启动和看门狗替补
+    spawner: Arc<Mutex<Option<WorkerSpawner>>>,
+    /// 工作线程、看门狗和周期任务调度线程持有的克隆，丢弃时不关闭命令池
+    retired: bool,
 }
 
 impl CommandPool {
@@ -145,24 +361,7 @@ impl CommandPool {
             "CommandPool initialized"
         );
 
-        // 如果配置了僵尸进程清理间隔，启动清理器
-        let zombie_reaper = config.zombie_reaper_interval.map(ZombieReaper::new);
-
-        Self {
-            tasks: Arc::new((Mutex::new(VecDeque::new()), Condvar::new())),
-            config,
-            backend,
-            running: Arc::new(AtomicBool::new(false)),
-            handles: Arc::new(Mutex::new(Vec::new())),
-            max_size: None,
-            #[cfg(feature = "metrics")]
-            metrics: Metrics::new(),
-            task_id_counter: Arc::new(AtomicU64::new(1)),
-            shutdown_flag: Arc::new(AtomicBool::new(false)),
-            shutdown_config: ShutdownConfig::default(),
-            zombie_reaper,
-            hooks: Vec::new(),
-        }
+        Self::from_backend(config, PoolOptions::default(), backend, None)
     }
 
     /// 使用指定配置和队列大小限制创建命令池
@@ -197,26 +396,165 @@ impl CommandPool {
             "CommandPool initialized with queue limit"
         );
 
+        Self::from_backend(config, PoolOptions::default(), backend, Some(max_size))
+    }
+
+    /// 使用自定义执行后端创建命令池
+    ///
+    /// 任务通过 `backend` 执行，而不是按 `config.mode` 创建的默认后端。
+    /// 可用于包装后端（如 [`RewritingBackend`](crate::RewritingBackend)）或在测试中接入
+    /// [`MockBackend`](crate::testing::MockBackend)。`config` 中的并发限制不会应用到自定义后端。
+    ///
+    /// ## 示例
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use execute::testing::MockBackend;
+    /// use execute::{CommandPool, ExecutionConfig};
+    ///
+    /// let backend = Arc::new(MockBackend::new());
+    /// let pool = CommandPool::with_backend(ExecutionConfig::default(), backend);
+    /// ```
+    pub fn with_backend(config: ExecutionConfig, backend: Arc<dyn ExecutionBackend>) -> Self {
+        Self::from_backend(config, PoolOptions::default(), backend, None)
+    }
+
+    /// 创建命令池构建器
+    ///
+    /// 在一处设置队列上限、执行模式、工作线程数、重试策略、回调等选项，
+    /// 详见 [`CommandPoolBuilder`]。
+    pub fn builder() -> CommandPoolBuilder {
+        CommandPoolBuilder::new()
+    }
+
+    /// 使用给定后端构造命令池
+    pub(crate) fn from_backend(
+        config: ExecutionConfig,
+        options: PoolOptions,
+        backend: Arc<dyn ExecutionBackend>,
+        max_size: Option<usize>,
+    ) -> Self {
         // 如果配置了僵尸进程清理间隔，启动清理器
         let zombie_reaper = config.zombie_reaper_interval.map(ZombieReaper::new);
+        let rate_limiter = options
+            .rate_limit
+            .map(|rate| Arc::new(RateLimiter::new(rate)));
+        let workers = config.workers;
 
         Self {
-            tasks: Arc::new((Mutex::new(VecDeque::new()), Condvar::new())),
+            tasks: Arc::new((Mutex::new(TaskQueue::new()), Condvar::new())),
             config,
+            options,
             backend,
             running: Arc::new(AtomicBool::new(false)),
             handles: Arc::new(Mutex::new(Vec::new())),
-            max_size: Some(max_size),
+            max_size,
             #[cfg(feature = "metrics")]
             metrics: Metrics::new(),
-            task_id_counter: Arc::new(AtomicU64::new(1)),
+            task_ids: Arc::new(TaskIdGenerator::new()),
+            task_statuses: TaskStatusTracker::new(),
             shutdown_flag: Arc::new(AtomicBool::new(false)),
             shutdown_config: ShutdownConfig::default(),
             zombie_reaper,
             hooks: Vec::new(),
+            rewriters: Vec::new(),
+            name: None,
+            singletons: Arc::new(Mutex::new(HashSet::new())),
+            coalesced: Arc::new(CoalesceTable::new()),
+            dedup_keys: Arc::new(Mutex::new(HashMap::new())),
+            serial_keys: Arc::new(Mutex::new(HashSet::new())),
+            backend_slots: Arc::new(Mutex::new(HashMap::new())),
+            key_slots: Arc::new(Mutex::new(HashMap::new())),
+            rate_limiter,
+            first_failure: Arc::new(Mutex::new(None)),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            outstanding: Arc::new((Mutex::new(0), Condvar::new())),
+            draining: Arc::new(AtomicBool::new(false)),
+            output_history: Arc::new(OutputHistory::new()),
+            callbacks: Arc::new(RwLock::new(TaskCallbacks::default())),
+            result_sink: None,
+            #[cfg(feature = "persistence")]
+            journal: None,
+            stats: Arc::new(StatsCounters::new()),
+            events: Arc::new(EventBus::new()),
+            above_watermark: Arc::new(AtomicBool::new(false)),
+            activity: Arc::new(WorkerActivity::new()),
+            worker_slots: Arc::new(Mutex::new(WorkerSlots::new(workers))),
+            spawner: Arc::new(Mutex::new(None)),
+            retired: false,
         }
     }
 
+    /// 创建从当前池并发预算中分配配额的子池
+    ///
+    /// 子池拥有独立的任务队列和工作线程，但所有命令都经由父池的执行后端运行，
+    /// 因此同时受两层限制：
+    /// - 子池自身最多同时执行 `limit` 个命令
+    /// - 父池的 `concurrency_limit` 由父池与其所有子池共同消耗
+    ///
+    /// 这样多个团队可以各自使用隔离的子池，而不会像多个独立命令池那样
+    /// 合计超额占用主机资源。子池可以继续创建子池，形成多级配额。
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 子池名称，用于日志和诊断
+    /// * `limit` - 子池的最大并发数（必须大于 0）
+    ///
+    /// # 注意事项
+    ///
+    /// - 子池需要单独调用 `start_executor()` 启动，并单独关闭
+    /// - 子池继承父池的执行模式和钩子，工作线程数等于 `limit`
+    ///
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// use execute::{CommandConfig, CommandPool, ExecutionConfig};
+    ///
+    /// // 父池最多同时运行 16 个子进程
+    /// let parent = CommandPool::with_config(
+    ///     ExecutionConfig::new().with_workers(16).with_concurrency_limit(16),
+    /// );
+    ///
+    /// // "video" 子池最多占用其中 4 个
+    /// let video = parent.sub_pool("video", 4);
+    /// video.start_executor();
+    /// video.push_task(CommandConfig::new("ffmpeg", vec!["-version".to_string()])).unwrap();
+    /// ```
+    pub fn sub_pool(&self, name: &str, limit: usize) -> CommandPool {
+        assert!(limit > 0, "sub pool limit must be greater than 0");
+
+        let mut config = self.config.clone();
+        config.workers = limit;
+        config.concurrency_limit = Some(limit);
+        // 僵尸进程清理由父池负责
+        config.zombie_reaper_interval = None;
+
+        let backend: Arc<dyn ExecutionBackend> =
+            Arc::new(QuotaBackend::new(Arc::clone(&self.backend), limit));
+
+        #[cfg(feature = "logging")]
+        tracing::info!(
+            name = name,
+            limit = limit,
+            parent = ?self.name,
+            "Sub pool created"
+        );
+
+        let mut pool = Self::from_backend(config, self.options.clone(), backend, self.max_size);
+        pool.shutdown_config = self.shutdown_config.clone();
+        pool.hooks = self.hooks.clone();
+        pool.rewriters = self.rewriters.clone();
+        pool.name = Some(name.to_string());
+        pool.singletons = Arc::clone(&self.singletons);
+        pool.output_history = Arc::clone(&self.output_history);
+        pool
+    }
+
+    /// 获取池名称（仅子池有名称）
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     /// 添加执行钩子
     ///
     /// 钩子允许在任务执行前后插入自定义逻辑，用于性能分析、监控等。
@@ -262,6 +600,142 @@ impl CommandPool {
         self
     }
 
+    /// 添加命令改写器
+    ///
+    /// 改写器在任务交给执行后端之前应用，可以统一包裹或修改命令行，
+    /// 例如加上 `nice -n 19` 或公司内部的包装脚本。可以多次调用，
+    /// 按注册顺序依次应用。子池继承父池的改写器。
+    ///
+    /// # 示例
+    ///
+    /// ```ignore
+    /// use execute::{CommandPool, PrefixRewriter};
+    /// use std::sync::Arc;
+    ///
+    /// let pool = CommandPool::new()
+    ///     .with_rewriter(Arc::new(PrefixRewriter::new("nice", ["-n", "19"])));
+    /// ```
+    pub fn with_rewriter(mut self, rewriter: Arc<dyn CommandRewriter>) -> Self {
+        self.rewriters.push(rewriter);
+        self
+    }
+
+    /// 设置任务结果接收端
+    ///
+    /// 每个结束的任务（包括失败、超时、取消和跳过，合并执行的任务各一次）都会把
+    /// `(任务 ID, 结果)` 交给 `sink`，可以按完成顺序收集结果而不必持有任务句柄。
+    /// 任务句柄仍然会收到同样的结果。再次调用会替换之前的接收端，子池不继承。
+    ///
+    /// # 示例
+    ///
+    /// ```ignore
+    /// use execute::CommandPool;
+    /// use std::sync::Arc;
+    /// use std::sync::mpsc::channel;
+    ///
+    /// let (sender, results) = channel();
+    /// let pool = CommandPool::new().with_result_sink(Arc::new(sender));
+    /// ```
+    pub fn with_result_sink(mut self, sink: Arc<dyn ResultSink>) -> Self {
+        self.result_sink = Some(sink);
+        self
+    }
+
+    /// 使用指定的指标收集器
+    ///
+    /// 多个命令池共享同一个 [`Metrics`] 时，指标合并统计。需要启用 `metrics` feature。
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 设置持久化任务日志
+    ///
+    /// 之后提交的任务在入队前写入日志，任务结束后确认（手动确认模式除外）。
+    /// 因命令池关闭而被取消的任务不会确认，下次启动时随
+    /// [`replay_journal`](Self::replay_journal) 重新执行。子池不继承日志。
+    ///
+    /// 需要启用 `persistence` feature。
+    #[cfg(feature = "persistence")]
+    pub fn with_journal(mut self, journal: TaskJournal) -> Self {
+        self.journal = Some(Arc::new(journal));
+        self
+    }
+
+    /// 重新提交持久化日志中上一次运行没有确认的任务
+    ///
+    /// 按原提交顺序入队，返回新任务的句柄；没有设置日志时返回空列表。
+    /// 重新提交的任务沿用原日志记录，结束后确认该记录。
+    ///
+    /// # 错误
+    ///
+    /// 命令池拒绝任务（如正在关闭）时停止重放并返回错误，剩余任务保留在日志中，
+    /// 已经重新提交的任务照常执行。
+    #[cfg(feature = "persistence")]
+    pub fn replay_journal(&self) -> Result<Vec<TaskHandle>, SubmitError> {
+        let Some(journal) = &self.journal else {
+            return Ok(Vec::new());
+        };
+        let mut recovered = journal.take_recovered().into_iter();
+        let mut handles = Vec::new();
+        while let Some((seq, config)) = recovered.next() {
+            match self.push_task_until(config.clone(), Admission::Wait(None), Some(seq)) {
+                Ok(handle) => handles.push(handle),
+                Err(e) => {
+                    journal.restore(seq, config);
+                    for (seq, config) in recovered {
+                        journal.restore(seq, config);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(handles)
+    }
+
+    /// 在持久化日志中确认任务已完成，返回任务是否有待确认的记录
+    ///
+    /// 手动确认模式下，调用方处理完任务结果后调用；自动确认模式下任务结束时已确认，
+    /// 再次调用返回 `Ok(false)`。没有设置日志时始终返回 `Ok(false)`。
+    #[cfg(feature = "persistence")]
+    pub fn acknowledge(&self, task_id: u64) -> std::io::Result<bool> {
+        match &self.journal {
+            Some(journal) => journal.acknowledge(task_id),
+            None => Ok(false),
+        }
+    }
+
+    /// 自动确认模式下确认结束的任务，因关闭而取消的任务保留记录以便重放
+    #[cfg(feature = "persistence")]
+    fn acknowledge_finished(&self, task_ids: &[u64], status: &FinishStatus) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        if journal.manual_ack()
+            || (*status == FinishStatus::Cancelled && self.shutdown_flag.load(Ordering::SeqCst))
+        {
+            return;
+        }
+        for &task_id in task_ids {
+            if let Err(e) = journal.acknowledge(task_id) {
+                #[cfg(feature = "logging")]
+                tracing::warn!(task_id = task_id, error = %e, "Failed to acknowledge journal entry");
+                #[cfg(not(feature = "logging"))]
+                let _ = e;
+            }
+        }
+    }
+
+    /// 应用命令改写器，未注册改写器时直接借用原配置
+    fn rewrite_command<'a>(&self, config: &'a CommandConfig) -> Cow<'a, CommandConfig> {
+        if self.rewriters.is_empty() {
+            Cow::Borrowed(config)
+        } else {
+            Cow::Owned(rewrite_config(config, &self.rewriters))
+        }
+    }
+
     /// 添加任务（如果设置了队列大小限制，队列满时会阻塞等待）
     ///
     /// # 返回
@@ -272,12 +746,131 @@ impl CommandPool {
     ///
     /// 如果命令池正在关闭，返回 `SubmitError::ShuttingDown`
     pub fn push_task(&self, task: CommandConfig) -> Result<TaskHandle, SubmitError> {
+        self.push_task_until(task, Admission::Wait(None), None)
+    }
+
+    /// 添加任务，队列满时最多等待 `timeout`
+    ///
+    /// 适合需要自行决定背压策略（丢弃、降级、告警）的生产者，避免在有界队列满时无限阻塞。
+    /// 无界队列或队列有空位时立即入队；`timeout` 为零时等同于
+    /// [`try_push_task`](Self::try_push_task)。
+    ///
+    /// # 错误
+    ///
+    /// * `SubmitError::ShuttingDown` - 命令池正在关闭（包括等待期间开始关闭）
+    /// * `SubmitError::QueueFull` - 等待 `timeout` 后队列仍然是满的
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool, ExecutionConfig, SubmitError};
+    /// use std::time::Duration;
+    ///
+    /// let pool = CommandPool::with_config_and_limit(ExecutionConfig::default(), 100);
+    /// pool.start_executor();
+    /// match pool.push_task_timeout(CommandConfig::new("true", vec![]), Duration::from_secs(1)) {
+    ///     Ok(handle) => println!("queued task {}", handle.id()),
+    ///     Err(SubmitError::QueueFull) => eprintln!("pool saturated, dropping task"),
+    ///     Err(e) => eprintln!("submit failed: {e}"),
+    /// }
+    /// ```
+    pub fn push_task_timeout(
+        &self,
+        task: CommandConfig,
+        timeout: Duration,
+    ) -> Result<TaskHandle, SubmitError> {
+        self.push_task_until(task, Admission::Wait(Some(Instant::now() + timeout)), None)
+    }
+
+    /// 按去重键添加任务，同键任务仍在等待或执行时跳过
+    ///
+    /// 适合同一事件可能被重复触发（如 webhook 重试）的场景：键相同的任务结束之前，
+    /// 后续提交不会入队，返回 `Ok(None)`；任务结束（包括失败和取消）或被
+    /// [`clear`](Self::clear) 移除后，同键任务可以再次提交。队列满时与
+    /// [`push_task`](Self::push_task) 一样阻塞等待，等待期间同键的提交同样被跳过。
+    ///
+    /// 与 [`CommandConfig::with_coalesce_key`] 不同，被跳过的提交不会得到任务句柄，
+    /// 也不会收到首个任务的结果。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    /// let deploy = CommandConfig::new("deploy", vec!["main".to_string()]);
+    /// let first = pool.push_task_dedup("deploy:main", deploy.clone()).unwrap();
+    /// let second = pool.push_task_dedup("deploy:main", deploy).unwrap();
+    /// assert!(first.is_some());
+    /// assert!(second.is_none());
+    /// ```
+    ///
+    /// # 错误
+    ///
+    /// 如果命令池正在关闭，返回 `SubmitError::ShuttingDown`
+    pub fn push_task_dedup(
+        &self,
+        key: impl Into<String>,
+        task: CommandConfig,
+    ) -> Result<Option<TaskHandle>, SubmitError> {
+        let key = key.into();
+        if !self.claim_dedup_key(&key) {
+            #[cfg(feature = "logging")]
+            tracing::info!(key = %key, "Task skipped, duplicate already queued or running");
+            return Ok(None);
+        }
+
+        match self.push_task(task) {
+            Ok(handle) => {
+                self.dedup_keys
+                    .lock()
+                    .unwrap()
+                    .insert(key, Some(handle.id()));
+                Ok(Some(handle))
+            }
+            Err(e) => {
+                self.dedup_keys.lock().unwrap().remove(&key);
+                Err(e)
+            }
+        }
+    }
+
+    /// 占用去重键，同键任务仍在提交、等待或执行时返回 false
+    ///
+    /// 持有键的任务结束或被移除后任务状态不再是等待或执行中，此时键会被清理。
+    fn claim_dedup_key(&self, key: &str) -> bool {
+        let mut keys = self.dedup_keys.lock().unwrap();
+        keys.retain(|_, task_id| match task_id {
+            Some(task_id) => self
+                .task_statuses
+                .get(*task_id)
+                .is_some_and(|status| !status.is_finished()),
+            None => true,
+        });
+        if keys.contains_key(key) {
+            return false;
+        }
+        keys.insert(key.to_string(), None);
+        true
+    }
+
+    /// 添加任务，按 `admission` 等待队列空位或使用预留的空位
+    ///
+    /// `replayed` 为从持久化日志重新提交的任务的记录序号，这类任务不再写入新记录。
+    fn push_task_until(
+        &self,
+        task: CommandConfig,
+        admission: Admission<'_>,
+        replayed: Option<u64>,
+    ) -> Result<TaskHandle, SubmitError> {
         // 检查是否正在关闭
-        if self.shutdown_flag.load(Ordering::SeqCst) {
+        if self.is_closing() {
             return Err(SubmitError::ShuttingDown);
         }
 
-        let task_id = self.task_id_counter.fetch_add(1, Ordering::SeqCst);
+        let task = self.apply_task_defaults(task);
+        let task_id = self.task_ids.next_id();
 
         #[cfg(feature = "logging")]
         tracing::debug!(
@@ -287,77 +880,511 @@ impl CommandPool {
             "Task submitted"
         );
 
-        #[cfg(feature = "metrics")]
-        self.metrics.record_task_submitted();
-
         // 创建 TaskHandle
         let (handle, result_sender) = TaskHandle::new(task_id);
+        handle.set_command(&task);
+        self.register_task(task_id, &task, replayed)?;
+
+        // 已有同键任务在等待或执行时直接合并，无需等待队列空位
+        if let Some(key) = task.coalesce_key()
+            && self.coalesced.join(key, &handle, &result_sender)
+        {
+            return Ok(handle);
+        }
 
         let (lock, cvar) = &*self.tasks;
         let mut tasks = lock.lock().unwrap();
 
-        // 如果设置了队列大小限制，等待队列有空位
-        if let Some(max) = self.max_size {
-            while tasks.len() >= max {
+        // 如果设置了队列大小限制，等待队列有空位（其他生产者预留的空位不可用）
+        if let (Some(max), Admission::Wait(deadline)) = (self.max_size, &admission) {
+            while tasks.occupied() >= max {
                 // 在等待期间再次检查是否正在关闭
-                if self.shutdown_flag.load(Ordering::SeqCst) {
+                if self.is_closing() {
+                    self.unregister_task(task_id);
                     return Err(SubmitError::ShuttingDown);
                 }
-                tasks = cvar.wait(tasks).unwrap();
+                tasks = match deadline {
+                    None => cvar.wait(tasks).unwrap(),
+                    Some(deadline) => {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+                        if remaining.is_zero() {
+                            self.unregister_task(task_id);
+                            return Err(SubmitError::QueueFull);
+                        }
+                        cvar.wait_timeout(tasks, remaining).unwrap().0
+                    }
+                };
             }
         }
 
         // 最后再检查一次
-        if self.shutdown_flag.load(Ordering::SeqCst) {
+        if self.is_closing() {
+            self.unregister_task(task_id);
             return Err(SubmitError::ShuttingDown);
         }
 
-        tasks.push_back(TaskItem {
+        // 持有队列锁登记合并键，避免并发提交产生多个首个任务
+        if let Some(key) = task.coalesce_key()
+            && self.coalesced.join_or_lead(key, &handle, &result_sender)
+        {
+            return Ok(handle);
+        }
+
+        // 只统计真正入队的任务，被拒绝或合并的任务不会开始执行
+        #[cfg(feature = "metrics")]
+        self.metrics.record_task_submitted();
+
+        if let Admission::Reserved(remaining) = admission {
+            *remaining -= 1;
+            tasks.release(1);
+        }
+        self.on_task_queued(task_id, &task, tasks.len() + 1);
+        let keyed = self.is_keyed(&task);
+        tasks.push(TaskItem {
             config: task,
             handle: handle.clone(),
             result_sender,
         });
-        cvar.notify_one();
+        *self.outstanding.0.lock().unwrap() += 1;
+        // 带亲和键、串行键或受限后端的任务不一定能被任意工作线程取走，需唤醒所有等待者
+        if keyed {
+            cvar.notify_all();
+        } else {
+            cvar.notify_one();
+        }
         Ok(handle)
     }
 
-    /// 尝试添加任务，如果队列满则返回错误
+    /// 为一批任务预留队列空位
     ///
-    /// # 返回
+    /// 成功时返回的 [`Reservation`] 保证之后通过它提交的 `count` 个任务不会因队列满而等待，
+    /// 生产者可以先确认有足够空间，再构造代价较高的任务配置。未使用的空位在
+    /// [`Reservation`] 丢弃时归还。无界队列总是预留成功。
     ///
-    /// 返回任务句柄，可用于等待任务完成、获取结果或取消任务
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool, ExecutionConfig};
+    ///
+    /// let pool = CommandPool::with_config_and_limit(ExecutionConfig::default(), 100);
+    /// pool.start_executor();
+    /// if let Ok(mut reservation) = pool.try_reserve(10) {
+    ///     for i in 0..10 {
+    ///         let config = CommandConfig::new("render", vec![i.to_string()]);
+    ///         reservation.push_task(config).unwrap();
+    ///     }
+    /// }
+    /// ```
     ///
     /// # 错误
     ///
     /// * `SubmitError::ShuttingDown` - 命令池正在关闭
-    /// * `SubmitError::QueueFull` - 队列已满（仅当设置了队列大小限制时）
-    pub fn try_push_task(&self, task: CommandConfig) -> Result<TaskHandle, SubmitError> {
-        // 检查是否正在关闭
-        if self.shutdown_flag.load(Ordering::SeqCst) {
+    /// * `SubmitError::QueueFull` - 剩余空位不足 `count` 个（包括其他生产者预留的空位）
+    pub fn try_reserve(&self, count: usize) -> Result<Reservation<'_>, SubmitError> {
+        if self.is_closing() {
             return Err(SubmitError::ShuttingDown);
         }
-
-        let task_id = self.task_id_counter.fetch_add(1, Ordering::SeqCst);
-
-        // 创建 TaskHandle
-        let (handle, result_sender) = TaskHandle::new(task_id);
-
-        let (lock, cvar) = &*self.tasks;
+        let (lock, _) = &*self.tasks;
+        let mut tasks = lock.lock().unwrap();
+        if let Some(max) = self.max_size
+            && tasks.occupied() + count > max
+        {
+            return Err(SubmitError::QueueFull);
+        }
+        tasks.reserve(count);
+        Ok(Reservation::new(self, count))
+    }
+
+    /// 使用预留的空位添加任务
+    pub(crate) fn push_reserved(
+        &self,
+        task: CommandConfig,
+        remaining: &mut usize,
+    ) -> Result<TaskHandle, SubmitError> {
+        self.push_task_until(task, Admission::Reserved(remaining), None)
+    }
+
+    /// 归还未使用的预留空位，唤醒等待空位的生产者
+    pub(crate) fn release_reserved(&self, count: usize) {
+        let (lock, cvar) = &*self.tasks;
+        lock.lock().unwrap().release(count);
+        cvar.notify_all();
+    }
+
+    /// 提交任务并返回任务句柄
+    ///
+    /// 与 [`push_task`](Self::push_task) 相同：队列满时阻塞等待，返回的
+    /// [`TaskHandle`] 可用于等待任务的 `Output`、查询状态或取消任务。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    /// let handle = pool
+    ///     .submit(CommandConfig::new("echo", vec!["hello".to_string()]))
+    ///     .unwrap();
+    /// let output = handle.wait().unwrap();
+    /// assert_eq!(output.stdout, b"hello\n");
+    /// ```
+    ///
+    /// # 错误
+    ///
+    /// 如果命令池正在关闭，返回 `SubmitError::ShuttingDown`
+    pub fn submit(&self, task: CommandConfig) -> Result<TaskHandle, SubmitError> {
+        self.push_task(task)
+    }
+
+    /// 以指定优先级提交任务
+    ///
+    /// 等同于 `push_task(task.with_priority(priority))`：数值越大越先出队，
+    /// 相同优先级的任务按提交顺序执行，适合交互请求与批量任务混合的场景。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.push_task(CommandConfig::new("batch-job", vec![])).unwrap();
+    /// let urgent = pool
+    ///     .push_task_with_priority(CommandConfig::new("render-preview", vec![]), 200)
+    ///     .unwrap();
+    /// pool.start_executor();
+    /// urgent.wait().unwrap();
+    /// ```
+    ///
+    /// # 错误
+    ///
+    /// 如果命令池正在关闭，返回 `SubmitError::ShuttingDown`
+    pub fn push_task_with_priority(
+        &self,
+        task: CommandConfig,
+        priority: u8,
+    ) -> Result<TaskHandle, SubmitError> {
+        self.push_task(task.with_priority(priority))
+    }
+
+    /// 提交一批任务并等待全部结束，结果按输入顺序返回
+    ///
+    /// 任务在共享的工作线程上并行执行（并发数由工作线程数决定），适合“用 N 个工作线程
+    /// 运行这 200 条命令”这类场景，无需自行保存任务句柄。有界队列满时提交会阻塞，
+    /// 直到工作线程腾出空位。
+    ///
+    /// 需要先启动执行器，否则会一直等待。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool, ExecutionConfig};
+    ///
+    /// let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(8));
+    /// pool.start_executor();
+    /// let hosts = ["a.example.com", "b.example.com"];
+    /// let results = pool
+    ///     .execute_all(
+    ///         hosts
+    ///             .iter()
+    ///             .map(|host| CommandConfig::new("ping", vec!["-c1".to_string(), host.to_string()]))
+    ///             .collect(),
+    ///     )
+    ///     .unwrap();
+    /// for (host, result) in hosts.iter().zip(&results) {
+    ///     println!("{host}: {}", result.is_ok());
+    /// }
+    /// ```
+    ///
+    /// # 错误
+    ///
+    /// 任一任务提交失败（如命令池正在关闭）时，取消已提交的任务并返回 [`SubmitError`]。
+    pub fn execute_all(&self, tasks: Vec<CommandConfig>) -> Result<Vec<TaskResult>, SubmitError> {
+        let mut handles = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            match self.push_task(task) {
+                Ok(handle) => handles.push(handle),
+                Err(e) => {
+                    for handle in &handles {
+                        let _ = handle.cancel();
+                    }
+                    for handle in &handles {
+                        let _ = handle.wait();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(handles.iter().map(TaskHandle::wait).collect())
+    }
+
+    /// 提交在指定时刻才开始执行的任务
+    ///
+    /// 任务立即入队，但在 `at` 之前不会被工作线程取出，也不占用工作线程；
+    /// 等同于 `push_task(task.with_start_at(..))`。`at` 已经过去时任务立即可执行。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    /// let handle = pool
+    ///     .schedule(
+    ///         CommandConfig::new("backup", vec![]),
+    ///         Instant::now() + Duration::from_secs(60),
+    ///     )
+    ///     .unwrap();
+    /// handle.wait().unwrap();
+    /// ```
+    ///
+    /// # 错误
+    ///
+    /// 如果命令池正在关闭，返回 `SubmitError::ShuttingDown`
+    pub fn schedule(&self, task: CommandConfig, at: Instant) -> Result<TaskHandle, SubmitError> {
+        let delay = at.saturating_duration_since(Instant::now());
+        self.push_task(task.with_start_at(SystemTime::now() + delay))
+    }
+
+    /// 按 cron 表达式周期性提交任务
+    ///
+    /// 在后台线程中按 [`CronSchedule`] 计算的时间（UTC）把 `task` 的副本提交到命令池，
+    /// 直到调用返回句柄的 [`RecurringHandle::cancel`] 或命令池开始关闭。
+    /// 前一次提交的任务尚未结束时仍会按时提交下一次，需要避免重叠时可以为任务设置
+    /// [`CommandConfig::with_singleton_key`]。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    /// let recurring = pool
+    ///     .schedule_recurring("*/5 * * * *", CommandConfig::new("sync-mirror", vec![]))
+    ///     .unwrap();
+    /// // ...
+    /// recurring.cancel();
+    /// ```
+    ///
+    /// # 错误
+    ///
+    /// 表达式无效时返回 `ConfigError::InvalidCronExpression`
+    pub fn schedule_recurring(
+        &self,
+        expression: &str,
+        task: CommandConfig,
+    ) -> Result<RecurringHandle, ConfigError> {
+        self.schedule_recurring_with_blackout(expression, task, BlackoutWindows::new())
+    }
+
+    /// 按 cron 表达式周期性提交任务，禁止窗口内的触发被跳过或推迟
+    ///
+    /// 与 [`schedule_recurring`](Self::schedule_recurring) 相同，但触发时间落在
+    /// `blackout` 声明的窗口内时按 [`BlackoutAction`] 处理：`Skip` 放弃这次触发
+    /// （计入 [`RecurringHandle::skipped`]），`Defer` 在窗口结束时提交一次，
+    /// 之后从窗口结束时间继续计算下一次触发。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{BlackoutAction, BlackoutWindows, CommandConfig, CommandPool};
+    /// use std::time::Duration;
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    /// // 每小时同步，每天 02:00-04:00（UTC）的维护窗口内推迟
+    /// let blackout = BlackoutWindows::new()
+    ///     .with_daily(Duration::from_secs(2 * 3600), Duration::from_secs(4 * 3600))
+    ///     .with_action(BlackoutAction::Defer);
+    /// let recurring = pool
+    ///     .schedule_recurring_with_blackout(
+    ///         "0 * * * *",
+    ///         CommandConfig::new("sync-mirror", vec![]),
+    ///         blackout,
+    ///     )
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # 错误
+    ///
+    /// 表达式无效时返回 `ConfigError::InvalidCronExpression`
+    pub fn schedule_recurring_with_blackout(
+        &self,
+        expression: &str,
+        task: CommandConfig,
+        blackout: BlackoutWindows,
+    ) -> Result<RecurringHandle, ConfigError> {
+        let schedule = CronSchedule::parse(expression)?;
+        let handle = RecurringHandle::new();
+
+        #[cfg(feature = "logging")]
+        tracing::info!(
+            schedule = expression,
+            command = %task.program(),
+            "Recurring task scheduled"
+        );
+
+        #[cfg(feature = "logging")]
+        let expression_owned = expression.to_string();
+        let recurring = handle.clone();
+        let pool = self.background_clone();
+        thread::spawn(move || {
+            // 分段等待，及时发现命令池关闭；被取消或命令池关闭时返回 false
+            let wait_until = |deadline: SystemTime| {
+                while SystemTime::now() < deadline {
+                    if pool.is_closing()
+                        || !recurring.sleep_until(deadline, Duration::from_millis(100))
+                    {
+                        return false;
+                    }
+                }
+                true
+            };
+
+            let mut last = SystemTime::now();
+            while let Some(next) = schedule.next_after(last) {
+                if !wait_until(next) {
+                    return;
+                }
+                // 到达触发时间时检查禁止窗口
+                let mut due = next;
+                if blackout.contains(next) {
+                    match (blackout.action(), blackout.next_allowed(next)) {
+                        (BlackoutAction::Defer, Some(end)) => {
+                            #[cfg(feature = "logging")]
+                            tracing::info!(
+                                schedule = expression_owned.as_str(),
+                                "Recurring task deferred by blackout window"
+                            );
+                            if !wait_until(end) {
+                                return;
+                            }
+                            due = end;
+                        }
+                        _ => {
+                            #[cfg(feature = "logging")]
+                            tracing::info!(
+                                schedule = expression_owned.as_str(),
+                                "Recurring task skipped by blackout window"
+                            );
+                            recurring.record_skip();
+                            last = next;
+                            continue;
+                        }
+                    }
+                }
+                if recurring.is_cancelled() || pool.push_task(task.clone()).is_err() {
+                    return;
+                }
+                recurring.record_run();
+                // 提交耗时超过周期时跳过错过的触发时间，不补发；
+                // 推迟提交时窗口内的其余触发也不再补发
+                last = due.max(SystemTime::now());
+            }
+        });
+        Ok(handle)
+    }
+
+    /// 登记新提交的任务：记录任务状态，配置了持久化日志时写入日志
+    #[cfg_attr(not(feature = "persistence"), allow(unused_variables))]
+    fn register_task(
+        &self,
+        task_id: u64,
+        task: &CommandConfig,
+        replayed: Option<u64>,
+    ) -> Result<(), SubmitError> {
+        #[cfg(feature = "persistence")]
+        if let Some(journal) = &self.journal {
+            match replayed {
+                Some(seq) => journal.adopt(task_id, seq),
+                None => journal
+                    .record(task_id, task)
+                    .map_err(SubmitError::Journal)?,
+            }
+        }
+        self.task_statuses.register(task_id);
+        Ok(())
+    }
+
+    /// 撤销被拒绝的任务的登记
+    fn unregister_task(&self, task_id: u64) {
+        self.task_statuses.remove(task_id);
+        #[cfg(feature = "persistence")]
+        if let Some(journal) = &self.journal
+            && let Err(e) = journal.reject(task_id)
+        {
+            #[cfg(feature = "logging")]
+            tracing::warn!(task_id = task_id, error = %e, "Failed to discard journal entry");
+            #[cfg(not(feature = "logging"))]
+            let _ = e;
+        }
+    }
+
+    /// 尝试添加任务，如果队列满则返回错误
+    ///
+    /// # 返回
+    ///
+    /// 返回任务句柄，可用于等待任务完成、获取结果或取消任务
+    ///
+    /// # 错误
+    ///
+    /// * `SubmitError::ShuttingDown` - 命令池正在关闭
+    /// * `SubmitError::QueueFull` - 队列已满（仅当设置了队列大小限制时）
+    pub fn try_push_task(&self, task: CommandConfig) -> Result<TaskHandle, SubmitError> {
+        // 检查是否正在关闭
+        if self.is_closing() {
+            return Err(SubmitError::ShuttingDown);
+        }
+
+        let task = self.apply_task_defaults(task);
+        let task_id = self.task_ids.next_id();
+
+        // 创建 TaskHandle
+        let (handle, result_sender) = TaskHandle::new(task_id);
+        handle.set_command(&task);
+        self.register_task(task_id, &task, None)?;
+
+        // 合并的任务不占用队列空位
+        if let Some(key) = task.coalesce_key()
+            && self.coalesced.join(key, &handle, &result_sender)
+        {
+            return Ok(handle);
+        }
+
+        let (lock, cvar) = &*self.tasks;
         let mut tasks = lock.lock().unwrap();
 
         // 如果设置了队列大小限制，检查是否有空位
         if let Some(max) = self.max_size
-            && tasks.len() >= max
+            && tasks.occupied() >= max
         {
+            self.unregister_task(task_id);
             return Err(SubmitError::QueueFull);
         }
 
-        tasks.push_back(TaskItem {
+        if let Some(key) = task.coalesce_key()
+            && self.coalesced.join_or_lead(key, &handle, &result_sender)
+        {
+            return Ok(handle);
+        }
+
+        self.on_task_queued(task_id, &task, tasks.len() + 1);
+        let keyed = self.is_keyed(&task);
+        tasks.push(TaskItem {
             config: task,
             handle: handle.clone(),
             result_sender,
         });
-        cvar.notify_one();
+        *self.outstanding.0.lock().unwrap() += 1;
+        // 带亲和键、串行键或受限后端的任务不一定能被任意工作线程取走，需唤醒所有等待者
+        if keyed {
+            cvar.notify_all();
+        } else {
+            cvar.notify_one();
+        }
         Ok(handle)
     }
 
@@ -365,36 +1392,270 @@ impl CommandPool {
     ///
     /// 使用条件变量等待新任务，避免轮询造成的 CPU 浪费。
     /// 当队列为空时，线程会阻塞等待，直到有新任务提交或命令池关闭。
+    ///
+    /// 取第一个已到计划执行时间的任务，不考虑亲和键和串行键。
     pub fn pop_task(&self) -> Option<TaskItem> {
+        let task = self.pop_task_for(None).map(|(task, _)| task);
+        // 由调用方取走的任务不再由命令池完成
+        if task.is_some() {
+            self.finish_outstanding(1);
+        }
+        task
+    }
+
+    /// 为第 `worker` 个工作线程弹出任务
+    ///
+    /// 跳过亲和键分配给其他工作线程的任务、串行键已有任务在执行的任务，
+    /// 以及所选后端或并发键已达到并发上限的任务；取出带串行键、受限后端或受限并发键的
+    /// 任务时占用对应的键和名额，守卫丢弃后才释放。`worker` 为 None 时取第一个已到期的任务。
+    ///
+    /// 设置了空闲超时时，工作线程空闲超过该时长且空闲线程多于最少空闲数时返回 None，
+    /// 工作线程随之退出。
+    fn pop_task_for(&self, worker: Option<usize>) -> Option<(TaskItem, Option<DispatchGuard>)> {
         let (lock, cvar) = &*self.tasks;
         let mut tasks = lock.lock().unwrap();
+        let mut retire_at = match (worker, self.options.idle_timeout) {
+            (Some(_), Some(timeout)) => Some(Instant::now() + timeout),
+            _ => None,
+        };
 
         loop {
-            // 尝试获取任务
-            if let Some(task) = tasks.pop_front() {
+            // 尝试获取任务，未到计划执行时间的任务留在队列中
+            let now = SystemTime::now();
+            // 启动速率受限且没有令牌时不出队，等到下一个令牌
+            let rate_delay = match (worker, &self.rate_limiter) {
+                (Some(_), Some(limiter)) => limiter.time_until_available(),
+                _ => None,
+            };
+            let task = match worker {
+                None => tasks.pop_first(|item| item.config.start_delay(now).is_none()),
+                Some(_) if rate_delay.is_some() => None,
+                Some(index) => {
+                    // 被替换的旧线程仍在执行任务时，替补线程不取亲和键任务，
+                    // 保证相同亲和键的任务不会并发执行
+                    let affinity_free =
+                        self.options.watchdog.is_none() || !self.activity.has_retired_task(index);
+                    let serial_keys = self.serial_keys.lock().unwrap();
+                    let backend_slots = self.backend_slots.lock().unwrap();
+                    let key_slots = self.key_slots.lock().unwrap();
+                    tasks.pop_first(|item| {
+                        item.config.start_delay(now).is_none()
+                            && item.config.affinity_key().is_none_or(|key| {
+                                affinity_free && worker_for_key(key, self.config.workers) == index
+                            })
+                            && item
+                                .config
+                                .serial_key()
+                                .is_none_or(|key| !serial_keys.contains(key))
+                            && self
+                                .limited_backend(&item.config)
+                                .is_none_or(|(name, limit)| {
+                                    backend_slots.get(name).copied().unwrap_or(0) < limit
+                                })
+                            && self.limited_concurrency_key(&item.config).is_none_or(
+                                |(key, limit)| key_slots.get(key).copied().unwrap_or(0) < limit,
+                            )
+                    })
+                }
+            };
+            if let Some(task) = task {
+                if let (Some(_), Some(limiter)) = (worker, &self.rate_limiter) {
+                    limiter.acquire();
+                }
+                let serial_key = task.config.serial_key();
+                let backend = self.limited_backend(&task.config).map(|(name, _)| name);
+                let concurrency_key = self
+                    .limited_concurrency_key(&task.config)
+                    .map(|(key, _)| key);
+                let guard = match worker {
+                    Some(_)
+                        if serial_key.is_some()
+                            || backend.is_some()
+                            || concurrency_key.is_some() =>
+                    {
+                        if let Some(key) = serial_key {
+                            self.serial_keys.lock().unwrap().insert(key.to_string());
+                        }
+                        if let Some(name) = backend {
+                            *self
+                                .backend_slots
+                                .lock()
+                                .unwrap()
+                                .entry(name.to_string())
+                                .or_insert(0) += 1;
+                        }
+                        if let Some(key) = concurrency_key {
+                            *self
+                                .key_slots
+                                .lock()
+                                .unwrap()
+                                .entry(key.to_string())
+                                .or_insert(0) += 1;
+                        }
+                        Some(DispatchGuard {
+                            serial_key: serial_key.map(str::to_string),
+                            backend: backend.map(str::to_string),
+                            concurrency_key: concurrency_key.map(str::to_string),
+                            tasks: Arc::clone(&self.tasks),
+                            serial_keys: Arc::clone(&self.serial_keys),
+                            backend_slots: Arc::clone(&self.backend_slots),
+                            key_slots: Arc::clone(&self.key_slots),
+                        })
+                    }
+                    _ => None,
+                };
+                // 队列回落到高水位以下后重新允许发布高水位事件
+                if let Some(watermark) = self.options.queue_high_watermark
+                    && tasks.len() < watermark
+                {
+                    self.above_watermark.store(false, Ordering::SeqCst);
+                }
                 // 通知可能在等待队列空位的线程
                 cvar.notify_one();
-                return Some(task);
+                return Some((task, guard));
             }
 
-            // 如果正在关闭且队列为空，返回 None
-            if self.shutdown_flag.load(Ordering::SeqCst) {
+            // 如果正在关闭且没有可取的任务，返回 None；
+            // 执行器停止后工作线程也不再等待
+            if self.shutdown_flag.load(Ordering::SeqCst)
+                || (worker.is_some() && !self.running.load(Ordering::SeqCst))
+            {
                 return None;
             }
 
-            // 队列为空且未关闭，等待新任务
-            tasks = cvar.wait(tasks).unwrap();
+            // 按需启动时，亲和键对应的工作线程可能尚未启动
+            if worker.is_some() && self.min_idle_workers().is_some() {
+                self.launch_affinity_targets(&tasks);
+            }
+
+            // 空闲超时：多于最少空闲数时退出，否则重新计时
+            let mut retire_in = retire_at.map(|at| at.saturating_duration_since(Instant::now()));
+            if let (Some(index), Some(Duration::ZERO)) = (worker, retire_in) {
+                let min_idle = self.min_idle_workers().unwrap_or(1);
+                if self.worker_slots.lock().unwrap().retire(index, min_idle) {
+                    #[cfg(feature = "logging")]
+                    tracing::debug!(worker = index, "Idle worker exiting");
+                    return None;
+                }
+                retire_at = self
+                    .options
+                    .idle_timeout
+                    .map(|timeout| Instant::now() + timeout);
+                retire_in = self.options.idle_timeout;
+            }
+
+            // 没有可取的任务且未关闭，等待新任务、最早的计划任务到期、下一个令牌或空闲超时
+            let next_due = tasks
+                .iter()
+                .filter_map(|item| item.config.start_delay(now))
+                .chain(rate_delay.filter(|_| !tasks.is_empty()))
+                .chain(retire_in)
+                .min();
+            tasks = match next_due {
+                Some(delay) => cvar.wait_timeout(tasks, delay).unwrap().0,
+                None => cvar.wait(tasks).unwrap(),
+            };
         }
     }
 
+    /// 任务选择的后端及其并发上限（未配置上限的后端返回 None）
+    fn limited_backend<'a>(&self, config: &'a CommandConfig) -> Option<(&'a str, usize)> {
+        let name = config.backend()?;
+        let limit = *self.options.backend_limits.get(name)?;
+        Some((name, limit))
+    }
+
+    /// 任务的并发键及其并发上限（未配置上限的键返回 None）
+    fn limited_concurrency_key<'a>(&self, config: &'a CommandConfig) -> Option<(&'a str, usize)> {
+        let key = config.concurrency_key()?;
+        let limit = *self.options.concurrency_key_limits.get(key)?;
+        Some((key, limit))
+    }
+
+    /// 任务是否只能由部分工作线程取走或需要等待占用释放
+    fn is_keyed(&self, config: &CommandConfig) -> bool {
+        config.affinity_key().is_some()
+            || config.serial_key().is_some()
+            || self.limited_backend(config).is_some()
+            || self.limited_concurrency_key(config).is_some()
+    }
+
     /// 清空所有任务
     pub fn clear(&self) -> usize {
+        self.remove_queued().len()
+    }
+
+    /// 取出所有等待中的任务而不执行，返回它们的配置
+    ///
+    /// 与 [`clear`](Self::clear) 相同，任务从队列中原子地移除，但返回按出队顺序排列的配置，
+    /// 可以提交到另一个命令池，例如重新配置时迁移任务。正在执行的任务不受影响；
+    /// 被取出任务的句柄不会收到结果。返回的配置已应用命令池的任务默认值。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool, ExecutionConfig};
+    ///
+    /// let old = CommandPool::new();
+    /// old.push_task(CommandConfig::new("echo", vec!["hello".to_string()])).unwrap();
+    ///
+    /// let new = CommandPool::with_config(ExecutionConfig::new().with_workers(8));
+    /// new.start_executor();
+    /// for config in old.drain() {
+    ///     new.push_task(config).unwrap();
+    /// }
+    /// ```
+    pub fn drain(&self) -> Vec<CommandConfig> {
+        self.remove_queued()
+            .into_iter()
+            .map(|item| item.config)
+            .collect()
+    }
+
+    /// 按出队顺序移除队列中的所有任务并撤销它们的登记
+    ///
+    /// 合并到被移除任务的跟随者收到 `Cancelled` 结果，之后提交的同键任务重新执行。
+    fn remove_queued(&self) -> Vec<TaskItem> {
         let (lock, cvar) = &*self.tasks;
         let mut tasks = lock.lock().unwrap();
-        let count = tasks.len();
-        tasks.clear();
+        let items = tasks.drain();
+        for item in &items {
+            self.unregister_task(item.handle.id());
+        }
         cvar.notify_all();
-        count
+        drop(tasks);
+        for item in &items {
+            if let Some(key) = item.config.coalesce_key() {
+                self.cancel_followers(key, item);
+            }
+        }
+        self.finish_outstanding(items.len());
+        items
+    }
+
+    /// 首个任务不再执行时，向合并到它的跟随者发送 `Cancelled` 结果并移除任务组
+    fn cancel_followers(&self, key: &str, leader: &TaskItem) {
+        let result = Err(ExecuteError::Cancelled(leader.handle.id()));
+        let followers = self.coalesced.complete(key, &leader.handle, &result);
+        if followers.is_empty() {
+            return;
+        }
+        let status = FinishStatus::Cancelled;
+        self.stats.record_finished(&status, followers.len() as u64);
+        for &task_id in &followers {
+            self.task_statuses.update(task_id, TaskStatus::Cancelled);
+        }
+        #[cfg(feature = "persistence")]
+        self.acknowledge_finished(&followers, &status);
+        if self.events.has_subscribers() {
+            for &task_id in &followers {
+                self.events.emit(PoolEvent::TaskFinished {
+                    task_id,
+                    status: status.clone(),
+                    duration: Duration::ZERO,
+                });
+            }
+        }
     }
 
     /// 获取当前队列大小
@@ -409,24 +1670,171 @@ impl CommandPool {
         self.len() == 0
     }
 
-    /// 获取队列大小限制
-    pub fn max_size(&self) -> Option<usize> {
-        self.max_size
+    /// 获取等待中任务的只读副本
+    ///
+    /// 按优先级从高到低、同优先级按提交顺序排列（即不考虑计划执行时间、亲和键等条件时的出队顺序）。
+    /// 只包含在队列中等待的任务，不包含正在执行的任务和合并到其他任务的跟随者。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.push_task(CommandConfig::new("make", vec![]).with_label("team", "infra"))
+    ///     .unwrap();
+    /// for task in pool.snapshot() {
+    ///     println!("#{} {:?} {:?} {:?}", task.task_id, task.program, task.args, task.labels);
+    /// }
+    /// ```
+    pub fn snapshot(&self) -> Vec<QueuedTask> {
+        let mut queued: Vec<QueuedTask> = {
+            let (lock, _) = &*self.tasks;
+            let tasks = lock.lock().unwrap();
+            tasks.iter().map(QueuedTask::from_item).collect()
+        };
+        queued.sort_by_key(|task| (std::cmp::Reverse(task.priority), task.task_id));
+        queued
     }
 
-    /// 获取执行模式
-    pub fn execution_mode(&self) -> ExecutionMode {
-        self.config.mode
+    /// 获取正在执行的任务的只读副本，按任务 ID 排列
+    ///
+    /// 包含任务的命令、子进程 ID 和本次执行已经运行的时间，用于查看当前卡在哪些任务上。
+    /// 按重试策略等待重新执行的任务不在其中。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::CommandPool;
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    /// for task in pool.running_tasks() {
+    ///     println!("#{} pid={:?} {:?} running for {:?}", task.task_id, task.pid, task.program, task.elapsed);
+    /// }
+    /// ```
+    pub fn running_tasks(&self) -> Vec<InFlightTask> {
+        let mut running: Vec<InFlightTask> = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .values()
+            .map(InFlightTask::from_entry)
+            .collect();
+        running.sort_by_key(|task| task.task_id);
+        running
     }
 
-    /// 获取指标快照
+    /// 等待命令池空闲
     ///
-    /// 返回当前的任务执行统计信息
-    #[cfg(feature = "metrics")]
-    pub fn metrics(&self) -> crate::metrics::MetricsSnapshot {
+    /// 阻塞直到队列为空且所有已出队的任务都已结束（结果已发送给任务句柄），
+    /// 适合提交一批任务后等待全部完成，而不必逐个等待句柄或睡眠一段时间。
+    /// 等待期间提交的任务同样需要完成。执行器未启动时队列中的任务不会被执行，
+    /// 此方法会一直阻塞，需要上限时使用 [`wait_idle_timeout`](Self::wait_idle_timeout)。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    /// for i in 0..10 {
+    ///     pool.push_task(CommandConfig::new("echo", vec![i.to_string()])).unwrap();
+    /// }
+    /// pool.wait_idle();
+    /// ```
+    pub fn wait_idle(&self) {
+        let (lock, cvar) = &*self.outstanding;
+        let _idle = cvar
+            .wait_while(lock.lock().unwrap(), |count| *count > 0)
+            .unwrap();
+    }
+
+    /// 等待命令池空闲，最多等待 `timeout`
+    ///
+    /// 在超时前变为空闲时返回 true，否则返回 false。
+    pub fn wait_idle_timeout(&self, timeout: Duration) -> bool {
+        let (lock, cvar) = &*self.outstanding;
+        let (_idle, result) = cvar
+            .wait_timeout_while(lock.lock().unwrap(), timeout, |count| *count > 0)
+            .unwrap();
+        !result.timed_out()
+    }
+
+    /// 减少未完成任务数，归零时唤醒等待空闲的线程
+    fn finish_outstanding(&self, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let (lock, cvar) = &*self.outstanding;
+        let mut outstanding = lock.lock().unwrap();
+        *outstanding = outstanding.saturating_sub(count);
+        if *outstanding == 0 {
+            cvar.notify_all();
+        }
+    }
+
+    /// 获取队列大小限制
+    pub fn max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    /// 获取执行模式
+    pub fn execution_mode(&self) -> ExecutionMode {
+        self.config.mode
+    }
+
+    /// 获取指标快照
+    ///
+    /// 返回当前的任务执行统计信息
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> crate::metrics::MetricsSnapshot {
         self.metrics.snapshot()
     }
 
+    /// 获取运行统计
+    ///
+    /// 返回排队和执行中的任务数、按结果分类的结束任务数，以及平均等待和运行时间。
+    /// 不依赖 `metrics` feature，开销只有几次原子读取和一次队列长度查询。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::CommandPool;
+    ///
+    /// let pool = CommandPool::new();
+    /// let stats = pool.stats();
+    /// if stats.queued > 100 && stats.avg_wait_time > std::time::Duration::from_secs(1) {
+    ///     println!("pool is saturated: {stats:?}");
+    /// }
+    /// ```
+    pub fn stats(&self) -> PoolStats {
+        self.stats.snapshot(self.len())
+    }
+
+    /// 获取任务状态
+    ///
+    /// 任务提交后为 `Pending`，工作线程取走后为 `Running`，结束后按结果变为
+    /// `Completed`、`Failed` 或 `Cancelled`；按命令池重试策略重新入队时回到 `Pending`。
+    /// 未知的任务 ID（或已通过 [`clear_finished_statuses`](Self::clear_finished_statuses)
+    /// 清理的任务）返回 None。
+    pub fn status(&self, task_id: u64) -> Option<TaskStatus> {
+        self.task_statuses.get(task_id)
+    }
+
+    /// 获取所有已记录任务的状态
+    pub fn statuses(&self) -> HashMap<u64, TaskStatus> {
+        self.task_statuses.get_all()
+    }
+
+    /// 清理已结束任务的状态，返回清理的数量
+    ///
+    /// 结束的任务状态会一直保留以便查询，长期运行的命令池应定期清理。
+    pub fn clear_finished_statuses(&self) -> usize {
+        self.task_statuses.remove_finished()
+    }
+
     /// 启动执行器
     pub fn start_executor(&self) {
         // 如果已经在运行，先停止
@@ -443,6 +1851,11 @@ impl CommandPool {
     pub fn stop(&self) {
         self.running.store(false, Ordering::SeqCst);
 
+        // 唤醒在空队列上等待的工作线程（先取锁，避免与等待前的检查竞争）
+        let (lock, cvar) = &*self.tasks;
+        drop(lock.lock().unwrap());
+        cvar.notify_all();
+
         // 等待所有线程结束
         let mut handles = self.handles.lock().unwrap();
         for handle in handles.drain(..) {
@@ -450,6 +1863,59 @@ impl CommandPool {
         }
     }
 
+    /// 停止执行器，正在执行的任务最多等待 `grace`，之后终止它们的子进程
+    ///
+    /// 与 [`stop`](Self::stop) 相同，工作线程不再取出新任务，队列中的任务保留，
+    /// 重新启动执行器后继续执行。宽限期结束时仍在执行的任务被取消：已启动的子进程被终止，
+    /// 任务句柄收到 [`ExecuteError::Cancelled`]，因此停止所需的时间有上限。
+    ///
+    /// 返回宽限期结束时被取消的任务数。使用自定义执行器时，只有执行器响应取消
+    /// （或子进程 PID 已上报）才能提前结束任务。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool};
+    /// use std::time::Duration;
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    /// pool.push_task(CommandConfig::new("sleep", vec!["600".to_string()])).unwrap();
+    /// let killed = pool.stop_with_timeout(Duration::from_secs(5));
+    /// println!("{killed} tasks terminated");
+    /// ```
+    pub fn stop_with_timeout(&self, grace: Duration) -> usize {
+        self.running.store(false, Ordering::SeqCst);
+
+        let (lock, cvar) = &*self.tasks;
+        drop(lock.lock().unwrap());
+        cvar.notify_all();
+
+        let handles: Vec<JoinHandle<()>> = self.handles.lock().unwrap().drain(..).collect();
+        let deadline = Instant::now() + grace;
+        while !handles.iter().all(JoinHandle::is_finished) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut killed = 0;
+        if !handles.iter().all(JoinHandle::is_finished) {
+            killed = self.in_flight.lock().unwrap().len();
+
+            #[cfg(feature = "logging")]
+            tracing::warn!(
+                tasks = killed,
+                grace_ms = grace.as_millis() as u64,
+                "Grace period elapsed, terminating running tasks"
+            );
+
+            self.cancel_running();
+        }
+        for handle in handles {
+            let _ = handle.join();
+        }
+        killed
+    }
+
     /// 检查执行器是否正在运行
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::SeqCst)
@@ -512,228 +1978,1013 @@ impl CommandPool {
         self.shutdown_flag.store(true, Ordering::SeqCst);
         self.running.store(false, Ordering::SeqCst);
 
-        // 2. 唤醒所有可能在等待的线程
-        let (_, cvar) = &*self.tasks;
-        cvar.notify_all();
+        // 2. 唤醒所有可能在等待的线程
+        let (_, cvar) = &*self.tasks;
+        cvar.notify_all();
+
+        // 3. 等待所有 worker 完成或超时
+        let start = Instant::now();
+        let handles_vec = self.collect_worker_handles();
+
+        // 等待 worker 完成并收集结果
+        let results = self.wait_for_workers(handles_vec, timeout, start);
+
+        // 检查结果
+        self.check_worker_results(&results)
+    }
+
+    /// 按指定模式关闭命令池
+    ///
+    /// 停止接受新任务，按 `mode` 处理队列中和正在执行的任务，再等待工作线程退出：
+    ///
+    /// - [`ShutdownMode::Drain`]：先执行完队列中的所有任务（包括尚未到期的计划任务）
+    /// - [`ShutdownMode::Finish`]：只等待正在执行的任务，队列中的任务被取消
+    /// - [`ShutdownMode::Abort`]：取消队列中的任务，并终止正在执行的子进程
+    ///
+    /// 被取消的任务句柄收到 [`ExecuteError::Cancelled`]，不会一直等待。
+    /// 整个过程（包括排空队列）使用关闭配置中的超时时间；排空超时时剩余任务被取消，
+    /// 并返回 `ShutdownError::Timeout`。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandConfig, CommandPool, ShutdownMode};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    /// pool.push_task(CommandConfig::new("sleep", vec!["1".to_string()])).unwrap();
+    /// pool.shutdown_with_mode(ShutdownMode::Drain).unwrap();
+    /// ```
+    pub fn shutdown_with_mode(&self, mode: ShutdownMode) -> Result<(), ShutdownError> {
+        let timeout = self.shutdown_config.timeout;
+        let start = Instant::now();
+
+        #[cfg(feature = "logging")]
+        tracing::info!(mode = ?mode, "Initiating shutdown");
+
+        let mut drained = true;
+        if mode == ShutdownMode::Drain {
+            self.draining.store(true, Ordering::SeqCst);
+            drained = self.wait_idle_timeout(timeout);
+        }
+
+        // 先设置关闭标志，之后出队的任务由工作线程取消而不是执行
+        self.shutdown_flag.store(true, Ordering::SeqCst);
+        self.running.store(false, Ordering::SeqCst);
+        self.cancel_queued();
+        if mode == ShutdownMode::Abort {
+            self.cancel_running();
+        }
+
+        let result = self.shutdown_with_timeout(timeout.saturating_sub(start.elapsed()));
+        if !drained {
+            return Err(ShutdownError::Timeout(timeout));
+        }
+        result
+    }
+
+    /// 是否已开始关闭（不再接受新任务）
+    fn is_closing(&self) -> bool {
+        self.shutdown_flag.load(Ordering::SeqCst) || self.draining.load(Ordering::SeqCst)
+    }
+
+    /// 收集所有 worker 线程句柄
+    fn collect_worker_handles(&self) -> Vec<JoinHandle<()>> {
+        let mut handles = self.handles.lock().unwrap();
+        handles.drain(..).collect()
+    }
+
+    /// 等待 worker 线程完成
+    ///
+    /// # 参数
+    ///
+    /// * `handles` - worker 线程句柄集合
+    /// * `timeout` - 总超时时间
+    /// * `start` - 开始时间
+    ///
+    /// # 返回
+    ///
+    /// 返回每个 worker 的执行结果（Option 表示是否成功获取结果）
+    fn wait_for_workers(
+        &self,
+        handles: Vec<JoinHandle<()>>,
+        timeout: Duration,
+        start: Instant,
+    ) -> Vec<Option<std::thread::Result<()>>> {
+        let total_workers = handles.len();
+        let mut worker_results: Vec<Option<std::thread::Result<()>>> =
+            Vec::with_capacity(total_workers);
+
+        // 创建通道用于接收 worker 完成通知
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut handles_iter = handles.into_iter();
+
+        // 为每个 worker 启动监控线程
+        for handle_id in 0..total_workers {
+            if let Some(handle) = handles_iter.next() {
+                // 检查是否已超时
+                let timeout_remaining = timeout.saturating_sub(start.elapsed());
+                if timeout_remaining.is_zero() {
+                    #[cfg(feature = "logging")]
+                    tracing::warn!(
+                        "Shutdown timeout reached, {} workers may still be running",
+                        total_workers - worker_results.len()
+                    );
+                    break;
+                }
+
+                // 启动监控线程等待 worker 完成
+                self.spawn_monitor_thread(handle, handle_id, tx.clone());
+
+                // 接收 worker 完成结果
+                self.receive_worker_result(&rx, handle_id, &mut worker_results);
+            }
+        }
+
+        // 填充剩余的 None
+        while worker_results.len() < total_workers {
+            worker_results.push(None);
+        }
+
+        worker_results
+    }
+
+    /// 启动监控线程等待 worker 完成
+    ///
+    /// # 参数
+    ///
+    /// * `handle` - worker 线程句柄
+    /// * `_handle_id` - worker ID，用于在结果中识别
+    /// * `tx` - 结果发送通道
+    fn spawn_monitor_thread(
+        &self,
+        handle: JoinHandle<()>,
+        _handle_id: usize,
+        tx: std::sync::mpsc::Sender<(usize, std::thread::Result<()>)>,
+    ) {
+        thread::spawn(move || {
+            let join_result = handle.join();
+            let _ = tx.send((_handle_id, join_result));
+        });
+    }
+
+    /// 接收 worker 完成结果
+    fn receive_worker_result(
+        &self,
+        rx: &std::sync::mpsc::Receiver<(usize, std::thread::Result<()>)>,
+        _handle_id: usize,
+        results: &mut Vec<Option<std::thread::Result<()>>>,
+    ) {
+        match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok((idx, result)) => {
+                // 确保 results 有足够空间
+                while results.len() <= idx {
+                    results.push(None);
+                }
+                results[idx] = Some(result);
+            }
+            Err(_) => {
+                // 超时或通道断开，不处理
+            }
+        }
+    }
+
+    /// 检查 worker 执行结果
+    ///
+    /// # 参数
+    ///
+    /// * `results` - worker 执行结果集合
+    ///
+    /// # 返回
+    ///
+    /// * `Ok(())` - 所有 worker 正常完成
+    /// * `Err(ShutdownError::WorkerPanic)` - 有 worker panic
+    fn check_worker_results(
+        &self,
+        results: &[Option<std::thread::Result<()>>],
+    ) -> Result<(), ShutdownError> {
+        for (idx, result) in results.iter().enumerate() {
+            if let Some(Err(_)) = result {
+                #[cfg(feature = "logging")]
+                tracing::error!("Worker {} panicked", idx);
+                return Err(ShutdownError::WorkerPanic);
+            }
+        }
+
+        #[cfg(feature = "logging")]
+        tracing::info!("Graceful shutdown completed successfully");
+        Ok(())
+    }
+
+    /// 配置关闭行为
+    ///
+    /// 设置关闭超时时间和是否强制终止进程。
+    ///
+    /// # 参数
+    ///
+    /// * `config` - 关闭配置
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use execute::{CommandPool, ShutdownConfig};
+    /// use std::time::Duration;
+    ///
+    /// let mut pool = CommandPool::new();
+    /// pool.set_shutdown_config(
+    ///     ShutdownConfig::new(Duration::from_secs(60))
+    ///         .with_force_kill(true)
+    /// );
+    /// ```
+    pub fn set_shutdown_config(&mut self, config: ShutdownConfig) {
+        self.shutdown_config = config;
+    }
+
+    /// 检查是否正在关闭或已关闭
+    ///
+    /// # 返回
+    ///
+    /// * `true` - 正在关闭或已关闭
+    /// * `false` - 正常运行
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown_flag.load(Ordering::SeqCst)
+    }
+
+    /// 计算第 `index` 个工作线程的启动延迟
+    fn worker_start_delay(&self, index: usize) -> Duration {
+        self.options
+            .worker_start_interval
+            .map(|interval| interval.saturating_mul(index as u32))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// 等待工作线程的启动延迟结束
+    ///
+    /// 等待期间若执行器被停止或关闭则返回 false。
+    fn wait_worker_start(&self, delay: Duration) -> bool {
+        let deadline = Instant::now() + delay;
+        loop {
+            if !self.running.load(Ordering::SeqCst) || self.shutdown_flag.load(Ordering::SeqCst) {
+                return false;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return true;
+            }
+            thread::sleep(remaining.min(Duration::from_millis(10)));
+        }
+    }
+
+    fn start_workers(&self) {
+        self.launch_workers(Arc::new(|pool, index, generation, delay| {
+            pool.spawn_worker(index, generation, delay)
+        }));
+    }
+
+    /// 以 `spawner` 启动工作线程和看门狗
+    ///
+    /// 设置了最少空闲工作线程数时只预先启动这么多工作线程，其余按需启动。
+    fn launch_workers(&self, spawner: WorkerSpawner) {
+        *self.spawner.lock().unwrap() = Some(spawner);
+        let prewarm = self
+            .options
+            .min_idle_workers
+            .map_or(self.config.workers, |min| min.min(self.config.workers));
+        for index in 0..prewarm {
+            self.worker_slots.lock().unwrap().claim(index);
+            self.launch_worker(index, self.worker_start_delay(index));
+        }
+        self.start_watchdog();
+    }
+
+    /// 启动已登记的第 `index` 个工作线程，沿用该序号当前的代号
+    fn launch_worker(&self, index: usize, delay: Duration) {
+        let Some(spawner) = self.spawner.lock().unwrap().clone() else {
+            return;
+        };
+        let handle = spawner(self, index, self.activity.generation(index), delay);
+        let mut handles = self.handles.lock().unwrap();
+        // 因空闲退出的工作线程已结束，不再保留句柄
+        handles.retain(|handle| !handle.is_finished());
+        handles.push(handle);
+    }
+
+    /// 登记工作线程开始处理任务，空闲工作线程少于最少空闲数时启动新的工作线程
+    fn begin_busy(&self) -> BusyGuard {
+        self.worker_slots.lock().unwrap().begin_task();
+        let guard = BusyGuard {
+            slots: Arc::clone(&self.worker_slots),
+        };
+        if let Some(min_idle) = self.min_idle_workers() {
+            loop {
+                let index = {
+                    let mut slots = self.worker_slots.lock().unwrap();
+                    if slots.idle() >= min_idle {
+                        break;
+                    }
+                    match slots.claim_vacant() {
+                        Some(index) => index,
+                        None => break,
+                    }
+                };
+                #[cfg(feature = "logging")]
+                tracing::debug!(worker = index, "Starting worker on demand");
+                self.launch_worker(index, Duration::ZERO);
+            }
+        }
+        guard
+    }
+
+    /// 需要保持的最少空闲工作线程数
+    ///
+    /// 只设置空闲超时时为 1，两者都未设置时为 None（不按需启动工作线程）。
+    fn min_idle_workers(&self) -> Option<usize> {
+        self.options
+            .min_idle_workers
+            .or(self.options.idle_timeout.map(|_| 1))
+    }
+
+    /// 启动队列中亲和键对应、尚未启动的工作线程（调用方持有队列锁）
+    fn launch_affinity_targets(&self, tasks: &TaskQueue) {
+        let mut targets: Vec<usize> = tasks
+            .iter()
+            .filter_map(|item| item.config.affinity_key())
+            .map(|key| worker_for_key(key, self.config.workers))
+            .collect();
+        targets.sort_unstable();
+        targets.dedup();
+        for index in targets {
+            {
+                let mut slots = self.worker_slots.lock().unwrap();
+                if slots.is_live(index) {
+                    continue;
+                }
+                slots.claim(index);
+            }
+            self.launch_worker(index, Duration::ZERO);
+        }
+    }
+
+    /// 启动使用命令池后端的第 `index` 个工作线程
+    ///
+    /// `generation` 区分同一序号的原工作线程和看门狗启动的替补线程。
+    fn spawn_worker(&self, index: usize, generation: u64, delay: Duration) -> JoinHandle<()> {
+        let pool = self.background_clone();
+        self.spawn_named_worker(index, move || {
+            if !delay.is_zero() && !pool.wait_worker_start(delay) {
+                return;
+            }
+            pool.events.emit(PoolEvent::WorkerStarted { worker: index });
+            while pool.running.load(Ordering::SeqCst) && !pool.shutdown_flag.load(Ordering::SeqCst)
+            {
+                if let Some((task_item, _dispatch)) = pool.pop_task_for(Some(index)) {
+                    let _busy = pool.begin_busy();
+                    if !pool.running.load(Ordering::SeqCst)
+                        || pool.shutdown_flag.load(Ordering::SeqCst)
+                    {
+                        pool.return_unstarted(task_item);
+                        break;
+                    }
+
+                    if task_item.handle.is_cancelled() || pool.fail_fast_tripped(&task_item) {
+                        let task_id = task_item.handle.id();
+                        pool.send_result(
+                            &task_item,
+                            Err(ExecuteError::Cancelled(task_id)),
+                            Duration::ZERO,
+                        );
+                        continue;
+                    }
+
+                    if pool.discard_expired(&task_item) {
+                        continue;
+                    }
+
+                    let Ok(singleton) = pool.claim_singleton(&task_item) else {
+                        continue;
+                    };
+
+                    let queue_wait = queue_wait(&task_item);
+                    pool.mark_running(&task_item);
+                    let activity = pool.track_activity(index, generation, &task_item);
+                    let started_at = SystemTime::now();
+                    let started = Instant::now();
+                    let result = pool
+                        .observe_spawns(&task_item.handle, || {
+                            pool.execute_task_with_handle(&task_item.config, &task_item.handle)
+                        })
+                        .map(|mut report| {
+                            // 后端没有提供时间信息时使用工作线程的测量值
+                            let timing = report.timing.get_or_insert_with(|| {
+                                ExecutionTiming::direct(started_at, started.elapsed())
+                            });
+                            timing.queue_wait = queue_wait;
+                            timing.attempt = task_item.handle.attempts();
+                            pool.output_history.record(&task_item.config, &mut report);
+                            // 输出通过结果通道发送，其余元数据保存在句柄中
+                            let output = report.take_output();
+                            task_item.handle.set_report(report);
+                            output
+                        });
+                    // 先释放单例键，保证调用方拿到结果后可立即再次提交
+                    drop(singleton);
+                    drop(activity);
+                    pool.finish_attempt(task_item, result, started.elapsed());
+                    // 已被看门狗替换的工作线程结束当前任务后退出
+                    if pool.activity.is_retired(index, generation) {
+                        pool.wake_replacement();
+                        break;
+                    }
+                } else {
+                    break;
+                }
+            }
+            pool.events.emit(PoolEvent::WorkerStopped { worker: index });
+        })
+    }
+
+    /// 被替换的旧线程结束任务后唤醒等待亲和键任务的替补线程
+    fn wake_replacement(&self) {
+        // 持有队列锁再通知，避免替补线程在检查后、等待前错过唤醒
+        let (lock, cvar) = &*self.tasks;
+        let _tasks = lock.lock().unwrap();
+        cvar.notify_all();
+    }
+
+    /// 供后台线程持有的克隆
+    ///
+    /// 后台线程因执行器停止而退出时丢弃克隆，不能因此关闭命令池，否则无法重新启动执行器。
+    fn background_clone(&self) -> CommandPool {
+        let mut pool = self.clone();
+        pool.retired = true;
+        pool
+    }
+
+    /// 启动名为 `<前缀>-<序号>` 的工作线程
+    ///
+    /// 调用方已在 `worker_slots` 中登记该序号，线程退出时注销。
+    /// 启用 `logging` feature 时线程内的日志事件都位于带 `worker` 字段的 span 中。
+    fn spawn_named_worker<F>(&self, index: usize, body: F) -> JoinHandle<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let slot = SlotGuard {
+            slots: Arc::clone(&self.worker_slots),
+            index,
+        };
+        thread::Builder::new()
+            .name(format!("{}-{}", self.options.worker_name_prefix, index))
+            .spawn(move || {
+                let _slot = slot;
+                #[cfg(feature = "logging")]
+                let _span = tracing::info_span!("worker", worker = index).entered();
+                body()
+            })
+            .expect("failed to spawn worker thread")
+    }
+
+    /// 看门狗启用时登记工作线程正在执行的任务
+    fn track_activity(
+        &self,
+        index: usize,
+        generation: u64,
+        item: &TaskItem,
+    ) -> Option<ActivityGuard> {
+        let threshold = self.options.watchdog.as_ref()?.threshold(&item.config)?;
+        Some(
+            self.activity
+                .begin(index, generation, item.handle.id(), threshold),
+        )
+    }
+
+    /// 启动看门狗线程（未启用时不启动）
+    ///
+    /// 卡住的工作线程由当前执行器的启动方式启动替补。
+    /// 看门狗在执行器停止或命令池关闭后退出。
+    fn start_watchdog(&self) {
+        let Some(watchdog) = self.options.watchdog.clone() else {
+            return;
+        };
+        let pool = self.background_clone();
+        thread::spawn(move || {
+            // 等待检查间隔，期间停止或关闭则退出
+            while pool.wait_worker_start(watchdog.check_interval) {
+                for stuck in pool.activity.find_stuck() {
+                    #[cfg(feature = "logging")]
+                    tracing::warn!(
+                        worker = stuck.worker,
+                        task_id = stuck.task_id,
+                        elapsed_ms = stuck.elapsed.as_millis(),
+                        replace = watchdog.replace_stuck,
+                        "Worker appears stuck"
+                    );
+                    #[cfg(feature = "metrics")]
+                    pool.metrics.record_worker_stuck();
+                    if watchdog.replace_stuck {
+                        pool.activity.replace(stuck.worker);
+                        pool.worker_slots.lock().unwrap().claim(stuck.worker);
+                        pool.launch_worker(stuck.worker, Duration::ZERO);
+                    }
+                    pool.events.emit(PoolEvent::WorkerStuck {
+                        worker: stuck.worker,
+                        task_id: stuck.task_id,
+                        elapsed: stuck.elapsed,
+                        replaced: watchdog.replace_stuck,
+                    });
+                }
+            }
+        });
+    }
+
+    /// 应用命令池级别的任务默认值
+    fn apply_task_defaults(&self, mut task: CommandConfig) -> CommandConfig {
+        self.options.task_defaults.apply(&mut task);
+        task
+    }
+
+    /// 丢弃在队列中等待超过存活时间的任务
+    ///
+    /// 任务被标记为取消并发送 [`ExecuteError::Expired`]，返回 `true`。
+    fn discard_expired(&self, item: &TaskItem) -> bool {
+        let Some(ttl) = item.config.queue_ttl() else {
+            return false;
+        };
+        let waited = queue_wait(item);
+        if waited <= ttl {
+            return false;
+        }
+
+        #[cfg(feature = "logging")]
+        tracing::info!(
+            task_id = item.handle.id(),
+            waited_ms = waited.as_millis() as u64,
+            "Task expired in queue"
+        );
+        item.handle.set_state(TaskState::Cancelled);
+        self.send_result(item, Err(ExecuteError::Expired(ttl)), Duration::ZERO);
+        true
+    }
+
+    /// 为单例任务占用键
+    ///
+    /// 返回的守卫在任务结束丢弃时释放键。同键任务正在运行时，
+    /// 任务被标记为跳过并发送 [`ExecuteError::Skipped`]，返回 `Err(())`。
+    fn claim_singleton(&self, item: &TaskItem) -> Result<Option<SingletonGuard>, ()> {
+        let Some(key) = item.config.singleton_key() else {
+            return Ok(None);
+        };
+
+        if self.singletons.lock().unwrap().insert(key.to_string()) {
+            return Ok(Some(SingletonGuard {
+                key: key.to_string(),
+                singletons: Arc::clone(&self.singletons),
+            }));
+        }
+
+        #[cfg(feature = "logging")]
+        tracing::info!(
+            task_id = item.handle.id(),
+            key = key,
+            "Task skipped, singleton already running"
+        );
+        item.handle.set_state(TaskState::Skipped);
+        self.send_result(
+            item,
+            Err(ExecuteError::Skipped(key.to_string())),
+            Duration::ZERO,
+        );
+        Err(())
+    }
+
+    /// 将任务标记为执行中，合并到该任务的句柄同步更新
+    fn mark_running(&self, item: &TaskItem) {
+        item.handle.set_state(TaskState::Running { pid: None });
+        self.task_statuses
+            .update(item.handle.id(), TaskStatus::Running);
+        let attempt = item.handle.begin_attempt();
+        self.stats
+            .record_started((attempt == 1).then(|| item.handle.submitted_at().elapsed()));
+        self.in_flight
+            .lock()
+            .unwrap()
+            .insert(item.handle.id(), InFlight::new(item));
+        if let Some(key) = item.config.coalesce_key() {
+            self.coalesced.mark_running(key);
+        }
+    }
+
+    /// 结束一次执行：按重试策略延迟后重新入队，否则发送最终结果
+    fn finish_attempt(&self, mut item: TaskItem, result: TaskResult, duration: Duration) {
+        if let Some(delay) = self.retry_delay(&item, &result)
+            && item.handle.requeue()
+        {
+            let task_id = item.handle.id();
+            let attempt = item.handle.attempts();
+            self.in_flight.lock().unwrap().remove(&task_id);
+            self.stats.record_run(duration);
+
+            #[cfg(feature = "logging")]
+            tracing::info!(
+                task_id = task_id,
+                attempt = attempt,
+                delay_ms = delay.as_millis() as u64,
+                "Task failed, re-queued for retry"
+            );
+
+            let (lock, cvar) = &*self.tasks;
+            let mut tasks = lock.lock().unwrap();
+            // 持有队列锁检查关闭标志，避免任务在关闭取消队列之后才放回
+            if !self.shutdown_flag.load(Ordering::SeqCst) {
+                self.events.emit(PoolEvent::TaskRetrying {
+                    task_id,
+                    attempt,
+                    delay,
+                });
+                item.config.start_at = Some(SystemTime::now() + delay);
+                self.task_statuses.update(task_id, TaskStatus::Pending);
+                tasks.push(item);
+                cvar.notify_all();
+                return;
+            }
+            drop(tasks);
+            item.handle.set_state(TaskState::Running { pid: None });
+        }
+        self.send_result(&item, result, duration);
+    }
+
+    /// 任务失败后按重试策略计算重试前的延迟，不需要重试时返回 None
+    ///
+    /// 自身设置了重试策略的任务只按自己的策略重试，否则使用命令池的重试策略。
+    fn retry_delay(&self, item: &TaskItem, result: &TaskResult) -> Option<Duration> {
+        if self.shutdown_flag.load(Ordering::SeqCst) {
+            return None;
+        }
+        let attempt = item.handle.attempts() as usize;
+        if let Some(policy) = item.config.retry_policy() {
+            let retryable = !matches!(
+                result,
+                Err(ExecuteError::Cancelled(_) | ExecuteError::Skipped(_))
+            ) && policy.should_retry(&item.config, result.as_ref());
+            return (retryable && attempt <= policy.max_attempts)
+                .then(|| policy.delay_for_attempt(attempt));
+        }
+        let policy = self.options.retry_policy.as_ref()?;
+        if attempt > policy.max_attempts || !policy.should_retry(&item.config, result) {
+            return None;
+        }
+        Some(policy.delay_for_attempt(attempt))
+    }
+
+    /// 发送任务结果，同时广播给合并到该任务的句柄
+    ///
+    /// 执行中的任务先标记为已完成再发布事件和发送结果，
+    /// 保证调用方拿到结果或收到事件时状态已经更新。
+    fn send_result(&self, item: &TaskItem, result: TaskResult, duration: Duration) {
+        item.handle.mark_completed();
+        // 未执行就被取消的任务不在执行集合中，不计入运行时间
+        if self
+            .in_flight
+            .lock()
+            .unwrap()
+            .remove(&item.handle.id())
+            .is_some()
+        {
+            self.stats.record_run(duration);
+        }
+        let trip = self.options.fail_fast && self.record_fail_fast(item, &result);
+        let followers = match item.config.coalesce_key() {
+            Some(key) => self.coalesced.complete(key, &item.handle, &result),
+            None => Vec::new(),
+        };
+        let status = FinishStatus::from_result(&result);
+        self.stats
+            .record_finished(&status, 1 + followers.len() as u64);
+        let task_status = match status {
+            FinishStatus::Success => TaskStatus::Completed,
+            FinishStatus::Cancelled | FinishStatus::Skipped | FinishStatus::Expired => {
+                TaskStatus::Cancelled
+            }
+            _ => TaskStatus::Failed,
+        };
+        for task_id in std::iter::once(item.handle.id()).chain(followers.iter().copied()) {
+            self.task_statuses.update(task_id, task_status);
+        }
+        #[cfg(feature = "persistence")]
+        {
+            let mut task_ids = vec![item.handle.id()];
+            task_ids.extend_from_slice(&followers);
+            self.acknowledge_finished(&task_ids, &status);
+        }
+        if self.events.has_subscribers() {
+            for task_id in std::iter::once(item.handle.id()).chain(followers.iter().copied()) {
+                self.events.emit(PoolEvent::TaskFinished {
+                    task_id,
+                    status: status.clone(),
+                    duration,
+                });
+            }
+        }
+        self.run_callbacks(item.handle.id(), &followers, &result);
+        if let Some(sink) = &self.result_sink {
+            for task_id in std::iter::once(item.handle.id()).chain(followers.iter().copied()) {
+                let copy = match &result {
+                    Ok(output) => Ok(output.clone()),
+                    Err(e) => Err(e.duplicate()),
+                };
+                sink.accept(task_id, copy);
+            }
+        }
+        let _ = item.result_sender.send(result);
+        if trip {
+            self.trip_fail_fast(item.handle.id());
+        }
+        self.finish_outstanding(1);
+    }
+
+    /// 对任务及合并到它的跟随者调用已注册的结束回调
+    fn run_callbacks(&self, task_id: u64, followers: &[u64], result: &TaskResult) {
+        // 复制回调列表后再调用，回调内部可以继续注册回调
+        let (complete, failed) = {
+            let callbacks = self.callbacks.read().unwrap();
+            if callbacks.complete.is_empty() && callbacks.failed.is_empty() {
+                return;
+            }
+            (callbacks.complete.clone(), callbacks.failed.clone())
+        };
+        let is_failure = FinishStatus::from_result(result).is_failure();
+        for id in std::iter::once(task_id).chain(followers.iter().copied()) {
+            for callback in &complete {
+                callback(id, result);
+            }
+            if is_failure {
+                for callback in &failed {
+                    callback(id, result);
+                }
+            }
+        }
+    }
 
-        // 3. 等待所有 worker 完成或超时
-        let start = Instant::now();
-        let handles_vec = self.collect_worker_handles();
+    /// 快速失败模式下记录任务结束，返回本次结果是否触发停止
+    fn record_fail_fast(&self, item: &TaskItem, result: &TaskResult) -> bool {
+        if !FinishStatus::from_result(result).is_failure() {
+            return false;
+        }
+        let mut first_failure = self.first_failure.lock().unwrap();
+        if first_failure.is_some() {
+            return false;
+        }
+        *first_failure = Some(item.handle.id());
+        true
+    }
 
-        // 等待 worker 完成并收集结果
-        let results = self.wait_for_workers(handles_vec, timeout, start);
+    /// 取消队列中和正在执行的任务
+    fn trip_fail_fast(&self, task_id: u64) {
+        #[cfg(feature = "logging")]
+        tracing::warn!(
+            task_id = task_id,
+            "Task failed, fail-fast cancelling remaining tasks"
+        );
+        #[cfg(not(feature = "logging"))]
+        let _ = task_id;
 
-        // 检查结果
-        self.check_worker_results(&results)
+        self.cancel_queued();
+        self.cancel_running();
     }
 
-    /// 收集所有 worker 线程句柄
-    fn collect_worker_handles(&self) -> Vec<JoinHandle<()>> {
-        let mut handles = self.handles.lock().unwrap();
-        handles.drain(..).collect()
+    /// 取消队列中的所有任务，任务句柄收到 `Cancelled` 结果
+    fn cancel_queued(&self) {
+        let queued: Vec<TaskItem> = {
+            let (lock, cvar) = &*self.tasks;
+            let mut tasks = lock.lock().unwrap();
+            let queued = tasks.drain();
+            cvar.notify_all();
+            queued
+        };
+        for item in queued {
+            self.cancel_unstarted(&item);
+        }
     }
 
-    /// 等待 worker 线程完成
-    ///
-    /// # 参数
-    ///
-    /// * `handles` - worker 线程句柄集合
-    /// * `timeout` - 总超时时间
-    /// * `start` - 开始时间
-    ///
-    /// # 返回
-    ///
-    /// 返回每个 worker 的执行结果（Option 表示是否成功获取结果）
-    fn wait_for_workers(
-        &self,
-        handles: Vec<JoinHandle<()>>,
-        timeout: Duration,
-        start: Instant,
-    ) -> Vec<Option<std::thread::Result<()>>> {
-        let total_workers = handles.len();
-        let mut worker_results: Vec<Option<std::thread::Result<()>>> =
-            Vec::with_capacity(total_workers);
-
-        // 创建通道用于接收 worker 完成通知
-        let (tx, rx) = std::sync::mpsc::channel();
-        let mut handles_iter = handles.into_iter();
-
-        // 为每个 worker 启动监控线程
-        for handle_id in 0..total_workers {
-            if let Some(handle) = handles_iter.next() {
-                // 检查是否已超时
-                let timeout_remaining = timeout.saturating_sub(start.elapsed());
-                if timeout_remaining.is_zero() {
-                    #[cfg(feature = "logging")]
-                    tracing::warn!(
-                        "Shutdown timeout reached, {} workers may still be running",
-                        total_workers - worker_results.len()
-                    );
-                    break;
-                }
+    /// 取消正在执行的任务，已启动的子进程被终止
+    fn cancel_running(&self) {
+        let running: Vec<TaskHandle> = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| entry.handle.clone())
+            .collect();
+        for handle in running {
+            let _ = handle.cancel();
+        }
+    }
 
-                // 启动监控线程等待 worker 完成
-                self.spawn_monitor_thread(handle, handle_id, tx.clone());
+    /// 取消尚未开始执行的任务并发送 `Cancelled` 结果
+    fn cancel_unstarted(&self, item: &TaskItem) {
+        let _ = item.handle.cancel();
+        let task_id = item.handle.id();
+        self.send_result(item, Err(ExecuteError::Cancelled(task_id)), Duration::ZERO);
+    }
 
-                // 接收 worker 完成结果
-                self.receive_worker_result(&rx, handle_id, &mut worker_results);
-            }
+    /// 工作线程退出时归还已出队但未执行的任务
+    ///
+    /// 命令池关闭时取消任务；仅停止执行器时放回队列原位置，重新启动后继续执行。
+    fn return_unstarted(&self, item: TaskItem) {
+        if self.shutdown_flag.load(Ordering::SeqCst) {
+            self.cancel_unstarted(&item);
+        } else {
+            let (lock, _) = &*self.tasks;
+            lock.lock().unwrap().push(item);
         }
+    }
 
-        // 填充剩余的 None
-        while worker_results.len() < total_workers {
-            worker_results.push(None);
+    /// 快速失败已触发时取消刚出队的任务，返回是否已取消
+    fn fail_fast_tripped(&self, item: &TaskItem) -> bool {
+        if !self.options.fail_fast || self.first_failure().is_none() {
+            return false;
         }
-
-        worker_results
+        let _ = item.handle.cancel();
+        true
     }
 
-    /// 启动监控线程等待 worker 完成
-    ///
-    /// # 参数
+    /// 快速失败模式下触发停止的首个失败任务 ID
     ///
-    /// * `handle` - worker 线程句柄
-    /// * `_handle_id` - worker ID，用于在结果中识别
-    /// * `tx` - 结果发送通道
-    fn spawn_monitor_thread(
-        &self,
-        handle: JoinHandle<()>,
-        _handle_id: usize,
-        tx: std::sync::mpsc::Sender<(usize, std::thread::Result<()>)>,
-    ) {
-        thread::spawn(move || {
-            let join_result = handle.join();
-            let _ = tx.send((_handle_id, join_result));
-        });
+    /// 未启用快速失败或尚无任务失败时返回 None。
+    pub fn first_failure(&self) -> Option<u64> {
+        *self.first_failure.lock().unwrap()
     }
 
-    /// 接收 worker 完成结果
-    fn receive_worker_result(
-        &self,
-        rx: &std::sync::mpsc::Receiver<(usize, std::thread::Result<()>)>,
-        _handle_id: usize,
-        results: &mut Vec<Option<std::thread::Result<()>>>,
-    ) {
-        match rx.recv_timeout(Duration::from_millis(100)) {
-            Ok((idx, result)) => {
-                // 确保 results 有足够空间
-                while results.len() <= idx {
-                    results.push(None);
-                }
-                results[idx] = Some(result);
-            }
-            Err(_) => {
-                // 超时或通道断开，不处理
-            }
+    /// 清除快速失败状态，恢复正常分派
+    pub fn reset_fail_fast(&self) {
+        *self.first_failure.lock().unwrap() = None;
+    }
+
+    /// 执行 `f`，期间启动的子进程 PID 记录到任务句柄并发布启动事件
+    fn observe_spawns<R>(&self, handle: &TaskHandle, f: impl FnOnce() -> R) -> R {
+        let events = Arc::clone(&self.events);
+        let handle = handle.clone();
+        with_spawn_observer(
+            move |pid| {
+                handle.set_running_pid(pid);
+                events.emit(PoolEvent::TaskStarted {
+                    task_id: handle.id(),
+                    pid,
+                });
+            },
+            f,
+        )
+    }
+
+    /// 发布任务入队事件，队列达到高水位时发布高水位事件
+    fn on_task_queued(&self, task_id: u64, task: &CommandConfig, depth: usize) {
+        if !self.events.has_subscribers() {
+            return;
+        }
+        self.events.emit(PoolEvent::TaskQueued {
+            task_id,
+            command: task.program().into_owned(),
+        });
+        if let Some(watermark) = self.options.queue_high_watermark
+            && depth >= watermark
+            && !self.above_watermark.swap(true, Ordering::SeqCst)
+        {
+            self.events.emit(PoolEvent::QueueHighWatermark { depth });
         }
     }
 
-    /// 检查 worker 执行结果
+    /// 订阅命令池事件
     ///
-    /// # 参数
+    /// 返回的接收端会收到订阅之后发布的所有事件，与执行钩子相互独立，
+    /// 可以有多个订阅者同时消费同一事件流。丢弃接收端即取消订阅。
     ///
-    /// * `results` - worker 执行结果集合
+    /// # 示例
     ///
-    /// # 返回
+    /// ```ignore
+    /// use execute::{CommandPool, PoolEvent};
     ///
-    /// * `Ok(())` - 所有 worker 正常完成
-    /// * `Err(ShutdownError::WorkerPanic)` - 有 worker panic
-    fn check_worker_results(
-        &self,
-        results: &[Option<std::thread::Result<()>>],
-    ) -> Result<(), ShutdownError> {
-        for (idx, result) in results.iter().enumerate() {
-            if let Some(Err(_)) = result {
-                #[cfg(feature = "logging")]
-                tracing::error!("Worker {} panicked", idx);
-                return Err(ShutdownError::WorkerPanic);
-            }
-        }
-
-        #[cfg(feature = "logging")]
-        tracing::info!("Graceful shutdown completed successfully");
-        Ok(())
+    /// let pool = CommandPool::new();
+    /// let events = pool.subscribe();
+    /// std::thread::spawn(move || {
+    ///     for event in events {
+    ///         if let PoolEvent::TaskFinished { task_id, status, .. } = event {
+    ///             println!("task {} finished: {:?}", task_id, status);
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn subscribe(&self) -> Receiver<PoolEvent> {
+        self.events.subscribe()
     }
 
-    /// 配置关闭行为
+    /// 注册任务结束回调
     ///
-    /// 设置关闭超时时间和是否强制终止进程。
+    /// 每个任务结束时（包括失败、超时、取消和跳过）在执行它的工作线程上调用，
+    /// 参数为任务 ID 和任务结果，调用发生在任务句柄收到结果之前。
+    /// 合并执行的任务对每个任务 ID 各调用一次。可以多次调用以注册多个回调，
+    /// 命令池的克隆共享同一组回调；回调应尽快返回，避免阻塞工作线程。
     ///
-    /// # 参数
+    /// # 示例
     ///
-    /// * `config` - 关闭配置
+    /// ```ignore
+    /// use execute::CommandPool;
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.on_task_complete(|task_id, result| {
+    ///     println!("task {} finished: ok={}", task_id, result.is_ok());
+    /// });
+    /// ```
+    pub fn on_task_complete<F>(&self, callback: F)
+    where
+        F: Fn(u64, &TaskResult) + Send + Sync + 'static,
+    {
+        self.callbacks
+            .write()
+            .unwrap()
+            .complete
+            .push(Arc::new(callback));
+    }
+
+    /// 注册任务失败回调
+    ///
+    /// 只在任务失败时调用：非零退出（或不在成功退出码中）、超时和执行错误；
+    /// 取消和跳过的任务不算失败。调用时机与 [`on_task_complete`](Self::on_task_complete) 相同。
     ///
     /// # 示例
     ///
-    /// ```no_run
-    /// use execute::{CommandPool, ShutdownConfig};
-    /// use std::time::Duration;
+    /// ```ignore
+    /// use execute::CommandPool;
     ///
-    /// let mut pool = CommandPool::new();
-    /// pool.set_shutdown_config(
-    ///     ShutdownConfig::new(Duration::from_secs(60))
-    ///         .with_force_kill(true)
-    /// );
+    /// let pool = CommandPool::new();
+    /// pool.on_task_failed(|task_id, result| {
+    ///     eprintln!("task {} failed: {:?}", task_id, result);
+    /// });
     /// ```
-    pub fn set_shutdown_config(&mut self, config: ShutdownConfig) {
-        self.shutdown_config = config;
+    pub fn on_task_failed<F>(&self, callback: F)
+    where
+        F: Fn(u64, &TaskResult) + Send + Sync + 'static,
+    {
+        self.callbacks
+            .write()
+            .unwrap()
+            .failed
+            .push(Arc::new(callback));
     }
 
-    /// 检查是否正在关闭或已关闭
+    /// 创建在接下来 `count` 个任务完成后解除的屏障
     ///
-    /// # 返回
+    /// 只统计屏障创建之后完成的任务（包括失败、超时、取消和跳过的任务）。
     ///
-    /// * `true` - 正在关闭或已关闭
-    /// * `false` - 正常运行
-    pub fn is_shutting_down(&self) -> bool {
-        self.shutdown_flag.load(Ordering::SeqCst)
+    /// # 示例
+    ///
+    /// ```ignore
+    /// let barrier = pool.barrier(20);
+    /// for job in jobs {
+    ///     pool.push_task(job)?;
+    /// }
+    /// barrier.wait();
+    /// ```
+    pub fn barrier(&self, count: usize) -> BarrierHandle {
+        BarrierHandle::count(self.events.subscribe(), count)
     }
 
-    fn start_workers(&self) {
-        for _ in 0..self.config.workers {
-            let pool = self.clone();
-            let handle = thread::spawn(move || {
-                while pool.running.load(Ordering::SeqCst)
-                    && !pool.shutdown_flag.load(Ordering::SeqCst)
-                {
-                    if let Some(task_item) = pool.pop_task() {
-                        if !pool.running.load(Ordering::SeqCst)
-                            || pool.shutdown_flag.load(Ordering::SeqCst)
-                        {
-                            break;
-                        }
-
-                        if task_item.handle.is_cancelled() {
-                            let task_id = task_item.handle.id();
-                            let _ = task_item
-                                .result_sender
-                                .send(Err(ExecuteError::Cancelled(task_id)));
-                            continue;
-                        }
-
-                        task_item.handle.set_state(TaskState::Running { pid: None });
-                        let result =
-                            pool.execute_task_with_handle(&task_item.config, &task_item.handle);
-                        let _ = task_item.result_sender.send(result);
-
-                        if !task_item.handle.is_cancelled() {
-                            task_item.handle.set_state(TaskState::Completed);
-                        }
-                    } else {
-                        break;
-                    }
-                }
-            });
-            self.handles.lock().unwrap().push(handle);
-        }
+    /// 创建在指定任务全部完成后解除的屏障
+    ///
+    /// 创建时已经完成的任务视为已满足。
+    ///
+    /// # 示例
+    ///
+    /// ```ignore
+    /// let handles: Vec<_> = jobs.into_iter().map(|job| pool.push_task(job)).collect::<Result<_, _>>()?;
+    /// pool.barrier_for(&handles).wait();
+    /// ```
+    pub fn barrier_for(&self, tasks: &[TaskHandle]) -> BarrierHandle {
+        // 先订阅再检查状态：任务在发布完成事件之前更新状态，
+        // 因此检查时未完成的任务，其完成事件一定会被收到
+        let events = self.events.subscribe();
+        let pending = tasks
+            .iter()
+            .filter(|task| matches!(task.state(), TaskState::Queued | TaskState::Running { .. }))
+            .map(TaskHandle::id)
+            .collect();
+        BarrierHandle::tasks(events, pending)
     }
 
     /// 执行单个任务
+    ///
+    /// 在调用线程中执行，按命令的重试策略重试时也在调用线程中等待退避。
     pub fn execute_task(
         &self,
         config: &CommandConfig,
     ) -> Result<std::process::Output, ExecuteError> {
-        let task_id = self.task_id_counter.load(Ordering::SeqCst);
+        let with_defaults;
+        let config = if self.options.task_defaults.is_empty() {
+            config
+        } else {
+            with_defaults = self.apply_task_defaults(config.clone());
+            &with_defaults
+        };
+        let config = &*self.rewrite_command(config);
+        let task_id = self.task_ids.peek();
         let start_time = Instant::now();
 
         #[cfg(feature = "logging")]
@@ -746,16 +2997,9 @@ impl CommandPool {
         #[cfg(feature = "metrics")]
         self.metrics.record_task_started();
 
-        // 如果配置了重试策略，使用 execute_with_retry，否则直接执行
-        let result = if config.retry_policy().is_some() {
-            // 使用带重试的执行逻辑
-            use crate::executor::execute_with_retry;
-            execute_with_retry(config, task_id)
-                .map_err(|e| ExecuteError::Io(std::io::Error::other(e.to_string())))
-        } else {
-            // 直接使用后端执行
-            self.backend.execute(config)
-        };
+        let result = self
+            .execute_report_with_retry(config, task_id)
+            .map(|report| report.output);
 
         let duration = start_time.elapsed();
 
@@ -795,7 +3039,7 @@ impl CommandPool {
         &self,
         config: &CommandConfig,
         handle: &TaskHandle,
-    ) -> Result<std::process::Output, ExecuteError> {
+    ) -> Result<ExecutionReport, ExecuteError> {
         let task_id = handle.id();
         let start_time = Instant::now();
 
@@ -816,16 +3060,10 @@ impl CommandPool {
             return Err(ExecuteError::Cancelled(task_id));
         }
 
-        // 如果配置了重试策略，使用 execute_with_retry，否则直接执行
-        let result = if config.retry_policy().is_some() {
-            // 使用带重试的执行逻辑
-            use crate::executor::execute_with_retry;
-            execute_with_retry(config, task_id)
-                .map_err(|e| ExecuteError::Io(std::io::Error::other(e.to_string())))
-        } else {
-            // 直接使用后端执行
-            self.backend.execute(config)
-        };
+        let config = &*self.rewrite_command(config);
+
+        // 任务自身的重试由 finish_attempt 重新入队完成，这里只执行一次
+        let result = self.backend.execute_report(config);
 
         let duration = start_time.elapsed();
 
@@ -839,8 +3077,8 @@ impl CommandPool {
         }
 
         match &result {
-            Ok(output) => {
-                let exit_code = output.status.code().unwrap_or(-1);
+            Ok(report) => {
+                let exit_code = report.output.status.code().unwrap_or(-1);
                 #[cfg(feature = "logging")]
                 tracing::info!(
                     task_id = task_id,
@@ -867,67 +3105,137 @@ impl CommandPool {
         result
     }
 
-    /// 使用自定义执行器启动（高级用法）
-    pub fn start_with_executor<E: CommandExecutor + 'static>(
+    /// 在调用线程中通过后端执行命令，按命令的重试策略重试
+    ///
+    /// 每次尝试都经过后端，配额、并发限制和执行报告与不重试时相同。
+    fn execute_report_with_retry(
         &self,
-        _interval: Duration,
-        executor: Arc<E>,
-    ) {
+        config: &CommandConfig,
+        task_id: u64,
+    ) -> Result<ExecutionReport, ExecuteError> {
+        let Some(policy) = config.retry_policy() else {
+            return self.backend.execute_report(config);
+        };
+        let max_attempts = policy.max_attempts + 1;
+        let mut attempt = 1;
+        loop {
+            let result = self.backend.execute_report(config);
+            let output = result.as_ref().map(|report| &report.output);
+            let retryable = !matches!(output, Err(ExecuteError::Cancelled(_)))
+                && policy.should_retry(config, output);
+            if !retryable || attempt >= max_attempts {
+                return result;
+            }
+
+            let delay = policy.delay_for_attempt(attempt);
+            #[cfg(feature = "logging")]
+            tracing::warn!(
+                task_id = task_id,
+                attempt = attempt,
+                max_attempts = max_attempts,
+                delay_ms = delay.as_millis(),
+                result = ?output.map(|output| output.status),
+                "Command failed, retrying"
+            );
+            #[cfg(not(feature = "logging"))]
+            let _ = task_id;
+            thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+
+    /// 使用自定义执行器启动（高级用法）
+    ///
+    /// 工作线程在条件变量上阻塞等待，有任务提交或命令池关闭时立即被唤醒。
+    pub fn start_with_executor<E: CommandExecutor + 'static>(&self, executor: Arc<E>) {
         if self.running.load(Ordering::SeqCst) {
             return;
         }
 
         self.running.store(true, Ordering::SeqCst);
 
-        for _ in 0..self.config.workers {
-            let pool = self.clone();
-            let exec = executor.clone();
-            let handle = thread::spawn(move || {
-                while pool.running.load(Ordering::SeqCst)
-                    && !pool.shutdown_flag.load(Ordering::SeqCst)
-                {
-                    // pop_task 会阻塞等待，不需要轮询
-                    if let Some(task_item) = pool.pop_task() {
-                        if !pool.running.load(Ordering::SeqCst)
-                            || pool.shutdown_flag.load(Ordering::SeqCst)
-                        {
-                            break;
-                        }
-
-                        // 检查任务是否已被取消
-                        if task_item.handle.is_cancelled() {
-                            let task_id = task_item.handle.id();
-                            #[cfg(feature = "logging")]
-                            tracing::info!(task_id = task_id, "Task cancelled before execution");
-                            let _ = task_item
-                                .result_sender
-                                .send(Err(ExecuteError::Cancelled(task_id)));
-                            continue;
-                        }
+        self.launch_workers(Arc::new(move |pool, index, generation, delay| {
+            pool.spawn_executor_worker(index, generation, delay, Arc::clone(&executor))
+        }));
+    }
 
-                        // 更新任务状态为 Running
-                        task_item.handle.set_state(TaskState::Running { pid: None });
+    /// 启动使用自定义执行器的第 `index` 个工作线程
+    fn spawn_executor_worker<E: CommandExecutor + 'static>(
+        &self,
+        index: usize,
+        generation: u64,
+        delay: Duration,
+        exec: Arc<E>,
+    ) -> JoinHandle<()> {
+        let pool = self.background_clone();
+        self.spawn_named_worker(index, move || {
+            if !delay.is_zero() && !pool.wait_worker_start(delay) {
+                return;
+            }
+            pool.events.emit(PoolEvent::WorkerStarted { worker: index });
+            while pool.running.load(Ordering::SeqCst) && !pool.shutdown_flag.load(Ordering::SeqCst)
+            {
+                // pop_task 会阻塞等待，不需要轮询
+                if let Some((task_item, _dispatch)) = pool.pop_task_for(Some(index)) {
+                    let _busy = pool.begin_busy();
+                    if !pool.running.load(Ordering::SeqCst)
+                        || pool.shutdown_flag.load(Ordering::SeqCst)
+                    {
+                        pool.return_unstarted(task_item);
+                        break;
+                    }
 
-                        // 执行任务
-                        let result = exec.execute(&task_item.config);
+                    // 检查任务是否已被取消（包括快速失败已触发）
+                    if task_item.handle.is_cancelled() || pool.fail_fast_tripped(&task_item) {
+                        let task_id = task_item.handle.id();
+                        #[cfg(feature = "logging")]
+                        tracing::info!(task_id = task_id, "Task cancelled before execution");
+                        pool.send_result(
+                            &task_item,
+                            Err(ExecuteError::Cancelled(task_id)),
+                            Duration::ZERO,
+                        );
+                        continue;
+                    }
 
-                        // 发送结果
-                        let _ = task_item.result_sender.send(result);
+                    // 在队列中等待过久的任务不再执行
+                    if pool.discard_expired(&task_item) {
+                        continue;
+                    }
 
-                        // 更新任务状态为 Completed（如果未被取消）
-                        if !task_item.handle.is_cancelled() {
-                            task_item.handle.set_state(TaskState::Completed);
-                        }
-                    } else {
-                        // pop_task 返回 None 表示正在关闭
+                    // 同键单例任务正在运行时跳过
+                    let Ok(singleton) = pool.claim_singleton(&task_item) else {
+                        continue;
+                    };
+
+                    // 更新任务状态为 Running
+                    pool.mark_running(&task_item);
+                    let activity = pool.track_activity(index, generation, &task_item);
+
+                    // 执行任务
+                    let started = Instant::now();
+                    let result = pool.observe_spawns(&task_item.handle, || {
+                        exec.execute(&pool.rewrite_command(&task_item.config))
+                    });
+                    drop(singleton);
+                    drop(activity);
+
+                    // 发送结果（同时更新任务状态为 Completed）
+                    pool.finish_attempt(task_item, result, started.elapsed());
+                    // 已被看门狗替换的工作线程结束当前任务后退出
+                    if pool.activity.is_retired(index, generation) {
+                        pool.wake_replacement();
                         break;
                     }
+                } else {
+                    // pop_task 返回 None 表示正在关闭
+                    break;
                 }
-                #[cfg(feature = "logging")]
-                tracing::debug!("Custom executor worker exiting");
-            });
-            self.handles.lock().unwrap().push(handle);
-        }
+            }
+            pool.events.emit(PoolEvent::WorkerStopped { worker: index });
+            #[cfg(feature = "logging")]
+            tracing::debug!("Custom executor worker exiting");
+        })
     }
 
     /// 统计存活的工作线程数
@@ -1003,7 +3311,7 @@ impl CommandPool {
     /// # 健康状态分类
     ///
     /// * `Healthy` - 所有检查都通过，系统运行正常
-    /// * `Degraded` - 存在一些问题但系统仍可运行（如队列使用率高、有长时间运行的任务）
+    /// * `Degraded` - 存在一些问题但系统仍可运行（如队列使用率高、有长时间运行的任务、有工作线程 panic 退出）
     /// * `Unhealthy` - 存在严重问题，系统无法正常运行（如所有工作线程都已停止）
     ///
     /// # 示例
@@ -1029,15 +3337,22 @@ impl CommandPool {
     pub fn health_check(&self) -> HealthCheck {
         let mut issues = Vec::new();
 
-        // 检查工作线程状态
+        // 检查工作线程状态：按需启动和空闲退出使存活数少于配置数，
+        // 只有没有存活的工作线程或有工作线程 panic 退出时才报告
         let workers_alive = self.count_alive_workers();
         let workers_total = self.config.workers;
+        let workers_crashed = self.worker_slots.lock().unwrap().crashed();
 
-        if workers_alive < workers_total {
+        if workers_alive == 0 {
             issues.push(format!(
                 "Only {}/{} workers alive",
                 workers_alive, workers_total
             ));
+        } else if workers_crashed > 0 {
+            issues.push(format!(
+                "{} workers exited unexpectedly, {}/{} workers alive",
+                workers_crashed, workers_alive, workers_total
+            ));
         }
 
         // 检查队列使用率
@@ -1087,17 +3402,44 @@ impl Clone for CommandPool {
         Self {
             tasks: Arc::clone(&self.tasks),
             config: self.config.clone(),
+            options: self.options.clone(),
             backend: Arc::clone(&self.backend),
             running: Arc::clone(&self.running),
             handles: Arc::clone(&self.handles),
             max_size: self.max_size,
             #[cfg(feature = "metrics")]
             metrics: self.metrics.clone(),
-            task_id_counter: Arc::clone(&self.task_id_counter),
+            task_ids: Arc::clone(&self.task_ids),
+            task_statuses: self.task_statuses.clone(),
             shutdown_flag: Arc::clone(&self.shutdown_flag),
             shutdown_config: self.shutdown_config.clone(),
             zombie_reaper: None, // 不克隆 zombie_reaper，因为它包含线程句柄
             hooks: self.hooks.clone(),
+            rewriters: self.rewriters.clone(),
+            name: self.name.clone(),
+            singletons: Arc::clone(&self.singletons),
+            coalesced: Arc::clone(&self.coalesced),
+            dedup_keys: Arc::clone(&self.dedup_keys),
+            serial_keys: Arc::clone(&self.serial_keys),
+            backend_slots: Arc::clone(&self.backend_slots),
+            key_slots: Arc::clone(&self.key_slots),
+            rate_limiter: self.rate_limiter.clone(),
+            first_failure: Arc::clone(&self.first_failure),
+            in_flight: Arc::clone(&self.in_flight),
+            outstanding: Arc::clone(&self.outstanding),
+            draining: Arc::clone(&self.draining),
+            output_history: Arc::clone(&self.output_history),
+            callbacks: Arc::clone(&self.callbacks),
+            result_sink: self.result_sink.clone(),
+            #[cfg(feature = "persistence")]
+            journal: self.journal.clone(),
+            stats: Arc::clone(&self.stats),
+            events: Arc::clone(&self.events),
+            above_watermark: Arc::clone(&self.above_watermark),
+            activity: Arc::clone(&self.activity),
+            worker_slots: Arc::clone(&self.worker_slots),
+            spawner: Arc::clone(&self.spawner),
+            retired: false,
         }
     }
 }
@@ -1137,8 +3479,8 @@ impl Drop for CommandPool {
         #[cfg(feature = "logging")]
         tracing::debug!("CommandPool dropped, initiating cleanup");
 
-        // 如果还没有关闭，尝试优雅关闭
-        if !self.shutdown_flag.load(Ordering::SeqCst) {
+        // 如果还没有关闭，尝试优雅关闭（被替换的工作线程退出时除外）
+        if !self.retired && !self.shutdown_flag.load(Ordering::SeqCst) {
             #[cfg(feature = "logging")]
             tracing::warn!("CommandPool dropped without explicit shutdown, cleaning up now");
 
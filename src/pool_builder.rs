@@ -0,0 +1,382 @@
+//! 命令池构建器
+//!
+//! [`CommandPoolBuilder`] 把分散在 `with_config`、`with_config_and_limit`、`with_backend`
+//! 以及 `with_hook`、`on_task_complete` 等方法上的选项集中到一个链式构建器中，
+//! 在 [`build`](CommandPoolBuilder::build) 时统一校验。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::backend::{BackendFactory, ExecutionBackend, ExecutionConfig, ExecutionMode};
+use crate::config::{PoolRetryPolicy, ShutdownConfig, TaskDefaults, WatchdogConfig};
+use crate::error::ConfigError;
+use crate::hooks::{CommandRewriter, ExecutionHook};
+#[cfg(feature = "persistence")]
+use crate::journal::TaskJournal;
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+use crate::pool::{CommandPool, PoolOptions};
+use crate::sink::ResultSink;
+use crate::task_handle::TaskResult;
+
+type TaskCallback = Arc<dyn Fn(u64, &TaskResult) + Send + Sync>;
+
+/// 命令池构建器
+///
+/// 由 [`CommandPool::builder`] 创建。未设置的选项与 [`CommandPool::new`] 相同：
+/// 工作线程数等于 CPU 核心数，队列无界，不重试。
+///
+/// # 示例
+///
+/// ```no_run
+/// use execute::{CommandConfig, CommandPool, ExecutionMode, PoolRetryPolicy, RetryStrategy};
+/// use std::time::Duration;
+///
+/// let pool = CommandPool::builder()
+///     .with_workers(8)
+///     .with_mode(ExecutionMode::Thread)
+///     .with_queue_limit(1000)
+///     .with_concurrency_limit(4)
+///     .with_retry_policy(PoolRetryPolicy::new(
+///         3,
+///         RetryStrategy::FixedInterval(Duration::from_secs(1)),
+///     ))
+///     .on_task_failed(|task_id, result| eprintln!("task {task_id} failed: {result:?}"))
+///     .start()
+///     .unwrap();
+///
+/// pool.push_task(CommandConfig::new("make", vec![])).unwrap();
+/// ```
+#[must_use]
+pub struct CommandPoolBuilder {
+    config: ExecutionConfig,
+    options: PoolOptions,
+    queue_limit: Option<usize>,
+    backend: Option<Arc<dyn ExecutionBackend>>,
+    shutdown_config: Option<ShutdownConfig>,
+    hooks: Vec<Arc<dyn ExecutionHook>>,
+    rewriters: Vec<Arc<dyn CommandRewriter>>,
+    result_sink: Option<Arc<dyn ResultSink>>,
+    on_complete: Vec<TaskCallback>,
+    on_failed: Vec<TaskCallback>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Metrics>,
+    #[cfg(feature = "persistence")]
+    journal: Option<TaskJournal>,
+}
+
+impl CommandPoolBuilder {
+    /// 创建使用默认配置的构建器
+    pub fn new() -> Self {
+        Self {
+            config: ExecutionConfig::default(),
+            options: PoolOptions::default(),
+            queue_limit: None,
+            backend: None,
+            shutdown_config: None,
+            hooks: Vec::new(),
+            rewriters: Vec::new(),
+            result_sink: None,
+            on_complete: Vec::new(),
+            on_failed: Vec::new(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "persistence")]
+            journal: None,
+        }
+    }
+
+    /// 以已有的执行配置为基础，之后的设置在其上修改
+    pub fn with_execution_config(mut self, config: ExecutionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 设置执行模式
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.config.mode = mode;
+        self
+    }
+
+    /// 设置工作线程数
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.config.workers = workers;
+        self
+    }
+
+    /// 限制同时执行的命令数
+    pub fn with_concurrency_limit(mut self, limit: usize) -> Self {
+        self.config.concurrency_limit = Some(limit);
+        self
+    }
+
+    /// 限制队列容量，队列满时 `push_task` 阻塞、`try_push_task` 返回 `QueueFull`
+    pub fn with_queue_limit(mut self, limit: usize) -> Self {
+        self.queue_limit = Some(limit);
+        self
+    }
+
+    /// 逐步启动工作线程，每隔 `interval` 启动一个
+    ///
+    /// 避免在打开大队列时所有工作线程同时派生子进程，
+    /// 对负载均衡器、License 服务器等下游造成瞬时冲击。
+    pub fn with_worker_start_interval(mut self, interval: Duration) -> Self {
+        self.options.worker_start_interval = Some(interval);
+        self
+    }
+
+    /// 设置命令池级别的任务默认值
+    ///
+    /// 基础环境变量、工作目录和 `PATH` 前缀会应用到提交的每个任务，
+    /// 任务自身的设置优先，生产者无需在每个配置上重复设置相同的环境。
+    pub fn with_task_defaults(mut self, defaults: TaskDefaults) -> Self {
+        self.options.task_defaults = defaults;
+        self
+    }
+
+    /// 设置队列高水位阈值
+    ///
+    /// 队列长度达到阈值时发布一次 `PoolEvent::QueueHighWatermark`，
+    /// 回落到阈值以下后才会再次发布。
+    pub fn with_queue_high_watermark(mut self, depth: usize) -> Self {
+        self.options.queue_high_watermark = Some(depth);
+        self
+    }
+
+    /// 启用快速失败模式
+    ///
+    /// 首个失败的任务（非零退出、超时或执行错误）结束后，命令池取消队列中的任务
+    /// 和正在执行的任务，之后出队的任务也直接取消，直到调用
+    /// `CommandPool::reset_fail_fast`。适合出现第一个失败结果后就不必继续的 CI 场景。
+    pub fn with_fail_fast(mut self, enabled: bool) -> Self {
+        self.options.fail_fast = enabled;
+        self
+    }
+
+    /// 限制选择后端 `name` 的任务同时执行的数量
+    ///
+    /// 由命令池在分派时执行：该后端的任务达到上限后，工作线程跳过它们，
+    /// 继续执行队列中其他后端的任务，不会阻塞在后端的信号量上。
+    /// 任务通过 [`CommandConfig::with_backend`](crate::CommandConfig::with_backend) 选择后端。
+    /// 上限只在单个命令池内生效，子池按继承的设置单独计数。
+    pub fn with_backend_limit(mut self, name: &str, limit: usize) -> Self {
+        assert!(limit > 0, "backend limit must be greater than 0");
+        self.options.backend_limits.insert(name.to_string(), limit);
+        self
+    }
+
+    /// 限制并发键为 `key` 的任务同时执行的数量
+    ///
+    /// 与后端上限相同，由命令池在分派时执行，达到上限的任务留在队列中，
+    /// 不影响其他键的任务。任务通过
+    /// [`CommandConfig::with_concurrency_key`](crate::CommandConfig::with_concurrency_key)
+    /// 设置并发键。
+    pub fn with_concurrency_key_limit(mut self, key: &str, limit: usize) -> Self {
+        assert!(limit > 0, "concurrency key limit must be greater than 0");
+        self.options
+            .concurrency_key_limits
+            .insert(key.to_string(), limit);
+        self
+    }
+
+    /// 启用卡住工作线程看门狗
+    ///
+    /// 发现卡住的工作线程时发布 `PoolEvent::WorkerStuck` 事件并计入指标，
+    /// 按配置启动替补工作线程。
+    pub fn with_watchdog(mut self, watchdog: WatchdogConfig) -> Self {
+        self.options.watchdog = Some(watchdog);
+        self
+    }
+
+    /// 设置命令池级别的自动重试策略
+    ///
+    /// 失败的任务按策略延迟后重新入队，而不是立即把失败结果交给调用方。
+    pub fn with_retry_policy(mut self, policy: PoolRetryPolicy) -> Self {
+        self.options.retry_policy = Some(policy);
+        self
+    }
+
+    /// 限制每秒最多启动 `per_second` 个任务
+    ///
+    /// 使用令牌桶实现：桶容量为每秒速率，空闲后最多允许一次性启动 `per_second` 个任务，
+    /// 之后按速率均匀启动。令牌耗尽时任务留在队列中（仍可取消），
+    /// 避免积压的大量任务同时冲击命令调用的下游服务。
+    pub fn with_rate_limit(mut self, per_second: u32) -> Self {
+        assert!(per_second > 0, "rate limit must be greater than 0");
+        self.options.rate_limit = Some(per_second);
+        self
+    }
+
+    /// 设置工作线程名称前缀
+    ///
+    /// 工作线程命名为 `<前缀>-<序号>`（默认 `execute-worker-0`、`execute-worker-1`……），
+    /// 便于在线程转储和性能分析工具中区分多个命令池。
+    pub fn with_worker_name_prefix(mut self, prefix: &str) -> Self {
+        self.options.worker_name_prefix = prefix.to_string();
+        self
+    }
+
+    /// 保持至少 `count` 个空闲工作线程
+    ///
+    /// 执行器启动时预先启动 `count` 个工作线程，之后每当空闲的工作线程少于 `count`
+    /// 就再启动一个，直到达到工作线程数，使突发任务不必等待线程启动，
+    /// 任务较少时也不会启动全部工作线程。未设置时执行器启动时即启动全部工作线程。
+    pub fn with_min_idle_workers(mut self, count: usize) -> Self {
+        assert!(count > 0, "min idle workers must be greater than 0");
+        self.options.min_idle_workers = Some(count);
+        self
+    }
+
+    /// 空闲超过 `timeout` 的多余工作线程退出
+    ///
+    /// 空闲的工作线程多于最少空闲数（参见 [`with_min_idle_workers`](Self::with_min_idle_workers)，
+    /// 未设置时为 1）时，空闲超过 `timeout` 的工作线程退出，有任务时再按需启动，
+    /// 负载间歇的长期服务不必一直保留全部工作线程。
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.options.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// 定期回收僵尸进程
+    pub fn with_zombie_reaper_interval(mut self, interval: Duration) -> Self {
+        self.config.zombie_reaper_interval = Some(interval);
+        self
+    }
+
+    /// 使用自定义执行后端，执行配置中的并发限制不会应用到该后端
+    pub fn with_backend(mut self, backend: Arc<dyn ExecutionBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// 设置关闭配置
+    pub fn with_shutdown_config(mut self, config: ShutdownConfig) -> Self {
+        self.shutdown_config = Some(config);
+        self
+    }
+
+    /// 添加执行钩子，参见 [`CommandPool::with_hook`]
+    pub fn with_hook(mut self, hook: Arc<dyn ExecutionHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// 添加命令改写器，参见 [`CommandPool::with_rewriter`]
+    pub fn with_rewriter(mut self, rewriter: Arc<dyn CommandRewriter>) -> Self {
+        self.rewriters.push(rewriter);
+        self
+    }
+
+    /// 设置任务结果接收端，参见 [`CommandPool::with_result_sink`]
+    pub fn with_result_sink(mut self, sink: Arc<dyn ResultSink>) -> Self {
+        self.result_sink = Some(sink);
+        self
+    }
+
+    /// 使用指定的指标收集器，参见 [`CommandPool::with_metrics`]
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// 设置持久化任务日志，参见 [`CommandPool::with_journal`]
+    #[cfg(feature = "persistence")]
+    pub fn with_journal(mut self, journal: TaskJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// 注册任务结束回调，参见 [`CommandPool::on_task_complete`]
+    pub fn on_task_complete<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(u64, &TaskResult) + Send + Sync + 'static,
+    {
+        self.on_complete.push(Arc::new(callback));
+        self
+    }
+
+    /// 注册任务失败回调，参见 [`CommandPool::on_task_failed`]
+    pub fn on_task_failed<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(u64, &TaskResult) + Send + Sync + 'static,
+    {
+        self.on_failed.push(Arc::new(callback));
+        self
+    }
+
+    /// 校验配置并创建命令池（不启动执行器）
+    ///
+    /// # 错误
+    ///
+    /// * `ConfigError::InvalidThreadCount` - 工作线程数为 0
+    /// * `ConfigError::InvalidQueueCapacity` - 队列容量为 0
+    pub fn build(self) -> Result<CommandPool, ConfigError> {
+        if self.config.workers < 1 {
+            return Err(ConfigError::InvalidThreadCount(self.config.workers));
+        }
+        if let Some(limit) = self.queue_limit
+            && limit < 1
+        {
+            return Err(ConfigError::InvalidQueueCapacity(limit));
+        }
+
+        #[cfg(feature = "logging")]
+        tracing::info!(
+            mode = ?self.config.mode,
+            workers = self.config.workers,
+            max_size = ?self.queue_limit,
+            "CommandPool initialized from builder"
+        );
+
+        let backend = self
+            .backend
+            .unwrap_or_else(|| BackendFactory::create(&self.config));
+        let mut pool =
+            CommandPool::from_backend(self.config, self.options, backend, self.queue_limit);
+        if let Some(config) = self.shutdown_config {
+            pool.set_shutdown_config(config);
+        }
+        for hook in self.hooks {
+            pool = pool.with_hook(hook);
+        }
+        for rewriter in self.rewriters {
+            pool = pool.with_rewriter(rewriter);
+        }
+        if let Some(sink) = self.result_sink {
+            pool = pool.with_result_sink(sink);
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics {
+            pool = pool.with_metrics(metrics);
+        }
+        #[cfg(feature = "persistence")]
+        if let Some(journal) = self.journal {
+            pool = pool.with_journal(journal);
+        }
+        for callback in self.on_complete {
+            pool.on_task_complete(move |task_id, result| callback(task_id, result));
+        }
+        for callback in self.on_failed {
+            pool.on_task_failed(move |task_id, result| callback(task_id, result));
+        }
+        Ok(pool)
+    }
+
+    /// 创建命令池并启动执行器
+    ///
+    /// # 错误
+    ///
+    /// 与 [`build`](Self::build) 相同
+    pub fn start(self) -> Result<CommandPool, ConfigError> {
+        let pool = self.build()?;
+        pool.start_executor();
+        Ok(pool)
+    }
+}
+
+impl Default for CommandPoolBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
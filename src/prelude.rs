@@ -2,6 +2,7 @@ pub use crate::backend::{ExecutionConfig, ExecutionMode};
 pub use crate::config::CommandConfig;
 pub use crate::config::{EnvConfig, ResourceLimits, RetryPolicy, RetryStrategy, TimeoutConfig};
 pub use crate::error::{ExecuteError, ShutdownError, SubmitError};
+pub use crate::fluent::Execute;
 #[cfg(feature = "metrics")]
 pub use crate::metrics::{Metrics, MetricsSnapshot};
 /// 常用类型预导入模块
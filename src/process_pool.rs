@@ -3,6 +3,7 @@ use std::io::{BufRead, BufReader, Write};
 use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, Condvar, Mutex};
 
+use crate::affinity::worker_for_key;
 use crate::config::CommandConfig;
 use crate::error::ExecuteError;
 
@@ -11,8 +12,7 @@ use crate::error::ExecuteError;
 /// 封装一个常驻子进程，通过 stdin/stdout 进行 IPC 通信。
 /// 用于执行命令并返回结果，避免频繁创建销毁进程的开销。
 struct WorkerProcess {
-    /// 工作进程 ID（用于调试）
-    #[allow(dead_code)]
+    /// 工作进程 ID（亲和键按此选择工作进程）
     id: usize,
 
     /// 子进程句柄
@@ -66,9 +66,18 @@ impl WorkerProcess {
         // 序列化命令配置
         let cmd_line = format!(
             "{}\t{}\t{}\t{}\n",
-            config.program,
-            config.args.join("\t"),
-            config.working_dir.as_deref().unwrap_or(""),
+            config.program.to_string_lossy(),
+            config
+                .args
+                .iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("\t"),
+            config
+                .working_dir
+                .as_deref()
+                .map(|dir| dir.to_string_lossy())
+                .unwrap_or_default(),
             config.timeout.map(|d| d.as_secs()).unwrap_or(0)
         );
 
@@ -148,16 +157,51 @@ impl ProcessPool {
         }
 
         // 获取一个工作进程
-        let mut worker = workers.pop_front().unwrap();
+        let worker = workers.pop_front().unwrap();
         drop(workers);
 
+        self.run_on(worker, config)
+    }
+
+    /// 在亲和键对应的工作进程上执行命令
+    ///
+    /// 相同键的命令总是由同一个常驻工作进程执行，该进程忙碌时等待其空闲，
+    /// 即使其他工作进程可用。工作进程可以借此按键保留预热状态。
+    pub fn execute_with_affinity(
+        &self,
+        key: &str,
+        config: &CommandConfig,
+    ) -> Result<std::process::Output, ExecuteError> {
+        let target = worker_for_key(key, self.size);
+        let (lock, cvar) = (&self.workers, &self.available);
+        let mut workers = lock.lock().unwrap();
+
+        // 等待目标工作进程归还
+        let position = loop {
+            if let Some(position) = workers.iter().position(|w| w.id == target) {
+                break position;
+            }
+            workers = cvar.wait(workers).unwrap();
+        };
+        let worker = workers.remove(position).unwrap();
+        drop(workers);
+
+        self.run_on(worker, config)
+    }
+
+    /// 在指定工作进程上执行命令并归还
+    fn run_on(
+        &self,
+        mut worker: WorkerProcess,
+        config: &CommandConfig,
+    ) -> Result<std::process::Output, ExecuteError> {
         // 执行命令
         let result = worker.execute(config);
 
-        // 归还工作进程
-        let mut workers = lock.lock().unwrap();
+        // 归还工作进程；等待特定工作进程的调用方可能不止一个，需全部唤醒
+        let mut workers = self.workers.lock().unwrap();
         workers.push_back(worker);
-        cvar.notify_one();
+        self.available.notify_all();
 
         result
     }
@@ -0,0 +1,405 @@
+//! 进程工具
+//!
+//! 独立于命令池使用的进程管理辅助函数，例如终止任务启动的整棵进程树。
+
+use std::io;
+use std::time::Duration;
+
+#[cfg(unix)]
+pub use nix::sys::signal::Signal;
+
+/// 终止进程树时发送的信号（非 Unix 平台）
+///
+/// Windows 上没有信号，无论哪种信号都直接终止进程。
+#[cfg(not(unix))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// 中断
+    SIGINT,
+    /// 请求终止
+    SIGTERM,
+    /// 强制终止
+    SIGKILL,
+}
+
+/// 终止进程及其所有后代进程
+///
+/// 先向整棵进程树发送 `signal`，等待最多 `grace` 让进程自行退出，
+/// 仍存活的进程随后被强制终止。`signal` 为 `SIGKILL` 时不等待。
+///
+/// - Unix：若 `pid` 是进程组组长则向整个进程组发送信号；在 Linux 上还会通过 /proc
+///   找出所有后代进程（包括不在同一进程组的），逐个发送信号
+/// - Windows：由本库启动且启用了 [`with_kill_tree`](crate::CommandConfig::with_kill_tree)
+///   的子进程在启动时加入作业对象，终止时结束整个作业；其他进程按进程快照中的父子关系
+///   逐个终止。Windows 上没有信号，`signal` 和 `grace` 被忽略
+///
+/// 进程树在发送信号前取快照，期间新创建的进程可能遗漏。
+///
+/// # 错误
+///
+/// `pid` 对应的进程不存在时返回 `NotFound`，没有权限时返回 `PermissionDenied`。
+///
+/// # 示例
+///
+/// ```no_run
+/// use execute::process_util::{Signal, kill_tree};
+/// use std::time::Duration;
+///
+/// # fn main() -> std::io::Result<()> {
+/// # let pid = 0;
+/// kill_tree(pid, Signal::SIGTERM, Duration::from_secs(2))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn kill_tree(pid: u32, signal: Signal, grace: Duration) -> io::Result<()> {
+    imp::kill_tree(pid, signal, grace)
+}
+
+#[cfg(windows)]
+pub(crate) use imp::attach_job;
+
+#[cfg(unix)]
+mod imp {
+    use std::io;
+    use std::time::{Duration, Instant};
+
+    use nix::errno::Errno;
+    use nix::sys::signal::{Signal, kill, killpg};
+    use nix::unistd::{Pid, getpgid};
+
+    /// 检查进程是否存活的间隔
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    pub(super) fn kill_tree(pid: u32, signal: Signal, grace: Duration) -> io::Result<()> {
+        let root = Pid::from_raw(pid as i32);
+        // 先确认根进程存在，避免向无关进程组发送信号
+        kill(root, None).map_err(|e| match e {
+            Errno::ESRCH => io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("process {} not found", pid),
+            ),
+            e => io::Error::from(e),
+        })?;
+
+        let group_leader = getpgid(Some(root)).is_ok_and(|pgid| pgid == root);
+        let tree = collect_tree(pid);
+
+        send(root, group_leader, &tree, signal);
+        if signal == Signal::SIGKILL {
+            return Ok(());
+        }
+
+        let deadline = Instant::now() + grace;
+        while tree.iter().any(|&p| is_alive(p)) {
+            if Instant::now() >= deadline {
+                #[cfg(feature = "logging")]
+                tracing::warn!(
+                    pid = pid,
+                    "Process tree did not exit within grace period, sending SIGKILL"
+                );
+                send(root, group_leader, &tree, Signal::SIGKILL);
+                break;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        Ok(())
+    }
+
+    /// 向进程树发送信号，已退出的进程忽略
+    fn send(root: Pid, group_leader: bool, tree: &[u32], signal: Signal) {
+        if group_leader {
+            let _ = killpg(root, signal);
+        }
+        for &p in tree {
+            match kill(Pid::from_raw(p as i32), signal) {
+                Ok(()) | Err(Errno::ESRCH) => {}
+                Err(_e) => {
+                    #[cfg(feature = "logging")]
+                    tracing::debug!(pid = p, error = %_e, "Failed to signal process");
+                }
+            }
+        }
+    }
+
+    /// 进程是否存活（僵尸进程视为已退出）
+    fn is_alive(pid: u32) -> bool {
+        if kill(Pid::from_raw(pid as i32), None).is_err() {
+            return false;
+        }
+        #[cfg(target_os = "linux")]
+        if let Some((state, _)) = read_stat(pid) {
+            return state != 'Z';
+        }
+        true
+    }
+
+    /// 根进程及其所有后代进程的 PID，根进程在前
+    #[cfg(target_os = "linux")]
+    fn collect_tree(root: u32) -> Vec<u32> {
+        use std::collections::HashMap;
+
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        if let Ok(entries) = std::fs::read_dir("/proc") {
+            for entry in entries.flatten() {
+                let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                    continue;
+                };
+                if let Some((_, ppid)) = read_stat(pid) {
+                    children.entry(ppid).or_default().push(pid);
+                }
+            }
+        }
+
+        let mut tree = vec![root];
+        let mut index = 0;
+        while index < tree.len() {
+            if let Some(kids) = children.get(&tree[index]) {
+                tree.extend(kids);
+            }
+            index += 1;
+        }
+        tree
+    }
+
+    /// 无法枚举后代进程的平台只包含根进程（进程组由 killpg 覆盖）
+    #[cfg(not(target_os = "linux"))]
+    fn collect_tree(root: u32) -> Vec<u32> {
+        vec![root]
+    }
+
+    /// 读取 /proc/[pid]/stat 中的进程状态和父进程 ID
+    #[cfg(target_os = "linux")]
+    fn read_stat(pid: u32) -> Option<(char, u32)> {
+        let content = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // 进程名可能包含空格，从最后一个右括号之后开始解析
+        let mut fields = content.rsplit_once(')')?.1.split_whitespace();
+        let state = fields.next()?.chars().next()?;
+        let ppid = fields.next()?.parse().ok()?;
+        Some((state, ppid))
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::collections::HashMap;
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+    use std::sync::{Mutex, OnceLock};
+    use std::time::Duration;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+        TH32CS_SNAPPROCESS,
+    };
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        JOBOBJECT_BASIC_ACCOUNTING_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JobObjectBasicAccountingInformation, JobObjectExtendedLimitInformation,
+        QueryInformationJobObject, SetInformationJobObject, TerminateJobObject,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+        TerminateProcess,
+    };
+
+    use super::Signal;
+
+    /// `GetExitCodeProcess` 对仍在运行的进程返回的退出码
+    const STILL_ACTIVE: u32 = 259;
+
+    /// 作业对象终止进程时使用的退出码
+    const KILLED_EXIT_CODE: u32 = 1;
+
+    /// 持有作业对象句柄
+    ///
+    /// 作业对象设置了 `JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE`：当前进程意外退出、句柄被系统关闭时，
+    /// 作业中的进程随之终止。正常释放时先清除该限制，与 Unix 上进程组的行为一致，
+    /// 任务结束后仍在运行的后代进程不受影响。
+    struct Job(HANDLE);
+
+    // SAFETY: 作业对象句柄可以在任意线程使用和关闭
+    unsafe impl Send for Job {}
+
+    impl Job {
+        fn new() -> io::Result<Self> {
+            // SAFETY: 参数均为空指针，表示默认安全属性和匿名作业对象
+            let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if handle.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let job = Job(handle);
+            job.set_limit_flags(JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE)?;
+            Ok(job)
+        }
+
+        fn set_limit_flags(&self, flags: u32) -> io::Result<()> {
+            // SAFETY: 结构体全零是合法的初始值
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+            info.BasicLimitInformation.LimitFlags = flags;
+            // SAFETY: info 在调用期间有效，长度与结构体一致
+            let ok = unsafe {
+                SetInformationJobObject(
+                    self.0,
+                    JobObjectExtendedLimitInformation,
+                    &info as *const _ as *const _,
+                    std::mem::size_of_val(&info) as u32,
+                )
+            };
+            if ok == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        fn assign(&self, child: &Child) -> io::Result<()> {
+            // SAFETY: 子进程句柄在 child 存活期间有效
+            let ok = unsafe { AssignProcessToJobObject(self.0, child.as_raw_handle() as HANDLE) };
+            if ok == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        fn terminate(&self) -> io::Result<()> {
+            // SAFETY: 句柄由 CreateJobObjectW 创建且尚未关闭
+            if unsafe { TerminateJobObject(self.0, KILLED_EXIT_CODE) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        /// 作业中是否还有运行中的进程
+        fn has_active_processes(&self) -> bool {
+            // SAFETY: 结构体全零是合法的初始值
+            let mut info: JOBOBJECT_BASIC_ACCOUNTING_INFORMATION = unsafe { std::mem::zeroed() };
+            // SAFETY: info 在调用期间有效，长度与结构体一致
+            let ok = unsafe {
+                QueryInformationJobObject(
+                    self.0,
+                    JobObjectBasicAccountingInformation,
+                    &mut info as *mut _ as *mut _,
+                    std::mem::size_of_val(&info) as u32,
+                    std::ptr::null_mut(),
+                )
+            };
+            ok != 0 && info.ActiveProcesses > 0
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            let _ = self.set_limit_flags(0);
+            // SAFETY: 句柄只在这里关闭一次
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    /// 按根进程 PID 登记的作业对象
+    fn jobs() -> &'static Mutex<HashMap<u32, Job>> {
+        static JOBS: OnceLock<Mutex<HashMap<u32, Job>>> = OnceLock::new();
+        JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    /// 把刚启动的子进程放入新的作业对象
+    ///
+    /// 子进程之后启动的所有后代进程都属于同一作业，超时时由
+    /// [`kill_tree`] 一次性终止。顺带释放进程已全部退出的作业。
+    pub(crate) fn attach_job(child: &Child) -> io::Result<()> {
+        let job = Job::new()?;
+        job.assign(child)?;
+        let mut jobs = jobs().lock().unwrap_or_else(|e| e.into_inner());
+        jobs.retain(|_, job| job.has_active_processes());
+        jobs.insert(child.id(), job);
+        Ok(())
+    }
+
+    pub(super) fn kill_tree(pid: u32, _signal: Signal, _grace: Duration) -> io::Result<()> {
+        let job = jobs()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&pid);
+        if let Some(job) = job {
+            return job.terminate();
+        }
+
+        // 不是由本库启动的进程：按快照中的父子关系逐个终止
+        if !is_alive(pid) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("process {} not found", pid),
+            ));
+        }
+        for p in collect_tree(pid)? {
+            terminate_process(p);
+        }
+        Ok(())
+    }
+
+    /// 根进程及其所有后代进程的 PID，根进程在前
+    fn collect_tree(root: u32) -> io::Result<Vec<u32>> {
+        // SAFETY: 创建进程快照，不涉及调用方提供的指针
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        // SAFETY: 结构体全零是合法的初始值，使用前按要求设置 dwSize
+        let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        // SAFETY: snapshot 有效，entry 在调用期间有效
+        let mut ok = unsafe { Process32FirstW(snapshot, &mut entry) };
+        while ok != 0 {
+            children
+                .entry(entry.th32ParentProcessID)
+                .or_default()
+                .push(entry.th32ProcessID);
+            // SAFETY: 同上
+            ok = unsafe { Process32NextW(snapshot, &mut entry) };
+        }
+        // SAFETY: 快照句柄只在这里关闭一次
+        unsafe { CloseHandle(snapshot) };
+
+        let mut tree = vec![root];
+        let mut index = 0;
+        while index < tree.len() {
+            if let Some(kids) = children.get(&tree[index]) {
+                // PID 可能被复用，跳过已经在树中的进程以免成环
+                for &kid in kids {
+                    if !tree.contains(&kid) {
+                        tree.push(kid);
+                    }
+                }
+            }
+            index += 1;
+        }
+        Ok(tree)
+    }
+
+    fn terminate_process(pid: u32) {
+        // SAFETY: OpenProcess 失败时返回空句柄，成功的句柄在使用后关闭
+        unsafe {
+            let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+            if !handle.is_null() {
+                TerminateProcess(handle, KILLED_EXIT_CODE);
+                CloseHandle(handle);
+            }
+        }
+    }
+
+    fn is_alive(pid: u32) -> bool {
+        // SAFETY: OpenProcess 失败时返回空句柄，成功的句柄在使用后关闭
+        unsafe {
+            let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if handle.is_null() {
+                return false;
+            }
+            let mut code = 0u32;
+            let ok = GetExitCodeProcess(handle, &mut code);
+            CloseHandle(handle);
+            ok != 0 && code == STILL_ACTIVE
+        }
+    }
+}
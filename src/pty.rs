@@ -0,0 +1,153 @@
+//! 伪终端执行
+//!
+//! 部分命令在输出不是终端时改变行为：关闭彩色输出、不显示提示、拒绝交互
+//! （如 `docker run -it`）。伪终端执行把子进程的 stdin、stdout 和 stderr 都连接到
+//! 新分配的伪终端从端，子进程成为新会话的组长并以该终端为控制终端，
+//! 调用方从主端读取子进程看到的终端输出。
+
+#![cfg(all(unix, feature = "pty"))]
+
+use std::fs::File;
+use std::io::Write;
+use std::process::{Output, Stdio};
+use std::time::Instant;
+
+use nix::pty::{Winsize, openpty};
+use nix::sys::termios::Termios;
+
+use crate::backend::ExecutionBackend;
+use crate::capture::OutputCollectors;
+use crate::config::CommandConfig;
+use crate::error::ExecuteError;
+use crate::executor::{
+    build_command_without_group, finish_output, notify_spawned, spawn_child, wait_for_exit,
+};
+
+/// 伪终端窗口大小
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtySize {
+    /// 行数
+    pub rows: u16,
+    /// 列数
+    pub cols: u16,
+}
+
+impl Default for PtySize {
+    /// 24 行 80 列
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
+/// 在伪终端中执行命令的后端
+///
+/// 子进程的 stdout 和 stderr 写入同一个终端，全部出现在 [`Output::stdout`] 中，
+/// `stderr` 总是为空；终端会把换行转换为 `\r\n`。配置了 [`CommandConfig::with_stdin`]
+/// 时，输入写入终端，可能按终端设置回显到输出中。
+///
+/// 超时、超时钩子和输出捕获模式与默认后端相同；配置的输出去向和输出文件不生效，
+/// 子进程总是成为新会话的组长，超时终止时按 [`CommandConfig::with_kill_tree`]
+/// 决定是否终止整个进程组。临时工作目录、输入文件、产物收集和文件锁不在这里处理。
+///
+/// 需要启用 `pty` feature，仅支持 Unix。
+///
+/// # 示例
+///
+/// ```ignore
+/// use std::sync::Arc;
+/// use execute::{CommandPool, ExecutionConfig, PtyBackend, RoutingBackend};
+///
+/// let backend = RoutingBackend::new(default_backend).with_route("pty", Arc::new(PtyBackend::new()));
+/// let pool = CommandPool::with_backend(ExecutionConfig::new(), Arc::new(backend));
+/// pool.push_task(CommandConfig::new("ls", vec!["--color=auto".to_string()]).with_backend("pty"))?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PtyBackend {
+    size: PtySize,
+}
+
+impl PtyBackend {
+    /// 创建使用默认窗口大小（24 行 80 列）的伪终端后端
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置伪终端窗口大小
+    pub fn with_size(mut self, rows: u16, cols: u16) -> Self {
+        self.size = PtySize { rows, cols };
+        self
+    }
+
+    /// 伪终端窗口大小
+    pub fn size(&self) -> PtySize {
+        self.size
+    }
+}
+
+impl ExecutionBackend for PtyBackend {
+    fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        execute_pty_with_size(config, self.size)
+    }
+}
+
+/// 在默认大小（24 行 80 列）的伪终端中执行命令，参见 [`PtyBackend`]
+pub fn execute_pty(config: &CommandConfig) -> Result<Output, ExecuteError> {
+    execute_pty_with_size(config, PtySize::default())
+}
+
+/// 在指定大小的伪终端中执行命令，参见 [`PtyBackend`]
+pub fn execute_pty_with_size(
+    config: &CommandConfig,
+    size: PtySize,
+) -> Result<Output, ExecuteError> {
+    let winsize = Winsize {
+        ws_row: size.rows,
+        ws_col: size.cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    let pty = openpty(&winsize, None::<&Termios>).map_err(std::io::Error::from)?;
+
+    let mut cmd = build_command_without_group(config, None)?;
+    cmd.stdin(Stdio::from(pty.slave.try_clone()?))
+        .stdout(Stdio::from(pty.slave.try_clone()?))
+        .stderr(Stdio::from(pty.slave));
+    make_controlling_terminal(&mut cmd);
+
+    let start = Instant::now();
+    let spawned = spawn_child(&mut cmd, config, None);
+    // Command 持有从端；父进程不关闭从端时，子进程退出后主端读不到结束
+    drop(cmd);
+    let mut child = spawned?;
+    notify_spawned(child.id());
+
+    if let Some(input) = config.stdin() {
+        let mut terminal = File::from(pty.master.try_clone()?);
+        let input = input.to_vec();
+        std::thread::spawn(move || {
+            let _ = terminal.write_all(&input);
+        });
+    }
+
+    let collectors =
+        OutputCollectors::start_single(File::from(pty.master), config.capture_mode, start);
+    let status = wait_for_exit(&mut child, config, start, &collectors)?;
+    config.check_exit(finish_output(status, collectors, config))
+}
+
+/// 让子进程成为新会话的组长，并以 stdin 上的伪终端为控制终端
+fn make_controlling_terminal(cmd: &mut std::process::Command) {
+    use nix::libc;
+    use std::os::unix::process::CommandExt;
+
+    // SAFETY: 闭包只执行 setsid 和 ioctl 系统调用，不分配内存，在 fork 后调用是安全的
+    unsafe {
+        cmd.pre_exec(|| {
+            nix::unistd::setsid()?;
+            if libc::ioctl(libc::STDIN_FILENO, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,60 @@
+//! 任务启动速率限制
+//!
+//! 令牌桶按固定速率补充令牌，命令池每启动一个任务消耗一个令牌；
+//! 令牌耗尽时任务留在队列中，直到下一个令牌到来。
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// 令牌桶，容量等于每秒速率（至少为 1），初始为满
+pub(crate) struct RateLimiter {
+    per_second: f64,
+    capacity: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(per_second: u32) -> Self {
+        let per_second = f64::from(per_second);
+        let capacity = per_second.max(1.0);
+        Self {
+            per_second,
+            capacity,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// 距离下一个令牌可用的时间，当前已有令牌时返回 None
+    pub(crate) fn time_until_available(&self) -> Option<Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        if bucket.tokens >= 1.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(
+            (1.0 - bucket.tokens) / self.per_second,
+        ))
+    }
+
+    /// 消耗一个令牌（调用方已确认有令牌可用）
+    pub(crate) fn acquire(&self) {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        bucket.tokens -= 1.0;
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.capacity);
+        bucket.refilled_at = now;
+    }
+}
@@ -0,0 +1,204 @@
+use std::path::PathBuf;
+use std::process::Output;
+use std::time::{Duration, SystemTime};
+
+/// 任务执行报告
+///
+/// 在命令输出之外附带执行过程中产生的元数据。
+/// 可通过 [`execute_with_report`](crate::execute_with_report) 直接获取，
+/// 或对命令池返回的句柄调用 [`TaskHandle::wait_report`](crate::TaskHandle::wait_report)。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, execute_with_report};
+///
+/// let config = CommandConfig::new("touch", vec!["out.txt".to_string()]).with_temp_workdir();
+/// let report = execute_with_report(&config)?;
+/// println!("ran in {:?}", report.temp_workdir);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExecutionReport {
+    /// 命令输出
+    pub output: Output,
+    /// 本次执行使用的托管临时工作目录
+    ///
+    /// 目录在任务结束后删除；如果设置了失败时保留且任务失败，目录会保留在磁盘上。
+    pub temp_workdir: Option<PathBuf>,
+    /// 收集到的输出产物
+    pub artifacts: Vec<Artifact>,
+    /// 未匹配到任何文件的产物模式
+    pub missing_artifacts: Vec<String>,
+    /// 捕获的 stdout 的 SHA-256（小写十六进制），仅在启用校验和时计算
+    pub stdout_sha256: Option<String>,
+    /// 与同键任务上一次运行相比 stdout 是否变化，仅在启用输出对比时设置
+    pub output_change: Option<OutputChange>,
+    /// 按时间顺序合并的 stdout/stderr 输出块，仅在使用 [`CaptureMode::Timeline`](crate::CaptureMode::Timeline) 时设置
+    pub timeline: Option<Vec<OutputChunk>>,
+    /// 启动时间、耗时、排队时间和执行次数
+    ///
+    /// 由 [`execute_with_report`](crate::execute_with_report) 和命令池设置；
+    /// 自定义后端未提供时，命令池按工作线程的测量值补全。
+    pub timing: Option<ExecutionTiming>,
+    /// 子进程的资源使用量
+    ///
+    /// 目前仅在 Linux 上由默认执行器在回收子进程时读取；其他平台、自定义后端
+    /// 或子进程未能启动时为 None。
+    pub resource_usage: Option<ResourceUsage>,
+}
+
+/// 子进程的资源使用量，取自子进程退出时内核记录的 `rusage`
+///
+/// 只统计直接子进程本身及其已回收的后代，不包括仍在运行或未被回收的后代。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResourceUsage {
+    /// 常驻内存峰值（字节）
+    pub max_rss: u64,
+    /// 用户态 CPU 时间
+    pub user_time: Duration,
+    /// 内核态 CPU 时间
+    pub system_time: Duration,
+    /// 无需磁盘 I/O 的缺页次数
+    pub minor_page_faults: u64,
+    /// 需要磁盘 I/O 的缺页次数
+    pub major_page_faults: u64,
+}
+
+impl ResourceUsage {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn from_rusage(usage: &nix::libc::rusage) -> Self {
+        let time = |t: nix::libc::timeval| {
+            Duration::from_secs(t.tv_sec.max(0) as u64)
+                + Duration::from_micros(t.tv_usec.max(0) as u64)
+        };
+        Self {
+            // Linux 上 ru_maxrss 以 KiB 为单位
+            max_rss: (usage.ru_maxrss.max(0) as u64) * 1024,
+            user_time: time(usage.ru_utime),
+            system_time: time(usage.ru_stime),
+            minor_page_faults: usage.ru_minflt.max(0) as u64,
+            major_page_faults: usage.ru_majflt.max(0) as u64,
+        }
+    }
+}
+
+/// 一次执行的时间信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionTiming {
+    /// 开始执行（启动子进程）的时间
+    pub started_at: SystemTime,
+    /// 从开始执行到命令结束的耗时（单调时钟）
+    pub duration: Duration,
+    /// 本次执行开始前在队列中等待的时间
+    ///
+    /// 从提交时开始计算，计划执行或重试的任务从计划时间开始计算；
+    /// 不经命令池直接执行时为零。
+    pub queue_wait: Duration,
+    /// 第几次执行（从 1 开始），命令池按重试策略重新执行时递增
+    pub attempt: u32,
+}
+
+impl ExecutionTiming {
+    /// 不经命令池的单次执行
+    pub(crate) fn direct(started_at: SystemTime, duration: Duration) -> Self {
+        Self {
+            started_at,
+            duration,
+            queue_wait: Duration::ZERO,
+            attempt: 1,
+        }
+    }
+}
+
+/// 输出来源流
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputStream {
+    /// 标准输出
+    Stdout,
+    /// 标准错误
+    Stderr,
+}
+
+/// 时间线中的一个输出块
+///
+/// 对应读取线程从管道中一次读到的数据，不保证按行切分。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputChunk {
+    /// 相对于子进程启动时刻的偏移（单调时钟）
+    pub offset: Duration,
+    /// 来源流
+    pub stream: OutputStream,
+    /// 读取到的数据
+    pub data: Vec<u8>,
+}
+
+/// 与上一次运行相比的输出变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputChange {
+    /// 该键的首次运行，没有可比较的输出
+    First,
+    /// 输出与上一次运行相同
+    Unchanged,
+    /// 输出与上一次运行不同
+    Changed {
+        /// 统一格式差异（未启用、输出不是文本或过大时为 None）
+        diff: Option<String>,
+    },
+}
+
+impl OutputChange {
+    /// 输出是否发生变化（首次运行不算变化）
+    pub fn is_changed(&self) -> bool {
+        matches!(self, OutputChange::Changed { .. })
+    }
+}
+
+/// 收集到的输出产物
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    /// 匹配到该文件的模式
+    pub pattern: String,
+    /// 相对于工作目录的路径（以 `/` 分隔）
+    pub relative_path: String,
+    /// 产物当前所在路径（收集到目标目录后为目标路径）
+    pub path: PathBuf,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 文件的 SHA-256（小写十六进制），仅在启用校验和时计算
+    pub sha256: Option<String>,
+}
+
+impl ExecutionReport {
+    /// 使用命令输出创建报告，元数据为空
+    pub fn new(output: Output) -> Self {
+        Self {
+            output,
+            temp_workdir: None,
+            artifacts: Vec::new(),
+            missing_artifacts: Vec::new(),
+            stdout_sha256: None,
+            output_change: None,
+            timeline: None,
+            timing: None,
+            resource_usage: None,
+        }
+    }
+
+    /// 取出输出，报告中留下仅包含退出状态的空输出
+    ///
+    /// 命令池将输出通过结果通道发送，元数据单独保存在句柄中，避免复制输出。
+    pub(crate) fn take_output(&mut self) -> Output {
+        let empty = Output {
+            status: self.output.status,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        };
+        std::mem::replace(&mut self.output, empty)
+    }
+}
+
+impl From<Output> for ExecutionReport {
+    fn from(output: Output) -> Self {
+        Self::new(output)
+    }
+}
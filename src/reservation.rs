@@ -0,0 +1,53 @@
+//! 队列空位预留
+//!
+//! [`CommandPool::try_reserve`](crate::CommandPool::try_reserve) 在有界队列中一次性预留多个空位，
+//! 预留的空位不计入其他生产者可用的容量，[`Reservation`] 丢弃时归还未使用的部分。
+
+use crate::config::CommandConfig;
+use crate::error::SubmitError;
+use crate::pool::CommandPool;
+use crate::task_handle::TaskHandle;
+
+/// 队列空位预留
+///
+/// 由 [`CommandPool::try_reserve`](crate::CommandPool::try_reserve) 返回。
+/// 每次 [`push_task`](Self::push_task) 使用一个预留的空位，丢弃时归还剩余空位。
+#[must_use = "dropping a reservation releases its slots immediately"]
+pub struct Reservation<'a> {
+    pool: &'a CommandPool,
+    remaining: usize,
+}
+
+impl<'a> Reservation<'a> {
+    pub(crate) fn new(pool: &'a CommandPool, remaining: usize) -> Self {
+        Self { pool, remaining }
+    }
+
+    /// 剩余的预留空位数
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// 使用一个预留的空位提交任务，不会因队列满而等待
+    ///
+    /// 合并到已有任务（参见 [`CommandConfig::with_coalesce_key`]）或提交失败时不消耗空位。
+    ///
+    /// # 错误
+    ///
+    /// * `SubmitError::QueueFull` - 预留的空位已用完
+    /// * `SubmitError::ShuttingDown` - 命令池正在关闭
+    pub fn push_task(&mut self, task: CommandConfig) -> Result<TaskHandle, SubmitError> {
+        if self.remaining == 0 {
+            return Err(SubmitError::QueueFull);
+        }
+        self.pool.push_reserved(task, &mut self.remaining)
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if self.remaining > 0 {
+            self.pool.release_reserved(self.remaining);
+        }
+    }
+}
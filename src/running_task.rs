@@ -0,0 +1,219 @@
+//! 运行中的任务
+//!
+//! [`spawn`](crate::spawn) 启动子进程后返回的句柄，由调用方决定何时以及如何等待、
+//! 轮询或终止。等待时复用命令池的超时、超时钩子和输出捕获逻辑。
+
+use std::process::{Child, ChildStderr, ChildStdin, ChildStdout, ExitStatus, Output};
+use std::time::{Duration, Instant};
+
+use crate::capture::OutputCollectors;
+use crate::child_wait::ChildExt;
+use crate::config::CommandConfig;
+use crate::error::ExecuteError;
+use crate::executor::{finish_output, terminate_child, wait_for_exit};
+use crate::output_lines::OutputLines;
+
+/// 已启动、尚未等待的子进程
+///
+/// 由 [`spawn`](crate::spawn) 创建。首次调用 [`is_alive`](Self::is_alive)、
+/// [`wait_timeout`](Self::wait_timeout) 或 [`wait`](Self::wait) 时开始在后台读取输出，
+/// 避免子进程因管道写满而阻塞。需要自行读取输出时，先调用
+/// [`take_output_readers`](Self::take_output_readers)。
+///
+/// 子进程的 stdin 连接到管道：通过 [`stdin_writer`](Self::stdin_writer) 取走后可边运行边写入，
+/// 丢弃写入端即关闭 stdin；未取走时在首次等待或查询状态时关闭，子进程读到 EOF。
+///
+/// 丢弃未等待的 `RunningTask` 会终止子进程并回收，不会留下僵尸进程。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, Duration, spawn};
+///
+/// let mut task = spawn(&CommandConfig::new("make", vec![]))?;
+/// while task.wait_timeout(Duration::from_secs(1))?.is_none() {
+///     if should_abort() {
+///         task.kill()?;
+///     }
+/// }
+/// let output = task.wait()?;
+/// ```
+#[derive(Debug)]
+pub struct RunningTask {
+    child: Option<Child>,
+    config: CommandConfig,
+    started: Instant,
+    collectors: Option<OutputCollectors>,
+    /// 输出管道已交给调用方，不再启动读取线程
+    readers_taken: bool,
+    /// 子进程因超过配置的超时被终止
+    timed_out: Option<Duration>,
+}
+
+impl RunningTask {
+    pub(crate) fn new(child: Child, config: CommandConfig, started: Instant) -> Self {
+        Self {
+            child: Some(child),
+            config,
+            started,
+            collectors: None,
+            readers_taken: false,
+            timed_out: None,
+        }
+    }
+
+    /// 子进程 ID
+    pub fn pid(&self) -> u32 {
+        self.child().id()
+    }
+
+    /// 子进程是否仍在运行
+    pub fn is_alive(&mut self) -> bool {
+        self.ensure_collectors();
+        matches!(self.child_mut().try_wait(), Ok(None))
+    }
+
+    /// 等待子进程完成并收集输出
+    ///
+    /// 使用配置中的超时（从启动时开始计算）、超时钩子和输出捕获模式，
+    /// 超时后子进程被终止并返回 [`ExecuteError::Timeout`]。
+    /// 输出管道已通过 [`take_output_readers`](Self::take_output_readers) 取走时，
+    /// 对应的输出为空。配置了 [`with_success_codes`](crate::CommandConfig::with_success_codes)
+    /// 且退出码不在其中时返回 [`ExecuteError::UnexpectedExit`]。
+    pub fn wait(mut self) -> Result<Output, ExecuteError> {
+        if let Some(timeout) = self.timed_out {
+            return Err(ExecuteError::Timeout(timeout));
+        }
+        self.ensure_collectors();
+        let mut child = self.child.take().expect("running task already waited");
+        let collectors = self.collectors.take().unwrap_or_else(|| {
+            OutputCollectors::start(&mut child, self.config.capture_mode, self.started)
+        });
+        let status = wait_for_exit(&mut child, &self.config, self.started, &collectors)?;
+        self.config
+            .check_exit(finish_output(status, collectors, &self.config))
+    }
+
+    /// 最多等待 `timeout`，子进程在此期间退出时返回退出状态
+    ///
+    /// 超时返回 `Ok(None)`，子进程继续运行，之后仍可调用 [`wait`](Self::wait) 获取输出。
+    /// 等待期间达到配置的超时时，子进程被终止并返回 [`ExecuteError::Timeout`]；
+    /// 配置了超时钩子时不在这里终止，钩子在 [`wait`](Self::wait) 中调用。
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Result<Option<ExitStatus>, ExecuteError> {
+        if let Some(timeout) = self.timed_out {
+            return Err(ExecuteError::Timeout(timeout));
+        }
+        self.ensure_collectors();
+
+        let deadline = self
+            .config
+            .timeout
+            .filter(|_| self.config.timeout_hook().is_none());
+        let remaining = deadline.map(|d| d.saturating_sub(self.started.elapsed()));
+        let wait_for = remaining.map_or(timeout, |r| r.min(timeout));
+
+        let child = self.child_mut();
+        if let Some(status) = child.wait_timeout(wait_for)? {
+            return Ok(Some(status));
+        }
+        match (deadline, remaining) {
+            (Some(deadline), Some(remaining)) if remaining <= timeout => {
+                let child = self.child.as_mut().expect("running task already waited");
+                terminate_child(child, &self.config);
+                self.timed_out = Some(deadline);
+                Err(ExecuteError::Timeout(deadline))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 强制终止子进程并回收
+    ///
+    /// 子进程已退出时直接返回。终止后 [`wait`](Self::wait) 返回被信号终止的退出状态。
+    pub fn kill(&mut self) -> Result<(), ExecuteError> {
+        let child = self.child_mut();
+        if child.try_wait()?.is_some() {
+            return Ok(());
+        }
+        child.kill()?;
+        child.wait()?;
+        Ok(())
+    }
+
+    /// 取走子进程的 stdin 写入端
+    ///
+    /// 写入的数据直接送达子进程，丢弃返回值即关闭 stdin（子进程读到 EOF）。
+    /// 必须在首次等待或查询状态之前调用，之后 stdin 已被关闭，返回 `None`。
+    ///
+    /// # 示例
+    ///
+    /// ```ignore
+    /// use std::io::Write;
+    /// use execute::{CommandConfig, spawn};
+    ///
+    /// let mut task = spawn(&CommandConfig::new("sort", vec![]))?;
+    /// let mut stdin = task.stdin_writer().unwrap();
+    /// stdin.write_all(b"b\na\n")?;
+    /// drop(stdin);
+    /// assert_eq!(task.wait()?.stdout, b"a\nb\n");
+    /// ```
+    pub fn stdin_writer(&mut self) -> Option<ChildStdin> {
+        self.child_mut().stdin.take()
+    }
+
+    /// 取走子进程的 stdout 和 stderr 管道，由调用方自行读取
+    ///
+    /// 必须在首次等待或查询状态之前调用，之后输出已由后台线程读取，返回 `(None, None)`。
+    pub fn take_output_readers(&mut self) -> (Option<ChildStdout>, Option<ChildStderr>) {
+        if self.collectors.is_some() {
+            return (None, None);
+        }
+        self.readers_taken = true;
+        let child = self.child_mut();
+        (child.stdout.take(), child.stderr.take())
+    }
+
+    /// 改为按行迭代 stdout 和 stderr，参见 [`OutputLines`]
+    ///
+    /// 需要写入 stdin 时，先通过 [`stdin_writer`](Self::stdin_writer) 取走写入端；
+    /// 未取走的 stdin 会被关闭。输出管道已被取走或读取时，迭代立即结束。
+    pub fn into_lines(self) -> OutputLines {
+        OutputLines::new(self)
+    }
+
+    pub(crate) fn started(&self) -> Instant {
+        self.started
+    }
+
+    pub(crate) fn config(&self) -> &CommandConfig {
+        &self.config
+    }
+
+    fn child(&self) -> &Child {
+        self.child.as_ref().expect("running task already waited")
+    }
+
+    fn child_mut(&mut self) -> &mut Child {
+        self.child.as_mut().expect("running task already waited")
+    }
+
+    /// 首次等待时关闭未取走的 stdin 并启动输出读取线程
+    fn ensure_collectors(&mut self) {
+        drop(self.child_mut().stdin.take());
+        if self.collectors.is_none() && !self.readers_taken {
+            let (mode, started) = (self.config.capture_mode, self.started);
+            self.collectors = Some(OutputCollectors::start(self.child_mut(), mode, started));
+        }
+    }
+}
+
+impl Drop for RunningTask {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take()
+            && child.try_wait().ok().flatten().is_none()
+        {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
@@ -0,0 +1,482 @@
+//! 按 cron 表达式周期性提交任务
+//!
+//! [`CronSchedule`] 解析类 cron 表达式并计算下一次触发时间，
+//! [`CommandPool::schedule_recurring`](crate::CommandPool::schedule_recurring)
+//! 在后台线程中按计划把任务提交到命令池，返回的 [`RecurringHandle`] 用于取消。
+//! [`BlackoutWindows`] 声明禁止执行的时间窗口（如变更冻结期），
+//! 落在窗口内的触发被跳过或推迟到窗口结束。
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::ConfigError;
+
+/// 单个字段的取值范围
+struct FieldSpec {
+    name: &'static str,
+    min: u32,
+    max: u32,
+}
+
+const SECOND: FieldSpec = FieldSpec {
+    name: "second",
+    min: 0,
+    max: 59,
+};
+const MINUTE: FieldSpec = FieldSpec {
+    name: "minute",
+    min: 0,
+    max: 59,
+};
+const HOUR: FieldSpec = FieldSpec {
+    name: "hour",
+    min: 0,
+    max: 23,
+};
+const DAY_OF_MONTH: FieldSpec = FieldSpec {
+    name: "day of month",
+    min: 1,
+    max: 31,
+};
+const MONTH: FieldSpec = FieldSpec {
+    name: "month",
+    min: 1,
+    max: 12,
+};
+// 7 与 0 都表示星期日，解析后折叠为 0
+const DAY_OF_WEEK: FieldSpec = FieldSpec {
+    name: "day of week",
+    min: 0,
+    max: 7,
+};
+
+/// 查找下一次触发时间时最多向后搜索的天数
+const MAX_SEARCH_DAYS: u64 = 366 * 5;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// cron 调度表达式
+///
+/// 支持标准的 5 字段格式（分 时 日 月 周），以及在最前面增加秒字段的 6 字段格式。
+/// 每个字段可以是 `*`、数值、范围 `a-b`、步长 `*/n` 或 `a-b/n`，以及逗号分隔的列表；
+/// 星期字段中 0 和 7 都表示星期日。日和周字段都受限时，满足任意一个即触发（与 cron 相同）。
+///
+/// 所有时间按 UTC 计算。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::CronSchedule;
+///
+/// // 每 5 分钟
+/// let every_five = CronSchedule::parse("*/5 * * * *").unwrap();
+/// // 工作日每天 02:30
+/// let nightly = CronSchedule::parse("30 2 * * 1-5").unwrap();
+/// // 每 10 秒（6 字段格式）
+/// let fast = CronSchedule::parse("*/10 * * * * *").unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// 日字段是否受限（不是 `*` 开头）
+    day_of_month_restricted: bool,
+    /// 周字段是否受限（不是 `*` 开头）
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    /// 解析 cron 表达式
+    ///
+    /// # 错误
+    ///
+    /// 字段数量不是 5 或 6、取值超出范围或格式错误时返回
+    /// `ConfigError::InvalidCronExpression`
+    pub fn parse(expression: &str) -> Result<Self, ConfigError> {
+        let invalid =
+            |reason: String| ConfigError::InvalidCronExpression(expression.to_string(), reason);
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let (seconds, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => return Err(invalid(format!("expected 5 or 6 fields, got {n}"))),
+        };
+
+        let mut days_of_week = parse_field(rest[4], &DAY_OF_WEEK).map_err(invalid)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            seconds: parse_field(seconds, &SECOND).map_err(invalid)?,
+            minutes: parse_field(rest[0], &MINUTE).map_err(invalid)?,
+            hours: parse_field(rest[1], &HOUR).map_err(invalid)?,
+            days_of_month: parse_field(rest[2], &DAY_OF_MONTH).map_err(invalid)?,
+            months: parse_field(rest[3], &MONTH).map_err(invalid)?,
+            days_of_week,
+            day_of_month_restricted: !rest[2].starts_with('*'),
+            day_of_week_restricted: !rest[4].starts_with('*'),
+        })
+    }
+
+    /// 计算严格晚于 `after` 的下一次触发时间
+    ///
+    /// 在之后 5 年内找不到触发时间时（如 `0 0 31 2 *`）返回 None。
+    pub fn next_after(&self, after: SystemTime) -> Option<SystemTime> {
+        let after = after.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        let start = after + 1;
+        let first_day = start / 86_400;
+
+        for day in first_day..first_day + MAX_SEARCH_DAYS {
+            if !self.matches_day(day) {
+                continue;
+            }
+            // 第一天只考虑 start 之后的时刻
+            let floor = if day == first_day { start % 86_400 } else { 0 };
+            if let Some(offset) = self.first_time_of_day(floor) {
+                return Some(UNIX_EPOCH + Duration::from_secs(day * 86_400 + offset));
+            }
+        }
+        None
+    }
+
+    /// 自 1970-01-01 起的第 `day` 天是否满足日、月、周字段
+    fn matches_day(&self, day: u64) -> bool {
+        let (_, month, day_of_month) = civil_from_days(day);
+        if self.months & (1 << month) == 0 {
+            return false;
+        }
+        // 1970-01-01 是星期四
+        let weekday = (day + 4) % 7;
+        let dom = self.days_of_month & (1 << day_of_month) != 0;
+        let dow = self.days_of_week & (1 << weekday) != 0;
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+
+    /// 一天之内不早于 `floor` 秒的第一个触发时刻（距当天零点的秒数）
+    fn first_time_of_day(&self, floor: u64) -> Option<u64> {
+        for hour in floor / 3600..24 {
+            if self.hours & (1 << hour) == 0 {
+                continue;
+            }
+            for minute in 0..60 {
+                if self.minutes & (1 << minute) == 0 {
+                    continue;
+                }
+                for second in 0..60 {
+                    let offset = hour * 3600 + minute * 60 + second;
+                    if offset >= floor && self.seconds & (1 << second) != 0 {
+                        return Some(offset);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl std::str::FromStr for CronSchedule {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// 解析单个字段为位掩码（第 n 位表示取值 n）
+fn parse_field(field: &str, spec: &FieldSpec) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid step {step:?} in {} field", spec.name))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (low, high) = if range == "*" {
+            (spec.min, spec.max)
+        } else if let Some((low, high)) = range.split_once('-') {
+            (parse_value(low, spec)?, parse_value(high, spec)?)
+        } else {
+            let value = parse_value(range, spec)?;
+            // `a/n` 表示从 a 开始直到最大值
+            (value, if step > 1 { spec.max } else { value })
+        };
+        if low > high {
+            return Err(format!("invalid range {range:?} in {} field", spec.name));
+        }
+        for value in (low..=high).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(value: &str, spec: &FieldSpec) -> Result<u32, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|value| (spec.min..=spec.max).contains(value))
+        .ok_or_else(|| {
+            format!(
+                "{} value {value:?} out of range {}-{}",
+                spec.name, spec.min, spec.max
+            )
+        })
+}
+
+/// 自 1970-01-01 起的天数转换为（年，月，日）
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// 自年、月、日计算自 1970-01-01 起的天数
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year % 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// 触发时间落在禁止窗口内时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlackoutAction {
+    /// 跳过这次触发
+    #[default]
+    Skip,
+    /// 推迟到窗口结束时提交；窗口内的多次触发只提交一次
+    Defer,
+}
+
+/// 周期任务的禁止执行窗口
+///
+/// 包括每天重复的时间段和整天的指定日期，时间与 [`CronSchedule`] 一样按 UTC 计算。
+/// 每日时间段为 `[start, end)`，以距零点的时长表示；`start` 晚于 `end` 时跨越零点，
+/// 例如 22:00 到 02:00。相邻或重叠的窗口合并计算，推迟的任务在所有窗口都结束后提交。
+///
+/// 通过 [`CommandPool::schedule_recurring_with_blackout`](crate::CommandPool::schedule_recurring_with_blackout)
+/// 使用。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{BlackoutAction, BlackoutWindows};
+/// use std::time::Duration;
+///
+/// // 每天 02:00-04:00 维护，以及 12 月 25 日变更冻结，期间的触发推迟执行
+/// let blackout = BlackoutWindows::new()
+///     .with_daily(Duration::from_secs(2 * 3600), Duration::from_secs(4 * 3600))
+///     .with_date(2024, 12, 25)
+///     .with_action(BlackoutAction::Defer);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlackoutWindows {
+    /// 每日时间段（距零点的秒数）
+    daily: Vec<(u64, u64)>,
+    /// 整天禁止的日期（自 1970-01-01 起的天数）
+    dates: Vec<u64>,
+    action: BlackoutAction,
+}
+
+impl BlackoutWindows {
+    /// 创建空的禁止窗口，默认跳过窗口内的触发
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加每天重复的时间段
+    ///
+    /// `start` 和 `end` 是距 UTC 零点的时长，超过一天的部分按一天取余，
+    /// 精确到秒。`start` 等于 `end` 时时间段为空。
+    pub fn with_daily(mut self, start: Duration, end: Duration) -> Self {
+        self.daily.push((
+            start.as_secs() % SECONDS_PER_DAY,
+            end.as_secs() % SECONDS_PER_DAY,
+        ));
+        self
+    }
+
+    /// 添加整天禁止的日期（UTC）
+    ///
+    /// # Panics
+    ///
+    /// 日期不存在（如 2 月 30 日）或早于 1970 年时 panic
+    pub fn with_date(mut self, year: u32, month: u32, day: u32) -> Self {
+        let (year, month, day) = (u64::from(year), u64::from(month), u64::from(day));
+        assert!(
+            year >= 1970 && (1..=12).contains(&month) && day >= 1,
+            "invalid blackout date {year}-{month}-{day}"
+        );
+        let days = days_from_civil(year, month, day);
+        assert!(
+            civil_from_days(days) == (year, month, day),
+            "invalid blackout date {year}-{month}-{day}"
+        );
+        self.dates.push(days);
+        self
+    }
+
+    /// 设置窗口内触发的处理方式
+    pub fn with_action(mut self, action: BlackoutAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// 窗口内触发的处理方式
+    pub fn action(&self) -> BlackoutAction {
+        self.action
+    }
+
+    /// `time` 是否落在禁止窗口内
+    pub fn contains(&self, time: SystemTime) -> bool {
+        time.duration_since(UNIX_EPOCH)
+            .is_ok_and(|since| self.window_end(since.as_secs()).is_some())
+    }
+
+    /// 不早于 `time` 且不在任何窗口内的最早时间
+    ///
+    /// `time` 不在窗口内时原样返回；窗口连续覆盖之后 5 年时返回 None。
+    pub fn next_allowed(&self, time: SystemTime) -> Option<SystemTime> {
+        let since = time.duration_since(UNIX_EPOCH).ok()?;
+        let mut secs = since.as_secs();
+        if self.window_end(secs).is_none() {
+            return Some(time);
+        }
+        let limit = secs + MAX_SEARCH_DAYS * SECONDS_PER_DAY;
+        while let Some(end) = self.window_end(secs) {
+            if end > limit {
+                return None;
+            }
+            secs = end;
+        }
+        Some(UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    /// `secs` 所在窗口的结束时间（自 UNIX 纪元的秒数），不在窗口内时返回 None
+    ///
+    /// 同时落在多个窗口内时返回最晚的结束时间。
+    fn window_end(&self, secs: u64) -> Option<u64> {
+        let day = secs / SECONDS_PER_DAY;
+        let midnight = day * SECONDS_PER_DAY;
+        let of_day = secs % SECONDS_PER_DAY;
+
+        let dates = self
+            .dates
+            .contains(&day)
+            .then_some(midnight + SECONDS_PER_DAY);
+        let daily = self.daily.iter().filter_map(|&(start, end)| {
+            if start < end {
+                (start..end).contains(&of_day).then_some(midnight + end)
+            } else if start > end {
+                if of_day >= start {
+                    Some(midnight + SECONDS_PER_DAY + end)
+                } else {
+                    (of_day < end).then_some(midnight + end)
+                }
+            } else {
+                None
+            }
+        });
+        dates.into_iter().chain(daily).max()
+    }
+}
+
+/// 周期任务句柄
+///
+/// 由 [`CommandPool::schedule_recurring`](crate::CommandPool::schedule_recurring) 返回，
+/// 调用 [`cancel`](Self::cancel) 停止后续提交，已经提交的任务不受影响。
+/// 丢弃句柄不会取消周期任务。
+#[derive(Clone)]
+pub struct RecurringHandle {
+    state: Arc<(Mutex<RecurringState>, Condvar)>,
+}
+
+#[derive(Default)]
+struct RecurringState {
+    cancelled: bool,
+    /// 已提交的次数
+    runs: u64,
+    /// 因禁止窗口跳过的次数
+    skipped: u64,
+}
+
+impl RecurringHandle {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Arc::new((Mutex::new(RecurringState::default()), Condvar::new())),
+        }
+    }
+
+    /// 取消周期任务，不再提交新的任务
+    pub fn cancel(&self) {
+        let (lock, cvar) = &*self.state;
+        lock.lock().unwrap().cancelled = true;
+        cvar.notify_all();
+    }
+
+    /// 周期任务是否已取消
+    pub fn is_cancelled(&self) -> bool {
+        self.state.0.lock().unwrap().cancelled
+    }
+
+    /// 已经提交到命令池的次数
+    pub fn runs(&self) -> u64 {
+        self.state.0.lock().unwrap().runs
+    }
+
+    /// 因禁止窗口跳过的触发次数
+    pub fn skipped(&self) -> u64 {
+        self.state.0.lock().unwrap().skipped
+    }
+
+    /// 等待到 `deadline` 或被取消，被取消时返回 false
+    pub(crate) fn sleep_until(&self, deadline: SystemTime, max_wait: Duration) -> bool {
+        let (lock, cvar) = &*self.state;
+        let state = lock.lock().unwrap();
+        let remaining = deadline
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO)
+            .min(max_wait);
+        let (state, _) = cvar
+            .wait_timeout_while(state, remaining, |state| !state.cancelled)
+            .unwrap();
+        !state.cancelled
+    }
+
+    /// 记录一次提交
+    pub(crate) fn record_run(&self) {
+        self.state.0.lock().unwrap().runs += 1;
+    }
+
+    /// 记录一次因禁止窗口跳过的触发
+    pub(crate) fn record_skip(&self) {
+        self.state.0.lock().unwrap().skipped += 1;
+    }
+}
@@ -0,0 +1,99 @@
+//! 作用域批量提交
+//!
+//! 在作用域内提交的任务仍由命令池的共享工作线程执行，
+//! 作用域结束时等待所有任务完成并按提交顺序返回结果，不会遗留未等待的任务。
+
+use std::sync::Mutex;
+
+use crate::config::CommandConfig;
+use crate::error::SubmitError;
+use crate::pool::CommandPool;
+use crate::task_handle::{TaskHandle, TaskResult};
+
+/// 任务作用域
+///
+/// 由 [`CommandPool::scope`] 创建，通过 [`TaskScope::submit`] 提交任务。
+pub struct TaskScope<'pool> {
+    pool: &'pool CommandPool,
+    handles: Mutex<Vec<TaskHandle>>,
+}
+
+impl<'pool> TaskScope<'pool> {
+    pub(crate) fn new(pool: &'pool CommandPool) -> Self {
+        Self {
+            pool,
+            handles: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 在作用域内提交任务
+    ///
+    /// # 返回
+    ///
+    /// 成功时返回该任务结果在作用域返回值中的下标。
+    ///
+    /// # 错误
+    ///
+    /// 提交失败（如命令池正在关闭）时返回 [`SubmitError`]，该任务不会出现在结果中。
+    pub fn submit(&self, config: CommandConfig) -> Result<usize, SubmitError> {
+        let handle = self.pool.push_task(config)?;
+        let mut handles = self.handles.lock().unwrap();
+        handles.push(handle);
+        Ok(handles.len() - 1)
+    }
+
+    /// 等待所有任务完成，按提交顺序返回结果
+    pub(crate) fn join(&self) -> Vec<TaskResult> {
+        let handles = std::mem::take(&mut *self.handles.lock().unwrap());
+        handles.iter().map(TaskHandle::wait).collect()
+    }
+}
+
+impl Drop for TaskScope<'_> {
+    /// 作用域闭包 panic 时取消尚未执行的任务并等待其余任务结束
+    fn drop(&mut self) {
+        let handles = match self.handles.get_mut() {
+            Ok(handles) => std::mem::take(handles),
+            Err(poisoned) => std::mem::take(poisoned.into_inner()),
+        };
+        for handle in &handles {
+            let _ = handle.cancel();
+        }
+        for handle in &handles {
+            let _ = handle.wait();
+        }
+    }
+}
+
+impl CommandPool {
+    /// 在作用域内批量提交任务并等待全部完成
+    ///
+    /// 闭包内通过 [`TaskScope::submit`] 提交的任务在共享工作线程上并行执行，
+    /// 本方法阻塞直到所有任务完成，并按提交顺序返回结果。
+    /// 如果闭包 panic，尚未执行的任务会被取消，并在所有任务结束后继续传播 panic。
+    ///
+    /// 需要先启动执行器，否则会一直等待。
+    ///
+    /// # 示例
+    ///
+    /// ```ignore
+    /// use execute::{CommandConfig, CommandPool};
+    ///
+    /// let pool = CommandPool::new();
+    /// pool.start_executor();
+    ///
+    /// let results = pool.scope(|s| {
+    ///     s.submit(CommandConfig::new("echo", vec!["a".to_string()])).unwrap();
+    ///     s.submit(CommandConfig::new("echo", vec!["b".to_string()])).unwrap();
+    /// });
+    /// assert_eq!(results.len(), 2);
+    /// ```
+    pub fn scope<F>(&self, f: F) -> Vec<TaskResult>
+    where
+        F: FnOnce(&TaskScope<'_>),
+    {
+        let scope = TaskScope::new(self);
+        f(&scope);
+        scope.join()
+    }
+}
@@ -0,0 +1,76 @@
+//! `OsString` 字段的序列化
+//!
+//! 合法 UTF-8 的值序列化为普通字符串，便于在 JSON/YAML 中手写任务定义；
+//! 其余值退回到 serde 对 `OsString` 的平台相关表示，保证往返不丢失内容。
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ffi::{OsStr, OsString};
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum OsStrRef<'a> {
+    Utf8(&'a str),
+    Native(&'a OsStr),
+}
+
+impl<'a> From<&'a OsStr> for OsStrRef<'a> {
+    fn from(value: &'a OsStr) -> Self {
+        match value.to_str() {
+            Some(text) => OsStrRef::Utf8(text),
+            None => OsStrRef::Native(value),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OsStringRepr {
+    Utf8(String),
+    Native(OsString),
+}
+
+impl From<OsStringRepr> for OsString {
+    fn from(value: OsStringRepr) -> Self {
+        match value {
+            OsStringRepr::Utf8(text) => text.into(),
+            OsStringRepr::Native(native) => native,
+        }
+    }
+}
+
+/// 单个 `OsString` 字段
+pub(crate) mod os_string {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        value: &OsString,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        OsStrRef::from(value.as_os_str()).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<OsString, D::Error> {
+        OsStringRepr::deserialize(deserializer).map(Into::into)
+    }
+}
+
+/// `Vec<OsString>` 字段
+pub(crate) mod os_string_vec {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        values: &[OsString],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|value| OsStrRef::from(value.as_os_str())))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<OsString>, D::Error> {
+        Vec::<OsStringRepr>::deserialize(deserializer)
+            .map(|values| values.into_iter().map(Into::into).collect())
+    }
+}
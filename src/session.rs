@@ -0,0 +1,287 @@
+//! 交互式子进程会话
+//!
+//! 启动长期运行的子进程后，调用方可以反复写入 stdin、按行或按分隔符读取 stdout，
+//! 最后关闭或终止子进程。每次读写都有独立的超时，适合驱动 sqlite3、Python REPL
+//! 等交互式命令行程序。
+//!
+//! stdin 由写入线程负责，stdout 和 stderr 各由一个读取线程负责，
+//! 子进程不读 stdin 或不再输出时，超时的操作返回错误而不会让调用方一直阻塞。
+
+use std::io::{self, Read, Write};
+use std::process::{ChildStdin, ExitStatus};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::config::CommandConfig;
+use crate::error::ExecuteError;
+use crate::executor::spawn;
+use crate::running_task::RunningTask;
+
+/// 交给写入线程的数据和写入结果的回传通道
+type WriteRequest = (Vec<u8>, Sender<io::Result<()>>);
+
+/// 与交互式子进程的会话
+///
+/// 由 [`Session::spawn`] 创建。读取方法在超时内没有读到所需的数据时返回
+/// [`io::ErrorKind::TimedOut`] 错误，已读到的数据保留给下一次读取；
+/// stdout 结束后仍未读到所需的数据时返回 [`io::ErrorKind::UnexpectedEof`]。
+/// stderr 在后台持续收集，通过 [`take_stderr`](Self::take_stderr) 取出。
+///
+/// 丢弃未关闭的 `Session` 会终止子进程并回收。
+///
+/// # 示例
+///
+/// ```ignore
+/// use execute::{CommandConfig, Session};
+/// use std::time::Duration;
+///
+/// let timeout = Duration::from_secs(5);
+/// let mut session = Session::spawn(&CommandConfig::new("python3", vec!["-iq".to_string()]))?;
+/// session.write_line("print(1 + 1)", timeout)?;
+/// assert_eq!(session.read_line(timeout)?.as_deref(), Some("2"));
+/// session.close(timeout)?;
+/// ```
+#[derive(Debug)]
+pub struct Session {
+    task: RunningTask,
+    /// 写入线程的请求通道，关闭 stdin 时丢弃
+    writer: Option<Sender<WriteRequest>>,
+    stdout: Receiver<Vec<u8>>,
+    /// 已从 stdout 读到、尚未交给调用方的数据
+    pending: Vec<u8>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+}
+
+impl Session {
+    /// 启动子进程并建立会话
+    ///
+    /// 与 [`spawn`](crate::spawn) 一样只应用命令本身的配置。配置中的超时从启动时开始计算，
+    /// 在 [`close`](Self::close) 等待退出时生效；交互式会话通常不设置超时，
+    /// 而是为每次读写指定超时。配置了 [`CommandConfig::with_stdin`] 时 stdin
+    /// 已用于写入配置的内容，会话不能再写入。
+    ///
+    /// # 错误
+    ///
+    /// 启动子进程失败时返回错误。
+    pub fn spawn(config: &CommandConfig) -> Result<Self, ExecuteError> {
+        let mut task = spawn(config)?;
+        let writer = task.stdin_writer().map(spawn_writer);
+        let (stdout, stderr) = task.take_output_readers();
+
+        let (sender, receiver) = mpsc::channel();
+        if let Some(mut pipe) = stdout {
+            std::thread::spawn(move || {
+                let mut chunk = [0u8; 8192];
+                loop {
+                    match pipe.read(&mut chunk) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => {
+                            if sender.send(chunk[..n].to_vec()).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+            });
+        }
+        let stderr_buffer = Arc::new(Mutex::new(Vec::new()));
+        if let Some(mut pipe) = stderr {
+            let buffer = Arc::clone(&stderr_buffer);
+            std::thread::spawn(move || {
+                let mut chunk = [0u8; 8192];
+                loop {
+                    match pipe.read(&mut chunk) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => buffer.lock().unwrap().extend_from_slice(&chunk[..n]),
+                    }
+                }
+            });
+        }
+
+        Ok(Self {
+            task,
+            writer,
+            stdout: receiver,
+            pending: Vec::new(),
+            stderr: stderr_buffer,
+        })
+    }
+
+    /// 子进程 ID
+    pub fn pid(&self) -> u32 {
+        self.task.pid()
+    }
+
+    /// 子进程是否仍在运行
+    pub fn is_alive(&mut self) -> bool {
+        self.task.is_alive()
+    }
+
+    /// 把数据写入子进程的 stdin，写入并刷新后返回
+    ///
+    /// # 错误
+    ///
+    /// * [`io::ErrorKind::TimedOut`] - 子进程在超时内没有读走数据，数据仍在后台继续写入
+    /// * [`io::ErrorKind::BrokenPipe`] - stdin 已关闭或子进程已退出
+    pub fn write(&mut self, data: &[u8], timeout: Duration) -> io::Result<()> {
+        let Some(writer) = &self.writer else {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "session stdin is closed",
+            ));
+        };
+        let (reply, result) = mpsc::channel();
+        writer
+            .send((data.to_vec(), reply))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "session stdin is closed"))?;
+        match result.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(timed_out("write", timeout)),
+            Err(RecvTimeoutError::Disconnected) => Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "session stdin is closed",
+            )),
+        }
+    }
+
+    /// 写入一行，自动追加换行符
+    ///
+    /// 错误与 [`write`](Self::write) 相同。
+    pub fn write_line(&mut self, line: &str, timeout: Duration) -> io::Result<()> {
+        let mut data = Vec::with_capacity(line.len() + 1);
+        data.extend_from_slice(line.as_bytes());
+        data.push(b'\n');
+        self.write(&data, timeout)
+    }
+
+    /// 关闭子进程的 stdin，子进程读到 EOF，之前写入的数据仍会送达
+    pub fn close_stdin(&mut self) {
+        self.writer = None;
+    }
+
+    /// 读取 stdout 的下一行，不含行尾的 `\n` 或 `\r\n`
+    ///
+    /// stdout 结束时返回没有换行符结尾的最后一行，之后返回 `Ok(None)`。
+    /// 非 UTF-8 字节按替换字符处理。
+    pub fn read_line(&mut self, timeout: Duration) -> io::Result<Option<String>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(end) = self.pending.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = self.pending.drain(..=end).collect();
+                let line = &line[..end];
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                return Ok(Some(String::from_utf8_lossy(line).into_owned()));
+            }
+            if !self.fill(deadline, timeout)? {
+                if self.pending.is_empty() {
+                    return Ok(None);
+                }
+                let line = std::mem::take(&mut self.pending);
+                return Ok(Some(String::from_utf8_lossy(&line).into_owned()));
+            }
+        }
+    }
+
+    /// 读取 stdout 直到出现 `delimiter`，返回包括分隔符在内的内容
+    ///
+    /// 用于等待交互式程序的提示符，例如 `read_until("sqlite> ", timeout)`。
+    /// 非 UTF-8 字节按替换字符处理。
+    pub fn read_until(&mut self, delimiter: &str, timeout: Duration) -> io::Result<String> {
+        let deadline = Instant::now() + timeout;
+        let delimiter = delimiter.as_bytes();
+        loop {
+            if let Some(start) = find(&self.pending, delimiter) {
+                let text: Vec<u8> = self.pending.drain(..start + delimiter.len()).collect();
+                return Ok(String::from_utf8_lossy(&text).into_owned());
+            }
+            if !self.fill(deadline, timeout)? {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stdout closed before the delimiter was read",
+                ));
+            }
+        }
+    }
+
+    /// 不等待，取出目前已经读到的 stdout 数据
+    pub fn read_available(&mut self) -> Vec<u8> {
+        while let Ok(chunk) = self.stdout.try_recv() {
+            self.pending.extend_from_slice(&chunk);
+        }
+        std::mem::take(&mut self.pending)
+    }
+
+    /// 取出目前已经收集到的 stderr 数据
+    pub fn take_stderr(&mut self) -> Vec<u8> {
+        std::mem::take(&mut *self.stderr.lock().unwrap())
+    }
+
+    /// 关闭 stdin 并等待子进程退出
+    ///
+    /// # 错误
+    ///
+    /// 子进程在 `timeout` 内没有退出时被终止并返回 [`ExecuteError::Timeout`]；
+    /// 配置的超时先到时同样返回 [`ExecuteError::Timeout`]。
+    pub fn close(mut self, timeout: Duration) -> Result<ExitStatus, ExecuteError> {
+        self.close_stdin();
+        match self.task.wait_timeout(timeout)? {
+            Some(status) => Ok(status),
+            None => {
+                self.task.kill()?;
+                Err(ExecuteError::Timeout(timeout))
+            }
+        }
+    }
+
+    /// 强制终止子进程并回收
+    pub fn kill(mut self) -> Result<(), ExecuteError> {
+        self.close_stdin();
+        self.task.kill()
+    }
+
+    /// 从 stdout 读取线程接收数据，stdout 已结束时返回 false
+    fn fill(&mut self, deadline: Instant, timeout: Duration) -> io::Result<bool> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match self.stdout.recv_timeout(remaining) {
+            Ok(chunk) => {
+                self.pending.extend_from_slice(&chunk);
+                Ok(true)
+            }
+            Err(RecvTimeoutError::Timeout) => Err(timed_out("read", timeout)),
+            Err(RecvTimeoutError::Disconnected) => Ok(false),
+        }
+    }
+}
+
+/// 启动写入线程，请求通道关闭后丢弃 stdin，子进程读到 EOF
+fn spawn_writer(mut stdin: ChildStdin) -> Sender<WriteRequest> {
+    let (sender, receiver) = mpsc::channel::<WriteRequest>();
+    std::thread::spawn(move || {
+        for (data, reply) in receiver {
+            let result = stdin.write_all(&data).and_then(|()| stdin.flush());
+            let failed = result.is_err();
+            let _ = reply.send(result);
+            if failed {
+                break;
+            }
+        }
+    });
+    sender
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn timed_out(operation: &str, timeout: Duration) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("session {operation} timed out after {timeout:?}"),
+    )
+}
@@ -0,0 +1,49 @@
+//! 任务结果收集
+//!
+//! 为命令池设置 [`ResultSink`] 后，每个结束的任务都会把 `(任务 ID, 结果)` 交给它，
+//! 批量任务的调用方可以按完成顺序消费结果，而不必持有每个任务句柄。
+
+use std::sync::mpsc::{Sender, SyncSender};
+
+use crate::task_handle::TaskResult;
+
+/// 任务结果接收端
+///
+/// 在执行任务的工作线程上调用，调用发生在任务句柄收到结果之前。
+/// 标准库的 [`Sender`] 和 [`SyncSender`] 已实现本 trait；
+/// 使用有界通道时通道满会阻塞工作线程，从而对执行形成背压。
+/// 接收端已关闭时结果被丢弃。
+///
+/// # 示例
+///
+/// ```no_run
+/// use execute::{CommandConfig, CommandPool};
+/// use std::sync::Arc;
+/// use std::sync::mpsc::sync_channel;
+///
+/// let (sender, results) = sync_channel(64);
+/// let pool = CommandPool::new().with_result_sink(Arc::new(sender));
+/// pool.start_executor();
+/// for i in 0..200 {
+///     pool.push_task(CommandConfig::new("echo", vec![i.to_string()])).unwrap();
+/// }
+/// for (task_id, result) in results.iter().take(200) {
+///     println!("task {} finished: ok={}", task_id, result.is_ok());
+/// }
+/// ```
+pub trait ResultSink: Send + Sync {
+    /// 接收一个结束任务的结果
+    fn accept(&self, task_id: u64, result: TaskResult);
+}
+
+impl ResultSink for Sender<(u64, TaskResult)> {
+    fn accept(&self, task_id: u64, result: TaskResult) {
+        let _ = self.send((task_id, result));
+    }
+}
+
+impl ResultSink for SyncSender<(u64, TaskResult)> {
+    fn accept(&self, task_id: u64, result: TaskResult) {
+        let _ = self.send((task_id, result));
+    }
+}
@@ -0,0 +1,104 @@
+//! 队列快照
+//!
+//! [`CommandPool::snapshot`](crate::CommandPool::snapshot) 复制等待中任务的基本信息，
+//! [`CommandPool::running_tasks`](crate::CommandPool::running_tasks) 复制正在执行的任务的信息，
+//! 用于界面展示和排查问题，不影响队列本身。
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::pool::TaskItem;
+use crate::task_handle::{TaskHandle, TaskState};
+
+/// 队列中等待执行的任务
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedTask {
+    /// 任务 ID
+    pub task_id: u64,
+    /// 要执行的程序
+    pub program: OsString,
+    /// 命令参数
+    pub args: Vec<OsString>,
+    /// 调度优先级
+    pub priority: u8,
+    /// 任务标签
+    pub labels: BTreeMap<String, String>,
+    /// 计划执行时间（未设置时为 None）
+    pub start_at: Option<SystemTime>,
+    /// 自提交以来等待的时间
+    pub waited: Duration,
+}
+
+impl QueuedTask {
+    pub(crate) fn from_item(item: &TaskItem) -> Self {
+        let config = &item.config;
+        Self {
+            task_id: item.handle.id(),
+            program: config.program_os().to_os_string(),
+            args: config.args_os().to_vec(),
+            priority: config.priority(),
+            labels: config.labels().clone(),
+            start_at: config.start_at(),
+            waited: item.handle.submitted_at().elapsed(),
+        }
+    }
+}
+
+/// 正在执行的任务
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InFlightTask {
+    /// 任务 ID
+    pub task_id: u64,
+    /// 要执行的程序
+    pub program: OsString,
+    /// 命令参数
+    pub args: Vec<OsString>,
+    /// 任务标签
+    pub labels: BTreeMap<String, String>,
+    /// 子进程 ID（子进程尚未启动或自定义执行器没有上报时为 None）
+    pub pid: Option<u32>,
+    /// 第几次执行（从 1 开始，按重试策略重新执行时递增）
+    pub attempt: u32,
+    /// 本次执行已经运行的时间
+    pub elapsed: Duration,
+}
+
+impl InFlightTask {
+    pub(crate) fn from_entry(entry: &InFlight) -> Self {
+        let pid = match entry.handle.state() {
+            TaskState::Running { pid } => pid,
+            _ => None,
+        };
+        Self {
+            task_id: entry.handle.id(),
+            program: entry.program.clone(),
+            args: entry.args.clone(),
+            labels: entry.labels.clone(),
+            pid,
+            attempt: entry.handle.attempts(),
+            elapsed: entry.started.elapsed(),
+        }
+    }
+}
+
+/// 命令池执行集合中的任务
+pub(crate) struct InFlight {
+    pub(crate) handle: TaskHandle,
+    program: OsString,
+    args: Vec<OsString>,
+    labels: BTreeMap<String, String>,
+    started: Instant,
+}
+
+impl InFlight {
+    pub(crate) fn new(item: &TaskItem) -> Self {
+        Self {
+            handle: item.handle.clone(),
+            program: item.config.program_os().to_os_string(),
+            args: item.config.args_os().to_vec(),
+            labels: item.config.labels().clone(),
+            started: Instant::now(),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! 命令池运行统计
+//!
+//! 计数器均为原子变量，记录时不加锁；[`CommandPool::stats`](crate::CommandPool::stats)
+//! 读取时生成 [`PoolStats`] 快照。与 `metrics` feature 的直方图不同，这里只维护计数和平均值，
+//! 始终可用。
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::events::FinishStatus;
+
+/// 命令池统计快照
+///
+/// 由 [`CommandPool::stats`](crate::CommandPool::stats) 返回，适合用于监控面板和自动扩缩容决策。
+/// 合并到其他任务的跟随者按独立任务计数。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// 当前在队列中等待的任务数
+    pub queued: usize,
+    /// 当前正在执行的任务数
+    pub running: usize,
+    /// 成功结束的任务数
+    pub completed: u64,
+    /// 失败的任务数（非零退出或执行错误，不含超时）
+    pub failed: u64,
+    /// 超时的任务数
+    pub timed_out: u64,
+    /// 从提交到首次开始执行的平均等待时间
+    pub avg_wait_time: Duration,
+    /// 每次执行的平均运行时间（按命令池重试时每次执行分别计入）
+    pub avg_run_time: Duration,
+}
+
+/// 命令池内部的统计计数器（与克隆共享，子池独立统计）
+#[derive(Default)]
+pub(crate) struct StatsCounters {
+    running: AtomicUsize,
+    completed: AtomicU64,
+    failed: AtomicU64,
+    timed_out: AtomicU64,
+    waits: AtomicU64,
+    wait_nanos: AtomicU64,
+    runs: AtomicU64,
+    run_nanos: AtomicU64,
+}
+
+impl StatsCounters {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录任务开始执行，`wait` 为首次执行前的排队时间（重试时为 None）
+    pub(crate) fn record_started(&self, wait: Option<Duration>) {
+        self.running.fetch_add(1, Ordering::Relaxed);
+        if let Some(wait) = wait {
+            self.waits.fetch_add(1, Ordering::Relaxed);
+            self.wait_nanos
+                .fetch_add(saturating_nanos(wait), Ordering::Relaxed);
+        }
+    }
+
+    /// 记录一次执行结束
+    pub(crate) fn record_run(&self, duration: Duration) {
+        self.running.fetch_sub(1, Ordering::Relaxed);
+        self.runs.fetch_add(1, Ordering::Relaxed);
+        self.run_nanos
+            .fetch_add(saturating_nanos(duration), Ordering::Relaxed);
+    }
+
+    /// 按结束状态记录 `count` 个任务的最终结果，取消、跳过和过期不计入
+    pub(crate) fn record_finished(&self, status: &FinishStatus, count: u64) {
+        let counter = match status {
+            FinishStatus::Success => &self.completed,
+            FinishStatus::TimedOut => &self.timed_out,
+            FinishStatus::Failed { .. } | FinishStatus::Error(_) => &self.failed,
+            FinishStatus::Cancelled | FinishStatus::Skipped | FinishStatus::Expired => return,
+        };
+        counter.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self, queued: usize) -> PoolStats {
+        PoolStats {
+            queued,
+            running: self.running.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            timed_out: self.timed_out.load(Ordering::Relaxed),
+            avg_wait_time: average(&self.wait_nanos, &self.waits),
+            avg_run_time: average(&self.run_nanos, &self.runs),
+        }
+    }
+}
+
+fn saturating_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+fn average(total_nanos: &AtomicU64, count: &AtomicU64) -> Duration {
+    match count.load(Ordering::Relaxed) {
+        0 => Duration::ZERO,
+        count => Duration::from_nanos(total_nanos.load(Ordering::Relaxed) / count),
+    }
+}
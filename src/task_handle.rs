@@ -1,9 +1,12 @@
 use std::process::Output;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::mpsc::{Receiver, Sender, channel};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-use crate::error::ExecuteError;
+use crate::config::CommandConfig;
+use crate::error::{ErrorContext, ExecuteError, TaskError};
+use crate::report::ExecutionReport;
 
 /// 任务结果
 pub type TaskResult = Result<Output, ExecuteError>;
@@ -63,6 +66,8 @@ pub enum TaskState {
     Completed,
     /// 任务已被取消
     Cancelled,
+    /// 任务被跳过（同键的单例任务正在运行）
+    Skipped,
 }
 
 /// 任务句柄
@@ -93,6 +98,14 @@ pub struct TaskHandle {
     state: Arc<Mutex<TaskState>>,
     /// 结果接收器
     receiver: Arc<Mutex<Receiver<TaskResult>>>,
+    /// 执行报告元数据（输出通过结果通道单独传递）
+    report: Arc<Mutex<Option<ExecutionReport>>>,
+    /// 提交的命令的错误上下文（由命令池在提交时记录）
+    context: Arc<Mutex<Option<ErrorContext>>>,
+    /// 已开始执行的次数
+    attempts: Arc<AtomicU32>,
+    /// 创建（即提交）时间
+    submitted_at: Instant,
 }
 
 impl TaskHandle {
@@ -116,6 +129,10 @@ impl TaskHandle {
                 cancel_token,
                 state,
                 receiver: Arc::new(Mutex::new(receiver)),
+                report: Arc::new(Mutex::new(None)),
+                context: Arc::new(Mutex::new(None)),
+                attempts: Arc::new(AtomicU32::new(0)),
+                submitted_at: Instant::now(),
             },
             sender,
         )
@@ -145,6 +162,10 @@ impl TaskHandle {
                 cancel_token,
                 state,
                 receiver: Arc::new(Mutex::new(receiver)),
+                report: Arc::new(Mutex::new(None)),
+                context: Arc::new(Mutex::new(None)),
+                attempts: Arc::new(AtomicU32::new(0)),
+                submitted_at: Instant::now(),
             },
             sender,
         )
@@ -227,6 +248,9 @@ impl TaskHandle {
                 Ok(())
             }
             TaskState::Running { pid: Some(pid) } => {
+                // 先设置取消标志，保证进程被终止后执行器将结果视为取消
+                self.cancel_token.cancel();
+
                 // 任务正在执行，终止进程
                 #[cfg(feature = "logging")]
                 tracing::warn!(
@@ -285,8 +309,6 @@ impl TaskHandle {
                     );
                 }
 
-                // 设置取消标志和状态
-                self.cancel_token.cancel();
                 *state = TaskState::Cancelled;
 
                 #[cfg(feature = "logging")]
@@ -305,8 +327,8 @@ impl TaskHandle {
 
                 Ok(())
             }
-            TaskState::Completed => {
-                // 任务已完成，无法取消
+            TaskState::Completed | TaskState::Skipped => {
+                // 任务已完成或已跳过，无法取消
                 Err(CancelError::AlreadyCompleted)
             }
             TaskState::Cancelled => {
@@ -328,6 +350,106 @@ impl TaskHandle {
             .map_err(|_| ExecuteError::Io(std::io::Error::other("failed to receive task result")))?
     }
 
+    /// 等待并获取任务的执行报告（阻塞）
+    ///
+    /// 与 `wait` 相同地消费结果，额外附带执行过程中的元数据。
+    /// 如果执行后端不提供元数据，报告中只包含输出。
+    ///
+    /// # 返回
+    /// - `Ok(ExecutionReport)`：任务成功执行
+    /// - `Err(ExecuteError)`：任务执行失败或结果已被获取
+    pub fn wait_report(&self) -> Result<ExecutionReport, ExecuteError> {
+        let output = self.wait()?;
+        let report = self.report.lock().unwrap().take();
+        Ok(match report {
+            Some(mut report) => {
+                report.output = output;
+                report
+            }
+            None => ExecutionReport::new(output),
+        })
+    }
+
+    /// 等待并获取任务结果，失败时附带任务上下文（阻塞）
+    ///
+    /// 与 `wait` 相同地消费结果；错误中包含任务 ID、命令摘要和工作目录，
+    /// 时间戳为取得错误的时间。未经命令池提交的句柄只包含任务 ID。
+    pub fn wait_with_context(&self) -> Result<Output, TaskError> {
+        self.wait()
+            .map_err(|e| e.with_context(self.error_context()))
+    }
+
+    /// 任务的错误上下文
+    pub fn error_context(&self) -> ErrorContext {
+        let mut context = self
+            .context
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| ErrorContext::new(self.task_id, "", std::path::Path::new(".")));
+        context.timestamp = std::time::SystemTime::now();
+        context
+    }
+
+    /// 记录提交的命令，用于构造错误上下文
+    pub(crate) fn set_command(&self, config: &CommandConfig) {
+        *self.context.lock().unwrap() = Some(ErrorContext::from_config(self.task_id, config));
+    }
+
+    /// 获取任务已开始执行的次数
+    ///
+    /// 尚未执行的任务为 0；命令池按任务的 [`RetryPolicy`](crate::RetryPolicy) 或
+    /// [`PoolRetryPolicy`](crate::PoolRetryPolicy) 自动重试时，每次重新执行加一。
+    pub fn attempts(&self) -> u32 {
+        self.attempts.load(Ordering::SeqCst)
+    }
+
+    /// 记录开始一次新的执行，返回这是第几次执行（从 1 开始）
+    pub(crate) fn begin_attempt(&self) -> u32 {
+        self.attempts.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// 任务句柄创建（即任务提交）的时间
+    pub(crate) fn submitted_at(&self) -> Instant {
+        self.submitted_at
+    }
+
+    /// 将执行失败、等待重试的任务放回排队状态，任务已被取消时返回 false
+    pub(crate) fn requeue(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if self.cancel_token.is_cancelled() || !matches!(*state, TaskState::Running { .. }) {
+            return false;
+        }
+        *state = TaskState::Queued;
+        true
+    }
+
+    /// 将执行中的任务标记为已完成，已取消或已跳过的任务保持原状态
+    pub(crate) fn mark_completed(&self) {
+        let mut state = self.state.lock().unwrap();
+        if matches!(*state, TaskState::Running { .. }) {
+            *state = TaskState::Completed;
+        }
+    }
+
+    /// 记录正在执行的子进程 PID，任务不处于执行状态时忽略
+    pub(crate) fn set_running_pid(&self, pid: u32) {
+        let mut state = self.state.lock().unwrap();
+        if matches!(*state, TaskState::Running { .. }) {
+            *state = TaskState::Running { pid: Some(pid) };
+        }
+    }
+
+    /// 保存执行报告元数据，需在发送结果之前调用
+    pub(crate) fn set_report(&self, report: ExecutionReport) {
+        *self.report.lock().unwrap() = Some(report);
+    }
+
+    /// 复制已保存的执行报告元数据
+    pub(crate) fn report_snapshot(&self) -> Option<ExecutionReport> {
+        self.report.lock().unwrap().clone()
+    }
+
     /// 尝试获取任务结果（非阻塞）
     ///
     /// # 返回
@@ -367,6 +489,10 @@ impl Clone for TaskHandle {
             cancel_token: self.cancel_token.clone(),
             state: Arc::clone(&self.state),
             receiver: Arc::clone(&self.receiver),
+            report: Arc::clone(&self.report),
+            context: Arc::clone(&self.context),
+            attempts: Arc::clone(&self.attempts),
+            submitted_at: self.submitted_at,
         }
     }
 }
@@ -0,0 +1,121 @@
+//! 命令池的优先级任务队列
+//!
+//! 基于二叉堆，按优先级从高到低出队；同优先级按任务 ID（即提交顺序）先进先出。
+//! 出队时可以跳过不满足条件的任务（未到期、亲和键不匹配等），被跳过的任务保持原有位置。
+//!
+//! 所有工作线程共享同一个队列，而不是各自持有本地队列再互相窃取：优先级、串行键、
+//! 并发键、亲和键、计划执行时间和速率限制都要求出队时能看到全部等待中的任务，
+//! 分散到多个本地队列后无法保证这些顺序与限制。锁内只做堆操作，持锁时间很短。
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::pool::TaskItem;
+
+/// 堆中的任务，按（优先级，逆序任务 ID）排序
+struct Queued(TaskItem);
+
+impl Queued {
+    fn key(&self) -> (u8, std::cmp::Reverse<u64>) {
+        (
+            self.0.config.priority(),
+            std::cmp::Reverse(self.0.handle.id()),
+        )
+    }
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Queued {}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// 优先级任务队列
+#[derive(Default)]
+pub(crate) struct TaskQueue {
+    heap: BinaryHeap<Queued>,
+    /// 通过 `try_reserve` 预留、尚未使用的空位数
+    reserved: usize,
+}
+
+impl TaskQueue {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加入任务
+    ///
+    /// 归还的任务保留原任务 ID，因此会回到它出队前的位置。
+    pub(crate) fn push(&mut self, item: TaskItem) {
+        self.heap.push(Queued(item));
+    }
+
+    /// 取出满足条件的、排序最靠前的任务
+    pub(crate) fn pop_first(
+        &mut self,
+        mut eligible: impl FnMut(&TaskItem) -> bool,
+    ) -> Option<TaskItem> {
+        let mut skipped = Vec::new();
+        let mut found = None;
+        while let Some(Queued(item)) = self.heap.pop() {
+            if eligible(&item) {
+                found = Some(item);
+                break;
+            }
+            skipped.push(Queued(item));
+        }
+        self.heap.extend(skipped);
+        found
+    }
+
+    /// 遍历队列中的任务（不保证顺序）
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &TaskItem> {
+        self.heap.iter().map(|queued| &queued.0)
+    }
+
+    /// 按出队顺序取出所有任务
+    pub(crate) fn drain(&mut self) -> Vec<TaskItem> {
+        let mut items: Vec<TaskItem> = std::mem::take(&mut self.heap)
+            .into_sorted_vec()
+            .into_iter()
+            .map(|queued| queued.0)
+            .collect();
+        items.reverse();
+        items
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// 已占用的空位数（队列中的任务加上预留的空位）
+    pub(crate) fn occupied(&self) -> usize {
+        self.heap.len() + self.reserved
+    }
+
+    pub(crate) fn reserve(&mut self, count: usize) {
+        self.reserved += count;
+    }
+
+    pub(crate) fn release(&mut self, count: usize) {
+        self.reserved -= count;
+    }
+}
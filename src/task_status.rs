@@ -13,6 +13,15 @@ pub enum TaskStatus {
     Completed,
     /// 失败
     Failed,
+    /// 已取消（未执行或执行中被取消，以及被跳过的单例任务）
+    Cancelled,
+}
+
+impl TaskStatus {
+    /// 是否已结束
+    pub fn is_finished(self) -> bool {
+        !matches!(self, TaskStatus::Pending | TaskStatus::Running)
+    }
 }
 
 impl std::fmt::Display for TaskStatus {
@@ -22,6 +31,7 @@ impl std::fmt::Display for TaskStatus {
             TaskStatus::Running => write!(f, "running"),
             TaskStatus::Completed => write!(f, "completed"),
             TaskStatus::Failed => write!(f, "failed"),
+            TaskStatus::Cancelled => write!(f, "cancelled"),
         }
     }
 }
@@ -43,6 +53,11 @@ impl TaskIdGenerator {
     pub fn next_id(&self) -> u64 {
         self.counter.fetch_add(1, Ordering::SeqCst)
     }
+
+    /// 查看下一个将生成的任务 ID（不消耗）
+    pub fn peek(&self) -> u64 {
+        self.counter.load(Ordering::SeqCst)
+    }
 }
 
 impl Default for TaskIdGenerator {
@@ -100,6 +115,14 @@ impl TaskStatusTracker {
         statuses.values().filter(|&&s| s == status).count()
     }
 
+    /// 移除已结束任务的状态，返回移除的数量
+    pub fn remove_finished(&self) -> usize {
+        let mut statuses = self.statuses.lock().unwrap();
+        let before = statuses.len();
+        statuses.retain(|_, status| !status.is_finished());
+        before - statuses.len()
+    }
+
     /// 清空所有任务状态
     pub fn clear(&self) {
         let mut statuses = self.statuses.lock().unwrap();
@@ -173,5 +196,21 @@ mod tests {
         assert_eq!(format!("{}", TaskStatus::Running), "running");
         assert_eq!(format!("{}", TaskStatus::Completed), "completed");
         assert_eq!(format!("{}", TaskStatus::Failed), "failed");
+        assert_eq!(format!("{}", TaskStatus::Cancelled), "cancelled");
+    }
+
+    #[test]
+    fn task_status_tracker_removes_finished() {
+        let tracker = TaskStatusTracker::new();
+
+        tracker.register(1);
+        tracker.register(2);
+        tracker.register(3);
+        tracker.update(2, TaskStatus::Failed);
+        tracker.update(3, TaskStatus::Cancelled);
+
+        assert_eq!(tracker.remove_finished(), 2);
+        assert_eq!(tracker.get(1), Some(TaskStatus::Pending));
+        assert_eq!(tracker.get(2), None);
     }
 }
@@ -0,0 +1,455 @@
+//! 测试支持
+//!
+//! 提供不启动真实进程的执行器和后端替身，便于使用本库的应用对命令池的集成逻辑做单元测试。
+
+use std::collections::{HashMap, VecDeque};
+use std::ffi::OsString;
+use std::process::{ExitStatus, Output};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::backend::ExecutionBackend;
+use crate::config::CommandConfig;
+use crate::error::ExecuteError;
+use crate::executor::CommandExecutor;
+
+/// 模拟的执行结果
+///
+/// 可以附加延迟：延迟超过命令配置的超时时，执行器等待到超时后返回
+/// [`ExecuteError::Timeout`]，与真实执行的超时行为一致。
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    kind: ResponseKind,
+    delay: Duration,
+}
+
+#[derive(Debug, Clone)]
+enum ResponseKind {
+    Output {
+        code: i32,
+        stdout: Vec<u8>,
+        stderr: Vec<u8>,
+    },
+    Error(String),
+    Timeout(Option<Duration>),
+    Panic(String),
+}
+
+impl MockResponse {
+    /// 以零退出码结束并输出 `stdout`
+    pub fn success(stdout: impl Into<Vec<u8>>) -> Self {
+        Self::from_kind(ResponseKind::Output {
+            code: 0,
+            stdout: stdout.into(),
+            stderr: Vec::new(),
+        })
+    }
+
+    /// 以指定退出码结束，无输出
+    pub fn exit(code: i32) -> Self {
+        Self::from_kind(ResponseKind::Output {
+            code,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        })
+    }
+
+    /// 返回 IO 错误（如程序不存在）
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::from_kind(ResponseKind::Error(message.into()))
+    }
+
+    /// 返回超时错误
+    ///
+    /// 错误中的时长取命令配置的超时，未配置时为零。
+    pub fn timeout() -> Self {
+        Self::from_kind(ResponseKind::Timeout(None))
+    }
+
+    /// 返回指定时长的超时错误
+    pub fn timeout_after(timeout: Duration) -> Self {
+        Self::from_kind(ResponseKind::Timeout(Some(timeout)))
+    }
+
+    /// 执行时 panic，模拟执行器自身的故障
+    pub fn panic(message: impl Into<String>) -> Self {
+        Self::from_kind(ResponseKind::Panic(message.into()))
+    }
+
+    /// 设置 stdout（仅对正常结束的结果有效）
+    pub fn with_stdout(mut self, data: impl Into<Vec<u8>>) -> Self {
+        if let ResponseKind::Output { stdout, .. } = &mut self.kind {
+            *stdout = data.into();
+        }
+        self
+    }
+
+    /// 设置 stderr（仅对正常结束的结果有效）
+    pub fn with_stderr(mut self, data: impl Into<Vec<u8>>) -> Self {
+        if let ResponseKind::Output { stderr, .. } = &mut self.kind {
+            *stderr = data.into();
+        }
+        self
+    }
+
+    /// 返回结果前等待的时长
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn from_kind(kind: ResponseKind) -> Self {
+        Self {
+            kind,
+            delay: Duration::ZERO,
+        }
+    }
+
+    fn respond(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        if let Some(timeout) = config.timeout
+            && self.delay > timeout
+        {
+            std::thread::sleep(timeout);
+            return Err(ExecuteError::Timeout(timeout));
+        }
+        std::thread::sleep(self.delay);
+
+        match &self.kind {
+            ResponseKind::Output {
+                code,
+                stdout,
+                stderr,
+            } => Ok(Output {
+                status: exit_status(*code),
+                stdout: stdout.clone(),
+                stderr: stderr.clone(),
+            }),
+            ResponseKind::Error(message) => {
+                Err(ExecuteError::Io(std::io::Error::other(message.clone())))
+            }
+            ResponseKind::Timeout(timeout) => Err(ExecuteError::Timeout(
+                timeout.or(config.timeout).unwrap_or(Duration::ZERO),
+            )),
+            ResponseKind::Panic(message) => panic!("{}", message),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+/// 匹配规则：程序名和可选的参数模式
+#[derive(Debug)]
+struct Rule {
+    program: String,
+    /// 参数模式，`*` 匹配任意单个参数；为 None 时匹配任意参数
+    args: Option<Vec<String>>,
+    response: MockResponse,
+}
+
+impl Rule {
+    fn matches(&self, config: &CommandConfig) -> bool {
+        if config.program != *self.program {
+            return false;
+        }
+        match &self.args {
+            None => true,
+            Some(pattern) => {
+                pattern.len() == config.args.len()
+                    && pattern
+                        .iter()
+                        .zip(&config.args)
+                        .all(|(p, arg)| p == "*" || *arg == **p)
+            }
+        }
+    }
+}
+
+/// 预设结果和调用记录，由 [`MockExecutor`] 和 [`MockBackend`] 共用
+#[derive(Debug)]
+struct Script {
+    rules: Vec<Rule>,
+    default: MockResponse,
+    /// 按程序排队的一次性结果，优先于规则
+    queued: Mutex<HashMap<OsString, VecDeque<MockResponse>>>,
+    calls: Mutex<Vec<CommandConfig>>,
+}
+
+impl Script {
+    fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            default: MockResponse::success(Vec::new()),
+            queued: Mutex::new(HashMap::new()),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn add_rule(&mut self, program: String, args: Option<Vec<String>>, response: MockResponse) {
+        self.rules.push(Rule {
+            program,
+            args,
+            response,
+        });
+    }
+
+    fn enqueue(&self, program: String, response: MockResponse) {
+        self.queued
+            .lock()
+            .unwrap()
+            .entry(program.into())
+            .or_default()
+            .push_back(response);
+    }
+
+    fn run(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        self.calls.lock().unwrap().push(config.clone());
+        let queued = self
+            .queued
+            .lock()
+            .unwrap()
+            .get_mut(&config.program)
+            .and_then(VecDeque::pop_front);
+        let response = queued.unwrap_or_else(|| {
+            self.rules
+                .iter()
+                .find(|rule| rule.matches(config))
+                .map_or(&self.default, |rule| &rule.response)
+                .clone()
+        });
+        response.respond(config)
+    }
+
+    fn calls(&self) -> Vec<CommandConfig> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn call_count(&self, program: &str) -> usize {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.program == program)
+            .count()
+    }
+
+    #[track_caller]
+    fn assert_executed(&self, program: &str, times: usize) {
+        let count = self.call_count(program);
+        assert_eq!(
+            count, times,
+            "expected {:?} to be executed {} time(s), but it was executed {} time(s)",
+            program, times, count
+        );
+    }
+}
+
+/// 按程序和参数模式返回预设结果的执行器
+///
+/// 规则按添加顺序匹配，第一个匹配的规则生效；没有规则匹配时返回默认结果
+/// （默认为无输出的成功）。所有调用都会被记录，可在测试中断言。
+///
+/// # 示例
+///
+/// ```
+/// use execute::testing::{MockExecutor, MockResponse};
+/// use execute::{CommandConfig, CommandExecutor, Duration};
+///
+/// let mock = MockExecutor::new()
+///     .on_args("git", &["push", "*"], MockResponse::error("permission denied"))
+///     .on("git", MockResponse::success("ok\n").with_delay(Duration::from_millis(5)));
+///
+/// let output = mock
+///     .execute(&CommandConfig::new("git", vec!["status".to_string()]))
+///     .unwrap();
+/// assert_eq!(output.stdout, b"ok\n");
+///
+/// let push = CommandConfig::new("git", vec!["push".to_string(), "origin".to_string()]);
+/// assert!(mock.execute(&push).is_err());
+/// assert_eq!(mock.call_count("git"), 2);
+/// ```
+#[derive(Debug)]
+pub struct MockExecutor {
+    script: Script,
+}
+
+impl MockExecutor {
+    /// 创建没有规则的执行器
+    pub fn new() -> Self {
+        Self {
+            script: Script::new(),
+        }
+    }
+
+    /// 程序名匹配时（不论参数）返回 `response`
+    pub fn on(mut self, program: impl Into<String>, response: MockResponse) -> Self {
+        self.script.add_rule(program.into(), None, response);
+        self
+    }
+
+    /// 程序名和参数都匹配时返回 `response`
+    ///
+    /// 参数个数必须一致，`*` 匹配任意单个参数。
+    pub fn on_args(
+        mut self,
+        program: impl Into<String>,
+        args: &[&str],
+        response: MockResponse,
+    ) -> Self {
+        let args = args.iter().map(|a| a.to_string()).collect();
+        self.script.add_rule(program.into(), Some(args), response);
+        self
+    }
+
+    /// 设置没有规则匹配时的结果
+    pub fn with_default(mut self, response: MockResponse) -> Self {
+        self.script.default = response;
+        self
+    }
+
+    /// 已执行的命令（按调用顺序）
+    pub fn calls(&self) -> Vec<CommandConfig> {
+        self.script.calls()
+    }
+
+    /// 指定程序被执行的次数
+    pub fn call_count(&self, program: &str) -> usize {
+        self.script.call_count(program)
+    }
+
+    /// 断言指定程序恰好被执行了 `times` 次
+    #[track_caller]
+    pub fn assert_executed(&self, program: &str, times: usize) {
+        self.script.assert_executed(program, times);
+    }
+}
+
+impl Default for MockExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandExecutor for MockExecutor {
+    fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        self.script.run(config)
+    }
+}
+
+/// 可编程的执行后端替身
+///
+/// 与 [`MockExecutor`] 使用相同的匹配规则，额外支持按程序排队一次性结果：
+/// 排队的结果按先进先出的顺序被消费，优先于规则，用完后回到规则匹配。
+/// 通过 [`CommandPool::with_backend`](crate::CommandPool::with_backend) 接入命令池后，
+/// 任务经过与真实后端相同的池内调度流程（任务默认值、改写器、单例和合并等）。
+///
+/// # 示例
+///
+/// ```
+/// use std::sync::Arc;
+/// use execute::testing::{MockBackend, MockResponse};
+/// use execute::{CommandConfig, CommandPool, ExecutionConfig};
+///
+/// let backend = Arc::new(MockBackend::new().on("rsync", MockResponse::success("synced")));
+/// backend.enqueue("rsync", MockResponse::exit(23));
+///
+/// let pool = CommandPool::with_backend(ExecutionConfig::default(), backend.clone());
+/// pool.start_executor();
+///
+/// let first = pool.push_task(CommandConfig::new("rsync", vec![])).unwrap();
+/// assert_eq!(first.wait().unwrap().status.code(), Some(23));
+/// let second = pool.push_task(CommandConfig::new("rsync", vec![])).unwrap();
+/// assert!(second.wait().unwrap().status.success());
+///
+/// backend.assert_executed("rsync", 2);
+/// pool.shutdown().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct MockBackend {
+    script: Script,
+}
+
+impl MockBackend {
+    /// 创建没有规则的后端
+    pub fn new() -> Self {
+        Self {
+            script: Script::new(),
+        }
+    }
+
+    /// 程序名匹配时（不论参数）返回 `response`
+    pub fn on(mut self, program: impl Into<String>, response: MockResponse) -> Self {
+        self.script.add_rule(program.into(), None, response);
+        self
+    }
+
+    /// 程序名和参数都匹配时返回 `response`
+    ///
+    /// 参数个数必须一致，`*` 匹配任意单个参数。
+    pub fn on_args(
+        mut self,
+        program: impl Into<String>,
+        args: &[&str],
+        response: MockResponse,
+    ) -> Self {
+        let args = args.iter().map(|a| a.to_string()).collect();
+        self.script.add_rule(program.into(), Some(args), response);
+        self
+    }
+
+    /// 设置没有排队结果且没有规则匹配时的结果
+    pub fn with_default(mut self, response: MockResponse) -> Self {
+        self.script.default = response;
+        self
+    }
+
+    /// 为程序排队一个一次性结果
+    ///
+    /// 可在后端接入命令池后调用。
+    pub fn enqueue(&self, program: impl Into<String>, response: MockResponse) {
+        self.script.enqueue(program.into(), response);
+    }
+
+    /// 已执行的命令（按调用顺序）
+    pub fn calls(&self) -> Vec<CommandConfig> {
+        self.script.calls()
+    }
+
+    /// 指定程序被执行的次数
+    pub fn call_count(&self, program: &str) -> usize {
+        self.script.call_count(program)
+    }
+
+    /// 断言指定程序恰好被执行了 `times` 次
+    #[track_caller]
+    pub fn assert_executed(&self, program: &str, times: usize) {
+        self.script.assert_executed(program, times);
+    }
+
+    /// 断言指定程序没有被执行
+    #[track_caller]
+    pub fn assert_not_executed(&self, program: &str) {
+        self.script.assert_executed(program, 0);
+    }
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExecutionBackend for MockBackend {
+    fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        self.script.run(config)
+    }
+}
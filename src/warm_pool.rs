@@ -15,6 +15,10 @@ use std::time::{Duration, Instant};
 
 use crate::config::CommandConfig;
 use crate::error::ExecuteError;
+use crate::executor::{
+    apply_cpu_affinity, apply_force_color, apply_nice, apply_process_group, apply_rlimits,
+    apply_umask, apply_user,
+};
 
 /// 预热的进程模板
 #[allow(dead_code)]
@@ -62,8 +66,17 @@ impl ProcessTemplate {
         if let Some(env_config) = self.config.env_config() {
             env_config.apply_to_command(&mut cmd);
         }
-
-        let child = cmd.spawn().map_err(ExecuteError::Io)?;
+        apply_force_color(&mut cmd, &self.config);
+        apply_nice(&mut cmd, &self.config);
+        apply_cpu_affinity(&mut cmd, &self.config);
+        apply_rlimits(&mut cmd, &self.config);
+        apply_user(&mut cmd, &self.config);
+        apply_process_group(&mut cmd, &self.config);
+        apply_umask(&mut cmd, &self.config);
+
+        let child = cmd.spawn().map_err(|e| {
+            ExecuteError::spawn_failed(e, &self.config.program, self.config.working_dir.as_deref())
+        })?;
         Ok(child)
     }
 
@@ -210,8 +223,17 @@ impl WarmProcessPool {
         if let Some(env_config) = config.env_config() {
             env_config.apply_to_command(&mut cmd);
         }
-
-        cmd.spawn().map_err(ExecuteError::Io)
+        apply_force_color(&mut cmd, config);
+        apply_nice(&mut cmd, config);
+        apply_cpu_affinity(&mut cmd, config);
+        apply_rlimits(&mut cmd, config);
+        apply_user(&mut cmd, config);
+        apply_process_group(&mut cmd, config);
+        apply_umask(&mut cmd, config);
+
+        cmd.spawn().map_err(|e| {
+            ExecuteError::spawn_failed(e, &config.program, config.working_dir.as_deref())
+        })
     }
 
     /// 归还进程到池中
@@ -0,0 +1,175 @@
+//! 卡住工作线程看门狗
+//!
+//! 工作线程开始执行任务时登记任务 ID、开始时间和允许的最长执行时间，
+//! 看门狗线程定期找出超过该时间仍未结束的任务。替补工作线程沿用被替换线程的序号，
+//! 被替换的线程在当前任务结束后退出。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 正在执行的任务
+struct Activity {
+    task_id: u64,
+    started: Instant,
+    threshold: Duration,
+    reported: bool,
+}
+
+/// 被判定为卡住的工作线程
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StuckWorker {
+    pub(crate) worker: usize,
+    pub(crate) task_id: u64,
+    pub(crate) elapsed: Duration,
+}
+
+/// 各工作线程当前执行的任务（与命令池的克隆共享）
+///
+/// 同一序号的工作线程按代区分：替补线程的代号更大，旧线程据此判断自己已被替换。
+#[derive(Default)]
+pub(crate) struct WorkerActivity {
+    entries: Mutex<HashMap<(usize, u64), Activity>>,
+    generations: Mutex<HashMap<usize, u64>>,
+}
+
+impl WorkerActivity {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记工作线程开始执行任务，守卫丢弃时注销
+    pub(crate) fn begin(
+        self: &Arc<Self>,
+        worker: usize,
+        generation: u64,
+        task_id: u64,
+        threshold: Duration,
+    ) -> ActivityGuard {
+        self.entries.lock().unwrap().insert(
+            (worker, generation),
+            Activity {
+                task_id,
+                started: Instant::now(),
+                threshold,
+                reported: false,
+            },
+        );
+        ActivityGuard {
+            activity: Arc::clone(self),
+            key: (worker, generation),
+        }
+    }
+
+    /// 找出新近卡住的工作线程
+    ///
+    /// 每个卡住的任务只报告一次。
+    pub(crate) fn find_stuck(&self) -> Vec<StuckWorker> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut stuck: Vec<StuckWorker> = entries
+            .iter_mut()
+            .filter(|(_, activity)| !activity.reported)
+            .filter_map(|(&(worker, _), activity)| {
+                let elapsed = activity.started.elapsed();
+                (elapsed > activity.threshold).then(|| {
+                    activity.reported = true;
+                    StuckWorker {
+                        worker,
+                        task_id: activity.task_id,
+                        elapsed,
+                    }
+                })
+            })
+            .collect();
+        stuck.sort_by_key(|worker| worker.worker);
+        stuck
+    }
+
+    /// 为第 `worker` 个工作线程分配替补的代号
+    pub(crate) fn replace(&self, worker: usize) -> u64 {
+        let mut generations = self.generations.lock().unwrap();
+        let generation = generations.entry(worker).or_insert(0);
+        *generation += 1;
+        *generation
+    }
+
+    /// 第 `worker` 个工作线程当前的代号
+    pub(crate) fn generation(&self, worker: usize) -> u64 {
+        self.generations
+            .lock()
+            .unwrap()
+            .get(&worker)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// 第 `worker` 个工作线程被替换的旧代是否仍在执行任务
+    pub(crate) fn has_retired_task(&self, worker: usize) -> bool {
+        let current = self.generation(worker);
+        self.entries
+            .lock()
+            .unwrap()
+            .keys()
+            .any(|&(index, generation)| index == worker && generation != current)
+    }
+
+    /// 第 `worker` 个工作线程的这一代是否已被替换
+    pub(crate) fn is_retired(&self, worker: usize, generation: u64) -> bool {
+        self.generations
+            .lock()
+            .unwrap()
+            .get(&worker)
+            .is_some_and(|current| *current != generation)
+    }
+}
+
+/// 任务执行登记的守卫，丢弃时注销
+pub(crate) struct ActivityGuard {
+    activity: Arc<WorkerActivity>,
+    key: (usize, u64),
+}
+
+impl Drop for ActivityGuard {
+    fn drop(&mut self) {
+        if let Ok(mut entries) = self.activity.entries.lock() {
+            entries.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_stuck_reports_once() {
+        let activity = Arc::new(WorkerActivity::new());
+        let _slow = activity.begin(0, 0, 7, Duration::ZERO);
+        let _fast = activity.begin(1, 0, 8, Duration::from_secs(60));
+        std::thread::sleep(Duration::from_millis(5));
+
+        let stuck = activity.find_stuck();
+        assert_eq!(stuck.len(), 1);
+        assert_eq!((stuck[0].worker, stuck[0].task_id), (0, 7));
+        assert!(activity.find_stuck().is_empty());
+    }
+
+    #[test]
+    fn test_guard_unregisters_task() {
+        let activity = Arc::new(WorkerActivity::new());
+        drop(activity.begin(0, 0, 1, Duration::ZERO));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(activity.find_stuck().is_empty());
+    }
+
+    #[test]
+    fn test_replace_retires_previous_generation() {
+        let activity = WorkerActivity::new();
+        assert!(!activity.is_retired(2, 0));
+        let generation = activity.replace(2);
+        assert_eq!(generation, 1);
+        assert!(activity.is_retired(2, 0));
+        assert!(!activity.is_retired(2, generation));
+        assert!(!activity.is_retired(3, 0));
+    }
+}
@@ -0,0 +1,116 @@
+//! 工作线程按需启动
+//!
+//! 设置了最少空闲工作线程数时，执行器启动时只预先启动这么多工作线程，
+//! 之后每当空闲的工作线程少于该数量就再启动一个，直到达到配置的工作线程数。
+//! 亲和键对应的工作线程尚未启动时，由等待中的工作线程代为启动。
+//! 设置了空闲超时时，多出的空闲工作线程在空闲超过该时长后退出，需要时再按需启动。
+
+use std::sync::{Arc, Mutex};
+
+/// 各序号工作线程的存活情况和忙碌的工作线程数（与命令池的克隆共享）
+#[derive(Default)]
+pub(crate) struct WorkerSlots {
+    /// 每个序号存活的工作线程数（看门狗替补期间可能大于 1）
+    live: Vec<usize>,
+    /// 每个序号已决定因空闲退出、但线程尚未结束的工作线程数
+    retiring: Vec<usize>,
+    /// 每个序号最近一个工作线程是否因 panic 退出且尚未重新启动
+    crashed: Vec<bool>,
+    /// 正在处理任务的工作线程数
+    busy: usize,
+}
+
+impl WorkerSlots {
+    pub(crate) fn new(workers: usize) -> Self {
+        Self {
+            live: vec![0; workers],
+            retiring: vec![0; workers],
+            crashed: vec![false; workers],
+            busy: 0,
+        }
+    }
+
+    /// 存活的工作线程数
+    pub(crate) fn live(&self) -> usize {
+        self.live.iter().sum()
+    }
+
+    /// 空闲的工作线程数
+    pub(crate) fn idle(&self) -> usize {
+        self.live().saturating_sub(self.busy)
+    }
+
+    /// 因 panic 退出且尚未重新启动的工作线程数
+    #[cfg(feature = "health")]
+    pub(crate) fn crashed(&self) -> usize {
+        self.crashed.iter().filter(|crashed| **crashed).count()
+    }
+
+    /// 第 `index` 个工作线程是否存活
+    pub(crate) fn is_live(&self, index: usize) -> bool {
+        self.live.get(index).is_some_and(|count| *count > 0)
+    }
+
+    /// 登记即将启动的第 `index` 个工作线程
+    pub(crate) fn claim(&mut self, index: usize) {
+        self.live[index] += 1;
+        self.crashed[index] = false;
+    }
+
+    /// 登记一个尚未启动的最小序号，全部已启动时返回 None
+    pub(crate) fn claim_vacant(&mut self) -> Option<usize> {
+        let index = self.live.iter().position(|count| *count == 0)?;
+        self.claim(index);
+        Some(index)
+    }
+
+    /// 空闲的工作线程多于 `min_idle` 时注销第 `index` 个工作线程，返回是否应退出
+    pub(crate) fn retire(&mut self, index: usize, min_idle: usize) -> bool {
+        if self.idle() <= min_idle {
+            return false;
+        }
+        self.live[index] -= 1;
+        self.retiring[index] += 1;
+        true
+    }
+
+    /// 登记一个工作线程开始处理任务
+    pub(crate) fn begin_task(&mut self) {
+        self.busy += 1;
+    }
+}
+
+/// 工作线程的登记，线程退出时丢弃以注销
+pub(crate) struct SlotGuard {
+    pub(crate) slots: Arc<Mutex<WorkerSlots>>,
+    pub(crate) index: usize,
+}
+
+impl Drop for SlotGuard {
+    fn drop(&mut self) {
+        if let Ok(mut slots) = self.slots.lock() {
+            // 因空闲退出的线程已在决定退出时注销
+            if slots.retiring[self.index] > 0 {
+                slots.retiring[self.index] -= 1;
+            } else {
+                slots.live[self.index] -= 1;
+            }
+            if std::thread::panicking() {
+                slots.crashed[self.index] = true;
+            }
+        }
+    }
+}
+
+/// 工作线程正在处理任务的登记，丢弃时恢复为空闲
+pub(crate) struct BusyGuard {
+    pub(crate) slots: Arc<Mutex<WorkerSlots>>,
+}
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        if let Ok(mut slots) = self.slots.lock() {
+            slots.busy -= 1;
+        }
+    }
+}
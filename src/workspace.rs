@@ -0,0 +1,316 @@
+//! 任务工作目录管理
+//!
+//! 负责托管临时工作目录的创建与清理、输入文件的写入、任务产物的收集以及跨进程文件锁。
+
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use nix::fcntl::{Flock, FlockArg};
+
+use crate::config::{ArtifactAction, ArtifactConfig, InputConfig, InputSource, TempWorkdirConfig};
+use crate::report::Artifact;
+
+/// 进程内临时目录序号，保证同一纳秒内创建的目录名也不冲突
+static TEMP_DIR_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// 托管的临时工作目录
+///
+/// 创建后由调用方在任务结束时调用 [`TempWorkdir::finish`] 决定删除或保留。
+/// 如果未调用 `finish` 就被丢弃（如 panic），目录会被删除。
+#[derive(Debug)]
+pub(crate) struct TempWorkdir {
+    path: PathBuf,
+    keep_on_failure: bool,
+    finished: bool,
+}
+
+impl TempWorkdir {
+    /// 按配置创建唯一的临时目录
+    pub(crate) fn create(config: &TempWorkdirConfig) -> io::Result<Self> {
+        let base = config.base_dir.clone().unwrap_or_else(std::env::temp_dir);
+
+        loop {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.subsec_nanos())
+                .unwrap_or(0);
+            let seq = TEMP_DIR_COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = base.join(format!(
+                "execute-{}-{}-{:08x}",
+                std::process::id(),
+                seq,
+                nanos
+            ));
+
+            match std::fs::create_dir(&path) {
+                Ok(()) => {
+                    return Ok(Self {
+                        path,
+                        keep_on_failure: config.keep_on_failure,
+                        finished: false,
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// 目录路径
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 任务结束：成功时删除目录，失败时按配置删除或保留
+    ///
+    /// 返回目录路径，供执行报告记录。
+    pub(crate) fn finish(mut self, success: bool) -> PathBuf {
+        self.finished = true;
+        if success || !self.keep_on_failure {
+            remove_dir(&self.path);
+        } else {
+            #[cfg(feature = "logging")]
+            tracing::info!(path = %self.path.display(), "Keeping temp workdir of failed task");
+        }
+        std::mem::take(&mut self.path)
+    }
+}
+
+impl Drop for TempWorkdir {
+    fn drop(&mut self) {
+        if !self.finished {
+            remove_dir(&self.path);
+        }
+    }
+}
+
+fn remove_dir(path: &Path) {
+    if let Err(_e) = std::fs::remove_dir_all(path) {
+        #[cfg(feature = "logging")]
+        tracing::warn!(path = %path.display(), error = %_e, "Failed to remove temp workdir");
+    }
+}
+
+/// 获取文件的排他咨询锁，阻塞直到成功
+///
+/// 返回的锁在丢弃时释放。
+pub(crate) fn lock_file(path: &Path) -> io::Result<Flock<File>> {
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    Flock::lock(file, FlockArg::LockExclusive).map_err(|(_, errno)| io::Error::from(errno))
+}
+
+/// 已写入工作目录的输入文件
+///
+/// 调用 [`StagedInputs::cleanup`] 删除本次写入的文件。
+#[derive(Debug, Default)]
+pub(crate) struct StagedInputs {
+    files: Vec<PathBuf>,
+}
+
+impl StagedInputs {
+    /// 删除写入的输入文件（仅删除文件，不删除创建的目录）
+    pub(crate) fn cleanup(self) {
+        for file in self.files {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+}
+
+/// 把声明的输入文件写入工作目录
+///
+/// 目标路径必须是不含 `..` 的相对路径。写入失败时已写入的文件会被删除。
+pub(crate) fn stage_inputs(root: &Path, config: &InputConfig) -> io::Result<StagedInputs> {
+    let mut staged = StagedInputs::default();
+
+    for input in &config.files {
+        let relative = input.path.as_path();
+        let escapes = relative.components().any(|c| {
+            !matches!(
+                c,
+                std::path::Component::Normal(_) | std::path::Component::CurDir
+            )
+        });
+        if escapes || input.path.as_os_str().is_empty() {
+            staged.cleanup();
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "input path must be relative to the working directory: {}",
+                    input.path.display()
+                ),
+            ));
+        }
+
+        let target = root.join(relative);
+        let result = target
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| match &input.source {
+                InputSource::Bytes(data) => std::fs::write(&target, data),
+                InputSource::File(source) => std::fs::copy(source, &target).map(|_| ()),
+            });
+        if let Err(e) = result {
+            staged.cleanup();
+            return Err(e);
+        }
+        staged.files.push(target);
+    }
+
+    Ok(staged)
+}
+
+/// 按声明的模式收集产物
+///
+/// 返回收集到的产物和未匹配到任何文件的模式。
+pub(crate) fn collect_artifacts(
+    root: &Path,
+    config: &ArtifactConfig,
+) -> io::Result<(Vec<Artifact>, Vec<String>)> {
+    let files = list_files(root)?;
+    let mut artifacts = Vec::new();
+    let mut missing = Vec::new();
+
+    for pattern in &config.patterns {
+        let mut matched = false;
+        for relative in files.iter().filter(|f| glob_match(pattern, f)) {
+            // 多个模式匹配同一文件时只收集一次
+            if artifacts
+                .iter()
+                .any(|a: &Artifact| &a.relative_path == relative)
+            {
+                matched = true;
+                continue;
+            }
+            let source = root.join(relative);
+            let size = std::fs::metadata(&source)?.len();
+            let path = match &config.destination {
+                Some(dest) => {
+                    let target = dest.join(relative);
+                    if let Some(parent) = target.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    match config.action {
+                        ArtifactAction::Copy => {
+                            std::fs::copy(&source, &target)?;
+                        }
+                        ArtifactAction::Move => move_file(&source, &target)?,
+                    }
+                    target
+                }
+                None => source,
+            };
+            artifacts.push(Artifact {
+                pattern: pattern.clone(),
+                relative_path: relative.clone(),
+                path,
+                size,
+                sha256: None,
+            });
+            matched = true;
+        }
+        if !matched {
+            missing.push(pattern.clone());
+        }
+    }
+
+    Ok((artifacts, missing))
+}
+
+/// 移动文件，跨文件系统时退化为复制后删除
+fn move_file(source: &Path, target: &Path) -> io::Result<()> {
+    if std::fs::rename(source, target).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(source, target)?;
+    std::fs::remove_file(source)
+}
+
+/// 递归列出目录下的所有普通文件，返回以 `/` 分隔的相对路径
+fn list_files(root: &Path) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+
+    while let Some(relative) = pending.pop() {
+        for entry in std::fs::read_dir(root.join(&relative))? {
+            let entry = entry?;
+            let path = relative.join(entry.file_name());
+            // 不跟随符号链接目录，避免循环
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else {
+                let parts: Vec<_> = path
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                files.push(parts.join("/"));
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// 简单的 glob 匹配
+///
+/// 支持 `*`（段内任意字符）、`?`（段内单个字符）和 `**`（任意层目录）。
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path_rest)) => {
+                match_segment(first.as_bytes(), segment.as_bytes())
+                    && match_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_segment(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| match_segment(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && match_segment(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && match_segment(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match_literal_and_wildcards() {
+        assert!(glob_match("report.xml", "report.xml"));
+        assert!(!glob_match("report.xml", "out/report.xml"));
+        assert!(glob_match("*.log", "build.log"));
+        assert!(!glob_match("*.log", "logs/build.log"));
+        assert!(glob_match("out/?.txt", "out/a.txt"));
+        assert!(!glob_match("out/?.txt", "out/ab.txt"));
+    }
+
+    #[test]
+    fn test_glob_match_double_star() {
+        assert!(glob_match("**/*.log", "build.log"));
+        assert!(glob_match("**/*.log", "a/b/c/build.log"));
+        assert!(glob_match("target/**/app", "target/release/app"));
+        assert!(glob_match("target/**/app", "target/app"));
+        assert!(!glob_match("target/**/app", "other/app"));
+    }
+}
@@ -0,0 +1,118 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::pool;
+use execute::{CommandConfig, CommandExecutor, ExecuteError};
+use std::collections::HashMap;
+use std::os::unix::process::ExitStatusExt;
+use std::process::{ExitStatus, Output};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+/// 记录每个命令在哪个工作线程上执行
+#[derive(Default)]
+struct ThreadRecorder {
+    runs: Mutex<Vec<(String, ThreadId)>>,
+}
+
+impl CommandExecutor for ThreadRecorder {
+    fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        thread::sleep(Duration::from_millis(5));
+        self.runs
+            .lock()
+            .unwrap()
+            .push((config.args()[0].clone(), thread::current().id()));
+        Ok(Output {
+            status: ExitStatus::from_raw(0),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        })
+    }
+}
+
+#[test]
+fn test_same_affinity_key_runs_on_same_worker() {
+    let pool = pool(4);
+    let recorder = Arc::new(ThreadRecorder::default());
+    pool.start_with_executor(Arc::clone(&recorder));
+
+    let handles: Vec<_> = (0..24)
+        .map(|i| {
+            let key = ["repo-a", "repo-b", "repo-c"][i % 3];
+            pool.push_task(CommandConfig::new("job", vec![key.to_string()]).with_affinity_key(key))
+                .unwrap()
+        })
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+
+    let mut threads: HashMap<String, Vec<ThreadId>> = HashMap::new();
+    for (key, thread) in recorder.runs.lock().unwrap().iter() {
+        threads.entry(key.clone()).or_default().push(*thread);
+    }
+    assert_eq!(threads.len(), 3);
+    for (key, ids) in &threads {
+        assert_eq!(ids.len(), 8);
+        assert!(
+            ids.iter().all(|id| *id == ids[0]),
+            "key {key} ran on several workers"
+        );
+    }
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_same_affinity_key_is_not_run_in_parallel() {
+    let pool = pool(4);
+    pool.start_executor();
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..3)
+        .map(|_| {
+            pool.push_task(
+                CommandConfig::new("sleep", vec!["0.3".to_string()]).with_affinity_key("model"),
+            )
+            .unwrap()
+        })
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+
+    assert!(start.elapsed() >= Duration::from_millis(900));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_unkeyed_tasks_run_alongside_keyed_tasks() {
+    let pool = pool(2);
+    pool.start_executor();
+
+    let keyed: Vec<_> = (0..2)
+        .map(|_| {
+            pool.push_task(
+                CommandConfig::new("sleep", vec!["0.5".to_string()]).with_affinity_key("model"),
+            )
+            .unwrap()
+        })
+        .collect();
+    thread::sleep(Duration::from_millis(100));
+
+    // 一个工作线程被亲和键占用，另一个仍可执行普通任务
+    let start = Instant::now();
+    pool.push_task(CommandConfig::new("true", vec![]))
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_millis(400));
+
+    for handle in keyed {
+        handle.wait().unwrap();
+    }
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,118 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{ArtifactConfig, CommandPool, execute_with_report};
+use std::path::PathBuf;
+
+/// 为每个测试创建独立的目录
+fn test_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("execute-artifacts-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_artifacts_reported_in_place_without_destination() {
+    let dir = test_dir("in-place");
+    let config = shell("mkdir -p out && printf abc > out/a.txt && printf hello > report.xml")
+        .with_working_dir(dir.to_str().unwrap())
+        .with_artifacts(
+            ArtifactConfig::new()
+                .with_pattern("out/*.txt")
+                .with_pattern("report.xml"),
+        );
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.missing_artifacts.is_empty());
+    assert_eq!(report.artifacts.len(), 2);
+
+    let a = &report.artifacts[0];
+    assert_eq!(a.pattern, "out/*.txt");
+    assert_eq!(a.relative_path, "out/a.txt");
+    assert_eq!(a.path, dir.join("out/a.txt"));
+    assert_eq!(a.size, 3);
+    assert_eq!(report.artifacts[1].size, 5);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_missing_artifacts_are_reported() {
+    let dir = test_dir("missing");
+    let config = shell("touch present.log")
+        .with_working_dir(dir.to_str().unwrap())
+        .with_artifacts(
+            ArtifactConfig::new()
+                .with_pattern("*.log")
+                .with_pattern("dist/*.tar.gz"),
+        );
+
+    let report = execute_with_report(&config).unwrap();
+    assert_eq!(report.artifacts.len(), 1);
+    assert_eq!(report.missing_artifacts, vec!["dist/*.tar.gz".to_string()]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_artifacts_copied_out_of_temp_workdir() {
+    let dest = test_dir("copy-dest");
+    let config = shell("mkdir -p build/bin && printf data > build/bin/app")
+        .with_temp_workdir()
+        .with_artifacts(ArtifactConfig::new().with_pattern("**/app").copy_to(&dest));
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(!report.temp_workdir.unwrap().exists());
+
+    let artifact = &report.artifacts[0];
+    assert_eq!(artifact.path, dest.join("build/bin/app"));
+    assert_eq!(std::fs::read(&artifact.path).unwrap(), b"data");
+
+    std::fs::remove_dir_all(&dest).unwrap();
+}
+
+#[test]
+fn test_artifacts_moved_to_destination() {
+    let dir = test_dir("move-src");
+    let dest = test_dir("move-dest");
+    let config = shell("printf x > result.bin")
+        .with_working_dir(dir.to_str().unwrap())
+        .with_artifacts(
+            ArtifactConfig::new()
+                .with_pattern("result.bin")
+                .move_to(&dest),
+        );
+
+    let report = execute_with_report(&config).unwrap();
+    assert_eq!(report.artifacts[0].path, dest.join("result.bin"));
+    assert!(dest.join("result.bin").exists());
+    assert!(!dir.join("result.bin").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    std::fs::remove_dir_all(&dest).unwrap();
+}
+
+#[test]
+fn test_pool_reports_artifacts() {
+    let dir = test_dir("pool");
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(
+            shell("printf 1 > one.txt")
+                .with_working_dir(dir.to_str().unwrap())
+                .with_artifacts(ArtifactConfig::new().with_pattern("*.txt")),
+        )
+        .unwrap();
+    let report = handle.wait_report().unwrap();
+    assert_eq!(report.artifacts.len(), 1);
+    assert_eq!(report.artifacts[0].relative_path, "one.txt");
+
+    pool.shutdown().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,143 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::sleep_task;
+use std::collections::HashMap;
+use std::process::Output;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use execute::{
+    CommandConfig, CommandPool, ExecuteError, ExecutionBackend, RoutingBackend, execute_with_report,
+};
+
+/// 记录各后端名称的最大并发数
+#[derive(Default)]
+struct Tracker {
+    state: Mutex<HashMap<&'static str, (usize, usize)>>,
+}
+
+impl Tracker {
+    fn max(&self, name: &'static str) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .get(name)
+            .map_or(0, |(_, max)| *max)
+    }
+}
+
+struct TrackingBackend {
+    name: &'static str,
+    tracker: Arc<Tracker>,
+}
+
+impl ExecutionBackend for TrackingBackend {
+    fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        {
+            let mut state = self.tracker.state.lock().unwrap();
+            let (current, max) = state.entry(self.name).or_default();
+            *current += 1;
+            *max = (*max).max(*current);
+        }
+        let result = execute_with_report(config).map(|report| report.output);
+        self.tracker
+            .state
+            .lock()
+            .unwrap()
+            .get_mut(self.name)
+            .unwrap()
+            .0 -= 1;
+        result
+    }
+}
+
+fn routing(tracker: &Arc<Tracker>) -> Arc<dyn ExecutionBackend> {
+    let backend = |name| -> Arc<dyn ExecutionBackend> {
+        Arc::new(TrackingBackend {
+            name,
+            tracker: Arc::clone(tracker),
+        })
+    };
+    Arc::new(RoutingBackend::new(backend("process")).with_route("docker", backend("docker")))
+}
+
+#[test]
+fn test_routing_backend_selects_by_name() {
+    let tracker = Arc::new(Tracker::default());
+    let backend = routing(&tracker);
+
+    backend.execute(&sleep_task("0")).unwrap();
+    backend
+        .execute(&sleep_task("0").with_backend("docker"))
+        .unwrap();
+    assert_eq!(tracker.max("process"), 1);
+    assert_eq!(tracker.max("docker"), 1);
+
+    let err = backend
+        .execute(&sleep_task("0").with_backend("missing"))
+        .unwrap_err();
+    assert!(matches!(&err, ExecuteError::UnknownBackend(name) if name == "missing"));
+    assert!(err.is_permanent());
+}
+
+#[test]
+fn test_backend_limit_caps_in_flight_tasks() {
+    let tracker = Arc::new(Tracker::default());
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_backend(routing(&tracker))
+        .with_backend_limit("docker", 1)
+        .build()
+        .unwrap();
+    pool.start_executor();
+
+    let handles: Vec<_> = (0..3)
+        .map(|_| {
+            pool.push_task(sleep_task("0.2").with_backend("docker"))
+                .unwrap()
+        })
+        .chain((0..3).map(|_| pool.push_task(sleep_task("0.2")).unwrap()))
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+
+    assert_eq!(tracker.max("docker"), 1);
+    assert_eq!(tracker.max("process"), 3);
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_limited_backend_does_not_block_other_tasks() {
+    let tracker = Arc::new(Tracker::default());
+    let pool = CommandPool::builder()
+        .with_workers(2)
+        .with_backend(routing(&tracker))
+        .with_backend_limit("docker", 1)
+        .build()
+        .unwrap();
+    pool.start_executor();
+
+    let start = Instant::now();
+    let slow: Vec<_> = (0..2)
+        .map(|_| {
+            pool.push_task(sleep_task("0.5").with_backend("docker"))
+                .unwrap()
+        })
+        .collect();
+    // 第二个 docker 任务等待名额时，空闲的工作线程继续执行默认后端的任务
+    let fast = pool.push_task(sleep_task("0")).unwrap();
+    fast.wait().unwrap();
+    assert!(start.elapsed() < Duration::from_millis(400));
+
+    for handle in slow {
+        handle.wait().unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(1000));
+    assert_eq!(tracker.max("docker"), 1);
+
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,85 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{sleep_task, started_pool};
+use execute::CommandConfig;
+use std::time::Duration;
+
+#[test]
+fn test_barrier_resolves_after_n_completions() {
+    let pool = started_pool(4);
+    let barrier = pool.barrier(3);
+
+    let handles: Vec<_> = (0..3)
+        .map(|_| pool.push_task(sleep_task("0.1")).unwrap())
+        .collect();
+    assert!(!barrier.is_complete());
+
+    barrier.wait();
+    assert!(barrier.is_complete());
+    for handle in handles {
+        assert!(handle.wait().is_ok());
+    }
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_barrier_counts_failures() {
+    let pool = started_pool(4);
+    let barrier = pool.barrier(2);
+
+    pool.push_task(CommandConfig::new("false", vec![])).unwrap();
+    pool.push_task(CommandConfig::new("/nonexistent/program", vec![]))
+        .unwrap();
+
+    assert!(barrier.wait_timeout(Duration::from_secs(5)));
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_barrier_wait_timeout_expires() {
+    let pool = started_pool(4);
+    let barrier = pool.barrier(2);
+
+    pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    assert!(!barrier.wait_timeout(Duration::from_millis(300)));
+
+    pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    assert!(barrier.wait_timeout(Duration::from_secs(5)));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_barrier_for_specific_tasks() {
+    let pool = started_pool(4);
+
+    let fast = pool.push_task(sleep_task("0.05")).unwrap();
+    let slow = pool.push_task(sleep_task("0.4")).unwrap();
+    let unrelated = pool.push_task(sleep_task("2")).unwrap();
+
+    let barrier = pool.barrier_for(&[fast.clone(), slow.clone()]);
+    assert!(barrier.wait_timeout(Duration::from_secs(1)));
+    assert_eq!(slow.state(), execute::TaskState::Completed);
+
+    unrelated.cancel().unwrap();
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_barrier_for_already_finished_tasks() {
+    let pool = started_pool(4);
+
+    let handle = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    handle.wait().unwrap();
+
+    let barrier = pool.barrier_for(&[handle]);
+    assert!(barrier.is_complete());
+
+    let empty = pool.barrier(0);
+    assert!(empty.is_complete());
+
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,148 @@
+use execute::{BlackoutAction, BlackoutWindows, CommandConfig, CommandPool, ExecutionConfig};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// UTC 时间戳（秒）对应的 SystemTime
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn hours(h: u64) -> Duration {
+    Duration::from_secs(h * 3600)
+}
+
+// 2024-01-01 00:00:00 UTC
+const JAN_1_2024: u64 = 1_704_067_200;
+const DAY: u64 = 86_400;
+
+#[test]
+fn test_daily_window() {
+    let blackout = BlackoutWindows::new().with_daily(hours(2), hours(4));
+
+    assert!(!blackout.contains(at(JAN_1_2024 + 3600)));
+    assert!(blackout.contains(at(JAN_1_2024 + 2 * 3600)));
+    assert!(blackout.contains(at(JAN_1_2024 + DAY + 3 * 3600)));
+    // 结束时间不在窗口内
+    assert!(!blackout.contains(at(JAN_1_2024 + 4 * 3600)));
+
+    assert_eq!(
+        blackout.next_allowed(at(JAN_1_2024 + 3 * 3600)),
+        Some(at(JAN_1_2024 + 4 * 3600))
+    );
+    assert_eq!(
+        blackout.next_allowed(at(JAN_1_2024 + 5 * 3600)),
+        Some(at(JAN_1_2024 + 5 * 3600))
+    );
+}
+
+#[test]
+fn test_daily_window_across_midnight() {
+    let blackout = BlackoutWindows::new().with_daily(hours(22), hours(2));
+
+    assert!(blackout.contains(at(JAN_1_2024 + 23 * 3600)));
+    assert!(blackout.contains(at(JAN_1_2024 + 3600)));
+    assert!(!blackout.contains(at(JAN_1_2024 + 12 * 3600)));
+    assert_eq!(
+        blackout.next_allowed(at(JAN_1_2024 + 23 * 3600)),
+        Some(at(JAN_1_2024 + DAY + 2 * 3600))
+    );
+}
+
+#[test]
+fn test_specific_date() {
+    let blackout = BlackoutWindows::new().with_date(2024, 1, 2);
+
+    assert!(!blackout.contains(at(JAN_1_2024 + DAY - 1)));
+    assert!(blackout.contains(at(JAN_1_2024 + DAY)));
+    assert!(blackout.contains(at(JAN_1_2024 + 2 * DAY - 1)));
+    assert_eq!(
+        blackout.next_allowed(at(JAN_1_2024 + DAY + 3600)),
+        Some(at(JAN_1_2024 + 2 * DAY))
+    );
+}
+
+#[test]
+fn test_adjacent_windows_are_merged() {
+    // 1 月 2 日整天冻结，紧接着 1 月 3 日 00:00-03:00 维护
+    let blackout = BlackoutWindows::new()
+        .with_date(2024, 1, 2)
+        .with_daily(Duration::ZERO, hours(3));
+
+    assert_eq!(
+        blackout.next_allowed(at(JAN_1_2024 + DAY + 3600)),
+        Some(at(JAN_1_2024 + 2 * DAY + 3 * 3600))
+    );
+}
+
+#[test]
+fn test_empty_daily_window() {
+    let blackout = BlackoutWindows::new().with_daily(hours(2), hours(2));
+    assert!(!blackout.contains(at(JAN_1_2024 + 2 * 3600)));
+}
+
+#[test]
+#[should_panic(expected = "invalid blackout date")]
+fn test_invalid_date_panics() {
+    let _ = BlackoutWindows::new().with_date(2023, 2, 29);
+}
+
+/// 覆盖当前 UTC 时刻、从现在起持续 `secs` 秒的每日窗口
+fn window_from_now(secs: u64) -> BlackoutWindows {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let start = Duration::from_secs(now - 1);
+    BlackoutWindows::new().with_daily(start, start + Duration::from_secs(secs + 1))
+}
+
+#[cfg(unix)]
+#[test]
+fn test_recurring_skips_triggers_in_blackout() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(1));
+    pool.start_executor();
+
+    let recurring = pool
+        .schedule_recurring_with_blackout(
+            "* * * * * *",
+            CommandConfig::new("true", vec![]),
+            window_from_now(60),
+        )
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(2500));
+    recurring.cancel();
+
+    assert_eq!(recurring.runs(), 0);
+    assert!(
+        recurring.skipped() >= 2,
+        "skipped = {}",
+        recurring.skipped()
+    );
+    pool.shutdown().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_recurring_defers_to_window_end() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(1));
+    pool.start_executor();
+
+    let recurring = pool
+        .schedule_recurring_with_blackout(
+            "* * * * * *",
+            CommandConfig::new("true", vec![]),
+            window_from_now(4).with_action(BlackoutAction::Defer),
+        )
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(2000));
+    assert_eq!(recurring.runs(), 0);
+
+    // 窗口结束后窗口内的触发合并为一次提交，之后恢复每秒一次
+    std::thread::sleep(Duration::from_millis(2700));
+    let runs = recurring.runs();
+    assert!((1..=2).contains(&runs), "runs = {runs}");
+    std::thread::sleep(Duration::from_millis(2000));
+    recurring.cancel();
+    assert!(recurring.runs() > runs);
+    assert_eq!(recurring.skipped(), 0);
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,150 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{sleep_task, started_pool};
+use execute::{BatchBudget, CommandConfig, CommandPool, ExecuteError, ExecutionConfig};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_batch_within_budget_completes() {
+    let pool = started_pool(4);
+
+    let budget = BatchBudget::new().with_wall_clock(Duration::from_secs(10));
+    let results: Vec<_> = pool
+        .submit_batch((0..3).map(|_| sleep_task("0.1")))
+        .unwrap()
+        .with_budget(budget)
+        .collect();
+
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|(_, r)| r.is_ok()));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_wall_clock_budget_cancels_remaining_tasks() {
+    let pool = started_pool(4);
+
+    // 4 个并行任务累计消耗约 4 倍墙钟时间，预算很快耗尽
+    let budget = BatchBudget::new().with_wall_clock(Duration::from_millis(400));
+    let start = Instant::now();
+    let results: Vec<_> = pool
+        .submit_batch((0..6).map(|_| sleep_task("5")))
+        .unwrap()
+        .with_budget(budget)
+        .ordered()
+        .collect();
+
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(results.len(), 6);
+    for (_, result) in &results {
+        assert!(
+            matches!(result, Err(ExecuteError::BudgetExceeded(reason)) if reason.contains("wall-clock")),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+
+    pool.shutdown().unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_cpu_budget_cancels_busy_tasks() {
+    let pool = started_pool(4);
+
+    let busy = CommandConfig::new("sh", vec!["-c".into(), "while :; do :; done".into()]);
+    let budget = BatchBudget::new().with_cpu_time(Duration::from_millis(300));
+    let start = Instant::now();
+    let results: Vec<_> = pool
+        .submit_batch(vec![busy.clone(), busy])
+        .unwrap()
+        .with_budget(budget)
+        .collect();
+
+    assert!(start.elapsed() < Duration::from_secs(10));
+    for (_, result) in &results {
+        assert!(
+            matches!(result, Err(ExecuteError::BudgetExceeded(reason)) if reason.contains("cpu-time")),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_user_cancellation_is_not_reported_as_budget() {
+    let pool = started_pool(4);
+
+    let budget = BatchBudget::new().with_wall_clock(Duration::from_secs(60));
+    let stream = pool
+        .submit_batch(vec![sleep_task("5")])
+        .unwrap()
+        .with_budget(budget);
+    let handle = stream.handles().next().unwrap().unwrap().clone();
+    std::thread::sleep(Duration::from_millis(100));
+    handle.cancel().unwrap();
+
+    let results: Vec<_> = stream.collect();
+    assert!(matches!(results[0].1, Err(ExecuteError::Cancelled(_))));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_deadline_kills_running_and_cancels_queued() {
+    let pool = started_pool(4);
+
+    let budget = BatchBudget::new().with_deadline(Duration::from_millis(300));
+    let start = Instant::now();
+    let results: Vec<_> = pool
+        .submit_batch((0..6).map(|_| sleep_task("5")))
+        .unwrap()
+        .with_budget(budget)
+        .collect();
+
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(results.len(), 6);
+    for (_, result) in &results {
+        assert!(
+            matches!(result, Err(ExecuteError::BudgetExceeded(reason)) if reason.contains("deadline")),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_deadline_without_kill_lets_running_tasks_finish() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(2));
+    pool.start_executor();
+
+    let budget = BatchBudget::new()
+        .with_deadline(Duration::from_millis(300))
+        .with_kill_running(false);
+    let results: Vec<_> = pool
+        .submit_batch((0..4).map(|_| sleep_task("0.8")))
+        .unwrap()
+        .with_budget(budget)
+        .ordered()
+        .collect();
+
+    // 两个工作线程上的任务正常完成，排队中的任务被取消
+    assert!(results[0].1.is_ok());
+    assert!(results[1].1.is_ok());
+    for (_, result) in &results[2..] {
+        assert!(
+            matches!(result, Err(ExecuteError::BudgetExceeded(reason)) if reason.contains("deadline")),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+
+    pool.shutdown().unwrap();
+}
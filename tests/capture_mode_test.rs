@@ -0,0 +1,57 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CaptureMode, CommandConfig, CommandPool};
+
+#[test]
+fn test_default_capture_mode_is_full() {
+    let config = CommandConfig::new("echo", vec![]);
+    assert_eq!(config.capture_mode(), CaptureMode::Full);
+}
+
+#[test]
+fn test_tail_capture_keeps_last_bytes() {
+    let config = shell("for i in $(seq 1 1000); do echo line$i; done")
+        .with_capture_mode(CaptureMode::Tail(17));
+
+    let output = CommandPool::new().execute_task(&config).unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"line999\nline1000\n");
+}
+
+#[test]
+fn test_tail_capture_applies_to_stderr() {
+    let config = shell("echo first >&2; echo second >&2; echo last >&2")
+        .with_capture_mode(CaptureMode::Tail(5));
+
+    let output = CommandPool::new().execute_task(&config).unwrap();
+    assert_eq!(output.stderr, b"last\n");
+}
+
+#[test]
+fn test_tail_capture_short_output_unchanged() {
+    let config = shell("echo hi").with_capture_mode(CaptureMode::Tail(1024));
+
+    let output = CommandPool::new().execute_task(&config).unwrap();
+    assert_eq!(output.stdout, b"hi\n");
+}
+
+#[test]
+fn test_tail_capture_bounds_large_output() {
+    // 8 MB 输出只保留最后 4 KB
+    let config = CommandConfig::new(
+        "head",
+        vec![
+            "-c".to_string(),
+            (8 * 1024 * 1024).to_string(),
+            "/dev/zero".to_string(),
+        ],
+    )
+    .with_capture_mode(CaptureMode::Tail(4096));
+
+    let output = CommandPool::new().execute_task(&config).unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout.len(), 4096);
+}
@@ -0,0 +1,90 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandPool, ExecuteError, ExecutionConfig, execute_checked, execute_with_report};
+
+#[test]
+fn test_execute_checked_returns_output_on_success() {
+    let output = execute_checked(&shell("echo ok")).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"ok\n");
+}
+
+#[test]
+fn test_execute_checked_converts_non_zero_exit() {
+    let result = execute_checked(&shell("echo out; echo err >&2; exit 3"));
+
+    match result {
+        Err(ExecuteError::ExitStatus {
+            code,
+            stdout,
+            stderr,
+        }) => {
+            assert_eq!(code, Some(3));
+            assert_eq!(stdout, b"out\n");
+            assert_eq!(stderr, b"err\n");
+        }
+        other => panic!("expected ExitStatus, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_execute_checked_signal_has_no_code() {
+    let result = execute_checked(&shell("kill -9 $$"));
+
+    assert!(matches!(
+        result,
+        Err(ExecuteError::ExitStatus { code: None, .. })
+    ));
+}
+
+#[test]
+fn test_check_exit_is_opt_in() {
+    let report = execute_with_report(&shell("exit 1")).unwrap();
+    assert_eq!(report.output.status.code(), Some(1));
+
+    let result = execute_with_report(&shell("exit 1").with_check_exit(true));
+    assert!(matches!(
+        result,
+        Err(ExecuteError::ExitStatus { code: Some(1), .. })
+    ));
+}
+
+#[test]
+fn test_check_exit_respects_success_codes_and_allow_failure() {
+    let accepted = shell("exit 1")
+        .with_check_exit(true)
+        .with_success_codes(&[0, 1]);
+    assert!(execute_with_report(&accepted).is_ok());
+
+    let rejected = shell("exit 2")
+        .with_check_exit(true)
+        .with_success_codes(&[0, 1]);
+    assert!(matches!(
+        execute_with_report(&rejected),
+        Err(ExecuteError::UnexpectedExit { .. })
+    ));
+
+    let allowed = shell("exit 2").with_check_exit(true).allow_failure();
+    assert!(execute_with_report(&allowed).is_ok());
+}
+
+#[test]
+fn test_pool_task_with_check_exit_fails() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(1));
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(shell("exit 4").with_check_exit(true))
+        .unwrap();
+    let result = handle.wait();
+
+    assert!(matches!(
+        result,
+        Err(ExecuteError::ExitStatus { code: Some(4), .. })
+    ));
+    pool.shutdown().unwrap();
+}
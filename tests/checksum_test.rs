@@ -0,0 +1,56 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{ArtifactConfig, CommandPool, execute_with_report};
+
+const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+#[test]
+fn test_checksums_disabled_by_default() {
+    let config = shell("printf abc");
+    assert!(!config.checksums());
+
+    let report = execute_with_report(&config).unwrap();
+    assert_eq!(report.stdout_sha256, None);
+}
+
+#[test]
+fn test_stdout_checksum() {
+    let config = shell("printf abc").with_checksums(true);
+
+    let report = execute_with_report(&config).unwrap();
+    assert_eq!(report.stdout_sha256.as_deref(), Some(ABC_SHA256));
+}
+
+#[test]
+fn test_artifact_checksums() {
+    let config = shell("printf abc > a.bin; head -c 100000 /dev/zero > zeros.bin")
+        .with_temp_workdir()
+        .with_checksums(true)
+        .with_artifacts(ArtifactConfig::new().with_pattern("*.bin"));
+
+    let report = execute_with_report(&config).unwrap();
+    assert_eq!(report.artifacts.len(), 2);
+    assert_eq!(report.artifacts[0].sha256.as_deref(), Some(ABC_SHA256));
+
+    let zeros = report.artifacts[1].sha256.as_deref().unwrap();
+    assert_eq!(zeros.len(), 64);
+    assert_ne!(zeros, ABC_SHA256);
+}
+
+#[test]
+fn test_pool_report_includes_checksums() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(shell("printf abc").with_checksums(true))
+        .unwrap();
+    let report = handle.wait_report().unwrap();
+    assert_eq!(report.output.stdout, b"abc");
+    assert_eq!(report.stdout_sha256.as_deref(), Some(ABC_SHA256));
+
+    pool.shutdown().unwrap();
+}
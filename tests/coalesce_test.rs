@@ -0,0 +1,194 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{pool, shell};
+use execute::{CommandConfig, CommandPool, ExecuteError, ExecutionConfig, TaskState};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// 为每个测试创建独立的计数文件
+fn counter_file(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("execute-coalesce-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn run_count(path: &PathBuf) -> usize {
+    std::fs::read_to_string(path)
+        .map(|s| s.lines().count())
+        .unwrap_or(0)
+}
+
+fn wait_running(handle: &execute::TaskHandle) {
+    for _ in 0..200 {
+        if matches!(handle.state(), TaskState::Running { .. }) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    panic!("task did not start");
+}
+
+#[test]
+fn test_concurrent_submissions_execute_once() {
+    let counter = counter_file("once");
+    let script = format!(
+        "echo run >> {}; sleep 0.3; echo refreshed",
+        counter.display()
+    );
+
+    let pool = pool(4);
+    pool.start_executor();
+
+    let handles: Vec<_> = (0..5)
+        .map(|_| {
+            pool.push_task(shell(&script).with_coalesce_key("cache"))
+                .unwrap()
+        })
+        .collect();
+    for handle in &handles {
+        assert_eq!(handle.wait().unwrap().stdout, b"refreshed\n");
+    }
+
+    assert_eq!(run_count(&counter), 1);
+    pool.shutdown().unwrap();
+    let _ = std::fs::remove_file(&counter);
+}
+
+#[test]
+fn test_join_while_running_shares_result() {
+    let counter = counter_file("running");
+    let script = format!("echo run >> {}; sleep 0.3", counter.display());
+
+    let pool = pool(4);
+    pool.start_executor();
+
+    let first = pool
+        .push_task(shell(&script).with_coalesce_key("k"))
+        .unwrap();
+    wait_running(&first);
+    let second = pool
+        .push_task(shell(&script).with_coalesce_key("k"))
+        .unwrap();
+    assert!(matches!(second.state(), TaskState::Running { .. }));
+
+    assert!(second.wait().unwrap().status.success());
+    assert!(first.wait().unwrap().status.success());
+    assert_eq!(second.state(), TaskState::Completed);
+    assert_eq!(run_count(&counter), 1);
+
+    pool.shutdown().unwrap();
+    let _ = std::fs::remove_file(&counter);
+}
+
+#[test]
+fn test_submission_after_completion_runs_again() {
+    let counter = counter_file("again");
+    let script = format!("echo run >> {}", counter.display());
+
+    let pool = pool(4);
+    pool.start_executor();
+
+    for _ in 0..2 {
+        let handle = pool
+            .push_task(shell(&script).with_coalesce_key("k"))
+            .unwrap();
+        handle.wait().unwrap();
+    }
+    assert_eq!(run_count(&counter), 2);
+
+    pool.shutdown().unwrap();
+    let _ = std::fs::remove_file(&counter);
+}
+
+#[test]
+fn test_errors_broadcast_to_all_handles() {
+    let pool = pool(4);
+    pool.start_executor();
+
+    let config = CommandConfig::new("sleep", vec!["5".to_string()])
+        .with_timeout(Duration::from_millis(200))
+        .with_coalesce_key("slow");
+    let a = pool.push_task(config.clone()).unwrap();
+    let b = pool.push_task(config).unwrap();
+
+    assert!(matches!(a.wait(), Err(ExecuteError::Timeout(_))));
+    assert!(matches!(b.wait(), Err(ExecuteError::Timeout(_))));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_cancelled_follower_does_not_affect_leader() {
+    let pool = pool(4);
+    pool.start_executor();
+
+    let config = shell("sleep 0.3; echo done").with_coalesce_key("k");
+    let leader = pool.push_task(config.clone()).unwrap();
+    let follower = pool.push_task(config).unwrap();
+    follower.cancel().unwrap();
+
+    assert!(matches!(follower.wait(), Err(ExecuteError::Cancelled(_))));
+    assert_eq!(leader.wait().unwrap().stdout, b"done\n");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_coalesced_task_skips_full_queue() {
+    let pool = CommandPool::with_config_and_limit(ExecutionConfig::new().with_workers(1), 1);
+
+    // 未启动执行器，首个任务占满队列
+    let config = CommandConfig::new("true", vec![]).with_coalesce_key("k");
+    let first = pool.try_push_task(config.clone()).unwrap();
+    let second = pool.try_push_task(config).unwrap();
+    assert!(
+        pool.try_push_task(CommandConfig::new("true", vec![]))
+            .is_err()
+    );
+
+    pool.start_executor();
+    assert!(first.wait().is_ok());
+    assert!(second.wait().is_ok());
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_clear_cancels_followers_of_removed_leader() {
+    let pool = pool(4);
+
+    // 未启动执行器，首个任务留在队列中
+    let config = shell("echo done").with_coalesce_key("k");
+    let _leader = pool.push_task(config.clone()).unwrap();
+    let follower = pool.push_task(config.clone()).unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let waiter = thread::spawn(move || tx.send(follower.wait()).unwrap());
+
+    assert_eq!(pool.clear(), 1);
+    let result = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(result, Err(ExecuteError::Cancelled(_))));
+    waiter.join().unwrap();
+
+    // 任务组已移除，之后提交的同键任务重新执行
+    pool.start_executor();
+    let again = pool.push_task(config).unwrap();
+    assert_eq!(again.wait().unwrap().stdout, b"done\n");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_drain_cancels_followers_of_removed_leader() {
+    let pool = pool(4);
+
+    let config = shell("echo done").with_coalesce_key("k");
+    let _leader = pool.push_task(config.clone()).unwrap();
+    let follower = pool.push_task(config).unwrap();
+
+    assert_eq!(pool.drain().len(), 1);
+    assert!(matches!(follower.wait(), Err(ExecuteError::Cancelled(_))));
+}
@@ -0,0 +1,91 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, EnvConfig, ExecuteError};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// 在系统临时目录下创建唯一的测试目录
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("execute-validate-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_file(path: &Path, mode: u32) {
+    std::fs::write(path, "#!/bin/sh\necho ok\n").unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+}
+
+#[test]
+fn test_validate_program_on_path() {
+    let config = CommandConfig::new("sh", vec![]);
+    assert!(config.validate().is_ok());
+    assert!(config.resolve_program().unwrap().ends_with("sh"));
+}
+
+#[test]
+fn test_validate_typo_program() {
+    let config = CommandConfig::new("definitely-not-a-real-program-xyz", vec![]);
+    assert!(matches!(
+        config.validate(),
+        Err(ExecuteError::ProgramNotFound { program }) if program == "definitely-not-a-real-program-xyz"
+    ));
+}
+
+#[test]
+fn test_validate_missing_working_dir() {
+    let dir = scratch_dir("missing-cwd").join("nope");
+    let config = CommandConfig::new("sh", vec![]).with_working_dir_path(&dir);
+    assert!(matches!(
+        config.validate(),
+        Err(ExecuteError::WorkingDirInvalid { dir: reported }) if reported == dir
+    ));
+}
+
+#[test]
+fn test_validate_path_to_executable() {
+    let dir = scratch_dir("exec");
+    let script = dir.join("run.sh");
+    write_file(&script, 0o755);
+
+    assert!(CommandConfig::new(&script, vec![]).validate().is_ok());
+    // 相对路径相对于工作目录解析
+    let relative = CommandConfig::new("./run.sh", vec![]).with_working_dir_path(&dir);
+    assert_eq!(relative.resolve_program().unwrap(), dir.join("./run.sh"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_validate_non_executable_file() {
+    let dir = scratch_dir("noexec");
+    let script = dir.join("data.txt");
+    write_file(&script, 0o644);
+
+    assert!(matches!(
+        CommandConfig::new(&script, vec![]).validate(),
+        Err(ExecuteError::PermissionDenied { .. })
+    ));
+    assert!(matches!(
+        CommandConfig::new(&dir, vec![]).validate(),
+        Err(ExecuteError::PermissionDenied { .. })
+    ));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_validate_uses_configured_path() {
+    let dir = scratch_dir("env-path");
+    write_file(&dir.join("only-here"), 0o755);
+
+    let config = CommandConfig::new("only-here", vec![]);
+    assert!(config.validate().is_err());
+
+    let config = config.with_env(EnvConfig::new().set("PATH", dir.to_str().unwrap()));
+    assert_eq!(config.resolve_program().unwrap(), dir.join("only-here"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
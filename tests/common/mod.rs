@@ -0,0 +1,33 @@
+//! 集成测试共用的辅助函数
+
+// 每个测试文件只用到其中一部分
+#![allow(dead_code)]
+
+use execute::{CommandConfig, CommandPool, ExecutionConfig};
+
+/// 通过 `sh -c` 执行脚本
+pub fn shell(script: &str) -> CommandConfig {
+    CommandConfig::new("sh", vec!["-c".to_string(), script.to_string()])
+}
+
+/// 休眠 `secs` 秒
+pub fn sleep_task(secs: &str) -> CommandConfig {
+    CommandConfig::new("sleep", vec![secs.to_string()])
+}
+
+/// 输出一行文本
+pub fn echo(text: &str) -> CommandConfig {
+    CommandConfig::new("echo", vec![text.to_string()])
+}
+
+/// 有 `workers` 个工作线程、尚未启动执行器的命令池
+pub fn pool(workers: usize) -> CommandPool {
+    CommandPool::with_config(ExecutionConfig::new().with_workers(workers))
+}
+
+/// 有 `workers` 个工作线程、已启动执行器的命令池
+pub fn started_pool(workers: usize) -> CommandPool {
+    let pool = pool(workers);
+    pool.start_executor();
+    pool
+}
@@ -0,0 +1,110 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::started_pool;
+use execute::CommandConfig;
+
+/// 输出 `text` 前先睡眠 `delay` 秒
+fn delayed_echo(delay: &str, text: &str) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec!["-c".to_string(), format!("sleep {}; echo {}", delay, text)],
+    )
+}
+
+fn stdout(result: execute::TaskResult) -> String {
+    String::from_utf8(result.unwrap().stdout)
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn test_stream_yields_in_completion_order() {
+    let pool = started_pool(4);
+
+    let stream = pool
+        .submit_batch(vec![delayed_echo("0.4", "slow"), delayed_echo("0", "fast")])
+        .unwrap();
+    let results: Vec<_> = stream.map(|(i, r)| (i, stdout(r))).collect();
+
+    assert_eq!(
+        results,
+        vec![(1, "fast".to_string()), (0, "slow".to_string())]
+    );
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_ordered_stream_yields_in_submission_order() {
+    let pool = started_pool(4);
+
+    let configs = vec![
+        delayed_echo("0.4", "a"),
+        delayed_echo("0.2", "b"),
+        delayed_echo("0", "c"),
+    ];
+    let results: Vec<_> = pool
+        .submit_batch(configs)
+        .unwrap()
+        .ordered()
+        .map(|(i, r)| (i, stdout(r)))
+        .collect();
+
+    assert_eq!(
+        results,
+        vec![
+            (0, "a".to_string()),
+            (1, "b".to_string()),
+            (2, "c".to_string())
+        ]
+    );
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_ordered_stream_includes_failures() {
+    let pool = started_pool(4);
+
+    let configs = vec![
+        CommandConfig::new("false", vec![]),
+        CommandConfig::new("nonexistent_command_for_stream", vec![]),
+        CommandConfig::new("true", vec![]),
+    ];
+    let results: Vec<_> = pool.submit_batch(configs).unwrap().ordered().collect();
+
+    assert_eq!(results.len(), 3);
+    assert!(!results[0].1.as_ref().unwrap().status.success());
+    assert!(results[1].1.is_err());
+    assert!(results[2].1.as_ref().unwrap().status.success());
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_empty_batch_stream_is_empty() {
+    let pool = started_pool(4);
+    let mut stream = pool.submit_batch(Vec::new()).unwrap();
+    assert_eq!(stream.remaining(), 0);
+    assert!(stream.next().is_none());
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_remaining_counts_undelivered_results() {
+    let pool = started_pool(4);
+
+    let mut stream = pool
+        .submit_batch((0..3).map(|i| CommandConfig::new("echo", vec![i.to_string()])))
+        .unwrap()
+        .ordered();
+    assert_eq!(stream.remaining(), 3);
+    assert_eq!(stream.next().unwrap().0, 0);
+    assert_eq!(stream.remaining(), 2);
+    assert_eq!(stream.count(), 2);
+
+    pool.shutdown().unwrap();
+}
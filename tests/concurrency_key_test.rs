@@ -0,0 +1,127 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, CommandPool};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 系统临时目录下的唯一日志文件路径
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "execute-concurrency-key-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// 开始和结束时分别写入 `+name` 和 `-name`
+fn tracked(log: &Path, name: &str) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            format!(
+                "echo +{name} >> {0}; sleep 0.3; echo -{name} >> {0}",
+                log.display()
+            ),
+        ],
+    )
+}
+
+/// 按日志计算各名称的最大并发数
+fn max_concurrency(log: &Path) -> HashMap<String, usize> {
+    let mut current: HashMap<String, usize> = HashMap::new();
+    let mut max: HashMap<String, usize> = HashMap::new();
+    for line in std::fs::read_to_string(log).unwrap().lines() {
+        let (sign, name) = line.split_at(1);
+        let count = current.entry(name.to_string()).or_default();
+        if sign == "+" {
+            *count += 1;
+            let peak = max.entry(name.to_string()).or_default();
+            *peak = (*peak).max(*count);
+        } else {
+            *count -= 1;
+        }
+    }
+    max
+}
+
+#[test]
+fn test_concurrency_key_default_is_none() {
+    assert_eq!(CommandConfig::new("true", vec![]).concurrency_key(), None);
+}
+
+#[test]
+fn test_key_limit_caps_same_key_only() {
+    let log = log_path("cap");
+    let pool = CommandPool::builder()
+        .with_workers(8)
+        .with_concurrency_key_limit("host:db1", 2)
+        .build()
+        .unwrap();
+    pool.start_executor();
+
+    let start = Instant::now();
+    let mut handles = Vec::new();
+    for _ in 0..4 {
+        handles.push(
+            pool.push_task(tracked(&log, "db1").with_concurrency_key("host:db1"))
+                .unwrap(),
+        );
+    }
+    for _ in 0..3 {
+        handles.push(
+            pool.push_task(tracked(&log, "db2").with_concurrency_key("host:db2"))
+                .unwrap(),
+        );
+    }
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    // 4 个同键任务每次最多 2 个，至少需要两轮
+    assert!(start.elapsed() >= Duration::from_millis(600));
+
+    let max = max_concurrency(&log);
+    assert_eq!(max["db1"], 2);
+    // 未配置上限的键不受限制
+    assert_eq!(max["db2"], 3);
+
+    pool.shutdown().unwrap();
+    std::fs::remove_file(log).unwrap();
+}
+
+#[test]
+fn test_limited_key_does_not_block_other_tasks() {
+    let pool = CommandPool::builder()
+        .with_workers(2)
+        .with_concurrency_key_limit("slow", 1)
+        .build()
+        .unwrap();
+    pool.start_executor();
+
+    let sleep = || CommandConfig::new("sleep", vec!["0.5".to_string()]);
+    let first = pool
+        .push_task(sleep().with_concurrency_key("slow"))
+        .unwrap();
+    let second = pool
+        .push_task(sleep().with_concurrency_key("slow"))
+        .unwrap();
+    let start = Instant::now();
+    pool.push_task(CommandConfig::new("true", vec![]))
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_millis(400));
+
+    first.wait().unwrap();
+    second.wait().unwrap();
+    pool.shutdown().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "concurrency key limit must be greater than 0")]
+fn test_zero_limit_panics() {
+    let _ = CommandPool::builder().with_concurrency_key_limit("host", 0);
+}
@@ -29,11 +29,6 @@ fn invalid_timeout_strategy() -> impl Strategy<Value = Duration> {
     Just(Duration::ZERO)
 }
 
-/// 生成无效轮询间隔策略
-fn invalid_poll_interval_strategy() -> impl Strategy<Value = Duration> {
-    Just(Duration::ZERO)
-}
-
 /// 验证错误消息的清晰度
 ///
 /// 清晰的错误消息应该包含：
@@ -81,13 +76,6 @@ fn verify_error_message_clarity(error: &ConfigError, invalid_value_str: &str) {
                 error_msg
             );
         }
-        ConfigError::InvalidPollInterval(_) => {
-            assert!(
-                error_msg.contains("must be") || error_msg.contains("positive"),
-                "Error message should describe the constraint: '{}'",
-                error_msg
-            );
-        }
         ConfigError::ThreadCountExceedsLimit(requested, limit) => {
             assert!(
                 error_msg.contains(&requested.to_string()),
@@ -105,6 +93,20 @@ fn verify_error_message_clarity(error: &ConfigError, invalid_value_str: &str) {
                 error_msg
             );
         }
+        ConfigError::InvalidCronExpression(_, reason) => {
+            assert!(
+                error_msg.contains(reason.as_str()),
+                "Error message should describe the problem: '{}'",
+                error_msg
+            );
+        }
+        ConfigError::GlobalPoolInitialized => {
+            assert!(
+                error_msg.contains("already"),
+                "Error message should describe the problem: '{}'",
+                error_msg
+            );
+        }
     }
 
     // 错误消息应该以大写字母开头或包含错误类型关键词
@@ -203,33 +205,6 @@ proptest! {
             verify_error_message_clarity(&error, &format!("{:?}", timeout));
         }
     }
-
-    /// 属性测试：对于任意无效的轮询间隔，错误消息应该清晰描述问题
-    ///
-    /// 此测试验证：
-    /// - 需求 5.6: 配置验证失败时提供清晰的错误消息
-    #[test]
-    fn prop_invalid_poll_interval_error_message(
-        interval in invalid_poll_interval_strategy()
-    ) {
-        let result = PoolConfigBuilder::new()
-            .poll_interval(interval)
-            .build();
-
-        // 验证配置失败
-        prop_assert!(result.is_err(), "Invalid poll interval should fail validation");
-
-        if let Err(error) = result {
-            // 验证错误类型
-            prop_assert!(
-                matches!(error, ConfigError::InvalidPollInterval(_)),
-                "Should be InvalidPollInterval error"
-            );
-
-            // 验证错误消息清晰度
-            verify_error_message_clarity(&error, &format!("{:?}", interval));
-        }
-    }
 }
 
 // 单元测试：验证特定错误场景的消息清晰度
@@ -318,27 +293,6 @@ fn test_invalid_timeout_zero_message() {
     }
 }
 
-#[test]
-fn test_invalid_poll_interval_zero_message() {
-    let result = PoolConfigBuilder::new()
-        .poll_interval(Duration::ZERO)
-        .build();
-
-    assert!(result.is_err(), "Zero poll interval should fail");
-
-    if let Err(error) = result {
-        let msg = error.to_string();
-
-        // 验证消息包含关键信息
-        assert!(msg.contains("Invalid poll interval"));
-        assert!(msg.contains("0s") || msg.contains("0ns"));
-        assert!(msg.contains("must be positive"));
-
-        // 验证错误类型
-        assert!(matches!(error, ConfigError::InvalidPollInterval(_)));
-    }
-}
-
 #[test]
 fn test_error_message_format_consistency() {
     // 测试所有错误消息格式的一致性
@@ -356,10 +310,6 @@ fn test_error_message_format_consistency() {
             .default_timeout(Duration::ZERO)
             .build()
             .unwrap_err(),
-        PoolConfigBuilder::new()
-            .poll_interval(Duration::ZERO)
-            .build()
-            .unwrap_err(),
     ];
 
     for error in errors {
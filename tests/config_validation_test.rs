@@ -7,7 +7,6 @@ fn test_valid_config() {
         .thread_count(4)
         .queue_capacity(100)
         .default_timeout(Duration::from_secs(30))
-        .poll_interval(Duration::from_millis(100))
         .build();
 
     assert!(config.is_ok());
@@ -15,7 +14,6 @@ fn test_valid_config() {
     assert_eq!(config.thread_count, 4);
     assert_eq!(config.queue_capacity, Some(100));
     assert_eq!(config.default_timeout, Some(Duration::from_secs(30)));
-    assert_eq!(config.poll_interval, Duration::from_millis(100));
 }
 
 #[test]
@@ -27,7 +25,6 @@ fn test_default_values() {
     assert!(config.thread_count >= 1);
     assert_eq!(config.queue_capacity, None);
     assert_eq!(config.default_timeout, None);
-    assert_eq!(config.poll_interval, Duration::from_millis(100));
 }
 
 #[test]
@@ -71,21 +68,6 @@ fn test_invalid_timeout_zero() {
     }
 }
 
-#[test]
-fn test_invalid_poll_interval_zero() {
-    let result = PoolConfigBuilder::new()
-        .poll_interval(Duration::ZERO)
-        .build();
-
-    assert!(result.is_err());
-    match result.unwrap_err() {
-        ConfigError::InvalidPollInterval(interval) => {
-            assert_eq!(interval, Duration::ZERO);
-        }
-        _ => panic!("Expected InvalidPollInterval error"),
-    }
-}
-
 #[test]
 fn test_thread_count_exceeds_limit() {
     // 使用一个非常大的线程数，应该超过系统限制
@@ -128,12 +110,4 @@ fn test_error_messages() {
     let msg = err.to_string();
     assert!(msg.contains("Invalid timeout"));
     assert!(msg.contains("must be positive"));
-
-    let err = PoolConfigBuilder::new()
-        .poll_interval(Duration::ZERO)
-        .build()
-        .unwrap_err();
-    let msg = err.to_string();
-    assert!(msg.contains("Invalid poll interval"));
-    assert!(msg.contains("must be positive"));
 }
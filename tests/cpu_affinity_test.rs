@@ -0,0 +1,57 @@
+#![cfg(target_os = "linux")]
+
+use execute::{CommandConfig, execute_with_report, spawn};
+
+/// 读取进程允许运行的 CPU 列表
+fn cpus_allowed(pid: u32) -> String {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).unwrap();
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Cpus_allowed_list:"))
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn test_cpu_affinity_default_is_none() {
+    assert_eq!(CommandConfig::new("true", vec![]).cpu_affinity(), None);
+    assert_eq!(
+        CommandConfig::new("true", vec![])
+            .with_cpu_affinity(&[0, 2])
+            .cpu_affinity(),
+        Some(&[0, 2][..])
+    );
+}
+
+#[test]
+fn test_cpu_affinity_applied_to_child() {
+    let config = CommandConfig::new("sleep", vec!["5".to_string()]).with_cpu_affinity(&[0]);
+    let mut task = spawn(&config).unwrap();
+
+    assert_eq!(cpus_allowed(task.pid()), "0");
+
+    task.kill().unwrap();
+}
+
+#[test]
+fn test_cpu_affinity_with_report_execution() {
+    let config = CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            "grep Cpus_allowed_list /proc/self/status".to_string(),
+        ],
+    )
+    .with_cpu_affinity(&[0]);
+
+    let report = execute_with_report(&config).unwrap();
+    let stdout = String::from_utf8(report.output.stdout).unwrap();
+    assert_eq!(stdout.split_whitespace().last(), Some("0"));
+}
+
+#[test]
+fn test_cpu_affinity_out_of_range_fails_to_spawn() {
+    let config = CommandConfig::new("true", vec![]).with_cpu_affinity(&[1 << 20]);
+    assert!(execute_with_report(&config).is_err());
+}
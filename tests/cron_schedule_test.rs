@@ -0,0 +1,120 @@
+use execute::{CommandConfig, CommandPool, ConfigError, CronSchedule, ExecutionConfig};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// UTC 时间戳（秒）对应的 SystemTime
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn next(expression: &str, after: u64) -> u64 {
+    CronSchedule::parse(expression)
+        .unwrap()
+        .next_after(at(after))
+        .unwrap()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+// 2024-01-01 00:00:00 UTC，星期一
+const JAN_1_2024: u64 = 1_704_067_200;
+
+#[test]
+fn test_every_five_minutes() {
+    assert_eq!(next("*/5 * * * *", JAN_1_2024), JAN_1_2024 + 300);
+    assert_eq!(next("*/5 * * * *", JAN_1_2024 + 301), JAN_1_2024 + 600);
+}
+
+#[test]
+fn test_fixed_time_rolls_to_next_day() {
+    // 02:30 已过，下一次是第二天 02:30
+    let after = JAN_1_2024 + 3 * 3600;
+    assert_eq!(
+        next("30 2 * * *", after),
+        JAN_1_2024 + 86_400 + 2 * 3600 + 1800
+    );
+}
+
+#[test]
+fn test_day_of_week_and_sunday_alias() {
+    // 2024-01-06 是星期六，2024-01-07 是星期日
+    assert_eq!(next("0 0 * * 6", JAN_1_2024), JAN_1_2024 + 5 * 86_400);
+    assert_eq!(next("0 0 * * 0", JAN_1_2024), JAN_1_2024 + 6 * 86_400);
+    assert_eq!(next("0 0 * * 7", JAN_1_2024), JAN_1_2024 + 6 * 86_400);
+}
+
+#[test]
+fn test_day_of_month_or_day_of_week() {
+    // 日和周都受限时满足任一即可：1 月 15 日或星期三（1 月 3 日）
+    assert_eq!(next("0 0 15 * 3", JAN_1_2024), JAN_1_2024 + 2 * 86_400);
+}
+
+#[test]
+fn test_leap_day() {
+    // 下一个 2 月 29 日是 2024-02-29
+    assert_eq!(next("0 12 29 2 *", JAN_1_2024), 1_709_208_000);
+}
+
+#[test]
+fn test_seconds_field_lists_and_ranges() {
+    assert_eq!(next("15,45 * * * * *", JAN_1_2024), JAN_1_2024 + 15);
+    assert_eq!(next("15,45 * * * * *", JAN_1_2024 + 15), JAN_1_2024 + 45);
+    assert_eq!(
+        next("0 10-20/5 * * * *", JAN_1_2024 + 700),
+        JAN_1_2024 + 900
+    );
+}
+
+#[test]
+fn test_impossible_schedule_has_no_next() {
+    let schedule = CronSchedule::parse("0 0 31 2 *").unwrap();
+    assert_eq!(schedule.next_after(at(JAN_1_2024)), None);
+}
+
+#[test]
+fn test_invalid_expressions() {
+    for expression in [
+        "* * * *",
+        "60 * * * *",
+        "* 24 * * *",
+        "*/0 * * * *",
+        "5-1 * * * *",
+        "a * * * *",
+    ] {
+        assert!(
+            matches!(
+                CronSchedule::parse(expression),
+                Err(ConfigError::InvalidCronExpression(..))
+            ),
+            "{expression}"
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_schedule_recurring_until_cancelled() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(1));
+    pool.start_executor();
+
+    let recurring = pool
+        .schedule_recurring("* * * * * *", CommandConfig::new("true", vec![]))
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(2500));
+    recurring.cancel();
+    let runs = recurring.runs();
+    assert!((2..=3).contains(&runs), "runs = {runs}");
+
+    std::thread::sleep(Duration::from_millis(1500));
+    assert_eq!(recurring.runs(), runs);
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_schedule_recurring_rejects_invalid_expression() {
+    let pool = CommandPool::new();
+    assert!(
+        pool.schedule_recurring("every minute", CommandConfig::new("true", vec![]))
+            .is_err()
+    );
+}
@@ -0,0 +1,118 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{pool, sleep_task};
+use execute::{CommandConfig, TaskStatus};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_duplicate_skipped_while_queued() {
+    // 执行器未启动，任务停留在队列中
+    let pool = pool(2);
+    let first = pool.push_task_dedup("job", sleep_task("0")).unwrap();
+    let second = pool.push_task_dedup("job", sleep_task("0")).unwrap();
+
+    assert!(first.is_some());
+    assert!(second.is_none());
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn test_duplicate_skipped_while_running() {
+    let pool = pool(2);
+    pool.start_executor();
+
+    let first = pool
+        .push_task_dedup("job", sleep_task("0.3"))
+        .unwrap()
+        .unwrap();
+    for _ in 0..200 {
+        if pool.status(first.id()) == Some(TaskStatus::Running) {
+            break;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(pool.status(first.id()), Some(TaskStatus::Running));
+
+    assert!(
+        pool.push_task_dedup("job", sleep_task("0"))
+            .unwrap()
+            .is_none()
+    );
+    first.wait().unwrap();
+}
+
+#[test]
+fn test_key_released_after_completion() {
+    let pool = pool(2);
+    pool.start_executor();
+
+    let first = pool
+        .push_task_dedup("job", CommandConfig::new("false", vec![]))
+        .unwrap()
+        .unwrap();
+    first.wait().unwrap();
+
+    // 失败的任务同样释放键
+    let second = pool.push_task_dedup("job", sleep_task("0")).unwrap();
+    assert!(second.is_some());
+    second.unwrap().wait().unwrap();
+}
+
+#[test]
+fn test_different_keys_are_independent() {
+    let pool = pool(2);
+    assert!(
+        pool.push_task_dedup("a", sleep_task("0"))
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        pool.push_task_dedup("b", sleep_task("0"))
+            .unwrap()
+            .is_some()
+    );
+    // 不带去重键的任务不受影响
+    pool.push_task(sleep_task("0")).unwrap();
+    assert_eq!(pool.len(), 3);
+}
+
+#[test]
+fn test_key_released_after_clear() {
+    let pool = pool(2);
+    assert!(
+        pool.push_task_dedup("job", sleep_task("0"))
+            .unwrap()
+            .is_some()
+    );
+    assert_eq!(pool.clear(), 1);
+    assert!(
+        pool.push_task_dedup("job", sleep_task("0"))
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn test_concurrent_dedup_submissions_enqueue_once() {
+    let pool = pool(2);
+    let accepted: usize = thread::scope(|scope| {
+        (0..8)
+            .map(|_| {
+                scope.spawn(|| {
+                    pool.push_task_dedup("job", sleep_task("0"))
+                        .unwrap()
+                        .is_some() as usize
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|t| t.join().unwrap())
+            .sum()
+    });
+
+    assert_eq!(accepted, 1);
+    assert_eq!(pool.len(), 1);
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use common::echo;
+use execute::CommandPool;
+
+#[test]
+fn test_drain_returns_pending_configs_in_order() {
+    let pool = CommandPool::new();
+    pool.push_task(echo("low")).unwrap();
+    pool.push_task(echo("high").with_priority(10)).unwrap();
+    pool.push_task(echo("low-2")).unwrap();
+
+    let drained = pool.drain();
+    let args: Vec<String> = drained.iter().map(|c| c.args()[0].clone()).collect();
+    assert_eq!(args, ["high", "low", "low-2"]);
+    assert!(pool.is_empty());
+    assert!(pool.statuses().is_empty());
+}
+
+#[test]
+fn test_drain_empty_pool() {
+    let pool = CommandPool::new();
+    assert!(pool.drain().is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_drained_tasks_run_on_another_pool() {
+    let old = CommandPool::new();
+    for i in 0..3 {
+        old.push_task(echo(&i.to_string())).unwrap();
+    }
+
+    let new = CommandPool::new();
+    new.start_executor();
+    let handles: Vec<_> = old
+        .drain()
+        .into_iter()
+        .map(|config| new.push_task(config).unwrap())
+        .collect();
+
+    let outputs: Vec<Vec<u8>> = handles.iter().map(|h| h.wait().unwrap().stdout).collect();
+    assert_eq!(outputs, [b"0\n".to_vec(), b"1\n".to_vec(), b"2\n".to_vec()]);
+    // 原命令池已空闲
+    assert!(old.wait_idle_timeout(std::time::Duration::from_millis(100)));
+}
@@ -70,7 +70,9 @@ fn verify_error_context(
     match error {
         CommandError::SpawnFailed { context, .. }
         | CommandError::ExecutionFailed { context, .. }
-        | CommandError::Timeout { context, .. } => {
+        | CommandError::Timeout { context, .. }
+        | CommandError::UnexpectedExit { context, .. }
+        | CommandError::ExitStatus { context, .. } => {
             // 验证需求 3.3: 包含任务 ID
             assert_eq!(
                 context.task_id, expected_task_id,
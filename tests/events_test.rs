@@ -0,0 +1,198 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::pool;
+use execute::{CommandConfig, CommandPool, FinishStatus, PoolEvent, TaskState};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+/// 收集事件直到满足条件或超时
+fn collect_until(
+    events: &Receiver<PoolEvent>,
+    done: impl Fn(&[PoolEvent]) -> bool,
+) -> Vec<PoolEvent> {
+    let mut collected = Vec::new();
+    while !done(&collected) {
+        match events.recv_timeout(Duration::from_secs(5)) {
+            Ok(event) => collected.push(event),
+            Err(_) => panic!("timed out waiting for events: {:?}", collected),
+        }
+    }
+    collected
+}
+
+fn finished(events: &[PoolEvent]) -> usize {
+    events
+        .iter()
+        .filter(|e| matches!(e, PoolEvent::TaskFinished { .. }))
+        .count()
+}
+
+#[test]
+fn test_task_lifecycle_events() {
+    let pool = pool(1);
+    let events = pool.subscribe();
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(CommandConfig::new(
+            "sh",
+            vec!["-c".to_string(), "exit 3".to_string()],
+        ))
+        .unwrap();
+    let task_id = handle.id();
+    handle.wait().unwrap();
+
+    let collected = collect_until(&events, |e| finished(e) == 1);
+    assert!(collected.contains(&PoolEvent::WorkerStarted { worker: 0 }));
+    assert!(collected.contains(&PoolEvent::TaskQueued {
+        task_id,
+        command: "sh".to_string(),
+    }));
+
+    let started = collected
+        .iter()
+        .find_map(|e| match e {
+            PoolEvent::TaskStarted { task_id: id, pid } if *id == task_id => Some(*pid),
+            _ => None,
+        })
+        .expect("TaskStarted event");
+    assert!(started > 0);
+
+    match collected.last().unwrap() {
+        PoolEvent::TaskFinished {
+            task_id: id,
+            status,
+            duration,
+        } => {
+            assert_eq!(*id, task_id);
+            assert_eq!(*status, FinishStatus::Failed { exit_code: Some(3) });
+            assert!(*duration > Duration::ZERO);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_multiple_subscribers_receive_same_stream() {
+    let pool = pool(2);
+    let a = pool.subscribe();
+    let b = pool.subscribe();
+    pool.start_executor();
+
+    for _ in 0..3 {
+        pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    }
+
+    let from_a = collect_until(&a, |e| finished(e) == 3);
+    let from_b = collect_until(&b, |e| finished(e) == 3);
+    assert_eq!(finished(&from_a), finished(&from_b));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_running_task_exposes_pid() {
+    let pool = pool(1);
+    let events = pool.subscribe();
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(CommandConfig::new("sleep", vec!["0.3".to_string()]))
+        .unwrap();
+    let collected = collect_until(&events, |e| {
+        e.iter().any(|e| matches!(e, PoolEvent::TaskStarted { .. }))
+    });
+    let pid = collected
+        .iter()
+        .find_map(|e| match e {
+            PoolEvent::TaskStarted { pid, .. } => Some(*pid),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(handle.state(), TaskState::Running { pid: Some(pid) });
+
+    handle.wait().unwrap();
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_timeout_and_cancel_statuses() {
+    let pool = pool(1);
+    let events = pool.subscribe();
+
+    let timed_out = pool
+        .push_task(
+            CommandConfig::new("sleep", vec!["5".to_string()])
+                .with_timeout(Duration::from_millis(100)),
+        )
+        .unwrap();
+    let cancelled = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    cancelled.cancel().unwrap();
+    pool.start_executor();
+
+    let collected = collect_until(&events, |e| finished(e) == 2);
+    let statuses: Vec<_> = collected
+        .iter()
+        .filter_map(|e| match e {
+            PoolEvent::TaskFinished {
+                task_id, status, ..
+            } => Some((*task_id, status.clone())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (timed_out.id(), FinishStatus::TimedOut),
+            (cancelled.id(), FinishStatus::Cancelled),
+        ]
+    );
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_queue_high_watermark_is_edge_triggered() {
+    let pool = CommandPool::builder()
+        .with_workers(1)
+        .with_queue_high_watermark(3)
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+
+    // 执行器未启动，队列只增不减
+    for _ in 0..5 {
+        pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    }
+
+    let watermarks: Vec<_> = events
+        .try_iter()
+        .filter(|e| matches!(e, PoolEvent::QueueHighWatermark { .. }))
+        .collect();
+    assert_eq!(watermarks, vec![PoolEvent::QueueHighWatermark { depth: 3 }]);
+}
+
+#[test]
+fn test_worker_stopped_on_shutdown() {
+    let pool = pool(2);
+    let events = pool.subscribe();
+    pool.start_executor();
+    pool.push_task(CommandConfig::new("true", vec![]))
+        .unwrap()
+        .wait()
+        .unwrap();
+    pool.shutdown().unwrap();
+
+    let stopped = collect_until(&events, |e| {
+        e.iter()
+            .filter(|e| matches!(e, PoolEvent::WorkerStopped { .. }))
+            .count()
+            == 2
+    });
+    assert!(stopped.contains(&PoolEvent::WorkerStopped { worker: 0 }));
+    assert!(stopped.contains(&PoolEvent::WorkerStopped { worker: 1 }));
+}
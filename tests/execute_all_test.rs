@@ -0,0 +1,68 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandPool, ExecutionConfig, SubmitError};
+
+#[test]
+fn test_results_in_input_order() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(4));
+    pool.start_executor();
+
+    // 先提交的任务睡得更久，完成顺序与输入顺序相反
+    let tasks = (0..4)
+        .map(|i| shell(&format!("sleep 0.{}; echo {i}", 4 - i)))
+        .collect();
+    let results = pool.execute_all(tasks).unwrap();
+
+    let outputs: Vec<String> = results
+        .into_iter()
+        .map(|r| String::from_utf8(r.unwrap().stdout).unwrap())
+        .collect();
+    assert_eq!(outputs, ["0\n", "1\n", "2\n", "3\n"]);
+}
+
+#[test]
+fn test_failures_reported_per_task() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(2));
+    pool.start_executor();
+
+    let results = pool
+        .execute_all(vec![shell("exit 0"), shell("exit 3"), shell("exit 0")])
+        .unwrap();
+    let codes: Vec<Option<i32>> = results
+        .iter()
+        .map(|r| r.as_ref().unwrap().status.code())
+        .collect();
+    assert_eq!(codes, [Some(0), Some(3), Some(0)]);
+}
+
+#[test]
+fn test_more_tasks_than_bounded_queue() {
+    let pool = CommandPool::with_config_and_limit(ExecutionConfig::new().with_workers(2), 2);
+    pool.start_executor();
+
+    let results = pool
+        .execute_all((0..10).map(|i| shell(&format!("echo {i}"))).collect())
+        .unwrap();
+    assert_eq!(results.len(), 10);
+    assert_eq!(results[9].as_ref().unwrap().stdout, b"9\n");
+}
+
+#[test]
+fn test_empty_batch() {
+    let pool = CommandPool::new();
+    assert!(pool.execute_all(Vec::new()).unwrap().is_empty());
+}
+
+#[test]
+fn test_rejected_after_shutdown() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+    pool.shutdown().unwrap();
+    assert!(matches!(
+        pool.execute_all(vec![shell("true")]),
+        Err(SubmitError::ShuttingDown)
+    ));
+}
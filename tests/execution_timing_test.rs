@@ -0,0 +1,74 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{
+    CommandPool, ExecutionConfig, PoolRetryPolicy, RetryOn, RetryStrategy, execute_with_report,
+};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// 系统临时目录下的唯一计数文件路径
+fn counter_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("execute-timing-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_direct_execution_reports_timing() {
+    let before = SystemTime::now();
+    let report = execute_with_report(&shell("sleep 0.2")).unwrap();
+    let timing = report.timing.unwrap();
+
+    assert!(timing.started_at >= before);
+    assert!(timing.duration >= Duration::from_millis(200));
+    assert!(timing.duration < Duration::from_secs(2));
+    assert_eq!(timing.queue_wait, Duration::ZERO);
+    assert_eq!(timing.attempt, 1);
+}
+
+#[test]
+fn test_pool_reports_queue_wait_separately() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(1));
+    pool.start_executor();
+
+    let first = pool.push_task(shell("sleep 0.3")).unwrap();
+    let second = pool.push_task(shell("sleep 0.1")).unwrap();
+
+    let first = first.wait_report().unwrap().timing.unwrap();
+    let second = second.wait_report().unwrap().timing.unwrap();
+    assert!(first.queue_wait < Duration::from_millis(200));
+    assert!(second.queue_wait >= Duration::from_millis(250));
+    assert!(second.duration >= Duration::from_millis(100));
+    assert!(second.duration < Duration::from_millis(250));
+    assert!(second.started_at >= first.started_at + first.duration);
+}
+
+#[test]
+fn test_pool_retry_reports_attempt_number() {
+    let counter = counter_path("attempt");
+    let pool = CommandPool::builder()
+        .with_workers(1)
+        .with_retry_policy(
+            PoolRetryPolicy::new(3, RetryStrategy::FixedInterval(Duration::from_millis(200)))
+                .with_retry_on(&[RetryOn::NonZeroExit]),
+        )
+        .build()
+        .unwrap();
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(shell(&format!(
+            "echo run >> {0}; test $(wc -l < {0}) -gt 2",
+            counter.display()
+        )))
+        .unwrap();
+    let timing = handle.wait_report().unwrap().timing.unwrap();
+
+    assert_eq!(timing.attempt, 3);
+    // 重试的排队时间从计划重新执行的时间开始计算，不包括之前的执行和重试延迟
+    assert!(timing.queue_wait < Duration::from_millis(150));
+    let _ = std::fs::remove_file(&counter);
+}
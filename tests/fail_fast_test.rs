@@ -0,0 +1,93 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{shell, sleep_task};
+use execute::{CommandPool, ExecuteError, ExecutionConfig};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_pool_fail_fast_cancels_running_and_queued_tasks() {
+    let pool = CommandPool::builder()
+        .with_workers(2)
+        .with_fail_fast(true)
+        .build()
+        .unwrap();
+    pool.start_executor();
+
+    let start = Instant::now();
+    let failing = pool.push_task(shell("sleep 0.1; exit 1")).unwrap();
+    let others: Vec<_> = (0..4)
+        .map(|_| pool.push_task(sleep_task("5")).unwrap())
+        .collect();
+
+    assert_eq!(failing.wait().unwrap().status.code(), Some(1));
+    for handle in others {
+        assert!(matches!(handle.wait(), Err(ExecuteError::Cancelled(_))));
+    }
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(pool.first_failure(), Some(failing.id()));
+
+    // 触发后提交的任务同样被取消，重置后恢复执行
+    let after = pool.push_task(shell("true")).unwrap();
+    assert!(matches!(after.wait(), Err(ExecuteError::Cancelled(_))));
+    pool.reset_fail_fast();
+    assert!(pool.push_task(shell("true")).unwrap().wait().is_ok());
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_pool_without_fail_fast_keeps_running() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(2));
+    pool.start_executor();
+
+    let failing = pool.push_task(shell("exit 1")).unwrap();
+    let next = pool.push_task(shell("sleep 0.2; echo ok")).unwrap();
+
+    assert_eq!(failing.wait().unwrap().status.code(), Some(1));
+    assert_eq!(next.wait().unwrap().stdout, b"ok\n");
+    assert_eq!(pool.first_failure(), None);
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_batch_fail_fast_cancels_remaining_tasks() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(4));
+    pool.start_executor();
+
+    let start = Instant::now();
+    let configs = vec![
+        sleep_task("5"),
+        shell("sleep 0.1; exit 2"),
+        sleep_task("5"),
+        sleep_task("5"),
+    ];
+    let mut stream = pool.submit_batch(configs).unwrap().fail_fast().ordered();
+    let results: Vec<_> = stream.by_ref().collect();
+
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(stream.first_failure(), Some(1));
+    assert_eq!(results[1].1.as_ref().unwrap().status.code(), Some(2));
+    for index in [0, 2, 3] {
+        assert!(matches!(results[index].1, Err(ExecuteError::Cancelled(_))));
+    }
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_batch_fail_fast_without_failures() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(4));
+    pool.start_executor();
+
+    let mut stream = pool
+        .submit_batch((0..3).map(|_| shell("true")))
+        .unwrap()
+        .fail_fast();
+    assert!(stream.by_ref().all(|(_, result)| result.is_ok()));
+    assert_eq!(stream.first_failure(), None);
+
+    pool.shutdown().unwrap();
+}
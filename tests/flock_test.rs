@@ -0,0 +1,97 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandConfig, CommandPool, ExecuteError, execute_with_report};
+use std::path::PathBuf;
+use std::thread;
+
+/// 为每个测试创建独立的目录
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("execute-flock-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// 同一把锁下的区间都应成对出现，不会交错
+fn assert_serialized(log: &str, runs: usize) {
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines.len(), runs * 2, "log: {:?}", lines);
+    for pair in lines.chunks(2) {
+        assert_eq!(pair, ["start", "end"], "log: {:?}", lines);
+    }
+}
+
+#[test]
+fn test_flock_creates_lock_file() {
+    let dir = test_dir("create");
+    let lock = dir.join("job.lock");
+
+    let config = CommandConfig::new("true", vec![]).with_flock(&lock);
+    assert_eq!(config.flock(), Some(lock.as_path()));
+
+    execute_with_report(&config).unwrap();
+    assert!(lock.exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_flock_serializes_concurrent_runs() {
+    let dir = test_dir("serialize");
+    let lock = dir.join("job.lock");
+    let log = dir.join("log");
+    let script = format!(
+        "echo start >> {log}; sleep 0.2; echo end >> {log}",
+        log = log.display()
+    );
+
+    let handles: Vec<_> = (0..3)
+        .map(|_| {
+            let config = shell(&script).with_flock(&lock);
+            thread::spawn(move || execute_with_report(&config).unwrap())
+        })
+        .collect();
+    for handle in handles {
+        assert!(handle.join().unwrap().output.status.success());
+    }
+
+    assert_serialized(&std::fs::read_to_string(&log).unwrap(), 3);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_flock_serializes_across_pool_workers() {
+    let dir = test_dir("pool");
+    let lock = dir.join("job.lock");
+    let log = dir.join("log");
+    let script = format!(
+        "echo start >> {log}; sleep 0.1; echo end >> {log}",
+        log = log.display()
+    );
+
+    let pool = CommandPool::new();
+    pool.start_executor();
+    let handles: Vec<_> = (0..4)
+        .map(|_| pool.push_task(shell(&script).with_flock(&lock)).unwrap())
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    pool.shutdown().unwrap();
+
+    assert_serialized(&std::fs::read_to_string(&log).unwrap(), 4);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_flock_unopenable_path_is_io_error() {
+    let config =
+        CommandConfig::new("true", vec![]).with_flock("/nonexistent/execute-flock/job.lock");
+    assert!(matches!(
+        execute_with_report(&config),
+        Err(ExecuteError::Io(_))
+    ));
+}
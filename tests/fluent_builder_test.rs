@@ -0,0 +1,63 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, CommandPool, Execute, ExecuteError, ExecutionConfig};
+use std::time::Duration;
+
+#[test]
+fn test_builder_produces_equivalent_config() {
+    let config = Execute::cmd("ls")
+        .arg("-l")
+        .args(["-a", "-h"])
+        .cwd("/tmp")
+        .timeout(Duration::from_secs(3))
+        .build();
+
+    let expected = CommandConfig::new("ls", vec!["-l".into(), "-a".into(), "-h".into()])
+        .with_working_dir("/tmp")
+        .with_timeout(Duration::from_secs(3));
+    assert_eq!(config, expected);
+}
+
+#[test]
+fn test_builder_run_applies_cwd_and_env() {
+    let output = Execute::cmd("sh")
+        .arg("-c")
+        .arg("pwd; echo $GREETING")
+        .cwd(std::path::Path::new("/"))
+        .env("GREETING", "hi")
+        .run()
+        .unwrap();
+    assert_eq!(output.stdout, b"/\nhi\n");
+}
+
+#[test]
+fn test_builder_submit_to_pool() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(2));
+    pool.start_executor();
+
+    let handle = Execute::cmd("echo").arg("pooled").submit(&pool).unwrap();
+    assert_eq!(handle.wait().unwrap().stdout, b"pooled\n");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_builder_timeout_and_configure() {
+    let result = Execute::cmd("sleep")
+        .arg("5")
+        .timeout(Duration::from_millis(100))
+        .run();
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+
+    let builder = Execute::cmd("true")
+        .no_timeout()
+        .configure(|c| c.with_singleton_key("only-one"));
+    assert_eq!(builder.config().timeout(), None);
+    assert_eq!(builder.config().singleton_key(), Some("only-one"));
+}
+
+#[test]
+fn test_builder_spawn() {
+    let task = Execute::cmd("echo").arg("spawned").spawn().unwrap();
+    assert_eq!(task.wait().unwrap().stdout, b"spawned\n");
+}
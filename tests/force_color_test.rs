@@ -0,0 +1,49 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, EnvConfig, execute_with_report};
+
+fn env_of(config: CommandConfig) -> String {
+    let report = execute_with_report(&config).unwrap();
+    String::from_utf8(report.output.stdout).unwrap()
+}
+
+fn print_env() -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            "echo \"$FORCE_COLOR|$CLICOLOR_FORCE|$TERM\"".to_string(),
+        ],
+    )
+}
+
+#[test]
+fn test_force_color_disabled_by_default() {
+    assert!(!print_env().force_color());
+    let env = EnvConfig::new().no_inherit();
+    assert_eq!(env_of(print_env().with_env(env)), "||\n");
+}
+
+#[test]
+fn test_force_color_sets_conventional_env() {
+    let config = print_env().with_force_color(true);
+    assert!(config.force_color());
+    assert_eq!(env_of(config), "1|1|xterm-256color\n");
+}
+
+#[test]
+fn test_force_color_survives_no_inherit() {
+    let config = print_env()
+        .with_env(EnvConfig::new().no_inherit())
+        .with_force_color(true);
+    assert_eq!(env_of(config), "1|1|xterm-256color\n");
+}
+
+#[test]
+fn test_explicit_env_overrides_force_color() {
+    let env = EnvConfig::new()
+        .set("TERM", "dumb")
+        .remove("CLICOLOR_FORCE");
+    let config = print_env().with_env(env).with_force_color(true);
+    assert_eq!(env_of(config), "1||dumb\n");
+}
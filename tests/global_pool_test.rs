@@ -0,0 +1,28 @@
+mod common;
+
+use common::echo;
+use execute::{ConfigError, ExecutionConfig, global_pool, init_global_pool};
+
+// 共享命令池在整个测试进程中只有一个，初始化顺序相关的断言放在同一个测试里
+#[test]
+fn test_init_then_share_global_pool() {
+    let pool = init_global_pool(ExecutionConfig::default().with_workers(2)).unwrap();
+    assert!(pool.is_running());
+    assert!(std::ptr::eq(pool, global_pool()));
+
+    assert!(matches!(
+        init_global_pool(ExecutionConfig::default()),
+        Err(ConfigError::GlobalPoolInitialized)
+    ));
+
+    let handle = global_pool().push_task(echo("hi")).unwrap();
+    assert!(handle.wait().unwrap().status.success());
+}
+
+#[test]
+fn test_init_rejects_zero_workers() {
+    assert!(matches!(
+        init_global_pool(ExecutionConfig::default().with_workers(0)),
+        Err(ConfigError::InvalidThreadCount(0))
+    ));
+}
@@ -122,3 +122,62 @@ fn test_health_check_details() {
     // 清理 - 使用 shutdown 而不是 stop
     let _ = pool.shutdown_with_timeout(Duration::from_secs(2));
 }
+
+#[test]
+fn test_health_check_on_demand_workers_healthy() {
+    let pool = CommandPool::builder()
+        .with_workers(8)
+        .with_min_idle_workers(1)
+        .with_idle_timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    pool.start_executor();
+    std::thread::sleep(Duration::from_millis(100));
+
+    let health = pool.health_check();
+    assert_eq!(health.status, HealthStatus::Healthy);
+    assert_eq!(health.details.workers_total, 8);
+    assert!(health.details.workers_alive < 8);
+
+    // 突发任务按需启动更多工作线程，空闲超时后退出
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            pool.push_task(CommandConfig::new("sleep", vec!["0.2".to_string()]))
+                .unwrap()
+        })
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(300));
+    assert_eq!(pool.health_check().status, HealthStatus::Healthy);
+
+    let _ = pool.shutdown_with_timeout(Duration::from_secs(2));
+}
+
+#[test]
+fn test_health_check_degraded_after_worker_panic() {
+    use execute::testing::{MockBackend, MockResponse};
+    use std::sync::Arc;
+
+    let backend = Arc::new(MockBackend::new().on("crash", MockResponse::panic("boom")));
+    let pool = CommandPool::with_backend(ExecutionConfig::new().with_workers(2), backend);
+    pool.start_executor();
+    let _handle = pool.push_task(CommandConfig::new("crash", vec![])).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+
+    let health = pool.health_check();
+    assert_eq!(health.details.workers_alive, 1);
+    match health.status {
+        HealthStatus::Degraded { issues } => {
+            assert!(
+                issues
+                    .iter()
+                    .any(|issue| issue.contains("exited unexpectedly"))
+            );
+        }
+        status => panic!("expected Degraded, got {status:?}"),
+    }
+
+    let _ = pool.shutdown_with_timeout(Duration::from_secs(2));
+}
@@ -0,0 +1,95 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::sleep_task;
+use execute::{CommandConfig, CommandPool, PoolEvent};
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 等待 `wait` 后统计工作线程启动和退出事件数
+fn worker_events(events: &Receiver<PoolEvent>, wait: Duration) -> (usize, usize) {
+    thread::sleep(wait);
+    events
+        .try_iter()
+        .fold((0, 0), |(started, stopped), event| match event {
+            PoolEvent::WorkerStarted { .. } => (started + 1, stopped),
+            PoolEvent::WorkerStopped { .. } => (started, stopped + 1),
+            _ => (started, stopped),
+        })
+}
+
+#[test]
+fn test_idle_workers_exit_down_to_one() {
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_idle_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_executor();
+    assert_eq!(worker_events(&events, Duration::from_millis(400)), (4, 3));
+}
+
+#[test]
+fn test_idle_workers_exit_down_to_min_idle() {
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_min_idle_workers(2)
+        .with_idle_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_executor();
+    assert_eq!(worker_events(&events, Duration::from_millis(100)), (2, 0));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| pool.push_task(sleep_task("0.2")).unwrap())
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    // 任务结束后多出的两个工作线程空闲退出
+    assert_eq!(worker_events(&events, Duration::from_millis(400)), (2, 2));
+}
+
+#[test]
+fn test_exited_workers_respawn_on_demand() {
+    let pool = CommandPool::builder()
+        .with_workers(3)
+        .with_idle_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_executor();
+    assert_eq!(worker_events(&events, Duration::from_millis(400)), (3, 2));
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..3)
+        .map(|_| pool.push_task(sleep_task("0.3")).unwrap())
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    assert!(start.elapsed() < Duration::from_millis(800));
+    let (started, _) = worker_events(&events, Duration::ZERO);
+    assert_eq!(started, 2);
+}
+
+#[test]
+fn test_affinity_task_after_worker_exit() {
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_idle_timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    pool.start_executor();
+    thread::sleep(Duration::from_millis(200));
+    for key in ["a", "b", "c", "d", "e", "f"] {
+        let handle = pool
+            .push_task(CommandConfig::new("echo", vec![key.to_string()]).with_affinity_key(key))
+            .unwrap();
+        assert!(handle.wait().unwrap().status.success());
+    }
+}
@@ -0,0 +1,121 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandConfig, CommandPool, ExecuteError, InputConfig, execute_with_report};
+use std::path::PathBuf;
+
+/// 为每个测试创建独立的目录
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("execute-inputs-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_bytes_staged_into_temp_workdir() {
+    let config = shell("cat job.txt")
+        .with_temp_workdir()
+        .with_inputs(InputConfig::new().with_bytes("job.txt", "payload"));
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.status.success());
+    assert_eq!(report.output.stdout, b"payload");
+}
+
+#[test]
+fn test_file_copied_into_nested_path() {
+    let src = test_dir("copy-src");
+    std::fs::write(src.join("data.csv"), "a,b\n1,2\n").unwrap();
+
+    let config = shell("cat data/in/input.csv")
+        .with_temp_workdir()
+        .with_inputs(InputConfig::new().with_file("data/in/input.csv", src.join("data.csv")));
+
+    let report = execute_with_report(&config).unwrap();
+    assert_eq!(report.output.stdout, b"a,b\n1,2\n");
+
+    std::fs::remove_dir_all(&src).unwrap();
+}
+
+#[test]
+fn test_inputs_cleaned_up_after_run() {
+    let dir = test_dir("cleanup");
+    let config = shell("test -f staged.txt")
+        .with_working_dir(dir.to_str().unwrap())
+        .with_inputs(
+            InputConfig::new()
+                .with_bytes("staged.txt", "x")
+                .with_cleanup(true),
+        );
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.status.success());
+    assert!(!dir.join("staged.txt").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_inputs_kept_without_cleanup() {
+    let dir = test_dir("keep");
+    let config = CommandConfig::new("true", vec![])
+        .with_working_dir(dir.to_str().unwrap())
+        .with_inputs(InputConfig::new().with_bytes("kept.txt", "x"));
+
+    execute_with_report(&config).unwrap();
+    assert_eq!(std::fs::read(dir.join("kept.txt")).unwrap(), b"x");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_escaping_input_path_rejected() {
+    for path in ["../escape.txt", "/tmp/absolute.txt", "a/../../b"] {
+        let config = CommandConfig::new("true", vec![])
+            .with_temp_workdir()
+            .with_inputs(InputConfig::new().with_bytes(path, "x"));
+
+        let err = execute_with_report(&config).unwrap_err();
+        match err {
+            ExecuteError::Io(e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput),
+            other => panic!("unexpected error for {}: {:?}", path, other),
+        }
+    }
+}
+
+#[test]
+fn test_missing_source_file_fails_before_spawn() {
+    let dir = test_dir("missing-src");
+    let config = shell("touch ran.txt")
+        .with_working_dir(dir.to_str().unwrap())
+        .with_inputs(InputConfig::new().with_file("in.txt", "/nonexistent/execute-input"));
+
+    assert!(matches!(
+        execute_with_report(&config),
+        Err(ExecuteError::Io(_))
+    ));
+    assert!(!dir.join("ran.txt").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_pool_stages_inputs() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(
+            shell("sh script.sh")
+                .with_temp_workdir()
+                .with_inputs(InputConfig::new().with_bytes("script.sh", "echo staged")),
+        )
+        .unwrap();
+    let output = handle.wait().unwrap();
+    assert_eq!(output.stdout, b"staged\n");
+
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,54 @@
+#![cfg(target_os = "linux")]
+
+use execute::{CommandConfig, IoPriority, IoPriorityClass, execute_with_report, spawn};
+use nix::libc;
+
+fn ioprio_of(pid: u32) -> i64 {
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid as libc::c_int) }
+}
+
+#[test]
+fn test_io_priority_level_is_clamped() {
+    let config =
+        CommandConfig::new("true", vec![]).with_io_priority(IoPriorityClass::BestEffort, 42);
+    assert_eq!(
+        config.io_priority(),
+        Some(IoPriority {
+            class: IoPriorityClass::BestEffort,
+            level: 7,
+        })
+    );
+}
+
+#[test]
+fn test_io_priority_applied_to_child() {
+    let config = CommandConfig::new("sleep", vec!["5".to_string()])
+        .with_io_priority(IoPriorityClass::BestEffort, 6);
+    let mut task = spawn(&config).unwrap();
+
+    // IOPRIO_CLASS_BE (2) << 13 | 6
+    assert_eq!(ioprio_of(task.pid()), (2 << 13) | 6);
+
+    task.kill().unwrap();
+}
+
+#[test]
+fn test_idle_io_priority_applied_to_child() {
+    let config = CommandConfig::new("sleep", vec!["5".to_string()])
+        .with_io_priority(IoPriorityClass::Idle, 0);
+    let mut task = spawn(&config).unwrap();
+
+    assert_eq!(ioprio_of(task.pid()) >> 13, 3);
+
+    task.kill().unwrap();
+}
+
+#[test]
+fn test_io_priority_with_report_execution() {
+    let config = CommandConfig::new("echo", vec!["ok".to_string()])
+        .with_io_priority(IoPriorityClass::Idle, 0);
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.status.success());
+    assert_eq!(report.output.stdout, b"ok\n");
+}
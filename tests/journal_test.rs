@@ -0,0 +1,116 @@
+#![cfg(all(unix, feature = "persistence"))]
+
+mod common;
+
+use common::echo;
+use execute::{CommandConfig, CommandPool, ShutdownMode, TaskJournal};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// 系统临时目录下的唯一日志路径
+fn journal_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "execute-journal-{}-{}.journal",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_unfinished_tasks_are_replayed() {
+    let path = journal_path("replay");
+    {
+        // 任务入队后进程“崩溃”，没有执行
+        let pool = CommandPool::new().with_journal(TaskJournal::open(&path).unwrap());
+        pool.push_task(echo("first")).unwrap();
+        pool.push_task(echo("second")).unwrap();
+    }
+
+    let journal = TaskJournal::open(&path).unwrap();
+    assert_eq!(journal.pending(), 2);
+    let pool = CommandPool::new().with_journal(journal);
+    pool.start_executor();
+    let outputs: Vec<String> = pool
+        .replay_journal()
+        .unwrap()
+        .into_iter()
+        .map(|handle| String::from_utf8(handle.wait().unwrap().stdout).unwrap())
+        .collect();
+    assert_eq!(outputs, ["first\n", "second\n"]);
+    pool.shutdown().unwrap();
+
+    assert_eq!(TaskJournal::open(&path).unwrap().pending(), 0);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_finished_tasks_are_acknowledged() {
+    let path = journal_path("auto-ack");
+    let pool = CommandPool::new().with_journal(TaskJournal::open(&path).unwrap());
+    pool.start_executor();
+    pool.push_task(echo("done")).unwrap().wait().unwrap();
+    pool.push_task(CommandConfig::new("false", vec![]))
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(pool.replay_journal().unwrap().is_empty());
+    pool.shutdown().unwrap();
+
+    assert_eq!(TaskJournal::open(&path).unwrap().pending(), 0);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_manual_ack() {
+    let path = journal_path("manual-ack");
+    let pool = CommandPool::new().with_journal(TaskJournal::open(&path).unwrap().with_manual_ack());
+    pool.start_executor();
+    let acked = pool.push_task(echo("acked")).unwrap();
+    let unacked = pool.push_task(echo("unacked")).unwrap();
+    acked.wait().unwrap();
+    unacked.wait().unwrap();
+
+    assert!(pool.acknowledge(acked.id()).unwrap());
+    assert!(!pool.acknowledge(acked.id()).unwrap());
+    pool.shutdown().unwrap();
+
+    let pool = CommandPool::new().with_journal(TaskJournal::open(&path).unwrap());
+    pool.start_executor();
+    let replayed = pool.replay_journal().unwrap();
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].wait().unwrap().stdout, b"unacked\n");
+    pool.shutdown().unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_tasks_cancelled_by_shutdown_stay_in_journal() {
+    let path = journal_path("shutdown");
+    let pool = CommandPool::new().with_journal(TaskJournal::open(&path).unwrap());
+    pool.push_task(echo("later")).unwrap();
+    pool.shutdown_with_mode(ShutdownMode::Abort).unwrap();
+
+    assert_eq!(TaskJournal::open(&path).unwrap().pending(), 1);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_torn_last_entry_is_ignored() {
+    let path = journal_path("torn");
+    {
+        let pool = CommandPool::new().with_journal(TaskJournal::open(&path).unwrap());
+        pool.push_task(echo("kept")).unwrap();
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    file.write_all(br#"{"op":"enqueue","seq":2,"con"#).unwrap();
+    drop(file);
+
+    let journal = TaskJournal::open(&path).unwrap();
+    assert_eq!(journal.pending(), 1);
+    std::fs::remove_file(path).unwrap();
+}
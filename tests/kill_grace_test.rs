@@ -0,0 +1,92 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, ExecuteError, execute_with_report, spawn};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 系统临时目录下的唯一标记文件路径
+fn marker_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "execute-kill-grace-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// 收到 SIGTERM 时写入标记文件后退出
+fn trap_term(marker: &Path) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            format!(
+                "trap 'echo cleaned > {}; exit 0' TERM; sleep 10 & wait",
+                marker.display()
+            ),
+        ],
+    )
+    .with_timeout(Duration::from_millis(300))
+}
+
+#[test]
+fn test_kill_grace_default_is_none() {
+    assert_eq!(CommandConfig::new("true", vec![]).kill_grace(), None);
+}
+
+#[test]
+fn test_timeout_without_grace_skips_cleanup() {
+    let marker = marker_path("no-grace");
+
+    let result = execute_with_report(&trap_term(&marker));
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_timeout_with_grace_allows_cleanup() {
+    let marker = marker_path("grace");
+    let config = trap_term(&marker).with_kill_grace(Duration::from_secs(5));
+
+    let start = Instant::now();
+    let result = execute_with_report(&config);
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+    // 子进程处理完 SIGTERM 后立即退出，不必等满宽限期
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(std::fs::read_to_string(&marker).unwrap(), "cleaned\n");
+
+    std::fs::remove_file(marker).unwrap();
+}
+
+#[test]
+fn test_grace_expiry_force_kills() {
+    let config = CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            "trap '' TERM; sleep 10 & wait".to_string(),
+        ],
+    )
+    .with_timeout(Duration::from_millis(200))
+    .with_kill_grace(Duration::from_millis(300));
+
+    let start = Instant::now();
+    let result = execute_with_report(&config);
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(500));
+    assert!(elapsed < Duration::from_secs(5));
+}
+
+#[test]
+fn test_spawned_task_uses_grace() {
+    let marker = marker_path("spawned");
+    let config = trap_term(&marker).with_kill_grace(Duration::from_secs(5));
+
+    let task = spawn(&config).unwrap();
+    assert!(matches!(task.wait(), Err(ExecuteError::Timeout(_))));
+    assert_eq!(std::fs::read_to_string(&marker).unwrap(), "cleaned\n");
+
+    std::fs::remove_file(marker).unwrap();
+}
@@ -0,0 +1,121 @@
+#![cfg(target_os = "linux")]
+
+use execute::{CommandConfig, ExecuteError, execute_with_report};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 系统临时目录下的唯一 PID 文件路径
+fn pid_path(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("execute-kill-tree-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// 启动一个孙进程并把其 PID 写入 `pid_file`
+fn spawn_grandchild(pid_file: &Path) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            format!("sleep 100 & echo $! > {}; wait", pid_file.display()),
+        ],
+    )
+    .with_timeout(Duration::from_millis(300))
+}
+
+fn read_pid(pid_file: &Path) -> u32 {
+    std::fs::read_to_string(pid_file)
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap()
+}
+
+/// 进程是否存活（僵尸进程视为已退出）
+fn is_alive(pid: u32) -> bool {
+    match std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+        Ok(stat) => !stat
+            .rsplit_once(')')
+            .unwrap()
+            .1
+            .trim_start()
+            .starts_with('Z'),
+        Err(_) => false,
+    }
+}
+
+fn wait_until_dead(pid: u32) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < deadline {
+        if !is_alive(pid) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+#[test]
+fn test_timeout_leaves_grandchild_by_default() {
+    let pid_file = pid_path("default");
+    let config = spawn_grandchild(&pid_file);
+    assert!(!config.kill_tree());
+
+    let result = execute_with_report(&config);
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+
+    let grandchild = read_pid(&pid_file);
+    assert!(is_alive(grandchild));
+
+    execute::process_util::kill_tree(
+        grandchild,
+        execute::process_util::Signal::SIGKILL,
+        Duration::ZERO,
+    )
+    .unwrap();
+    std::fs::remove_file(pid_file).unwrap();
+}
+
+#[test]
+fn test_timeout_kills_process_tree() {
+    let pid_file = pid_path("tree");
+    let config = spawn_grandchild(&pid_file).with_kill_tree(true);
+
+    let start = Instant::now();
+    let result = execute_with_report(&config);
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    assert!(wait_until_dead(read_pid(&pid_file)));
+    std::fs::remove_file(pid_file).unwrap();
+}
+
+#[test]
+fn test_kill_tree_with_grace() {
+    let pid_file = pid_path("grace");
+    let config = spawn_grandchild(&pid_file)
+        .with_kill_tree(true)
+        .with_kill_grace(Duration::from_secs(5));
+
+    let start = Instant::now();
+    let result = execute_with_report(&config);
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+    // sleep 收到 SIGTERM 后立即退出，不必等满宽限期
+    assert!(start.elapsed() < Duration::from_secs(3));
+
+    assert!(wait_until_dead(read_pid(&pid_file)));
+    std::fs::remove_file(pid_file).unwrap();
+}
+
+#[test]
+fn test_kill_tree_child_leads_process_group() {
+    let config = CommandConfig::new("sh", vec!["-c".to_string(), "ps -o pgid= $$".to_string()])
+        .with_kill_tree(true);
+    let report = execute_with_report(&config).unwrap();
+    let pgid: u32 = String::from_utf8_lossy(&report.output.stdout)
+        .trim()
+        .parse()
+        .unwrap();
+    assert_ne!(pgid, nix::unistd::getpgrp().as_raw() as u32);
+}
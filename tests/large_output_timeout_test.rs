@@ -0,0 +1,67 @@
+#![cfg(unix)]
+
+use execute::{
+    CommandConfig, ResourceLimits, TimeoutConfig, execute_command_with_context,
+    execute_with_report, execute_with_timeouts,
+};
+use std::time::{Duration, Instant};
+
+/// 输出 1 MiB，远超管道缓冲区
+fn large_output() -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            "head -c 1048576 /dev/zero; head -c 1048576 /dev/zero >&2".to_string(),
+        ],
+    )
+}
+
+#[test]
+fn test_large_output_with_timeout_completes() {
+    let start = Instant::now();
+    let report =
+        execute_with_report(&large_output().with_timeout(Duration::from_secs(10))).unwrap();
+
+    assert!(report.output.status.success());
+    assert_eq!(report.output.stdout.len(), 1 << 20);
+    assert_eq!(report.output.stderr.len(), 1 << 20);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_large_output_with_context_and_timeout_completes() {
+    let start = Instant::now();
+    let output =
+        execute_command_with_context(&large_output().with_timeout(Duration::from_secs(10)), 1)
+            .unwrap();
+
+    assert_eq!(output.stdout.len(), 1 << 20);
+    assert_eq!(output.stderr.len(), 1 << 20);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_output_limit_keeps_draining_pipe() {
+    let config = large_output()
+        .with_timeout(Duration::from_secs(10))
+        .with_resource_limits(ResourceLimits::new().with_max_output_size(1024));
+    let start = Instant::now();
+    let output = execute_command_with_context(&config, 1).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout.len(), 1024);
+    assert_eq!(output.stderr.len(), 1024);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_large_output_with_execution_timeout_completes() {
+    let config = large_output()
+        .with_timeouts(TimeoutConfig::new().with_execution_timeout(Duration::from_secs(10)));
+    let start = Instant::now();
+    let output = execute_with_timeouts(&config, 1).unwrap();
+
+    assert_eq!(output.stdout.len(), 1 << 20);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,98 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::sleep_task;
+use execute::{CommandConfig, CommandPool, ExecutionConfig, PoolEvent};
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 短暂等待后统计已启动的工作线程数
+fn started_workers(events: &Receiver<PoolEvent>) -> usize {
+    thread::sleep(Duration::from_millis(100));
+    events
+        .try_iter()
+        .filter(|event| matches!(event, PoolEvent::WorkerStarted { .. }))
+        .count()
+}
+
+#[test]
+fn test_only_min_idle_workers_prestarted() {
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_min_idle_workers(2)
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_executor();
+    assert_eq!(started_workers(&events), 2);
+}
+
+#[test]
+fn test_all_workers_started_by_default() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(4));
+    let events = pool.subscribe();
+    pool.start_executor();
+    assert_eq!(started_workers(&events), 4);
+}
+
+#[test]
+fn test_workers_grow_with_burst() {
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_min_idle_workers(1)
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_executor();
+    assert_eq!(started_workers(&events), 1);
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..4)
+        .map(|_| pool.push_task(sleep_task("0.3")).unwrap())
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    // 四个任务并行执行
+    assert!(start.elapsed() < Duration::from_millis(900));
+    assert_eq!(started_workers(&events), 3);
+}
+
+#[test]
+fn test_affinity_tasks_start_their_worker() {
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_min_idle_workers(1)
+        .build()
+        .unwrap();
+    pool.start_executor();
+    let handles: Vec<_> = ["a", "b", "c", "d", "e", "f", "g", "h"]
+        .iter()
+        .map(|key| {
+            pool.push_task(CommandConfig::new("echo", vec![key.to_string()]).with_affinity_key(key))
+                .unwrap()
+        })
+        .collect();
+    for handle in handles {
+        assert!(handle.wait().unwrap().status.success());
+    }
+}
+
+#[test]
+fn test_restart_prestarts_again() {
+    let pool = CommandPool::builder()
+        .with_workers(3)
+        .with_min_idle_workers(1)
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_executor();
+    assert_eq!(started_workers(&events), 1);
+    pool.stop();
+    pool.start_executor();
+    assert_eq!(started_workers(&events), 1);
+    let handle = pool.push_task(sleep_task("0")).unwrap();
+    handle.wait().unwrap();
+}
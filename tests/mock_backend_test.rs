@@ -0,0 +1,103 @@
+use execute::testing::{MockBackend, MockResponse};
+use execute::{CommandConfig, CommandPool, ExecuteError, ExecutionBackend, ExecutionConfig};
+use std::sync::Arc;
+
+fn cmd(program: &str, args: &[&str]) -> CommandConfig {
+    CommandConfig::new(program, args.iter().map(|a| a.to_string()).collect())
+}
+
+fn pool(backend: Arc<MockBackend>) -> CommandPool {
+    let pool = CommandPool::with_backend(ExecutionConfig::new().with_workers(2), backend);
+    pool.start_executor();
+    pool
+}
+
+#[test]
+fn test_queued_results_are_consumed_in_order() {
+    let backend = MockBackend::new().on("rsync", MockResponse::success("fallback"));
+    backend.enqueue("rsync", MockResponse::exit(23));
+    backend.enqueue("rsync", MockResponse::error("connection reset"));
+
+    assert_eq!(
+        backend.execute(&cmd("rsync", &[])).unwrap().status.code(),
+        Some(23)
+    );
+    assert!(matches!(
+        backend.execute(&cmd("rsync", &[])),
+        Err(ExecuteError::Io(_))
+    ));
+    assert_eq!(
+        backend.execute(&cmd("rsync", &[])).unwrap().stdout,
+        b"fallback"
+    );
+    backend.assert_executed("rsync", 3);
+}
+
+#[test]
+fn test_queue_is_per_program() {
+    let backend = MockBackend::new();
+    backend.enqueue("scp", MockResponse::exit(1));
+
+    assert!(
+        backend
+            .execute(&cmd("rsync", &[]))
+            .unwrap()
+            .status
+            .success()
+    );
+    assert_eq!(
+        backend.execute(&cmd("scp", &[])).unwrap().status.code(),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_backend_drives_pool_dispatch() {
+    let backend = Arc::new(
+        MockBackend::new()
+            .on_args("rsync", &["-a", "*", "*"], MockResponse::success("sent"))
+            .with_default(MockResponse::exit(127)),
+    );
+    let pool = pool(Arc::clone(&backend));
+
+    let handles: Vec<_> = (0..3)
+        .map(|i| {
+            let src = format!("src{}", i);
+            pool.push_task(cmd("rsync", &["-a", &src, "dest"])).unwrap()
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.wait().unwrap().stdout, b"sent");
+    }
+    let other = pool.push_task(cmd("unknown", &[])).unwrap();
+    assert_eq!(other.wait().unwrap().status.code(), Some(127));
+
+    backend.assert_executed("rsync", 3);
+    backend.assert_not_executed("scp");
+    assert_eq!(backend.calls().len(), 4);
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_enqueue_after_pool_start() {
+    let backend = Arc::new(MockBackend::new());
+    let pool = pool(Arc::clone(&backend));
+
+    backend.enqueue("deploy", MockResponse::exit(3).with_stderr("rollback"));
+    let output = pool.push_task(cmd("deploy", &[])).unwrap().wait().unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(output.stderr, b"rollback");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+#[should_panic(
+    expected = "expected \"rsync\" to be executed 3 time(s), but it was executed 1 time(s)"
+)]
+fn test_assert_executed_reports_mismatch() {
+    let backend = MockBackend::new();
+    backend.execute(&cmd("rsync", &[])).unwrap();
+    backend.assert_executed("rsync", 3);
+}
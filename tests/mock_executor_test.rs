@@ -0,0 +1,101 @@
+use execute::testing::{MockExecutor, MockResponse};
+use execute::{CommandConfig, CommandExecutor, CommandPool, ExecuteError, ExecutionConfig};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn cmd(program: &str, args: &[&str]) -> CommandConfig {
+    CommandConfig::new(program, args.iter().map(|a| a.to_string()).collect())
+}
+
+#[test]
+fn test_first_matching_rule_wins() {
+    let mock = MockExecutor::new()
+        .on_args(
+            "git",
+            &["push", "*"],
+            MockResponse::exit(128).with_stderr("denied"),
+        )
+        .on("git", MockResponse::success("clean"))
+        .with_default(MockResponse::error("not found"));
+
+    let push = mock.execute(&cmd("git", &["push", "origin"])).unwrap();
+    assert_eq!(push.status.code(), Some(128));
+    assert_eq!(push.stderr, b"denied");
+
+    let status = mock.execute(&cmd("git", &["status"])).unwrap();
+    assert!(status.status.success());
+    assert_eq!(status.stdout, b"clean");
+
+    // 参数个数不同不匹配带参数模式的规则
+    let push_all = mock.execute(&cmd("git", &["push"])).unwrap();
+    assert!(push_all.status.success());
+
+    assert!(matches!(
+        mock.execute(&cmd("hg", &[])),
+        Err(ExecuteError::Io(_))
+    ));
+}
+
+#[test]
+fn test_calls_are_recorded() {
+    let mock = MockExecutor::new();
+    mock.execute(&cmd("rsync", &["a", "b"])).unwrap();
+    mock.execute(&cmd("ls", &[])).unwrap();
+    mock.execute(&cmd("rsync", &["c", "d"])).unwrap();
+
+    assert_eq!(mock.call_count("rsync"), 2);
+    assert_eq!(mock.call_count("cp"), 0);
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 3);
+    assert_eq!(calls[1].program(), "ls");
+}
+
+#[test]
+fn test_delay_longer_than_timeout_times_out() {
+    let mock = MockExecutor::new().on(
+        "slow",
+        MockResponse::success("late").with_delay(Duration::from_secs(5)),
+    );
+
+    let start = Instant::now();
+    let config = cmd("slow", &[]).with_timeout(Duration::from_millis(100));
+    let result = mock.execute(&config);
+    assert!(matches!(result, Err(ExecuteError::Timeout(t)) if t == Duration::from_millis(100)));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_scripted_timeout_and_panic() {
+    let mock = MockExecutor::new()
+        .on("hang", MockResponse::timeout_after(Duration::from_secs(30)))
+        .on("crash", MockResponse::panic("executor failure"));
+
+    assert!(matches!(
+        mock.execute(&cmd("hang", &[])),
+        Err(ExecuteError::Timeout(t)) if t == Duration::from_secs(30)
+    ));
+
+    let panicked = std::panic::catch_unwind(|| mock.execute(&cmd("crash", &[])));
+    assert!(panicked.is_err());
+    assert_eq!(mock.call_count("crash"), 1);
+}
+
+#[test]
+fn test_mock_executor_drives_pool() {
+    let mock = Arc::new(
+        MockExecutor::new()
+            .on("deploy", MockResponse::success("deployed\n"))
+            .on("fail", MockResponse::exit(2)),
+    );
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(2));
+    pool.start_with_executor(Arc::clone(&mock));
+
+    let deploy = pool.push_task(cmd("deploy", &["prod"])).unwrap();
+    let fail = pool.push_task(cmd("fail", &[])).unwrap();
+
+    assert_eq!(deploy.wait().unwrap().stdout, b"deployed\n");
+    assert_eq!(fail.wait().unwrap().status.code(), Some(2));
+    assert_eq!(mock.call_count("deploy"), 1);
+
+    pool.shutdown().unwrap();
+}
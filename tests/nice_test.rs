@@ -0,0 +1,39 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, execute_with_report, spawn};
+use nix::libc;
+
+fn nice_of(pid: u32) -> i32 {
+    unsafe { libc::getpriority(libc::PRIO_PROCESS as _, pid as libc::id_t) }
+}
+
+#[test]
+fn test_nice_is_clamped() {
+    assert_eq!(CommandConfig::new("true", vec![]).nice(), None);
+    assert_eq!(
+        CommandConfig::new("true", vec![]).with_nice(42).nice(),
+        Some(19)
+    );
+    assert_eq!(
+        CommandConfig::new("true", vec![]).with_nice(-42).nice(),
+        Some(-20)
+    );
+}
+
+#[test]
+fn test_nice_applied_to_child() {
+    let config = CommandConfig::new("sleep", vec!["5".to_string()]).with_nice(12);
+    let mut task = spawn(&config).unwrap();
+
+    assert_eq!(nice_of(task.pid()), 12);
+
+    task.kill().unwrap();
+}
+
+#[test]
+fn test_nice_with_report_execution() {
+    let config = CommandConfig::new("nice", vec![]).with_nice(7);
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.status.success());
+    assert_eq!(report.output.stdout, b"7\n");
+}
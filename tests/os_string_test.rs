@@ -0,0 +1,105 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, CommandLine, CommandPool, Execute, execute_with_report};
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+
+/// 不是合法 UTF-8 的文件名
+fn non_utf8_name() -> OsString {
+    OsString::from_vec(b"data-\xff\xfe.bin".to_vec())
+}
+
+/// 在系统临时目录下创建唯一的测试目录
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("execute-os-string-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_str_api_still_works() {
+    let config = CommandConfig::new("echo", vec!["hello".to_string()])
+        .with_arg("world")
+        .with_working_dir("/tmp");
+
+    assert_eq!(config.program(), "echo");
+    assert_eq!(config.args(), &["hello".to_string(), "world".to_string()]);
+    assert_eq!(config.working_dir(), Some("/tmp"));
+
+    assert_eq!(config.program_os(), "echo");
+    assert_eq!(config.args_os(), ["hello", "world"]);
+    assert_eq!(config.working_dir_path(), Some(Path::new("/tmp")));
+}
+
+#[test]
+fn test_str_getters_are_lossy() {
+    let dir = PathBuf::from(non_utf8_name());
+    let config = CommandConfig::new(non_utf8_name(), vec![])
+        .with_arg(non_utf8_name())
+        .with_working_dir_path(&dir);
+
+    assert_eq!(config.program(), "data-\u{fffd}\u{fffd}.bin");
+    assert_eq!(config.args(), &["data-\u{fffd}\u{fffd}.bin".to_string()]);
+    assert_eq!(config.working_dir(), None);
+
+    assert_eq!(config.program_os(), non_utf8_name());
+    assert_eq!(config.args_os(), [non_utf8_name()]);
+    assert_eq!(config.working_dir_path(), Some(dir.as_path()));
+}
+
+#[test]
+fn test_non_utf8_argument_passed_verbatim() {
+    let config = CommandConfig::new("printf", vec!["%s".to_string()]).with_arg(non_utf8_name());
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.status.success());
+    assert_eq!(report.output.stdout, non_utf8_name().as_bytes());
+}
+
+#[test]
+fn test_non_utf8_working_dir() {
+    let dir = scratch_dir("cwd").join(non_utf8_name());
+    std::fs::create_dir(&dir).unwrap();
+
+    let config = CommandConfig::new("pwd", vec![]).with_working_dir_path(&dir);
+    assert_eq!(config.working_dir_path(), Some(dir.as_path()));
+
+    let report = execute_with_report(&config).unwrap();
+    let stdout = &report.output.stdout;
+    let cwd = Path::new(OsStr::from_bytes(stdout.strip_suffix(b"\n").unwrap()));
+    assert_eq!(cwd.file_name(), dir.file_name());
+
+    std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_program_from_path() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let output = Execute::cmd(Path::new("/bin/echo"))
+        .arg(Path::new("from-path"))
+        .submit(&pool)
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "from-path\n");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_command_line_wrap_keeps_os_strings() {
+    let mut command = CommandLine {
+        program: "cat".into(),
+        args: vec![non_utf8_name()],
+    };
+    command.wrap("nice", ["-n", "19"]);
+
+    assert_eq!(command.program, "nice");
+    assert_eq!(command.args[2], "cat");
+    assert_eq!(command.args[3], non_utf8_name());
+}
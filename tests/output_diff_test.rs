@@ -0,0 +1,88 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandConfig, CommandPool, OutputChange, OutputDiffConfig};
+
+fn run(pool: &CommandPool, config: CommandConfig) -> Option<OutputChange> {
+    pool.push_task(config)
+        .unwrap()
+        .wait_report()
+        .unwrap()
+        .output_change
+}
+
+#[test]
+fn test_output_change_disabled_by_default() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    assert_eq!(run(&pool, shell("echo hi")), None);
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_output_change_tracks_previous_run() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let diff = OutputDiffConfig::new("status");
+    let first = run(&pool, shell("echo a").with_output_diff(diff.clone()));
+    assert_eq!(first, Some(OutputChange::First));
+
+    let same = run(&pool, shell("echo a").with_output_diff(diff.clone()));
+    assert_eq!(same, Some(OutputChange::Unchanged));
+
+    let changed = run(&pool, shell("echo b").with_output_diff(diff.clone())).unwrap();
+    assert!(changed.is_changed());
+    assert_eq!(changed, OutputChange::Changed { diff: None });
+
+    // 不同键互不影响
+    let other = run(
+        &pool,
+        shell("echo b").with_output_diff(OutputDiffConfig::new("other")),
+    );
+    assert_eq!(other, Some(OutputChange::First));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_output_change_includes_unified_diff() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let diff = OutputDiffConfig::new("lines").with_unified_diff(true);
+    run(
+        &pool,
+        shell("printf '1\\n2\\n3\\n'").with_output_diff(diff.clone()),
+    );
+    let change = run(&pool, shell("printf '1\\nX\\n3\\n'").with_output_diff(diff));
+
+    assert_eq!(
+        change,
+        Some(OutputChange::Changed {
+            diff: Some("--- previous\n+++ current\n@@ -1,3 +1,3 @@\n 1\n-2\n+X\n 3\n".to_string()),
+        })
+    );
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_output_history_shared_with_sub_pool() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+    let sub = pool.sub_pool("sub", 1);
+    sub.start_executor();
+
+    let diff = OutputDiffConfig::new("shared");
+    run(&pool, shell("echo a").with_output_diff(diff.clone()));
+    let change = run(&sub, shell("echo a").with_output_diff(diff));
+    assert_eq!(change, Some(OutputChange::Unchanged));
+
+    sub.shutdown().unwrap();
+    pool.shutdown().unwrap();
+}
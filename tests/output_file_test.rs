@@ -0,0 +1,111 @@
+use execute::{CommandConfig, ExecuteError, OutputFile, OutputMode, execute_with_report};
+use std::path::PathBuf;
+
+/// 系统临时目录下的唯一文件路径
+fn file_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "execute-output-file-{}-{}.log",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn echo_both(text: &str) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            format!("echo {text}-out; echo {text}-err >&2"),
+        ],
+    )
+}
+
+#[test]
+fn test_stdout_file_leaves_stderr_captured() {
+    let path = file_path("stdout-only");
+    let config = echo_both("a").with_stdout_file(&path, false);
+    assert_eq!(config.stdout_file(), Some(&OutputFile::new(&path, false)));
+    assert_eq!(config.stderr_file(), None);
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.stdout.is_empty());
+    assert_eq!(report.output.stderr, b"a-err\n");
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "a-out\n");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_separate_files_for_each_stream() {
+    let out = file_path("split-out");
+    let err = file_path("split-err");
+    let config = echo_both("b")
+        .with_stdout_file(&out, false)
+        .with_stderr_file(&err, false);
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.stdout.is_empty());
+    assert!(report.output.stderr.is_empty());
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "b-out\n");
+    assert_eq!(std::fs::read_to_string(&err).unwrap(), "b-err\n");
+
+    std::fs::remove_file(out).unwrap();
+    std::fs::remove_file(err).unwrap();
+}
+
+#[test]
+fn test_truncate_and_append() {
+    let path = file_path("modes");
+
+    execute_with_report(&echo_both("first").with_stdout_file(&path, true)).unwrap();
+    execute_with_report(&echo_both("second").with_stdout_file(&path, true)).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "first-out\nsecond-out\n"
+    );
+
+    execute_with_report(&echo_both("third").with_stdout_file(&path, false)).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "third-out\n");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_same_file_for_both_streams() {
+    let path = file_path("shared");
+    let config = echo_both("c")
+        .with_stdout_file(&path, false)
+        .with_stderr_file(&path, false);
+
+    execute_with_report(&config).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "c-out\nc-err\n");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_stream_file_overrides_output_mode() {
+    let path = file_path("override");
+    let config = echo_both("d")
+        .with_output_mode(OutputMode::Discard)
+        .with_stderr_file(&path, false);
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.stdout.is_empty());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "d-err\n");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_unwritable_stream_file_fails() {
+    let path = file_path("missing-dir").join("out.log");
+    let config = echo_both("e").with_stdout_file(path, true);
+
+    assert!(matches!(
+        execute_with_report(&config),
+        Err(ExecuteError::Io(_))
+    ));
+}
@@ -0,0 +1,75 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandConfig, ExecuteError, OutputStream, spawn, spawn_lines};
+use std::io::{ErrorKind, Write};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_lines_are_tagged_by_stream() {
+    let mut lines = spawn_lines(&shell("echo out; echo err >&2; printf 'last\\r\\n'")).unwrap();
+    let collected: Vec<_> = (&mut lines).map(Result::unwrap).collect();
+
+    let stdout: Vec<&str> = collected
+        .iter()
+        .filter(|line| line.stream == OutputStream::Stdout)
+        .map(|line| line.text.as_str())
+        .collect();
+    let stderr: Vec<&str> = collected
+        .iter()
+        .filter(|line| line.stream == OutputStream::Stderr)
+        .map(|line| line.text.as_str())
+        .collect();
+    assert_eq!(stdout, ["out", "last"]);
+    assert_eq!(stderr, ["err"]);
+    assert!(lines.wait().unwrap().success());
+}
+
+#[test]
+fn test_lines_arrive_before_exit_with_increasing_offsets() {
+    let start = Instant::now();
+    let mut lines = spawn_lines(&shell("echo first; sleep 0.3; echo second; sleep 1")).unwrap();
+
+    let first = lines.next().unwrap().unwrap();
+    assert_eq!(first.text, "first");
+    assert!(start.elapsed() < Duration::from_millis(800));
+
+    let second = lines.next().unwrap().unwrap();
+    assert_eq!(second.text, "second");
+    assert!(second.offset >= first.offset + Duration::from_millis(250));
+}
+
+#[test]
+fn test_lines_timeout_kills_child() {
+    let config = shell("echo started; sleep 5").with_timeout(Duration::from_millis(300));
+    let start = Instant::now();
+    let mut lines = spawn_lines(&config).unwrap();
+
+    assert_eq!(lines.next().unwrap().unwrap().text, "started");
+    let err = lines.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    assert!(lines.next().is_none());
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(matches!(lines.wait(), Err(ExecuteError::Timeout(_))));
+}
+
+#[test]
+fn test_into_lines_after_taking_stdin() {
+    let mut task = spawn(&CommandConfig::new("cat", vec![])).unwrap();
+    let mut stdin = task.stdin_writer().unwrap();
+    let lines = task.into_lines();
+    stdin.write_all(b"a\nb\n").unwrap();
+    drop(stdin);
+
+    let texts: Vec<String> = lines.map(|line| line.unwrap().text).collect();
+    assert_eq!(texts, ["a", "b"]);
+}
+
+#[test]
+fn test_wait_reports_exit_status() {
+    let mut lines = spawn_lines(&shell("echo bye; exit 3")).unwrap();
+    assert_eq!(lines.by_ref().count(), 1);
+    assert_eq!(lines.wait().unwrap().code(), Some(3));
+}
@@ -0,0 +1,97 @@
+use execute::{CommandConfig, CommandPool, ExecuteError, OutputMode, execute_with_report};
+use std::path::PathBuf;
+
+/// 系统临时目录下的唯一日志文件路径
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "execute-output-mode-{}-{}.log",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn echo_both(text: &str) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            format!("echo {text}-out; echo {text}-err >&2"),
+        ],
+    )
+}
+
+#[test]
+fn test_default_output_mode_captures() {
+    let config = echo_both("a");
+    assert_eq!(config.output_mode(), &OutputMode::Capture);
+
+    let report = execute_with_report(&config).unwrap();
+    assert_eq!(report.output.stdout, b"a-out\n");
+    assert_eq!(report.output.stderr, b"a-err\n");
+}
+
+#[test]
+fn test_discard_returns_empty_output() {
+    let config = echo_both("b").with_output_mode(OutputMode::Discard);
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.status.success());
+    assert!(report.output.stdout.is_empty());
+    assert!(report.output.stderr.is_empty());
+}
+
+#[test]
+fn test_inherit_returns_empty_output() {
+    let config = CommandConfig::new("true", vec![]).with_output_mode(OutputMode::Inherit);
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.status.success());
+    assert!(report.output.stdout.is_empty());
+}
+
+#[test]
+fn test_to_file_appends_both_streams() {
+    let path = log_path("append");
+    let mode = OutputMode::ToFile(path.clone());
+
+    execute_with_report(&echo_both("first").with_output_mode(mode.clone())).unwrap();
+    let report = execute_with_report(&echo_both("second").with_output_mode(mode)).unwrap();
+    assert!(report.output.stdout.is_empty());
+
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(log, "first-out\nfirst-err\nsecond-out\nsecond-err\n");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_to_file_unwritable_path_fails() {
+    let path = log_path("missing-dir").join("out.log");
+    let config = echo_both("c").with_output_mode(OutputMode::ToFile(path));
+
+    assert!(matches!(
+        execute_with_report(&config),
+        Err(ExecuteError::Io(_))
+    ));
+}
+
+#[test]
+fn test_to_file_through_pool() {
+    let path = log_path("pool");
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let config = echo_both("pooled").with_output_mode(OutputMode::ToFile(path.clone()));
+    let output = pool.push_task(config).unwrap().wait().unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "pooled-out\npooled-err\n"
+    );
+
+    pool.shutdown().unwrap();
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,70 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CaptureMode, CommandPool, Duration, OutputStream, execute_with_report};
+
+#[test]
+fn test_timeline_disabled_by_default() {
+    let report = execute_with_report(&shell("echo hi")).unwrap();
+    assert!(report.timeline.is_none());
+
+    let tail = shell("echo hi").with_capture_mode(CaptureMode::Tail(16));
+    assert!(execute_with_report(&tail).unwrap().timeline.is_none());
+}
+
+#[test]
+fn test_timeline_interleaves_streams_in_order() {
+    let config = shell("echo one; sleep 0.2; echo two >&2; sleep 0.2; echo three")
+        .with_capture_mode(CaptureMode::Timeline);
+    let report = execute_with_report(&config).unwrap();
+
+    // 完整输出仍然保留
+    assert_eq!(report.output.stdout, b"one\nthree\n");
+    assert_eq!(report.output.stderr, b"two\n");
+
+    let timeline = report.timeline.unwrap();
+    let streams: Vec<_> = timeline.iter().map(|chunk| chunk.stream).collect();
+    assert_eq!(
+        streams,
+        vec![
+            OutputStream::Stdout,
+            OutputStream::Stderr,
+            OutputStream::Stdout
+        ]
+    );
+    assert_eq!(timeline[1].data, b"two\n");
+    assert!(timeline.windows(2).all(|w| w[0].offset <= w[1].offset));
+    assert!(timeline[2].offset - timeline[0].offset >= Duration::from_millis(300));
+}
+
+#[test]
+fn test_timeline_with_timeout() {
+    let config = shell("echo early")
+        .with_capture_mode(CaptureMode::Timeline)
+        .with_timeout(Duration::from_secs(5));
+    let timeline = execute_with_report(&config).unwrap().timeline.unwrap();
+    let data: Vec<u8> = timeline.into_iter().flat_map(|chunk| chunk.data).collect();
+    assert_eq!(data, b"early\n");
+}
+
+#[test]
+fn test_timeline_from_pool_report() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let report = pool
+        .push_task(shell("echo out; echo err >&2").with_capture_mode(CaptureMode::Timeline))
+        .unwrap()
+        .wait_report()
+        .unwrap();
+    let timeline = report.timeline.unwrap();
+    assert!(
+        timeline
+            .iter()
+            .any(|chunk| chunk.stream == OutputStream::Stderr && chunk.data == b"err\n")
+    );
+
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,85 @@
+#![cfg(unix)]
+
+use execute::{
+    CommandConfig, CommandPool, ConfigError, ExecutionMode, PoolRetryPolicy, RetryOn,
+    RetryStrategy, SubmitError,
+};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[test]
+fn test_defaults_match_new() {
+    let pool = CommandPool::builder().build().unwrap();
+    assert_eq!(pool.max_size(), None);
+    assert!(!pool.is_running());
+}
+
+#[test]
+fn test_options_applied() {
+    let pool = CommandPool::builder()
+        .with_workers(2)
+        .with_mode(ExecutionMode::Thread)
+        .with_queue_limit(1)
+        .build()
+        .unwrap();
+    assert_eq!(pool.max_size(), Some(1));
+    assert_eq!(pool.execution_mode(), ExecutionMode::Thread);
+
+    pool.try_push_task(CommandConfig::new("true", vec![]))
+        .unwrap();
+    assert!(matches!(
+        pool.try_push_task(CommandConfig::new("true", vec![])),
+        Err(SubmitError::QueueFull)
+    ));
+}
+
+#[test]
+fn test_invalid_options_rejected() {
+    assert!(matches!(
+        CommandPool::builder().with_workers(0).build(),
+        Err(ConfigError::InvalidThreadCount(0))
+    ));
+    assert!(matches!(
+        CommandPool::builder().with_queue_limit(0).build(),
+        Err(ConfigError::InvalidQueueCapacity(0))
+    ));
+}
+
+#[test]
+fn test_callbacks_and_retry_policy() {
+    let completed = Arc::new(AtomicUsize::new(0));
+    let failed = Arc::new(AtomicUsize::new(0));
+    let pool = {
+        let completed = Arc::clone(&completed);
+        let failed = Arc::clone(&failed);
+        CommandPool::builder()
+            .with_workers(2)
+            .with_retry_policy(
+                PoolRetryPolicy::new(2, RetryStrategy::FixedInterval(Duration::from_millis(10)))
+                    .with_retry_on(&[RetryOn::NonZeroExit]),
+            )
+            .on_task_complete(move |_, _| {
+                completed.fetch_add(1, Ordering::SeqCst);
+            })
+            .on_task_failed(move |_, _| {
+                failed.fetch_add(1, Ordering::SeqCst);
+            })
+            .start()
+            .unwrap()
+    };
+    assert!(pool.is_running());
+
+    pool.push_task(CommandConfig::new("true", vec![]))
+        .unwrap()
+        .wait()
+        .unwrap();
+    let failing = pool.push_task(CommandConfig::new("false", vec![])).unwrap();
+    failing.wait().unwrap();
+    pool.wait_idle();
+
+    // 失败的任务重试两次后才结束，回调只在最终结束时调用
+    assert_eq!(failing.attempts(), 3);
+    assert_eq!(completed.load(Ordering::SeqCst), 2);
+    assert_eq!(failed.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,162 @@
+#![cfg(unix)]
+
+use execute::{
+    CommandConfig, CommandPool, ExecuteError, PoolEvent, PoolRetryPolicy, RetryOn, RetryPolicy,
+    RetryStrategy,
+};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+fn pool(policy: PoolRetryPolicy) -> CommandPool {
+    let pool = CommandPool::builder()
+        .with_workers(2)
+        .with_retry_policy(policy)
+        .build()
+        .unwrap();
+    pool.start_executor();
+    pool
+}
+
+fn fixed(max_attempts: usize, millis: u64) -> PoolRetryPolicy {
+    PoolRetryPolicy::new(
+        max_attempts,
+        RetryStrategy::FixedInterval(Duration::from_millis(millis)),
+    )
+}
+
+/// 系统临时目录下的唯一计数文件路径
+fn counter_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "execute-pool-retry-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// 每次执行追加一行到计数文件，前 `failures` 次以退出码 1 失败
+fn flaky(counter: &Path, failures: usize) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            format!(
+                "echo run >> {0}; test $(wc -l < {0}) -gt {1}",
+                counter.display(),
+                failures
+            ),
+        ],
+    )
+}
+
+fn runs(counter: &Path) -> usize {
+    std::fs::read_to_string(counter).unwrap().lines().count()
+}
+
+#[test]
+fn test_default_policy_retries_errors_and_timeouts() {
+    let policy = fixed(2, 10);
+    assert_eq!(policy.retry_on, [RetryOn::Error, RetryOn::Timeout]);
+}
+
+#[test]
+fn test_timeout_is_retried_until_attempts_exhausted() {
+    let pool = pool(fixed(2, 10));
+    let events = pool.subscribe();
+
+    let handle = pool
+        .push_task(
+            CommandConfig::new("sleep", vec!["5".to_string()])
+                .with_timeout(Duration::from_millis(100)),
+        )
+        .unwrap();
+    assert!(matches!(handle.wait(), Err(ExecuteError::Timeout(_))));
+    assert_eq!(handle.attempts(), 3);
+
+    let retries: Vec<u32> = events
+        .try_iter()
+        .filter_map(|event| match event {
+            PoolEvent::TaskRetrying { attempt, .. } => Some(attempt),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(retries, [1, 2]);
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_non_zero_exit_not_retried_by_default() {
+    let counter = counter_path("default");
+    let pool = pool(fixed(3, 10));
+
+    let output = pool.push_task(flaky(&counter, 1)).unwrap().wait().unwrap();
+    assert!(!output.status.success());
+    assert_eq!(runs(&counter), 1);
+
+    pool.shutdown().unwrap();
+    std::fs::remove_file(counter).unwrap();
+}
+
+#[test]
+fn test_non_zero_exit_retried_when_enabled() {
+    let counter = counter_path("enabled");
+    let pool = pool(fixed(3, 50).with_retry_on(&[RetryOn::NonZeroExit]));
+
+    let start = Instant::now();
+    let handle = pool.push_task(flaky(&counter, 2)).unwrap();
+    assert!(handle.wait().unwrap().status.success());
+    assert_eq!(handle.attempts(), 3);
+    assert_eq!(runs(&counter), 3);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    pool.shutdown().unwrap();
+    std::fs::remove_file(counter).unwrap();
+}
+
+#[test]
+fn test_task_retry_policy_takes_precedence() {
+    let counter = counter_path("task-policy");
+    let pool = pool(fixed(3, 10).with_retry_on(&[RetryOn::NonZeroExit]));
+
+    let config = flaky(&counter, 5).with_retry(RetryPolicy::new(
+        1,
+        RetryStrategy::FixedInterval(Duration::from_millis(10)),
+    ));
+    let handle = pool.push_task(config).unwrap();
+    assert!(!handle.wait().unwrap().status.success());
+    // 只按任务自身的策略重试一次，不再叠加命令池的三次重试
+    assert_eq!(handle.attempts(), 2);
+    assert_eq!(runs(&counter), 2);
+
+    pool.shutdown().unwrap();
+    std::fs::remove_file(counter).unwrap();
+}
+
+#[test]
+fn test_retry_waits_without_blocking_worker() {
+    let pool = CommandPool::builder()
+        .with_workers(1)
+        .with_retry_policy(fixed(1, 800))
+        .build()
+        .unwrap();
+    pool.start_executor();
+
+    let start = Instant::now();
+    let failing = pool
+        .push_task(
+            CommandConfig::new("sleep", vec!["5".to_string()])
+                .with_timeout(Duration::from_millis(50)),
+        )
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(150));
+    pool.push_task(CommandConfig::new("true", vec![]))
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_millis(700));
+
+    assert!(failing.wait().is_err());
+    assert_eq!(failing.attempts(), 2);
+    pool.shutdown().unwrap();
+}
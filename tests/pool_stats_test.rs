@@ -0,0 +1,98 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{sleep_task, started_pool};
+use execute::{CommandConfig, CommandPool, ExecuteError, PoolStats};
+use std::time::{Duration, Instant};
+
+/// 等待统计满足条件
+fn wait_for_stats(pool: &CommandPool, condition: impl Fn(&PoolStats) -> bool) -> PoolStats {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let stats = pool.stats();
+        if condition(&stats) || Instant::now() > deadline {
+            return stats;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_new_pool_has_empty_stats() {
+    assert_eq!(CommandPool::new().stats(), PoolStats::default());
+}
+
+#[test]
+fn test_finished_tasks_counted_by_outcome() {
+    let pool = started_pool(3);
+    let ok = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    let failed = pool.push_task(CommandConfig::new("false", vec![])).unwrap();
+    let timed_out = pool
+        .push_task(sleep_task("5").with_timeout(Duration::from_millis(100)))
+        .unwrap();
+    ok.wait().unwrap();
+    failed.wait().unwrap();
+    assert!(matches!(timed_out.wait(), Err(ExecuteError::Timeout(_))));
+
+    let stats = pool.stats();
+    assert_eq!(stats.completed, 1);
+    assert_eq!(stats.failed, 1);
+    assert_eq!(stats.timed_out, 1);
+    assert_eq!(stats.queued, 0);
+    assert_eq!(stats.running, 0);
+    assert!(stats.avg_run_time > Duration::ZERO);
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_queued_and_running_counts() {
+    let pool = started_pool(1);
+    let running = pool.push_task(sleep_task("5")).unwrap();
+    let queued: Vec<_> = (0..2)
+        .map(|_| pool.push_task(CommandConfig::new("true", vec![])).unwrap())
+        .collect();
+
+    let stats = wait_for_stats(&pool, |stats| stats.running == 1);
+    assert_eq!(stats.running, 1);
+    assert_eq!(stats.queued, 2);
+
+    running.cancel().unwrap();
+    for handle in queued {
+        handle.wait().unwrap();
+    }
+    let stats = wait_for_stats(&pool, |stats| stats.running == 0);
+    assert_eq!(stats.running, 0);
+    assert_eq!(stats.queued, 0);
+    assert_eq!(stats.completed, 2);
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_average_wait_time_includes_queueing() {
+    let pool = started_pool(1);
+    let first = pool.push_task(sleep_task("0.3")).unwrap();
+    let second = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    first.wait().unwrap();
+    second.wait().unwrap();
+
+    // 第一个任务几乎不用等待，第二个等待约 300ms
+    let stats = pool.stats();
+    assert!(stats.avg_wait_time >= Duration::from_millis(100));
+    assert!(stats.avg_run_time >= Duration::from_millis(100));
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_cancelled_tasks_not_counted() {
+    let pool = CommandPool::new();
+    let handle = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    handle.cancel().unwrap();
+    pool.start_executor();
+    assert!(matches!(handle.wait(), Err(ExecuteError::Cancelled(_))));
+
+    let stats = pool.stats();
+    assert_eq!(stats.completed + stats.failed + stats.timed_out, 0);
+    assert_eq!(stats.avg_wait_time, Duration::ZERO);
+    pool.shutdown().unwrap();
+}
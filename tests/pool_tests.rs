@@ -139,3 +139,59 @@ fn command_pool_try_push_with_limit() {
 
     assert_eq!(pool.len(), 5);
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn command_pool_staggers_worker_startup() {
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_worker_start_interval(std::time::Duration::from_millis(150))
+        .build()
+        .unwrap();
+
+    // 每个任务先输出启动时间（纳秒），再持续足够久，保证各自占用一个工作线程
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            pool.push_task(CommandConfig::new(
+                "sh",
+                vec!["-c".to_string(), "date +%s%N; sleep 0.6".to_string()],
+            ))
+            .unwrap()
+        })
+        .collect();
+    pool.start_executor();
+
+    let mut starts: Vec<u128> = handles
+        .into_iter()
+        .map(|h| {
+            let output = h.wait().unwrap();
+            String::from_utf8_lossy(&output.stdout)
+                .trim()
+                .parse()
+                .unwrap()
+        })
+        .collect();
+    starts.sort();
+
+    // 4 个工作线程依次间隔 150ms 启动，首末任务的启动时间至少相差约 450ms
+    let spread_ms = (starts[3] - starts[0]) / 1_000_000;
+    assert!(spread_ms >= 400, "start spread was {}ms", spread_ms);
+
+    pool.shutdown_with_timeout(std::time::Duration::from_secs(5))
+        .unwrap();
+}
+
+#[test]
+fn command_pool_shutdown_during_staggered_startup() {
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_worker_start_interval(std::time::Duration::from_secs(10))
+        .build()
+        .unwrap();
+    pool.start_executor();
+
+    let start = std::time::Instant::now();
+    pool.shutdown_with_timeout(std::time::Duration::from_secs(5))
+        .unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(2));
+}
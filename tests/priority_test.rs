@@ -0,0 +1,90 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, CommandPool, ExecutionConfig};
+
+fn record(log: &str, name: &str) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec!["-c".to_string(), format!("echo {name} >> {log}")],
+    )
+}
+
+/// 在单线程命令池启动前排好所有任务，按执行顺序返回任务名
+fn run_in_order(tasks: Vec<(&str, u8)>) -> Vec<String> {
+    let dir = std::env::temp_dir().join(format!(
+        "execute-priority-{}-{}",
+        std::process::id(),
+        tasks.len()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let log = dir.join("log");
+    let _ = std::fs::remove_file(&log);
+    let log_str = log.to_str().unwrap();
+
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(1));
+    let handles: Vec<_> = tasks
+        .iter()
+        .map(|(name, priority)| {
+            pool.push_task(record(log_str, name).with_priority(*priority))
+                .unwrap()
+        })
+        .collect();
+    pool.start_executor();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    pool.shutdown().unwrap();
+
+    let order = std::fs::read_to_string(&log)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+    order
+}
+
+#[test]
+fn test_default_priority_is_zero() {
+    assert_eq!(CommandConfig::new("true", vec![]).priority(), 0);
+}
+
+#[test]
+fn test_higher_priority_runs_first() {
+    let order = run_in_order(vec![
+        ("batch-1", 0),
+        ("batch-2", 0),
+        ("interactive", 200),
+        ("batch-3", 0),
+    ]);
+    assert_eq!(order, ["interactive", "batch-1", "batch-2", "batch-3"]);
+}
+
+#[test]
+fn test_equal_priority_keeps_submission_order() {
+    let order = run_in_order(vec![
+        ("low", 1),
+        ("high-1", 9),
+        ("mid", 5),
+        ("high-2", 9),
+        ("high-3", 9),
+    ]);
+    assert_eq!(order, ["high-1", "high-2", "high-3", "mid", "low"]);
+}
+
+#[test]
+fn test_push_task_with_priority() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(1));
+    let batch = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    let urgent = pool
+        .push_task_with_priority(CommandConfig::new("true", vec![]), 200)
+        .unwrap();
+    assert_eq!(pool.len(), 2);
+
+    // 单线程命令池应先取出高优先级任务
+    let first = pool.pop_task().unwrap();
+    assert_eq!(first.handle.id(), urgent.id());
+    assert_eq!(first.config.priority(), 200);
+    assert_eq!(pool.pop_task().unwrap().handle.id(), batch.id());
+    pool.shutdown().unwrap();
+}
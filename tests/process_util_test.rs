@@ -0,0 +1,111 @@
+#![cfg(unix)]
+
+use execute::process_util::{Signal, kill_tree};
+use std::io::{BufRead, BufReader};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// 启动一个带两个后台 sleep 子进程的 shell，返回 shell 进程和子进程 PID
+fn spawn_tree(script: &str, own_group: bool) -> (Child, Vec<u32>) {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", script]).stdout(Stdio::piped());
+    if own_group {
+        cmd.process_group(0);
+    }
+    let mut child = cmd.spawn().unwrap();
+    let mut reader = BufReader::new(child.stdout.take().unwrap());
+    let mut pids = Vec::new();
+    for _ in 0..2 {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        pids.push(line.trim().parse().unwrap());
+    }
+    (child, pids)
+}
+
+const TREE: &str = "sleep 30 & echo $!; sleep 30 & echo $!; wait";
+
+/// 进程已退出（不存在或为僵尸进程）
+fn is_gone(pid: u32) -> bool {
+    match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        Ok(stat) => stat
+            .rsplit_once(')')
+            .unwrap()
+            .1
+            .trim_start()
+            .starts_with('Z'),
+        Err(_) => true,
+    }
+}
+
+fn wait_gone(pid: u32) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < deadline {
+        if is_gone(pid) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_kill_tree_terminates_descendants() {
+    let (mut child, pids) = spawn_tree(TREE, false);
+
+    kill_tree(child.id(), Signal::SIGTERM, Duration::from_secs(1)).unwrap();
+
+    assert!(!child.wait().unwrap().success());
+    for pid in pids {
+        assert!(wait_gone(pid), "descendant {} still running", pid);
+    }
+}
+
+#[test]
+fn test_kill_tree_signals_process_group() {
+    let (mut child, pids) = spawn_tree(TREE, true);
+
+    kill_tree(child.id(), Signal::SIGKILL, Duration::ZERO).unwrap();
+
+    assert!(!child.wait().unwrap().success());
+    for pid in pids {
+        assert!(wait_gone(pid), "group member {} still running", pid);
+    }
+}
+
+#[test]
+fn test_kill_tree_escalates_after_grace() {
+    let mut child = Command::new("sh")
+        .args([
+            "-c",
+            "trap '' TERM; echo ready; while :; do sleep 0.05; done",
+        ])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+
+    let start = Instant::now();
+    kill_tree(child.id(), Signal::SIGTERM, Duration::from_millis(200)).unwrap();
+    let elapsed = start.elapsed();
+
+    let status = child.wait().unwrap();
+    assert!(!status.success());
+    assert!(elapsed >= Duration::from_millis(200));
+    assert!(elapsed < Duration::from_secs(2));
+}
+
+#[test]
+fn test_kill_tree_missing_process() {
+    let mut child = Command::new("true").spawn().unwrap();
+    let pid = child.id();
+    child.wait().unwrap();
+
+    let err = kill_tree(pid, Signal::SIGTERM, Duration::ZERO).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
@@ -0,0 +1,54 @@
+#![cfg(all(unix, feature = "pty"))]
+
+mod common;
+
+use common::shell;
+use execute::{CommandConfig, ExecuteError, ExecutionBackend, PtyBackend, execute_pty};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_pty_child_sees_terminal() {
+    let output = execute_pty(&shell(
+        "if [ -t 0 ] && [ -t 1 ] && [ -t 2 ]; then echo tty; else echo notty; fi",
+    ))
+    .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"tty\r\n");
+}
+
+#[test]
+fn test_pty_merges_stderr_into_stdout() {
+    let output = execute_pty(&shell("echo out; echo err >&2")).unwrap();
+
+    assert_eq!(output.stdout, b"out\r\nerr\r\n");
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_pty_backend_applies_window_size() {
+    let backend = PtyBackend::new().with_size(40, 132);
+    let output = backend
+        .execute(&CommandConfig::new("stty", vec!["size".to_string()]))
+        .unwrap();
+
+    assert_eq!(output.stdout, b"40 132\r\n");
+}
+
+#[test]
+fn test_pty_reports_exit_code() {
+    let output = execute_pty(&shell("exit 3")).unwrap();
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_pty_timeout_kills_child() {
+    let config = shell("sleep 5").with_timeout(Duration::from_millis(300));
+    let start = Instant::now();
+
+    assert!(matches!(
+        execute_pty(&config),
+        Err(ExecuteError::Timeout(_))
+    ));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::echo;
+use execute::{CommandPool, ExecutionConfig, SubmitError};
+use std::time::{Duration, Instant};
+
+/// 未启动执行器、容量为 1 且已满的命令池
+fn full_pool() -> CommandPool {
+    let pool = CommandPool::with_config_and_limit(ExecutionConfig::default(), 1);
+    pool.push_task(echo("hi")).unwrap();
+    pool
+}
+
+#[test]
+fn test_push_timeout_reports_queue_full() {
+    let pool = full_pool();
+    let start = Instant::now();
+    let result = pool.push_task_timeout(echo("hi"), Duration::from_millis(100));
+    assert!(matches!(result, Err(SubmitError::QueueFull)));
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool.statuses().len(), 1);
+}
+
+#[test]
+fn test_push_timeout_succeeds_when_space_frees() {
+    let pool = full_pool();
+    let other = pool.clone();
+    let clearer = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(100));
+        other.clear();
+        other
+    });
+
+    let handle = pool
+        .push_task_timeout(echo("hi"), Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(pool.len(), 1);
+    assert!(handle.id() > 1);
+    let _other = clearer.join().unwrap();
+}
+
+#[test]
+fn test_zero_timeout_behaves_like_try_push() {
+    let pool = full_pool();
+    assert!(matches!(
+        pool.push_task_timeout(echo("hi"), Duration::ZERO),
+        Err(SubmitError::QueueFull)
+    ));
+}
+
+#[test]
+fn test_unbounded_queue_never_waits() {
+    let pool = CommandPool::new();
+    for _ in 0..10 {
+        pool.push_task_timeout(echo("hi"), Duration::ZERO).unwrap();
+    }
+    assert_eq!(pool.len(), 10);
+}
+
+#[test]
+fn test_push_timeout_rejected_during_shutdown() {
+    let pool = full_pool();
+    pool.shutdown().unwrap();
+    assert!(matches!(
+        pool.push_task_timeout(echo("hi"), Duration::from_secs(1)),
+        Err(SubmitError::ShuttingDown)
+    ));
+}
@@ -0,0 +1,76 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::echo;
+use execute::{CommandPool, ExecuteError, ExecutionConfig, FinishStatus, PoolEvent, TaskState};
+use std::time::{Duration, SystemTime};
+
+fn single_worker_pool() -> CommandPool {
+    CommandPool::with_config(ExecutionConfig::new().with_workers(1))
+}
+
+#[test]
+fn test_stale_task_expires_instead_of_running() {
+    let pool = single_worker_pool();
+    let stale = pool
+        .push_task(echo("hi").with_queue_ttl(Duration::from_millis(50)))
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(150));
+    pool.start_executor();
+
+    match stale.wait() {
+        Err(ExecuteError::Expired(ttl)) => assert_eq!(ttl, Duration::from_millis(50)),
+        other => panic!("expected Expired, got {other:?}"),
+    }
+    assert_eq!(stale.state(), TaskState::Cancelled);
+    assert_eq!(pool.stats().failed, 0);
+}
+
+#[test]
+fn test_fresh_task_runs() {
+    let pool = single_worker_pool();
+    pool.start_executor();
+    let handle = pool
+        .push_task(echo("hi").with_queue_ttl(Duration::from_secs(30)))
+        .unwrap();
+    assert!(handle.wait().unwrap().status.success());
+}
+
+#[test]
+fn test_ttl_counts_from_scheduled_time() {
+    let pool = single_worker_pool();
+    pool.start_executor();
+    let handle = pool
+        .push_task(
+            echo("hi")
+                .with_start_at(SystemTime::now() + Duration::from_millis(200))
+                .with_queue_ttl(Duration::from_millis(100)),
+        )
+        .unwrap();
+    assert!(handle.wait().unwrap().status.success());
+}
+
+#[test]
+fn test_expired_task_does_not_block_queue() {
+    let pool = single_worker_pool();
+    let events = pool.subscribe();
+    let stale = pool
+        .push_task(echo("hi").with_queue_ttl(Duration::from_millis(10)))
+        .unwrap();
+    let fresh = pool.push_task(echo("hi")).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    pool.start_executor();
+
+    assert!(fresh.wait().unwrap().status.success());
+    assert!(matches!(stale.wait(), Err(ExecuteError::Expired(_))));
+
+    let expired = events.try_iter().any(|event| {
+        matches!(
+            event,
+            PoolEvent::TaskFinished { task_id, status: FinishStatus::Expired, .. }
+                if task_id == stale.id()
+        )
+    });
+    assert!(expired);
+}
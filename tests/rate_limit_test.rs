@@ -0,0 +1,53 @@
+use execute::{CommandConfig, CommandPool, ExecuteError};
+use std::time::{Duration, Instant};
+
+fn pool(per_second: u32) -> CommandPool {
+    let pool = CommandPool::builder()
+        .with_workers(4)
+        .with_rate_limit(per_second)
+        .build()
+        .unwrap();
+    pool.start_executor();
+    pool
+}
+
+#[test]
+fn test_burst_then_steady_rate() {
+    let pool = pool(5);
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..10)
+        .map(|_| pool.push_task(CommandConfig::new("true", vec![])).unwrap())
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    // 前 5 个任务用完初始令牌，其余 5 个按每秒 5 个启动，约需 1 秒
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(800), "{elapsed:?}");
+    assert!(elapsed < Duration::from_secs(3), "{elapsed:?}");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_waiting_task_can_be_cancelled() {
+    let pool = pool(1);
+
+    pool.push_task(CommandConfig::new("true", vec![]))
+        .unwrap()
+        .wait()
+        .unwrap();
+    // 令牌已用完，下一个任务留在队列中等待
+    let waiting = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    waiting.cancel().unwrap();
+    assert!(matches!(waiting.wait(), Err(ExecuteError::Cancelled(_))));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "rate limit must be greater than 0")]
+fn test_zero_rate_panics() {
+    let _ = CommandPool::builder().with_rate_limit(0);
+}
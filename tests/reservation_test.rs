@@ -0,0 +1,113 @@
+mod common;
+
+use common::echo;
+use execute::{CommandPool, ExecutionConfig, SubmitError};
+use std::time::Duration;
+
+/// 未启动执行器、容量为 `limit` 的命令池
+fn bounded_pool(limit: usize) -> CommandPool {
+    CommandPool::with_config_and_limit(ExecutionConfig::default(), limit)
+}
+
+#[test]
+fn test_reservation_guarantees_capacity() {
+    let pool = bounded_pool(3);
+    let mut reservation = pool.try_reserve(2).unwrap();
+    assert_eq!(reservation.remaining(), 2);
+
+    // 预留的空位不对其他提交开放
+    pool.try_push_task(echo("hi")).unwrap();
+    assert!(matches!(
+        pool.try_push_task(echo("hi")),
+        Err(SubmitError::QueueFull)
+    ));
+    assert!(matches!(
+        pool.push_task_timeout(echo("hi"), Duration::from_millis(50)),
+        Err(SubmitError::QueueFull)
+    ));
+
+    reservation.push_task(echo("hi")).unwrap();
+    reservation.push_task(echo("hi")).unwrap();
+    assert_eq!(reservation.remaining(), 0);
+    assert_eq!(pool.len(), 3);
+}
+
+#[test]
+fn test_reserve_fails_without_enough_space() {
+    let pool = bounded_pool(3);
+    pool.push_task(echo("hi")).unwrap();
+    assert!(matches!(pool.try_reserve(3), Err(SubmitError::QueueFull)));
+
+    let _first = pool.try_reserve(1).unwrap();
+    assert!(matches!(pool.try_reserve(2), Err(SubmitError::QueueFull)));
+    let _second = pool.try_reserve(1).unwrap();
+}
+
+#[test]
+fn test_exhausted_reservation_rejects_push() {
+    let pool = bounded_pool(5);
+    let mut reservation = pool.try_reserve(1).unwrap();
+    reservation.push_task(echo("hi")).unwrap();
+    assert!(matches!(
+        reservation.push_task(echo("hi")),
+        Err(SubmitError::QueueFull)
+    ));
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn test_drop_releases_unused_slots() {
+    let pool = bounded_pool(2);
+    let mut reservation = pool.try_reserve(2).unwrap();
+    reservation.push_task(echo("hi")).unwrap();
+    assert!(matches!(
+        pool.try_push_task(echo("hi")),
+        Err(SubmitError::QueueFull)
+    ));
+    drop(reservation);
+    pool.try_push_task(echo("hi")).unwrap();
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn test_drop_wakes_blocked_producer() {
+    let pool = bounded_pool(1);
+    let reservation = pool.try_reserve(1).unwrap();
+    std::thread::scope(|s| {
+        let producer = s.spawn(|| pool.push_task_timeout(echo("hi"), Duration::from_secs(5)));
+        std::thread::sleep(Duration::from_millis(100));
+        drop(reservation);
+        producer.join().unwrap().unwrap();
+    });
+    assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn test_unbounded_pool_always_reserves() {
+    let pool = CommandPool::new();
+    let mut reservation = pool.try_reserve(100).unwrap();
+    reservation.push_task(echo("hi")).unwrap();
+    pool.push_task(echo("hi")).unwrap();
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn test_reserve_rejected_during_shutdown() {
+    let pool = bounded_pool(2);
+    pool.shutdown().unwrap();
+    assert!(matches!(
+        pool.try_reserve(1),
+        Err(SubmitError::ShuttingDown)
+    ));
+}
+
+#[test]
+fn test_reserved_tasks_execute() {
+    let pool = bounded_pool(4);
+    pool.start_executor();
+    let mut reservation = pool.try_reserve(2).unwrap();
+    let first = reservation.push_task(echo("hi")).unwrap();
+    let second = reservation.push_task(echo("hi")).unwrap();
+    assert!(first.wait().unwrap().status.success());
+    assert!(second.wait().unwrap().status.success());
+}
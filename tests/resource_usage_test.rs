@@ -0,0 +1,50 @@
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::shell;
+use execute::{CommandPool, ExecutionConfig, execute_with_report};
+use std::time::Duration;
+
+/// 在用户态空转约 300 毫秒
+const BUSY_LOOP: &str = "end=$(($(date +%s%N) + 300000000)); \
+                         while [ $(date +%s%N) -lt $end ]; do :; done";
+
+#[test]
+fn test_report_includes_resource_usage() {
+    let report = execute_with_report(&shell(BUSY_LOOP)).unwrap();
+    let usage = report.resource_usage.unwrap();
+
+    assert!(usage.max_rss > 0);
+    assert!(usage.user_time + usage.system_time > Duration::from_millis(50));
+    assert!(usage.minor_page_faults > 0);
+}
+
+#[test]
+fn test_resource_usage_with_timeout_configured() {
+    let config = shell("echo done").with_timeout(Duration::from_secs(5));
+    let report = execute_with_report(&config).unwrap();
+
+    assert_eq!(report.output.stdout, b"done\n");
+    assert!(report.resource_usage.unwrap().max_rss > 0);
+}
+
+#[test]
+fn test_resource_usage_keeps_exit_status() {
+    let report = execute_with_report(&shell("exit 3")).unwrap();
+
+    assert_eq!(report.output.status.code(), Some(3));
+    assert!(report.resource_usage.is_some());
+}
+
+#[test]
+fn test_pool_report_includes_resource_usage() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(1));
+    pool.start_executor();
+
+    let handle = pool.push_task(shell("echo hi")).unwrap();
+    let report = handle.wait_report().unwrap();
+
+    assert!(report.resource_usage.unwrap().max_rss > 0);
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,94 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, CommandPool, ExecuteError, ExecutionConfig, ResultSink, TaskResult};
+use std::collections::HashSet;
+use std::sync::mpsc::{channel, sync_channel};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn exit_with(code: i32) -> CommandConfig {
+    CommandConfig::new("sh", vec!["-c".to_string(), format!("exit {code}")])
+}
+
+/// 记录收到的任务 ID 和退出码
+#[derive(Default)]
+struct Recorder {
+    seen: Mutex<Vec<(u64, Option<i32>)>>,
+}
+
+impl ResultSink for Recorder {
+    fn accept(&self, task_id: u64, result: TaskResult) {
+        let code = result.ok().and_then(|output| output.status.code());
+        self.seen.lock().unwrap().push((task_id, code));
+    }
+}
+
+#[test]
+fn test_channel_sink_receives_every_result() {
+    let (sender, results) = channel();
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(4))
+        .with_result_sink(Arc::new(sender));
+    pool.start_executor();
+
+    let ids: HashSet<u64> = (0..20)
+        .map(|i| pool.push_task(exit_with(i % 2)).unwrap().id())
+        .collect();
+
+    let received: Vec<(u64, TaskResult)> = results.iter().take(ids.len()).collect();
+    let received_ids: HashSet<u64> = received.iter().map(|(id, _)| *id).collect();
+    assert_eq!(received_ids, ids);
+    assert!(received.iter().all(|(_, result)| result.is_ok()));
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_handles_still_receive_results() {
+    let recorder = Arc::new(Recorder::default());
+    let pool = CommandPool::new().with_result_sink(recorder.clone());
+    pool.start_executor();
+
+    let handle = pool.push_task(exit_with(3)).unwrap();
+    let output = handle.wait().unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(*recorder.seen.lock().unwrap(), [(handle.id(), Some(3))]);
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_sink_receives_cancelled_tasks() {
+    let (sender, results) = channel();
+    let pool = CommandPool::new().with_result_sink(Arc::new(sender));
+    let handle = pool.push_task(exit_with(0)).unwrap();
+    handle.cancel().unwrap();
+    pool.start_executor();
+
+    let (task_id, result) = results.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(task_id, handle.id());
+    assert!(matches!(result, Err(ExecuteError::Cancelled(id)) if id == task_id));
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_bounded_sink_applies_backpressure() {
+    let (sender, results) = sync_channel(1);
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(2))
+        .with_result_sink(Arc::new(sender));
+    pool.start_executor();
+
+    let handles: Vec<_> = (0..3)
+        .map(|_| pool.push_task(exit_with(0)).unwrap())
+        .collect();
+    std::thread::sleep(Duration::from_millis(300));
+    // 通道容量为 1，尚未消费时最多一个任务把结果交给句柄
+    let (delivered, pending): (Vec<_>, Vec<_>) = handles
+        .into_iter()
+        .partition(|handle| matches!(handle.try_get(), Ok(Some(_))));
+    assert!(delivered.len() <= 1);
+
+    let received: Vec<_> = results.iter().take(3).collect();
+    assert_eq!(received.len(), 3);
+    for handle in pending {
+        handle.wait().unwrap();
+    }
+    pool.shutdown().unwrap();
+}
@@ -156,3 +156,209 @@ exit 0
     assert!(output.status.success());
     assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "success");
 }
+
+#[test]
+fn test_with_retries_uses_default_backoff() {
+    let config = CommandConfig::new("true", vec![]).with_retries(4);
+    let policy = config.retry_policy().unwrap();
+    assert_eq!(policy.max_attempts, 4);
+    assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(100));
+    assert_eq!(policy.delay_for_attempt(2), Duration::from_millis(200));
+}
+
+#[test]
+fn test_with_retry_backoff_order_independent() {
+    let a = CommandConfig::new("true", vec![])
+        .with_retries(2)
+        .with_retry_backoff(Duration::from_millis(30));
+    let b = CommandConfig::new("true", vec![])
+        .with_retry_backoff(Duration::from_millis(30))
+        .with_retries(2);
+    assert_eq!(a.retry_policy(), b.retry_policy());
+
+    let policy = a.retry_policy().unwrap();
+    assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(30));
+    assert_eq!(policy.delay_for_attempt(3), Duration::from_millis(120));
+}
+
+#[test]
+fn test_retry_backoff_alone_does_not_retry() {
+    let config = CommandConfig::new("true", vec![]).with_retry_backoff(Duration::from_secs(1));
+    assert_eq!(config.retry_policy().unwrap().max_attempts, 0);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_with_retries_reruns_timed_out_command_in_pool() {
+    let counter = std::env::temp_dir().join(format!("execute-with-retries-{}", std::process::id()));
+    let _ = std::fs::remove_file(&counter);
+
+    // 前两次执行超时，第三次立即成功
+    let script = format!(
+        "n=$(cat {0} 2>/dev/null || echo 0); echo $((n + 1)) > {0}; \
+         [ \"$n\" -ge 2 ] || sleep 10; echo done",
+        counter.display()
+    );
+    let config = CommandConfig::new("sh", vec!["-c".to_string(), script])
+        .with_timeout(Duration::from_millis(200))
+        .with_retries(3)
+        .with_retry_backoff(Duration::from_millis(10));
+
+    let pool = execute::CommandPool::new();
+    pool.start_executor();
+    let output = pool.push_task(config).unwrap().wait().unwrap();
+    pool.shutdown().unwrap();
+
+    let attempts = std::fs::read_to_string(&counter).unwrap();
+    let _ = std::fs::remove_file(&counter);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "done");
+    assert_eq!(attempts.trim(), "3");
+}
+
+#[test]
+fn test_with_retries_runs_each_attempt_through_pool_backend() {
+    use execute::testing::{MockBackend, MockResponse};
+    use execute::{CommandPool, ExecutionConfig};
+    use std::sync::Arc;
+
+    let backend = Arc::new(MockBackend::new().on("deploy", MockResponse::success("ok")));
+    backend.enqueue("deploy", MockResponse::error("connection reset"));
+    backend.enqueue("deploy", MockResponse::timeout());
+
+    let pool = CommandPool::with_backend(ExecutionConfig::new().with_workers(1), backend.clone());
+    pool.start_executor();
+    let config = CommandConfig::new("deploy", vec![])
+        .with_retries(3)
+        .with_retry_backoff(Duration::from_millis(1));
+    let output = pool.push_task(config).unwrap().wait().unwrap();
+    pool.shutdown().unwrap();
+
+    assert_eq!(output.stdout, b"ok");
+    backend.assert_executed("deploy", 3);
+}
+
+#[test]
+fn test_retry_backoff_does_not_hold_worker() {
+    use execute::testing::{MockBackend, MockResponse};
+    use execute::{CommandPool, ExecutionConfig};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    let backend = Arc::new(
+        MockBackend::new()
+            .on("flaky", MockResponse::success("ok"))
+            .on("other", MockResponse::success("other")),
+    );
+    backend.enqueue("flaky", MockResponse::error("connection reset"));
+
+    let pool = CommandPool::with_backend(ExecutionConfig::new().with_workers(1), backend.clone());
+    pool.start_executor();
+    let started = Instant::now();
+    let flaky = pool
+        .push_task(
+            CommandConfig::new("flaky", vec![])
+                .with_retries(1)
+                .with_retry_backoff(Duration::from_secs(2)),
+        )
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+
+    // 唯一的工作线程在退避期间执行其他任务
+    let other = pool.push_task(CommandConfig::new("other", vec![])).unwrap();
+    assert_eq!(other.wait().unwrap().stdout, b"other");
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(flaky.attempts(), 1);
+
+    assert_eq!(flaky.wait().unwrap().stdout, b"ok");
+    assert_eq!(flaky.attempts(), 2);
+    assert!(started.elapsed() >= Duration::from_secs(2));
+    pool.shutdown().unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn test_with_retries_keeps_execution_report() {
+    let pool = execute::CommandPool::new();
+    pool.start_executor();
+    let config = CommandConfig::new("true", vec![]).with_retries(2);
+    let report = pool.push_task(config).unwrap().wait_report().unwrap();
+    pool.shutdown().unwrap();
+
+    assert!(report.timing.is_some());
+    #[cfg(target_os = "linux")]
+    assert!(report.resource_usage.is_some());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_with_retries_reruns_failed_exit() {
+    let counter = std::env::temp_dir().join(format!("execute-failed-exit-{}", std::process::id()));
+    let _ = std::fs::remove_file(&counter);
+
+    // 前两次以退出码 7 失败，第三次成功
+    let script = format!(
+        "n=$(cat {0} 2>/dev/null || echo 0); echo $((n + 1)) > {0}; [ \"$n\" -ge 2 ] || exit 7",
+        counter.display()
+    );
+    let config = CommandConfig::new("sh", vec!["-c".to_string(), script])
+        .with_retries(3)
+        .with_retry_backoff(Duration::from_millis(1));
+
+    let pool = execute::CommandPool::new();
+    pool.start_executor();
+    let output = pool.push_task(config.clone()).unwrap().wait().unwrap();
+    pool.shutdown().unwrap();
+    assert!(output.status.success());
+    assert_eq!(std::fs::read_to_string(&counter).unwrap().trim(), "3");
+
+    std::fs::remove_file(&counter).unwrap();
+    let output = execute_with_retry(&config, 1).unwrap();
+    let _ = std::fs::remove_file(&counter);
+    assert!(output.status.success());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_failed_exit_returned_after_retries_exhausted() {
+    let config = CommandConfig::new("sh", vec!["-c".to_string(), "exit 5".to_string()])
+        .with_retries(2)
+        .with_retry_backoff(Duration::from_millis(1));
+
+    let output = execute_with_retry(&config, 1).unwrap();
+    assert_eq!(output.status.code(), Some(5));
+}
+
+#[test]
+fn test_retry_entry_points_share_failed_exit_rule() {
+    let policy = RetryPolicy::new(1, RetryStrategy::FixedInterval(Duration::ZERO));
+    assert!(policy.retry_failed_exit());
+    assert!(!policy.with_retry_failed_exit(false).retry_failed_exit());
+    assert!(
+        CommandConfig::new("true", vec![])
+            .with_retries(1)
+            .retry_policy()
+            .unwrap()
+            .retry_failed_exit()
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_failed_exit_not_retried_when_disabled() {
+    let counter =
+        std::env::temp_dir().join(format!("execute-no-exit-retry-{}", std::process::id()));
+    let _ = std::fs::remove_file(&counter);
+    let script = format!("echo x >> {}; exit 3", counter.display());
+    let policy = RetryPolicy::new(2, RetryStrategy::FixedInterval(Duration::from_millis(1)))
+        .with_retry_failed_exit(false);
+    let config = CommandConfig::new("sh", vec!["-c".to_string(), script]).with_retry(policy);
+
+    let output = execute_with_retry(&config, 1).unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        std::fs::read_to_string(&counter).unwrap().lines().count(),
+        1
+    );
+    let _ = std::fs::remove_file(&counter);
+}
@@ -0,0 +1,111 @@
+#![cfg(unix)]
+
+use execute::{
+    CommandConfig, CommandLine, CommandPool, CommandRewriter, ExecuteError, ExecutionBackend,
+    PrefixRewriter, RetryPolicy, RetryStrategy, RewritingBackend, execute_with_report,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 把命令改为输出其命令行，便于断言改写结果
+struct EchoCommandLine;
+
+impl CommandRewriter for EchoCommandLine {
+    fn rewrite(&self, command: &mut CommandLine) {
+        command.wrap("echo", Vec::<String>::new());
+    }
+}
+
+/// 直接执行命令的后端
+struct DirectBackend;
+
+impl ExecutionBackend for DirectBackend {
+    fn execute(&self, config: &CommandConfig) -> Result<std::process::Output, ExecuteError> {
+        execute_with_report(config).map(|report| report.output)
+    }
+}
+
+fn stdout(output: &std::process::Output) -> String {
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn test_command_line_wrap() {
+    let mut command = CommandLine {
+        program: "make".into(),
+        args: vec!["all".into()],
+    };
+    command.wrap("timeout", ["300"]);
+    command.wrap("nice", ["-n", "19"]);
+
+    assert_eq!(command.program, "nice");
+    assert_eq!(command.args, ["-n", "19", "timeout", "300", "make", "all"]);
+}
+
+#[test]
+fn test_pool_rewriters_apply_in_order() {
+    let pool = CommandPool::new()
+        .with_rewriter(Arc::new(PrefixRewriter::new("nice", ["-n", "19"])))
+        .with_rewriter(Arc::new(EchoCommandLine));
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(CommandConfig::new("make", vec!["all".to_string()]))
+        .unwrap();
+    assert_eq!(stdout(&handle.wait().unwrap()), "nice -n 19 make all");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_prefix_rewriter_runs_wrapped_command() {
+    let pool = CommandPool::new().with_rewriter(Arc::new(PrefixRewriter::new("nice", ["-n", "5"])));
+
+    let output = pool
+        .execute_task(&CommandConfig::new("nice", vec![]))
+        .unwrap();
+    assert!(output.status.success());
+    // 外层 nice 生效后，内层 nice 输出的优先级至少为 5
+    let niceness: i32 = stdout(&output).parse().unwrap();
+    assert!(niceness >= 5, "niceness = {}", niceness);
+}
+
+#[test]
+fn test_rewriter_applies_to_retried_tasks() {
+    let pool = CommandPool::new().with_rewriter(Arc::new(EchoCommandLine));
+    pool.start_executor();
+
+    let config = CommandConfig::new("false", vec![]).with_retry(RetryPolicy::new(
+        1,
+        RetryStrategy::FixedInterval(Duration::from_millis(10)),
+    ));
+    let handle = pool.push_task(config).unwrap();
+    assert_eq!(stdout(&handle.wait().unwrap()), "false");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_sub_pool_inherits_rewriters() {
+    let pool = CommandPool::new().with_rewriter(Arc::new(EchoCommandLine));
+    let sub = pool.sub_pool("team", 1);
+    sub.start_executor();
+
+    let handle = sub
+        .push_task(CommandConfig::new("rm", vec!["-rf".to_string()]))
+        .unwrap();
+    assert_eq!(stdout(&handle.wait().unwrap()), "rm -rf");
+
+    sub.shutdown().unwrap();
+}
+
+#[test]
+fn test_rewriting_backend() {
+    let backend =
+        RewritingBackend::new(Arc::new(DirectBackend)).with_rewriter(Arc::new(EchoCommandLine));
+
+    let output = backend
+        .execute(&CommandConfig::new("ls", vec!["/".to_string()]))
+        .unwrap();
+    assert_eq!(stdout(&output), "ls /");
+}
@@ -0,0 +1,71 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, Rlimits, execute_with_report};
+use std::time::Duration;
+
+/// 在 shell 中查询子进程看到的硬上限
+fn ulimit(flag: &str) -> CommandConfig {
+    CommandConfig::new("sh", vec!["-c".to_string(), format!("ulimit -H {flag}")])
+}
+
+fn stdout_of(config: &CommandConfig) -> String {
+    let report = execute_with_report(config).unwrap();
+    assert!(report.output.status.success());
+    String::from_utf8_lossy(&report.output.stdout)
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn test_rlimits_default_empty() {
+    let config = CommandConfig::new("true", vec![]);
+    assert_eq!(config.rlimits(), Rlimits::default());
+    assert!(config.rlimits().is_empty());
+}
+
+#[test]
+fn test_rlimits_builders() {
+    let config = CommandConfig::new("true", vec![])
+        .with_max_memory(64 * 1024 * 1024)
+        .with_max_cpu_time(Duration::from_secs(2))
+        .with_max_open_files(32);
+    assert_eq!(
+        config.rlimits(),
+        Rlimits {
+            max_memory: Some(64 * 1024 * 1024),
+            max_cpu_time: Some(Duration::from_secs(2)),
+            max_open_files: Some(32),
+        }
+    );
+}
+
+#[test]
+fn test_max_open_files_applied_to_child() {
+    let config = ulimit("-n").with_max_open_files(64);
+    assert_eq!(stdout_of(&config), "64");
+}
+
+#[test]
+fn test_max_cpu_time_rounded_up_to_seconds() {
+    let config = ulimit("-t").with_max_cpu_time(Duration::from_millis(1500));
+    assert_eq!(stdout_of(&config), "2");
+}
+
+#[test]
+fn test_max_memory_applied_to_child() {
+    // ulimit -v 以 KiB 为单位
+    let config = ulimit("-v").with_max_memory(512 * 1024 * 1024);
+    assert_eq!(stdout_of(&config), "524288");
+}
+
+#[test]
+fn test_max_cpu_time_kills_busy_loop() {
+    let config = CommandConfig::new(
+        "sh",
+        vec!["-c".to_string(), "while :; do :; done".to_string()],
+    )
+    .with_timeout(Duration::from_secs(30))
+    .with_max_cpu_time(Duration::from_secs(1));
+    let report = execute_with_report(&config).unwrap();
+    assert!(!report.output.status.success());
+}
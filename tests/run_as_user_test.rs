@@ -0,0 +1,70 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, ExecuteError, execute_with_report};
+use nix::libc;
+
+/// nobody 用户和用户组
+const NOBODY: u32 = 65534;
+
+fn is_root() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+fn id(flag: &str) -> CommandConfig {
+    CommandConfig::new("id", vec![flag.to_string()])
+}
+
+fn stdout_of(config: &CommandConfig) -> String {
+    let report = execute_with_report(config).unwrap();
+    assert!(report.output.status.success());
+    String::from_utf8_lossy(&report.output.stdout)
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn test_uid_gid_default_none() {
+    let config = CommandConfig::new("true", vec![]);
+    assert_eq!(config.uid(), None);
+    assert_eq!(config.gid(), None);
+
+    let config = config.with_uid(1000).with_gid(100);
+    assert_eq!(config.uid(), Some(1000));
+    assert_eq!(config.gid(), Some(100));
+}
+
+#[test]
+fn test_run_as_uid_and_gid() {
+    if !is_root() {
+        return;
+    }
+
+    let config = id("-u").with_uid(NOBODY).with_gid(NOBODY);
+    assert_eq!(stdout_of(&config), NOBODY.to_string());
+
+    let config = id("-g").with_uid(NOBODY).with_gid(NOBODY);
+    assert_eq!(stdout_of(&config), NOBODY.to_string());
+}
+
+#[test]
+fn test_drop_privileges_clears_supplementary_groups() {
+    if !is_root() {
+        return;
+    }
+
+    let config = id("-G").with_uid(NOBODY).with_gid(NOBODY);
+    assert_eq!(stdout_of(&config), NOBODY.to_string());
+}
+
+#[test]
+fn test_switch_user_without_privileges_fails_to_spawn() {
+    if is_root() {
+        return;
+    }
+
+    let config = id("-u").with_uid(0);
+    assert!(matches!(
+        execute_with_report(&config),
+        Err(ExecuteError::PermissionDenied { .. } | ExecuteError::Io(_))
+    ));
+}
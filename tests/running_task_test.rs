@@ -0,0 +1,198 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, ExecuteError, spawn};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_spawn_returns_before_child_exits() {
+    let start = Instant::now();
+    let task = spawn(&CommandConfig::new(
+        "sh",
+        vec!["-c".to_string(), "sleep 0.3; echo done".to_string()],
+    ))
+    .unwrap();
+    assert!(start.elapsed() < Duration::from_millis(200));
+
+    let output = task.wait().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"done\n");
+    assert!(start.elapsed() >= Duration::from_millis(300));
+}
+
+#[test]
+fn test_spawn_timeout_counts_from_start() {
+    let config =
+        CommandConfig::new("sleep", vec!["5".to_string()]).with_timeout(Duration::from_millis(300));
+    let task = spawn(&config).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+
+    let start = Instant::now();
+    let result = task.wait();
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+    assert!(start.elapsed() < Duration::from_millis(250));
+}
+
+#[test]
+fn test_spawn_missing_program_fails() {
+    let result = spawn(&CommandConfig::new("nonexistent_command_for_spawn", vec![]));
+    assert!(matches!(result, Err(ExecuteError::ProgramNotFound { .. })));
+}
+
+#[test]
+fn test_pid_and_is_alive() {
+    let mut task = spawn(&CommandConfig::new("sleep", vec!["0.2".to_string()])).unwrap();
+    assert!(task.pid() > 0);
+    assert!(task.is_alive());
+
+    std::thread::sleep(Duration::from_millis(400));
+    assert!(!task.is_alive());
+    assert!(task.wait().unwrap().status.success());
+}
+
+#[test]
+fn test_wait_timeout_polls_without_consuming() {
+    let mut task = spawn(&CommandConfig::new(
+        "sh",
+        vec!["-c".to_string(), "sleep 0.3; echo finished".to_string()],
+    ))
+    .unwrap();
+
+    assert!(
+        task.wait_timeout(Duration::from_millis(50))
+            .unwrap()
+            .is_none()
+    );
+    let status = task.wait_timeout(Duration::from_secs(5)).unwrap().unwrap();
+    assert!(status.success());
+
+    let output = task.wait().unwrap();
+    assert_eq!(output.stdout, b"finished\n");
+}
+
+#[test]
+fn test_wait_timeout_enforces_config_timeout() {
+    let config =
+        CommandConfig::new("sleep", vec!["5".to_string()]).with_timeout(Duration::from_millis(200));
+    let mut task = spawn(&config).unwrap();
+
+    let start = Instant::now();
+    let result = task.wait_timeout(Duration::from_secs(5));
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(!task.is_alive());
+    assert!(matches!(task.wait(), Err(ExecuteError::Timeout(_))));
+}
+
+#[test]
+fn test_kill_terminates_child() {
+    let mut task = spawn(&CommandConfig::new("sleep", vec!["5".to_string()])).unwrap();
+    task.kill().unwrap();
+    assert!(!task.is_alive());
+    // 重复终止是无害的
+    task.kill().unwrap();
+
+    let output = task.wait().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_take_output_readers() {
+    use std::io::Read;
+
+    let mut task = spawn(&CommandConfig::new("echo", vec!["streamed".to_string()])).unwrap();
+    let (stdout, stderr) = task.take_output_readers();
+    assert!(stderr.is_some());
+
+    let mut text = String::new();
+    stdout.unwrap().read_to_string(&mut text).unwrap();
+    assert_eq!(text, "streamed\n");
+    assert!(task.take_output_readers().0.is_none());
+
+    let output = task.wait().unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_readers_unavailable_after_waiting_starts() {
+    let mut task = spawn(&CommandConfig::new("echo", vec!["hi".to_string()])).unwrap();
+    assert!(task.wait_timeout(Duration::from_secs(5)).unwrap().is_some());
+    let (stdout, stderr) = task.take_output_readers();
+    assert!(stdout.is_none() && stderr.is_none());
+    assert_eq!(task.wait().unwrap().stdout, b"hi\n");
+}
+
+#[test]
+fn test_large_output_does_not_block_polling() {
+    // 输出超过管道缓冲区容量，后台读取保证子进程不会阻塞
+    let mut task = spawn(&CommandConfig::new(
+        "sh",
+        vec!["-c".to_string(), "head -c 1000000 /dev/zero".to_string()],
+    ))
+    .unwrap();
+    let status = task.wait_timeout(Duration::from_secs(5)).unwrap();
+    assert!(status.is_some());
+    assert_eq!(task.wait().unwrap().stdout.len(), 1_000_000);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_dropping_running_task_kills_child() {
+    let task = spawn(&CommandConfig::new("sleep", vec!["30".to_string()])).unwrap();
+    let pid = task.pid();
+    drop(task);
+    assert!(!std::path::Path::new(&format!("/proc/{}", pid)).exists());
+}
+
+#[test]
+fn test_stdin_writer_feeds_child_incrementally() {
+    use std::io::Write;
+
+    let mut task = spawn(&CommandConfig::new("sort", vec![])).unwrap();
+    let mut stdin = task.stdin_writer().unwrap();
+    for word in ["pear", "apple", "fig"] {
+        writeln!(stdin, "{}", word).unwrap();
+    }
+    // 关闭 stdin 前 sort 不会输出
+    assert!(task.stdin_writer().is_none());
+    drop(stdin);
+
+    let output = task.wait().unwrap();
+    assert_eq!(output.stdout, b"apple\nfig\npear\n");
+}
+
+#[test]
+fn test_stdin_closed_when_waiting_without_writer() {
+    let mut task = spawn(&CommandConfig::new("cat", vec![])).unwrap();
+    let status = task.wait_timeout(Duration::from_secs(5)).unwrap();
+    assert!(status.unwrap().success());
+    assert!(task.stdin_writer().is_none());
+    assert!(task.wait().unwrap().stdout.is_empty());
+}
+
+#[test]
+fn test_stdin_writer_answers_prompt() {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut task = spawn(&CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            "echo 'name?'; read name; echo \"hello $name\"".to_string(),
+        ],
+    ))
+    .unwrap();
+    let mut stdin = task.stdin_writer().unwrap();
+    let (stdout, _) = task.take_output_readers();
+    let mut stdout = BufReader::new(stdout.unwrap());
+
+    let mut prompt = String::new();
+    stdout.read_line(&mut prompt).unwrap();
+    assert_eq!(prompt, "name?\n");
+    writeln!(stdin, "world").unwrap();
+
+    let mut reply = String::new();
+    stdout.read_line(&mut reply).unwrap();
+    assert_eq!(reply, "hello world\n");
+    assert!(task.wait().unwrap().status.success());
+}
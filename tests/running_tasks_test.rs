@@ -0,0 +1,60 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::sleep_task;
+use execute::{CommandPool, ExecutionConfig, TaskHandle, TaskState};
+use std::ffi::OsString;
+use std::thread;
+use std::time::Duration;
+
+/// 等待任务的子进程启动
+fn wait_for_pid(handle: &TaskHandle) -> u32 {
+    for _ in 0..200 {
+        if let TaskState::Running { pid: Some(pid) } = handle.state() {
+            return pid;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    panic!("task did not start a child process");
+}
+
+#[test]
+fn test_running_tasks_reports_in_flight_commands() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(2));
+    pool.start_executor();
+    let first = pool
+        .push_task(sleep_task("0.5").with_label("team", "infra"))
+        .unwrap();
+    let second = pool.push_task(sleep_task("0.6")).unwrap();
+    let first_pid = wait_for_pid(&first);
+    let second_pid = wait_for_pid(&second);
+    thread::sleep(Duration::from_millis(50));
+
+    let running = pool.running_tasks();
+    assert_eq!(running.len(), 2);
+    assert_eq!(running[0].task_id, first.id());
+    assert_eq!(running[0].program, OsString::from("sleep"));
+    assert_eq!(running[0].args, vec![OsString::from("0.5")]);
+    assert_eq!(
+        running[0].labels.get("team").map(String::as_str),
+        Some("infra")
+    );
+    assert_eq!(running[0].pid, Some(first_pid));
+    assert_eq!(running[0].attempt, 1);
+    assert!(running[0].elapsed >= Duration::from_millis(50));
+    assert_eq!(running[1].task_id, second.id());
+    assert_eq!(running[1].pid, Some(second_pid));
+
+    first.wait().unwrap();
+    second.wait().unwrap();
+    assert!(pool.running_tasks().is_empty());
+}
+
+#[test]
+fn test_queued_tasks_are_not_running() {
+    let pool = CommandPool::new();
+    pool.push_task(sleep_task("0")).unwrap();
+    assert!(pool.running_tasks().is_empty());
+    assert_eq!(pool.snapshot().len(), 1);
+}
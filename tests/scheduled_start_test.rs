@@ -0,0 +1,121 @@
+mod common;
+
+use common::started_pool;
+use execute::CommandConfig;
+use std::time::{Duration, Instant, SystemTime};
+
+#[test]
+fn test_start_after_sets_start_time() {
+    assert_eq!(CommandConfig::new("true", vec![]).start_at(), None);
+
+    let before = SystemTime::now();
+    let config = CommandConfig::new("true", vec![]).with_start_after(Duration::from_secs(60));
+    let at = config.start_at().unwrap();
+    assert!(at >= before + Duration::from_secs(60));
+}
+
+#[test]
+fn test_pool_holds_task_until_start_time() {
+    let pool = started_pool(2);
+
+    let start = Instant::now();
+    let handle = pool
+        .push_task(CommandConfig::new("true", vec![]).with_start_after(Duration::from_millis(400)))
+        .unwrap();
+    handle.wait().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(400));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_delayed_task_does_not_block_queue() {
+    let pool = started_pool(1);
+
+    let start = Instant::now();
+    let delayed = pool
+        .push_task(CommandConfig::new("true", vec![]).with_start_after(Duration::from_millis(800)))
+        .unwrap();
+    let immediate = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+
+    immediate.wait().unwrap();
+    assert!(start.elapsed() < Duration::from_millis(800));
+    delayed.wait().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(800));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_past_start_time_runs_immediately() {
+    let pool = started_pool(1);
+
+    let start = Instant::now();
+    let at = SystemTime::now() - Duration::from_secs(3600);
+    pool.push_task(CommandConfig::new("true", vec![]).with_start_at(at))
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_later_submitted_earlier_schedule_runs_first() {
+    let pool = started_pool(1);
+
+    let now = SystemTime::now();
+    let late = pool
+        .push_task(
+            CommandConfig::new("true", vec![]).with_start_at(now + Duration::from_millis(600)),
+        )
+        .unwrap();
+    let early = pool
+        .push_task(
+            CommandConfig::new("true", vec![]).with_start_at(now + Duration::from_millis(200)),
+        )
+        .unwrap();
+
+    let start = Instant::now();
+    early.wait().unwrap();
+    assert!(start.elapsed() < Duration::from_millis(550));
+    late.wait().unwrap();
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_schedule_at_instant() {
+    let pool = started_pool(1);
+
+    let start = Instant::now();
+    let scheduled = pool
+        .schedule(
+            CommandConfig::new("true", vec![]),
+            start + Duration::from_millis(400),
+        )
+        .unwrap();
+    let immediate = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+
+    immediate.wait().unwrap();
+    assert!(start.elapsed() < Duration::from_millis(400));
+    scheduled.wait().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(400));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_schedule_in_the_past_runs_immediately() {
+    let pool = started_pool(1);
+
+    let start = Instant::now();
+    pool.schedule(CommandConfig::new("true", vec![]), start)
+        .unwrap()
+        .wait()
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,103 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{echo, started_pool};
+use execute::{CommandConfig, ExecuteError};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_scope_returns_results_in_submission_order() {
+    let pool = started_pool(4);
+
+    let results = pool.scope(|s| {
+        assert_eq!(
+            s.submit(CommandConfig::new(
+                "sh",
+                vec!["-c".into(), "sleep 0.2; echo first".into()]
+            ))
+            .unwrap(),
+            0
+        );
+        assert_eq!(s.submit(echo("second")).unwrap(), 1);
+    });
+
+    let outputs: Vec<String> = results
+        .into_iter()
+        .map(|r| String::from_utf8(r.unwrap().stdout).unwrap())
+        .collect();
+    assert_eq!(outputs, vec!["first\n".to_string(), "second\n".to_string()]);
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_scope_runs_tasks_concurrently() {
+    let pool = started_pool(4);
+
+    let start = Instant::now();
+    let results = pool.scope(|s| {
+        for _ in 0..3 {
+            s.submit(CommandConfig::new("sleep", vec!["0.3".to_string()]))
+                .unwrap();
+        }
+    });
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|r| r.is_ok()));
+    assert!(start.elapsed() < Duration::from_millis(800));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_scope_reports_failures_per_task() {
+    let pool = started_pool(4);
+
+    let results = pool.scope(|s| {
+        s.submit(echo("ok")).unwrap();
+        s.submit(CommandConfig::new("false", vec![])).unwrap();
+        s.submit(CommandConfig::new("nonexistent_command_for_scope", vec![]))
+            .unwrap();
+    });
+
+    assert!(results[0].is_ok());
+    assert!(!results[1].as_ref().unwrap().status.success());
+    assert!(matches!(
+        results[2],
+        Err(ExecuteError::ProgramNotFound { .. })
+    ));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_empty_scope_returns_immediately() {
+    let pool = started_pool(4);
+    let results = pool.scope(|_| {});
+    assert!(results.is_empty());
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_scope_waits_for_tasks_when_closure_panics() {
+    let pool = started_pool(4);
+
+    let start = Instant::now();
+    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        pool.scope(|s| {
+            s.submit(CommandConfig::new("sleep", vec!["0.2".to_string()]))
+                .unwrap();
+            panic!("scope body failed");
+        })
+    }));
+    assert!(outcome.is_err());
+    // 正在执行的任务会被取消或执行完毕，scope 返回前不会遗留任务
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    let results = pool.scope(|s| {
+        s.submit(echo("after")).unwrap();
+    });
+    assert!(results[0].is_ok());
+
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "serde")]
+
+use execute::{
+    CaptureMode, CommandConfig, EnvConfig, ExecutionConfig, ExecutionMode, OutputMode, Pipeline,
+    RetryPolicy, RetryStrategy, TaskDefaults, WatchdogConfig,
+};
+use std::time::Duration;
+
+#[test]
+fn test_command_config_round_trip() {
+    let config = CommandConfig::new("make", vec!["all".to_string()])
+        .with_working_dir("/srv/build")
+        .with_timeout(Duration::from_secs(300))
+        .with_retry(RetryPolicy::new(
+            2,
+            RetryStrategy::FixedInterval(Duration::from_millis(250)),
+        ))
+        .with_env(EnvConfig::new().set("CC", "clang"))
+        .with_capture_mode(CaptureMode::Tail(4096))
+        .with_output_mode(OutputMode::ToFile("/var/log/build.log".into()))
+        .with_max_open_files(256)
+        .with_serial_key("build");
+
+    let json = serde_json::to_string(&config).unwrap();
+    let restored: CommandConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, config);
+}
+
+#[test]
+fn test_program_and_args_are_plain_strings() {
+    let config = CommandConfig::new("echo", vec!["hello".to_string()]);
+    let value = serde_json::to_value(&config).unwrap();
+    assert_eq!(value["program"], "echo");
+    assert_eq!(value["args"], serde_json::json!(["hello"]));
+}
+
+#[test]
+fn test_missing_fields_use_defaults() {
+    let config: CommandConfig =
+        serde_json::from_str(r#"{"program": "ls", "args": ["-la"]}"#).unwrap();
+    assert_eq!(config, CommandConfig::new("ls", vec!["-la".to_string()]));
+    assert_eq!(config.timeout(), Some(Duration::from_secs(10)));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_non_utf8_argument_round_trip() {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    let config =
+        CommandConfig::new("cat", vec![]).with_arg(OsString::from_vec(b"\xff.bin".to_vec()));
+    let json = serde_json::to_string(&config).unwrap();
+    let restored: CommandConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.args(), config.args());
+}
+
+#[test]
+fn test_pipeline_round_trip() {
+    let pipeline = Pipeline::new()
+        .pipe(CommandConfig::new("echo", vec!["hello".to_string()]))
+        .pipe(CommandConfig::new(
+            "tr",
+            vec!["a-z".to_string(), "A-Z".to_string()],
+        ));
+
+    let json = serde_json::to_string(&pipeline).unwrap();
+    let restored: Pipeline = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.len(), 2);
+    assert_eq!(restored.stages()[1].config, pipeline.stages()[1].config);
+}
+
+#[test]
+fn test_execution_config_round_trip() {
+    let config = ExecutionConfig::new()
+        .with_mode(ExecutionMode::Thread)
+        .with_workers(3)
+        .with_concurrency_limit(2)
+        .with_zombie_reaper_interval(Duration::from_secs(5));
+
+    let json = serde_json::to_string(&config).unwrap();
+    let restored: ExecutionConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.mode, ExecutionMode::Thread);
+    assert_eq!(restored.workers, 3);
+    assert_eq!(restored.concurrency_limit, Some(2));
+    assert_eq!(
+        restored.zombie_reaper_interval,
+        Some(Duration::from_secs(5))
+    );
+}
+
+#[test]
+fn test_pool_options_round_trip() {
+    let defaults = TaskDefaults::new().with_working_dir("/srv");
+    let watchdog = WatchdogConfig::new(Duration::from_secs(5));
+
+    let restored: TaskDefaults =
+        serde_json::from_str(&serde_json::to_string(&defaults).unwrap()).unwrap();
+    assert_eq!(restored, defaults);
+    let restored: WatchdogConfig =
+        serde_json::from_str(&serde_json::to_string(&watchdog).unwrap()).unwrap();
+    assert_eq!(restored, watchdog);
+}
+
+#[test]
+fn test_execution_config_missing_fields_use_defaults() {
+    let config: ExecutionConfig = serde_json::from_str(r#"{"workers": 2}"#).unwrap();
+    assert_eq!(config.workers, 2);
+    assert_eq!(config.mode, ExecutionMode::Process);
+    assert!(config.concurrency_limit.is_none());
+}
@@ -0,0 +1,90 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{shell, started_pool};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_serial_key_runs_in_submission_order_one_at_a_time() {
+    let pool = started_pool(4);
+    let dir = std::env::temp_dir().join(format!("execute-serial-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let log = dir.join("log");
+    let log = log.to_str().unwrap();
+
+    // 每个任务记录开始和结束；串行执行时记录不会交错
+    let handles: Vec<_> = (0..5)
+        .map(|i| {
+            let script = format!("echo start {i} >> {log}; sleep 0.05; echo end {i} >> {log}");
+            pool.push_task(shell(&script).with_serial_key("customer-42"))
+                .unwrap()
+        })
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+
+    let expected: String = (0..5).map(|i| format!("start {i}\nend {i}\n")).collect();
+    assert_eq!(std::fs::read_to_string(log).unwrap(), expected);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_different_serial_keys_run_concurrently() {
+    let pool = started_pool(4);
+
+    let start = Instant::now();
+    let handles: Vec<_> = ["a", "b", "c"]
+        .iter()
+        .map(|key| {
+            pool.push_task(shell("sleep 0.4").with_serial_key(key))
+                .unwrap()
+        })
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+
+    assert!(start.elapsed() < Duration::from_millis(1000));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_failed_task_releases_serial_key() {
+    let pool = started_pool(4);
+
+    let first = pool
+        .push_task(shell("sleep 0.1; exit 3").with_serial_key("k"))
+        .unwrap();
+    let second = pool
+        .push_task(shell("echo next").with_serial_key("k"))
+        .unwrap();
+
+    assert_eq!(first.wait().unwrap().status.code(), Some(3));
+    assert_eq!(second.wait().unwrap().stdout, b"next\n");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_unkeyed_tasks_pass_blocked_serial_tasks() {
+    let pool = started_pool(4);
+
+    let slow = pool
+        .push_task(shell("sleep 0.5").with_serial_key("k"))
+        .unwrap();
+    let queued = pool.push_task(shell("true").with_serial_key("k")).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+
+    let start = Instant::now();
+    pool.push_task(shell("true")).unwrap().wait().unwrap();
+    assert!(start.elapsed() < Duration::from_millis(300));
+
+    slow.wait().unwrap();
+    queued.wait().unwrap();
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,114 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandConfig, ExecuteError, Session};
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[test]
+fn test_session_round_trips_lines() {
+    let mut session = Session::spawn(&shell("while read l; do echo \"got $l\"; done")).unwrap();
+
+    session.write_line("one", TIMEOUT).unwrap();
+    assert_eq!(
+        session.read_line(TIMEOUT).unwrap().as_deref(),
+        Some("got one")
+    );
+    session.write_line("two", TIMEOUT).unwrap();
+    assert_eq!(
+        session.read_line(TIMEOUT).unwrap().as_deref(),
+        Some("got two")
+    );
+
+    assert!(session.close(TIMEOUT).unwrap().success());
+}
+
+#[test]
+fn test_session_reads_until_prompt() {
+    let mut session = Session::spawn(&shell(
+        "printf '> '; while read l; do printf '%s\\n> ' \"$l\"; done",
+    ))
+    .unwrap();
+
+    assert_eq!(session.read_until("> ", TIMEOUT).unwrap(), "> ");
+    session.write_line("select 1", TIMEOUT).unwrap();
+    assert_eq!(session.read_until("> ", TIMEOUT).unwrap(), "select 1\n> ");
+
+    session.kill().unwrap();
+}
+
+#[test]
+fn test_session_read_times_out_and_keeps_partial_data() {
+    let mut session = Session::spawn(&shell("printf partial; sleep 5")).unwrap();
+
+    let start = Instant::now();
+    let err = session.read_line(Duration::from_millis(300)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert_eq!(session.read_available(), b"partial");
+
+    session.kill().unwrap();
+}
+
+#[test]
+fn test_session_read_line_returns_none_at_eof() {
+    let mut session = Session::spawn(&shell("echo last; printf tail")).unwrap();
+
+    assert_eq!(session.read_line(TIMEOUT).unwrap().as_deref(), Some("last"));
+    assert_eq!(session.read_line(TIMEOUT).unwrap().as_deref(), Some("tail"));
+    assert_eq!(session.read_line(TIMEOUT).unwrap(), None);
+    let err = session.read_until("x", TIMEOUT).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_session_stderr_is_available_before_exit() {
+    let mut session = Session::spawn(&shell("echo oops >&2; echo ready; sleep 5")).unwrap();
+
+    assert_eq!(
+        session.read_line(TIMEOUT).unwrap().as_deref(),
+        Some("ready")
+    );
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(session.take_stderr(), b"oops\n");
+
+    session.kill().unwrap();
+}
+
+#[test]
+fn test_session_close_stdin_sends_eof() {
+    let mut session = Session::spawn(&CommandConfig::new("cat", vec![])).unwrap();
+
+    session.write(b"abc", TIMEOUT).unwrap();
+    session.close_stdin();
+    assert_eq!(session.read_line(TIMEOUT).unwrap().as_deref(), Some("abc"));
+    assert_eq!(session.read_line(TIMEOUT).unwrap(), None);
+
+    let err = session.write(b"more", TIMEOUT).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    assert!(session.close(TIMEOUT).unwrap().success());
+}
+
+#[test]
+fn test_session_close_kills_child_that_ignores_eof() {
+    let session = Session::spawn(&shell("sleep 5")).unwrap();
+
+    let start = Instant::now();
+    let result = session.close(Duration::from_millis(300));
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_session_kill_stops_child() {
+    let mut session = Session::spawn(&shell("sleep 5")).unwrap();
+    assert!(session.is_alive());
+
+    let start = Instant::now();
+    session.kill().unwrap();
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
@@ -0,0 +1,81 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandConfig, ExecuteError, execute_with_report};
+use std::time::{Duration, Instant};
+
+fn stdout_of(config: &CommandConfig) -> String {
+    let report = execute_with_report(config).unwrap();
+    String::from_utf8(report.output.stdout)
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn test_umask_builder() {
+    assert_eq!(CommandConfig::new("true", vec![]).umask(), None);
+    assert_eq!(
+        CommandConfig::new("true", vec![])
+            .with_umask(0o7022)
+            .umask(),
+        Some(0o022)
+    );
+}
+
+#[test]
+fn test_umask_applied_to_child() {
+    assert_eq!(stdout_of(&shell("umask").with_umask(0o077)), "0077");
+    assert_eq!(stdout_of(&shell("umask").with_umask(0o027)), "0027");
+}
+
+#[test]
+fn test_umask_affects_created_files() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join(format!("execute-umask-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let config = shell(&format!("touch {}", path.display())).with_umask(0o077);
+    execute_with_report(&config).unwrap();
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_detach_session_starts_new_session() {
+    let parent_sid = nix::unistd::getsid(None).unwrap().as_raw().to_string();
+
+    let attached = shell("ps -o sid= -p $$");
+    assert!(!attached.is_session_detached());
+    assert_eq!(stdout_of(&attached), parent_sid);
+
+    // 新会话的会话 ID 等于子进程自身的 PID
+    let detached = shell("echo $$; ps -o sid= -p $$").detach_session();
+    assert!(detached.is_session_detached());
+    let output = stdout_of(&detached);
+    let ids: Vec<&str> = output.split_whitespace().collect();
+    assert_eq!(ids.len(), 2);
+    assert_eq!(ids[0], ids[1]);
+    assert_ne!(ids[1], parent_sid);
+}
+
+#[test]
+fn test_detach_session_with_kill_tree() {
+    let config = shell("sleep 10")
+        .detach_session()
+        .with_kill_tree(true)
+        .with_timeout(Duration::from_millis(200));
+
+    let start = Instant::now();
+    assert!(matches!(
+        execute_with_report(&config),
+        Err(ExecuteError::Timeout(_))
+    ));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
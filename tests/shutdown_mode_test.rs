@@ -0,0 +1,77 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{sleep_task, started_pool};
+use execute::{ExecuteError, ShutdownMode, SubmitError};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_drain_runs_queued_tasks() {
+    let pool = started_pool(1);
+    let handles: Vec<_> = (0..3)
+        .map(|_| pool.push_task(sleep_task("0.1")).unwrap())
+        .collect();
+
+    pool.shutdown_with_mode(ShutdownMode::Drain).unwrap();
+    for handle in handles {
+        assert!(handle.wait().unwrap().status.success());
+    }
+}
+
+#[test]
+fn test_finish_cancels_queued_tasks() {
+    let pool = started_pool(1);
+    let running = pool.push_task(sleep_task("0.3")).unwrap();
+    let queued = pool.push_task(sleep_task("0.3")).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+
+    pool.shutdown_with_mode(ShutdownMode::Finish).unwrap();
+    assert!(running.wait().unwrap().status.success());
+    assert!(matches!(queued.wait(), Err(ExecuteError::Cancelled(_))));
+}
+
+#[test]
+fn test_abort_kills_running_children() {
+    let pool = started_pool(1);
+    let running = pool.push_task(sleep_task("10")).unwrap();
+    let queued = pool.push_task(sleep_task("10")).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+
+    let start = Instant::now();
+    pool.shutdown_with_mode(ShutdownMode::Abort).unwrap();
+    assert!(running.wait().is_err());
+    assert!(matches!(queued.wait(), Err(ExecuteError::Cancelled(_))));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_submit_rejected_after_shutdown() {
+    for mode in [
+        ShutdownMode::Drain,
+        ShutdownMode::Finish,
+        ShutdownMode::Abort,
+    ] {
+        let pool = started_pool(1);
+        pool.shutdown_with_mode(mode).unwrap();
+        assert!(matches!(
+            pool.push_task(sleep_task("0")),
+            Err(SubmitError::ShuttingDown)
+        ));
+    }
+}
+
+#[test]
+fn test_submit_rejected_while_draining() {
+    let pool = started_pool(1);
+    pool.push_task(sleep_task("0.5")).unwrap();
+
+    let closer = pool.clone();
+    let shutdown = std::thread::spawn(move || closer.shutdown_with_mode(ShutdownMode::Drain));
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(matches!(
+        pool.try_push_task(sleep_task("0")),
+        Err(SubmitError::ShuttingDown)
+    ));
+    shutdown.join().unwrap().unwrap();
+}
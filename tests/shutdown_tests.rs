@@ -70,3 +70,19 @@ fn test_push_after_shutdown() {
     let result = pool.try_push_task(CommandConfig::new("echo", vec!["test2".to_string()]));
     assert!(result.is_err());
 }
+
+#[test]
+fn test_stop_returns_with_idle_workers() {
+    let pool = std::sync::Arc::new(CommandPool::new());
+    pool.start_executor();
+    std::thread::sleep(Duration::from_millis(50));
+
+    // 工作线程在空队列上等待，stop() 应唤醒它们并返回
+    let (tx, rx) = std::sync::mpsc::channel();
+    let stopper = std::sync::Arc::clone(&pool);
+    std::thread::spawn(move || {
+        stopper.stop();
+        let _ = tx.send(());
+    });
+    assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+}
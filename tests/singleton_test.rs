@@ -0,0 +1,122 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{pool, sleep_task};
+use execute::{CommandConfig, ExecuteError, TaskState};
+use std::thread;
+use std::time::Duration;
+
+/// 等待任务进入运行状态
+fn wait_running(handle: &execute::TaskHandle) {
+    for _ in 0..200 {
+        if matches!(handle.state(), TaskState::Running { .. }) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    panic!("task did not start");
+}
+
+#[test]
+fn test_singleton_skipped_while_same_key_running() {
+    let pool = pool(4);
+    pool.start_executor();
+
+    let first = pool
+        .push_task(sleep_task("0.5").with_singleton_key("backup"))
+        .unwrap();
+    wait_running(&first);
+
+    let second = pool
+        .push_task(sleep_task("0.5").with_singleton_key("backup"))
+        .unwrap();
+    match second.wait() {
+        Err(ExecuteError::Skipped(key)) => assert_eq!(key, "backup"),
+        other => panic!("expected skipped, got {:?}", other),
+    }
+    assert_eq!(second.state(), TaskState::Skipped);
+
+    assert!(first.wait().unwrap().status.success());
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_singleton_runs_again_after_previous_finished() {
+    let pool = pool(4);
+    pool.start_executor();
+
+    for _ in 0..3 {
+        let handle = pool
+            .push_task(CommandConfig::new("true", vec![]).with_singleton_key("job"))
+            .unwrap();
+        assert!(handle.wait().unwrap().status.success());
+    }
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_different_singleton_keys_run_concurrently() {
+    let pool = pool(4);
+    pool.start_executor();
+
+    let a = pool
+        .push_task(sleep_task("0.3").with_singleton_key("a"))
+        .unwrap();
+    wait_running(&a);
+    let b = pool
+        .push_task(sleep_task("0.1").with_singleton_key("b"))
+        .unwrap();
+
+    assert!(b.wait().is_ok());
+    assert!(a.wait().is_ok());
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_singleton_key_shared_with_sub_pool() {
+    let pool = pool(4);
+    pool.start_executor();
+    let sub = pool.sub_pool("team", 2);
+    sub.start_executor();
+
+    let first = pool
+        .push_task(sleep_task("0.5").with_singleton_key("shared"))
+        .unwrap();
+    wait_running(&first);
+
+    let second = sub
+        .push_task(sleep_task("0.5").with_singleton_key("shared"))
+        .unwrap();
+    assert!(matches!(second.wait(), Err(ExecuteError::Skipped(_))));
+    assert!(first.wait().is_ok());
+
+    sub.shutdown().unwrap();
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_skipped_task_cannot_be_cancelled() {
+    let pool = pool(4);
+    pool.start_executor();
+
+    let first = pool
+        .push_task(sleep_task("0.3").with_singleton_key("k"))
+        .unwrap();
+    wait_running(&first);
+    let second = pool
+        .push_task(sleep_task("0.3").with_singleton_key("k"))
+        .unwrap();
+    let _ = second.wait();
+
+    assert_eq!(
+        second.cancel().unwrap_err(),
+        execute::CancelError::AlreadyCompleted
+    );
+    first.wait().unwrap();
+
+    pool.shutdown().unwrap();
+}
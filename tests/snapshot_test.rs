@@ -0,0 +1,52 @@
+mod common;
+
+use common::echo;
+use execute::CommandPool;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+#[test]
+fn test_snapshot_lists_queued_tasks_in_dequeue_order() {
+    let pool = CommandPool::new();
+    let low = pool.push_task(echo("low")).unwrap();
+    let high = pool.push_task(echo("high").with_priority(5)).unwrap();
+    let low_2 = pool.push_task(echo("low-2")).unwrap();
+
+    let snapshot = pool.snapshot();
+    let ids: Vec<u64> = snapshot.iter().map(|task| task.task_id).collect();
+    assert_eq!(ids, [high.id(), low.id(), low_2.id()]);
+    assert_eq!(snapshot[0].program, OsString::from("echo"));
+    assert_eq!(snapshot[0].args, [OsString::from("high")]);
+    assert_eq!(snapshot[0].priority, 5);
+}
+
+#[test]
+fn test_snapshot_includes_labels() {
+    let pool = CommandPool::new();
+    pool.push_task(
+        echo("hi")
+            .with_label("team", "infra")
+            .with_label("trigger", "webhook")
+            .with_label("team", "build"),
+    )
+    .unwrap();
+
+    let expected: BTreeMap<String, String> = [
+        ("team".to_string(), "build".to_string()),
+        ("trigger".to_string(), "webhook".to_string()),
+    ]
+    .into();
+    assert_eq!(pool.snapshot()[0].labels, expected);
+}
+
+#[test]
+fn test_snapshot_does_not_remove_tasks() {
+    let pool = CommandPool::new();
+    pool.push_task(echo("a")).unwrap();
+    pool.push_task(echo("b")).unwrap();
+
+    assert_eq!(pool.snapshot().len(), 2);
+    assert_eq!(pool.len(), 2);
+    pool.clear();
+    assert!(pool.snapshot().is_empty());
+}
@@ -0,0 +1,76 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, CommandPool, ExecuteError, execute_with_report};
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("execute-spawn-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_missing_program_is_program_not_found() {
+    let err =
+        execute_with_report(&CommandConfig::new("nonexistent_program_xyz", vec![])).unwrap_err();
+    match &err {
+        ExecuteError::ProgramNotFound { program } => assert_eq!(program, "nonexistent_program_xyz"),
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(err.is_permanent());
+    assert!(err.to_string().contains("nonexistent_program_xyz"));
+}
+
+#[test]
+fn test_non_executable_file_is_permission_denied() {
+    let dir = temp_dir("perm");
+    let script = dir.join("script.sh");
+    std::fs::write(&script, "#!/bin/sh\necho hi\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    let program = script.to_str().unwrap();
+    let err = execute_with_report(&CommandConfig::new(program, vec![])).unwrap_err();
+    match &err {
+        ExecuteError::PermissionDenied { program: p } => assert_eq!(p, program),
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(err.is_permanent());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_missing_working_dir_is_working_dir_invalid() {
+    let config =
+        CommandConfig::new("true", vec![]).with_working_dir("/nonexistent/execute-spawn-dir");
+    let err = execute_with_report(&config).unwrap_err();
+    match &err {
+        ExecuteError::WorkingDirInvalid { dir } => {
+            assert_eq!(dir, &PathBuf::from("/nonexistent/execute-spawn-dir"))
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(err.is_permanent());
+}
+
+#[test]
+fn test_pool_reports_classified_spawn_error() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(CommandConfig::new("nonexistent_program_for_pool", vec![]))
+        .unwrap();
+    assert!(matches!(
+        handle.wait(),
+        Err(ExecuteError::ProgramNotFound { .. })
+    ));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_timeout_is_not_permanent() {
+    assert!(!ExecuteError::Timeout(std::time::Duration::from_secs(1)).is_permanent());
+}
@@ -0,0 +1,54 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, CommandPool, Duration, execute_with_report, spawn};
+
+fn stdout(config: &CommandConfig) -> String {
+    let report = execute_with_report(config).unwrap();
+    String::from_utf8(report.output.stdout).unwrap()
+}
+
+#[test]
+fn test_stdin_bytes_piped_to_command() {
+    let config = CommandConfig::new("sort", vec![]).with_stdin(b"b\na\nc\n".to_vec());
+    assert_eq!(config.stdin(), Some(&b"b\na\nc\n"[..]));
+    assert_eq!(stdout(&config), "a\nb\nc\n");
+}
+
+#[test]
+fn test_stdin_str_with_timeout_path() {
+    let config = CommandConfig::new("grep", vec!["x".to_string()])
+        .with_stdin_str("ax\nb\nxc\n")
+        .with_timeout(Duration::from_secs(5));
+    assert_eq!(stdout(&config), "ax\nxc\n");
+}
+
+#[test]
+fn test_large_stdin_does_not_block_waiting() {
+    // 输入超过管道缓冲区，写入在后台进行，不阻塞等待流程
+    let input = "0123456789\n".repeat(100_000);
+    let config = CommandConfig::new("wc", vec!["-c".to_string()]).with_stdin_str(&input);
+    assert_eq!(stdout(&config).trim(), input.len().to_string());
+}
+
+#[test]
+fn test_stdin_ignored_when_child_exits_early() {
+    let input = vec![b'x'; 1 << 20];
+    let config = CommandConfig::new("true", vec![]).with_stdin(input);
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.status.success());
+}
+
+#[test]
+fn test_stdin_fed_to_spawned_and_pooled_tasks() {
+    let config = CommandConfig::new("cat", vec![]).with_stdin_str("hello");
+
+    let mut task = spawn(&config).unwrap();
+    assert!(task.stdin_writer().is_none());
+    assert_eq!(task.wait().unwrap().stdout, b"hello");
+
+    let pool = CommandPool::new();
+    pool.start_executor();
+    let output = pool.push_task(config).unwrap().wait().unwrap();
+    assert_eq!(output.stdout, b"hello");
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,50 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::started_pool;
+use execute::CommandConfig;
+use std::time::{Duration, Instant};
+
+fn sleep(secs: &str) -> CommandConfig {
+    CommandConfig::new("sleep", vec![secs.to_string()]).with_timeout(Duration::from_secs(30))
+}
+
+#[test]
+fn test_kills_running_children_after_grace() {
+    let pool = started_pool(1);
+    let running = pool.push_task(sleep("10")).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+
+    let start = Instant::now();
+    let killed = pool.stop_with_timeout(Duration::from_millis(200));
+    assert_eq!(killed, 1);
+    assert!(running.wait().is_err());
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(!pool.is_running());
+}
+
+#[test]
+fn test_waits_for_tasks_finishing_within_grace() {
+    let pool = started_pool(1);
+    let running = pool.push_task(sleep("0.2")).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+
+    assert_eq!(pool.stop_with_timeout(Duration::from_secs(5)), 0);
+    assert!(running.wait().unwrap().status.success());
+}
+
+#[test]
+fn test_queued_tasks_kept_for_restart() {
+    let pool = started_pool(1);
+    let running = pool.push_task(sleep("10")).unwrap();
+    let queued = pool.push_task(sleep("0")).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+
+    assert_eq!(pool.stop_with_timeout(Duration::ZERO), 1);
+    assert!(running.wait().is_err());
+    assert_eq!(pool.len(), 1);
+
+    pool.start_executor();
+    assert!(queued.wait().unwrap().status.success());
+}
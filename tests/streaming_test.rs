@@ -0,0 +1,64 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{ExecuteError, execute_streaming};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn collect() -> (Arc<Mutex<Vec<String>>>, impl FnMut(&str) + Send + 'static) {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&lines);
+    (lines, move |line: &str| {
+        sink.lock().unwrap().push(line.to_string())
+    })
+}
+
+#[test]
+fn test_streaming_delivers_lines_per_stream() {
+    let (stdout, on_stdout) = collect();
+    let (stderr, on_stderr) = collect();
+    let output = execute_streaming(
+        &shell("echo one; echo warn >&2; printf 'two\\nthree'"),
+        on_stdout,
+        on_stderr,
+    )
+    .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(*stdout.lock().unwrap(), ["one", "two", "three"]);
+    assert_eq!(*stderr.lock().unwrap(), ["warn"]);
+    assert_eq!(output.stdout, b"one\ntwo\nthree");
+    assert_eq!(output.stderr, b"warn\n");
+}
+
+#[test]
+fn test_streaming_delivers_lines_before_exit() {
+    let (tx, rx) = mpsc::channel();
+    let start = Instant::now();
+    let worker = std::thread::spawn(move || {
+        execute_streaming(
+            &shell("echo ready; sleep 1"),
+            move |line| {
+                let _ = tx.send(line.to_string());
+            },
+            |_| {},
+        )
+    });
+
+    assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), "ready");
+    assert!(start.elapsed() < Duration::from_millis(800));
+    assert!(worker.join().unwrap().is_ok());
+}
+
+#[test]
+fn test_streaming_timeout_keeps_delivered_lines() {
+    let (stdout, on_stdout) = collect();
+    let config = shell("echo started; sleep 5").with_timeout(Duration::from_millis(300));
+    let result = execute_streaming(&config, on_stdout, |_| {});
+
+    assert!(matches!(result, Err(ExecuteError::Timeout(_))));
+    assert_eq!(*stdout.lock().unwrap(), ["started"]);
+}
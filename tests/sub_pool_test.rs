@@ -0,0 +1,87 @@
+mod common;
+
+use common::sleep_task;
+use execute::{CommandPool, ExecutionConfig};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_sub_pool_has_name_and_inherits_mode() {
+    let parent = CommandPool::with_config(ExecutionConfig::new().with_workers(4));
+    let sub = parent.sub_pool("video", 2);
+
+    assert_eq!(parent.name(), None);
+    assert_eq!(sub.name(), Some("video"));
+    assert_eq!(sub.execution_mode(), parent.execution_mode());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sub_pool_limit_is_enforced() {
+    let parent = CommandPool::with_config(ExecutionConfig::new().with_workers(8));
+    let sub = parent.sub_pool("video", 1);
+    sub.start_executor();
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..3)
+        .map(|_| sub.push_task(sleep_task("0.2")).unwrap())
+        .collect();
+    for handle in handles {
+        assert!(handle.wait().unwrap().status.success());
+    }
+
+    // 配额为 1，三个任务只能串行执行
+    assert!(start.elapsed() >= Duration::from_millis(600));
+    sub.shutdown_with_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sub_pools_share_parent_budget() {
+    let parent = CommandPool::with_config(
+        ExecutionConfig::new()
+            .with_workers(4)
+            .with_concurrency_limit(2),
+    );
+    let a = parent.sub_pool("a", 2);
+    let b = parent.sub_pool("b", 2);
+    a.start_executor();
+    b.start_executor();
+
+    let start = Instant::now();
+    let mut handles = Vec::new();
+    for _ in 0..2 {
+        handles.push(a.push_task(sleep_task("0.3")).unwrap());
+        handles.push(b.push_task(sleep_task("0.3")).unwrap());
+    }
+    for handle in handles {
+        assert!(handle.wait().unwrap().status.success());
+    }
+
+    // 两个子池各自允许 2 个并发，但父池总预算只有 2，四个任务需要两轮
+    assert!(start.elapsed() >= Duration::from_millis(600));
+
+    a.shutdown_with_timeout(Duration::from_secs(5)).unwrap();
+    b.shutdown_with_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_nested_sub_pool_respects_ancestor_limit() {
+    let parent = CommandPool::with_config(ExecutionConfig::new().with_workers(4));
+    let team = parent.sub_pool("team", 1);
+    let job = team.sub_pool("job", 4);
+    assert_eq!(job.name(), Some("job"));
+    job.start_executor();
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..2)
+        .map(|_| job.push_task(sleep_task("0.2")).unwrap())
+        .collect();
+    for handle in handles {
+        assert!(handle.wait().unwrap().status.success());
+    }
+
+    // 上级子池配额为 1
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    job.shutdown_with_timeout(Duration::from_secs(5)).unwrap();
+}
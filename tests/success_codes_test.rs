@@ -0,0 +1,117 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, CommandPool, ExecuteError, execute_with_report, spawn};
+#[cfg(feature = "pipeline")]
+use execute::{Pipeline, PipelineExecutor};
+
+fn exit_with(code: i32) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec!["-c".to_string(), format!("echo out; exit {code}")],
+    )
+}
+
+#[test]
+fn test_default_non_zero_exit_is_ok() {
+    let report = execute_with_report(&exit_with(3)).unwrap();
+    assert_eq!(report.output.status.code(), Some(3));
+}
+
+#[test]
+fn test_accepted_exit_code_is_ok() {
+    let config = exit_with(1).with_success_codes(&[0, 1]);
+    assert_eq!(config.success_codes(), Some(&[0, 1][..]));
+
+    let report = execute_with_report(&config).unwrap();
+    assert_eq!(report.output.status.code(), Some(1));
+}
+
+#[test]
+fn test_unexpected_exit_code_is_error() {
+    let config = exit_with(2).with_success_codes(&[0, 1]);
+
+    match execute_with_report(&config) {
+        Err(ExecuteError::UnexpectedExit { output }) => {
+            assert_eq!(output.status.code(), Some(2));
+            assert_eq!(output.stdout, b"out\n");
+        }
+        other => panic!("expected UnexpectedExit, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_zero_exit_can_be_rejected() {
+    let config = exit_with(0).with_success_codes(&[3]);
+    assert!(matches!(
+        execute_with_report(&config),
+        Err(ExecuteError::UnexpectedExit { .. })
+    ));
+}
+
+#[test]
+fn test_allow_failure_overrides_success_codes() {
+    let config = exit_with(2).with_success_codes(&[0]).allow_failure();
+    assert!(config.is_failure_allowed());
+    assert!(execute_with_report(&config).is_ok());
+}
+
+#[test]
+fn test_spawned_task_checks_exit_code() {
+    let task = spawn(&exit_with(5).with_success_codes(&[0])).unwrap();
+    assert!(matches!(
+        task.wait(),
+        Err(ExecuteError::UnexpectedExit { .. })
+    ));
+}
+
+#[test]
+fn test_pool_reports_unexpected_exit() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(exit_with(4).with_success_codes(&[0]))
+        .unwrap();
+    assert!(matches!(
+        handle.wait(),
+        Err(ExecuteError::UnexpectedExit { output }) if output.status.code() == Some(4)
+    ));
+
+    // 带重试策略的任务在重试耗尽后同样保留输出
+    let handle = pool
+        .push_task(exit_with(4).with_success_codes(&[0]).with_retries(1))
+        .unwrap();
+    assert!(matches!(
+        handle.wait(),
+        Err(ExecuteError::UnexpectedExit { .. })
+    ));
+
+    pool.shutdown().unwrap();
+}
+
+#[cfg(feature = "pipeline")]
+#[test]
+fn test_pipeline_stage_allow_failure() {
+    let failing = CommandConfig::new("sh", vec!["-c".to_string(), "echo a; exit 1".to_string()]);
+
+    let pipeline = Pipeline::new()
+        .pipe(failing.clone())
+        .pipe(CommandConfig::new("cat", vec![]));
+    assert!(matches!(
+        PipelineExecutor::execute(&pipeline),
+        Err(ExecuteError::PipelineStage { index: 0, .. })
+    ));
+
+    let pipeline = Pipeline::new()
+        .pipe(failing.allow_failure())
+        .pipe(CommandConfig::new("cat", vec![]));
+    let output = PipelineExecutor::execute(&pipeline).unwrap();
+    assert_eq!(output.stdout, b"a\n");
+}
+
+#[cfg(feature = "pipeline")]
+#[test]
+fn test_pipeline_stage_success_codes() {
+    let pipeline = Pipeline::new().pipe(exit_with(1).with_success_codes(&[1]));
+    assert!(PipelineExecutor::execute(&pipeline).is_ok());
+}
@@ -0,0 +1,91 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::started_pool;
+use execute::{CommandConfig, ExecuteError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn exit_with(code: i32) -> CommandConfig {
+    CommandConfig::new("sh", vec!["-c".to_string(), format!("exit {code}")])
+}
+
+#[test]
+fn test_on_task_complete_sees_every_result() {
+    let pool = started_pool(2);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&seen);
+    pool.on_task_complete(move |task_id, result| {
+        recorder
+            .lock()
+            .unwrap()
+            .push((task_id, result.as_ref().map(|o| o.status.code()).ok()));
+    });
+
+    let ok = pool.push_task(exit_with(0)).unwrap();
+    let failed = pool.push_task(exit_with(3)).unwrap();
+    ok.wait().unwrap();
+    failed.wait().unwrap();
+
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort();
+    assert_eq!(
+        seen,
+        [(ok.id(), Some(Some(0))), (failed.id(), Some(Some(3)))]
+    );
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_on_task_failed_only_sees_failures() {
+    let pool = started_pool(2);
+    let failed_ids = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&failed_ids);
+    pool.on_task_failed(move |task_id, _| recorder.lock().unwrap().push(task_id));
+
+    let ok = pool.push_task(exit_with(0)).unwrap();
+    let failed = pool.push_task(exit_with(1)).unwrap();
+    let timed_out = pool
+        .push_task(
+            CommandConfig::new("sleep", vec!["5".to_string()])
+                .with_timeout(Duration::from_millis(100)),
+        )
+        .unwrap();
+    ok.wait().unwrap();
+    failed.wait().unwrap();
+    assert!(matches!(timed_out.wait(), Err(ExecuteError::Timeout(_))));
+
+    let mut ids = failed_ids.lock().unwrap().clone();
+    ids.sort();
+    assert_eq!(ids, [failed.id(), timed_out.id()]);
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_callbacks_run_before_handle_receives_result() {
+    let pool = started_pool(2);
+    let done = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&done);
+    pool.on_task_complete(move |task_id, _| recorder.lock().unwrap().push(task_id));
+
+    let handle = pool.push_task(exit_with(0)).unwrap();
+    handle.wait().unwrap();
+    assert_eq!(*done.lock().unwrap(), [handle.id()]);
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_clones_share_callbacks() {
+    let pool = started_pool(2);
+    let count = Arc::new(Mutex::new(0));
+    let counter = Arc::clone(&count);
+    let clone = pool.clone();
+    clone.on_task_complete(move |_, _| *counter.lock().unwrap() += 1);
+
+    for _ in 0..3 {
+        pool.push_task(exit_with(0)).unwrap().wait().unwrap();
+    }
+    assert_eq!(*count.lock().unwrap(), 3);
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,118 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandConfig, CommandPool, EnvConfig, TaskDefaults};
+use std::path::PathBuf;
+
+fn pool_with(defaults: TaskDefaults) -> CommandPool {
+    let pool = CommandPool::builder()
+        .with_task_defaults(defaults)
+        .build()
+        .unwrap();
+    pool.start_executor();
+    pool
+}
+
+fn run(pool: &CommandPool, config: CommandConfig) -> String {
+    let output = pool.push_task(config).unwrap().wait().unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn test_default_env_applied() {
+    let pool = pool_with(TaskDefaults::new().with_env("EXECUTE_DEFAULT", "pool"));
+
+    assert_eq!(run(&pool, shell("echo $EXECUTE_DEFAULT")), "pool");
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_task_env_overrides_default() {
+    let pool = pool_with(
+        TaskDefaults::new()
+            .with_env("EXECUTE_DEFAULT", "pool")
+            .with_env("EXECUTE_OTHER", "kept"),
+    );
+
+    let config = shell("echo $EXECUTE_DEFAULT $EXECUTE_OTHER")
+        .with_env(EnvConfig::new().set("EXECUTE_DEFAULT", "task"));
+    assert_eq!(run(&pool, config), "task kept");
+
+    let config =
+        shell("echo \"[$EXECUTE_DEFAULT]\"").with_env(EnvConfig::new().remove("EXECUTE_DEFAULT"));
+    assert_eq!(run(&pool, config), "[]");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_default_working_dir() {
+    let dir = std::env::temp_dir().join(format!("execute-defaults-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let dir = dir.canonicalize().unwrap();
+
+    let pool = pool_with(TaskDefaults::new().with_working_dir(dir.to_str().unwrap()));
+    assert_eq!(
+        PathBuf::from(run(&pool, CommandConfig::new("pwd", vec![]))),
+        dir
+    );
+
+    let own = std::env::temp_dir().canonicalize().unwrap();
+    let config = CommandConfig::new("pwd", vec![]).with_working_dir(own.to_str().unwrap());
+    assert_eq!(PathBuf::from(run(&pool, config)), own);
+
+    pool.shutdown().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_path_prepend() {
+    let pool = pool_with(
+        TaskDefaults::new()
+            .with_path_prepend("/opt/first/bin")
+            .with_path_prepend("/opt/second/bin"),
+    );
+
+    let path = run(&pool, shell("echo $PATH"));
+    assert!(
+        path.starts_with("/opt/first/bin:/opt/second/bin:"),
+        "PATH = {}",
+        path
+    );
+    // 保留原有 PATH，命令仍可找到
+    assert!(path.contains("/bin"));
+
+    let config = shell("echo $PATH").with_env(EnvConfig::new().set("PATH", "/usr/bin"));
+    assert_eq!(
+        run(&pool, config),
+        "/opt/first/bin:/opt/second/bin:/usr/bin"
+    );
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_defaults_apply_to_execute_task() {
+    let pool = CommandPool::builder()
+        .with_task_defaults(TaskDefaults::new().with_env("EXECUTE_DEFAULT", "sync"))
+        .build()
+        .unwrap();
+
+    let output = pool.execute_task(&shell("echo $EXECUTE_DEFAULT")).unwrap();
+    assert_eq!(output.stdout, b"sync\n");
+}
+
+#[test]
+fn test_sub_pool_inherits_defaults() {
+    let pool = CommandPool::builder()
+        .with_task_defaults(TaskDefaults::new().with_env("TEAM", "shared"))
+        .build()
+        .unwrap();
+    let sub = pool.sub_pool("team", 1);
+    sub.start_executor();
+
+    assert_eq!(run(&sub, shell("echo $TEAM")), "shared");
+    sub.shutdown().unwrap();
+}
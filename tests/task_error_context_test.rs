@@ -0,0 +1,79 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::started_pool;
+use execute::{CommandConfig, ErrorContext, ExecuteError};
+use std::path::Path;
+use std::time::Duration;
+
+#[test]
+fn test_wait_with_context_identifies_failing_command() {
+    let pool = started_pool(2);
+
+    let handle = pool
+        .push_task(
+            CommandConfig::new("nonexistent_program_ctx", vec!["--flag".to_string()])
+                .with_working_dir("/tmp"),
+        )
+        .unwrap();
+    let err = handle.wait_with_context().unwrap_err();
+
+    assert_eq!(err.task_id(), handle.id());
+    assert_eq!(err.context.command, "nonexistent_program_ctx --flag");
+    assert_eq!(err.context.working_dir, Path::new("/tmp"));
+    assert!(matches!(err.source, ExecuteError::ProgramNotFound { .. }));
+    let message = err.to_string();
+    assert!(
+        message.contains("nonexistent_program_ctx --flag"),
+        "{message}"
+    );
+    assert!(
+        message.contains(&format!("task_id={}", handle.id())),
+        "{message}"
+    );
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_wait_with_context_for_timeout() {
+    let pool = started_pool(2);
+
+    let handle = pool
+        .push_task(
+            CommandConfig::new("sleep", vec!["5".to_string()])
+                .with_timeout(Duration::from_millis(100)),
+        )
+        .unwrap();
+    let err = handle.wait_with_context().unwrap_err();
+
+    assert!(matches!(err.source, ExecuteError::Timeout(_)));
+    assert_eq!(err.context.command, "sleep 5");
+    assert_eq!(err.context.working_dir, Path::new("."));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_wait_with_context_success() {
+    let pool = started_pool(2);
+
+    let handle = pool
+        .push_task(CommandConfig::new("echo", vec!["ok".to_string()]))
+        .unwrap();
+    assert_eq!(handle.wait_with_context().unwrap().stdout, b"ok\n");
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_error_context_summarizes_long_commands() {
+    let args: Vec<String> = (0..500).map(|i| format!("file-{i}.txt")).collect();
+    let context = ErrorContext::from_config(7, &CommandConfig::new("rm", args));
+
+    assert_eq!(context.task_id, 7);
+    assert!(context.command.starts_with("rm file-0.txt file-1.txt"));
+    assert!(context.command.ends_with("... (500 args)"));
+    assert!(context.command.len() < 300);
+}
@@ -0,0 +1,142 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::started_pool;
+use execute::{CommandConfig, ExecuteError, TaskGraph};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 系统临时目录下的唯一日志文件路径
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("execute-graph-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn record(log: &Path, name: &str) -> CommandConfig {
+    CommandConfig::new(
+        "sh",
+        vec![
+            "-c".to_string(),
+            format!("echo {name} >> {}", log.display()),
+        ],
+    )
+}
+
+fn order(log: &Path) -> Vec<String> {
+    std::fs::read_to_string(log)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_dependencies_run_in_order() {
+    let log = log_path("order");
+    let pool = started_pool(4);
+
+    let mut graph = TaskGraph::new();
+    let fetch = graph.add(record(&log, "fetch"));
+    let build = graph.add_after(record(&log, "build"), &[fetch]);
+    let lint = graph.add_after(record(&log, "lint"), &[fetch]);
+    let package = graph.add_after(record(&log, "package"), &[build, lint]);
+    assert_eq!(package.index(), 3);
+    assert_eq!(graph.len(), 4);
+
+    let results = pool.run_graph(graph).unwrap();
+    assert!(results.iter().all(Result::is_ok));
+
+    let order = order(&log);
+    assert_eq!(order.first().unwrap(), "fetch");
+    assert_eq!(order.last().unwrap(), "package");
+    assert_eq!(order.len(), 4);
+
+    pool.shutdown().unwrap();
+    std::fs::remove_file(log).unwrap();
+}
+
+#[test]
+fn test_independent_nodes_run_in_parallel() {
+    let pool = started_pool(3);
+    let mut graph = TaskGraph::new();
+    for _ in 0..3 {
+        graph.add(CommandConfig::new("sleep", vec!["0.4".to_string()]));
+    }
+
+    let start = Instant::now();
+    let results = pool.run_graph(graph).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(start.elapsed() < Duration::from_millis(1000));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_failure_fails_dependents_transitively() {
+    let log = log_path("failure");
+    let pool = started_pool(2);
+
+    let mut graph = TaskGraph::new();
+    let ok = graph.add(record(&log, "ok"));
+    let broken = graph.add(CommandConfig::new("false", vec![]));
+    let direct = graph.add_after(record(&log, "direct"), &[ok, broken]);
+    let indirect = graph.add_after(record(&log, "indirect"), &[direct]);
+    let unrelated = graph.add_after(record(&log, "unrelated"), &[ok]);
+
+    let results = pool.run_graph(graph).unwrap();
+    assert!(results[ok.index()].is_ok());
+    assert!(!results[broken.index()].as_ref().unwrap().status.success());
+    assert!(matches!(
+        results[direct.index()],
+        Err(ExecuteError::DependencyFailed(node)) if node == broken.index()
+    ));
+    assert!(matches!(
+        results[indirect.index()],
+        Err(ExecuteError::DependencyFailed(node)) if node == direct.index()
+    ));
+    assert!(results[unrelated.index()].is_ok());
+
+    let mut order = order(&log);
+    order.sort();
+    assert_eq!(order, ["ok", "unrelated"]);
+
+    pool.shutdown().unwrap();
+    std::fs::remove_file(log).unwrap();
+}
+
+#[test]
+fn test_success_codes_count_as_success() {
+    let pool = started_pool(1);
+    let mut graph = TaskGraph::new();
+    let grep = graph.add(
+        CommandConfig::new("sh", vec!["-c".to_string(), "exit 1".to_string()])
+            .with_success_codes(&[0, 1]),
+    );
+    let next = graph.add_after(CommandConfig::new("true", vec![]), &[grep]);
+
+    let results = pool.run_graph(graph).unwrap();
+    assert!(results[next.index()].is_ok());
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_empty_graph() {
+    let pool = started_pool(1);
+    let graph = TaskGraph::new();
+    assert!(graph.is_empty());
+    assert!(pool.run_graph(graph).unwrap().is_empty());
+    pool.shutdown().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "dependency does not belong to this graph")]
+fn test_foreign_dependency_panics() {
+    let mut other = TaskGraph::new();
+    other.add(CommandConfig::new("true", vec![]));
+    let foreign = other.add(CommandConfig::new("true", vec![]));
+
+    let mut graph = TaskGraph::new();
+    graph.add_after(CommandConfig::new("true", vec![]), &[foreign]);
+}
@@ -110,3 +110,32 @@ fn test_task_state_transitions() {
 
     pool.shutdown().expect("Failed to shutdown pool");
 }
+
+#[test]
+fn test_submit_waits_for_output() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let handles: Vec<_> = (0..3)
+        .map(|i| {
+            pool.submit(CommandConfig::new("echo", vec![format!("task {i}")]))
+                .expect("Failed to submit task")
+        })
+        .collect();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        let output = handle.wait().expect("Task should complete successfully");
+        assert_eq!(output.stdout, format!("task {i}\n").into_bytes());
+    }
+
+    pool.shutdown().expect("Failed to shutdown pool");
+}
+
+#[test]
+fn test_submit_after_shutdown_fails() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+    pool.shutdown().expect("Failed to shutdown pool");
+
+    assert!(pool.submit(CommandConfig::new("true", vec![])).is_err());
+}
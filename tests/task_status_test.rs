@@ -0,0 +1,83 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::started_pool;
+use execute::{CommandConfig, CommandPool, ExecuteError, TaskStatus};
+use std::time::{Duration, Instant};
+
+fn wait_for_status(pool: &CommandPool, task_id: u64, status: TaskStatus) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while pool.status(task_id) != Some(status) {
+        assert!(
+            Instant::now() < deadline,
+            "task {task_id} never became {status}"
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_status_follows_task_lifecycle() {
+    let pool = started_pool(1);
+    let running = pool
+        .push_task(CommandConfig::new("sleep", vec!["0.3".to_string()]))
+        .unwrap();
+    let queued = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+
+    wait_for_status(&pool, running.id(), TaskStatus::Running);
+    assert_eq!(pool.status(queued.id()), Some(TaskStatus::Pending));
+
+    running.wait().unwrap();
+    queued.wait().unwrap();
+    assert_eq!(pool.status(running.id()), Some(TaskStatus::Completed));
+    assert_eq!(pool.status(queued.id()), Some(TaskStatus::Completed));
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_failed_and_cancelled_statuses() {
+    let pool = CommandPool::new();
+    let failed = pool.push_task(CommandConfig::new("false", vec![])).unwrap();
+    let cancelled = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    cancelled.cancel().unwrap();
+    pool.start_executor();
+
+    failed.wait().unwrap();
+    assert!(matches!(cancelled.wait(), Err(ExecuteError::Cancelled(_))));
+    assert_eq!(pool.status(failed.id()), Some(TaskStatus::Failed));
+    assert_eq!(pool.status(cancelled.id()), Some(TaskStatus::Cancelled));
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_statuses_and_cleanup() {
+    let pool = CommandPool::new();
+    let first = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    let second = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    assert_eq!(first.id() + 1, second.id());
+    assert_eq!(pool.status(second.id() + 1), None);
+
+    let statuses = pool.statuses();
+    assert_eq!(statuses.len(), 2);
+    assert!(
+        statuses
+            .values()
+            .all(|status| *status == TaskStatus::Pending)
+    );
+
+    pool.start_executor();
+    first.wait().unwrap();
+    second.wait().unwrap();
+    assert_eq!(pool.clear_finished_statuses(), 2);
+    assert!(pool.statuses().is_empty());
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_cleared_queue_forgets_statuses() {
+    let pool = CommandPool::new();
+    let handle = pool.push_task(CommandConfig::new("true", vec![])).unwrap();
+    assert_eq!(pool.clear(), 1);
+    assert_eq!(pool.status(handle.id()), None);
+}
@@ -0,0 +1,82 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{CommandConfig, CommandPool, TempWorkdirConfig, execute_with_report};
+use std::path::PathBuf;
+
+#[test]
+fn test_temp_workdir_is_cwd_and_removed() {
+    let config = shell("pwd; touch artifact.txt").with_temp_workdir();
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(report.output.status.success());
+
+    let dir = report
+        .temp_workdir
+        .expect("temp workdir should be reported");
+    let cwd = PathBuf::from(String::from_utf8_lossy(&report.output.stdout).trim());
+    assert_eq!(cwd.file_name(), dir.file_name());
+    assert!(!dir.exists());
+}
+
+#[test]
+fn test_each_task_gets_unique_temp_workdir() {
+    let config = CommandConfig::new("pwd", vec![]).with_temp_workdir();
+
+    let a = execute_with_report(&config).unwrap().temp_workdir.unwrap();
+    let b = execute_with_report(&config).unwrap().temp_workdir.unwrap();
+    assert_ne!(a, b);
+}
+
+#[test]
+fn test_temp_workdir_kept_on_failure_when_configured() {
+    let config = shell("touch partial.log; exit 3")
+        .with_temp_workdir_config(TempWorkdirConfig::new().with_keep_on_failure(true));
+
+    let report = execute_with_report(&config).unwrap();
+    assert_eq!(report.output.status.code(), Some(3));
+
+    let dir = report.temp_workdir.unwrap();
+    assert!(dir.join("partial.log").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_temp_workdir_removed_on_failure_by_default() {
+    let config = shell("exit 1").with_temp_workdir();
+
+    let report = execute_with_report(&config).unwrap();
+    assert!(!report.temp_workdir.unwrap().exists());
+}
+
+#[test]
+fn test_temp_workdir_under_base_dir() {
+    let base = std::env::temp_dir().join(format!("execute-base-{}", std::process::id()));
+    std::fs::create_dir_all(&base).unwrap();
+
+    let config = CommandConfig::new("true", vec![])
+        .with_temp_workdir_config(TempWorkdirConfig::new().with_base_dir(&base));
+    let dir = execute_with_report(&config).unwrap().temp_workdir.unwrap();
+    assert_eq!(dir.parent(), Some(base.as_path()));
+
+    std::fs::remove_dir_all(&base).unwrap();
+}
+
+#[test]
+fn test_pool_exposes_temp_workdir_in_report() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let handle = pool
+        .push_task(shell("echo hi > out.txt; cat out.txt").with_temp_workdir())
+        .unwrap();
+    let report = handle.wait_report().unwrap();
+
+    assert_eq!(report.output.stdout, b"hi\n");
+    let dir = report.temp_workdir.unwrap();
+    assert!(!dir.exists());
+
+    pool.shutdown().unwrap();
+}
@@ -0,0 +1,129 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::shell;
+use execute::{
+    CommandPool, ExecuteError, TimeoutContext, TimeoutDecision, TimeoutHook, TimeoutHookConfig,
+};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 记录每次调用的上下文并返回固定决策
+struct RecordingHook {
+    decision: TimeoutDecision,
+    calls: Mutex<Vec<TimeoutContext>>,
+}
+
+impl RecordingHook {
+    fn new(decision: TimeoutDecision) -> Arc<Self> {
+        Arc::new(Self {
+            decision,
+            calls: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn calls(&self) -> Vec<TimeoutContext> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl TimeoutHook for RecordingHook {
+    fn on_timeout_imminent(&self, ctx: &TimeoutContext) -> TimeoutDecision {
+        self.calls.lock().unwrap().push(ctx.clone());
+        self.decision
+    }
+}
+
+#[test]
+fn test_hook_extension_lets_task_finish() {
+    let hook = RecordingHook::new(TimeoutDecision::Extend(Duration::from_millis(500)));
+    let config = shell("sleep 0.6; echo done")
+        .with_timeout(Duration::from_millis(300))
+        .with_timeout_hook(
+            TimeoutHookConfig::new(hook.clone())
+                .with_lead_time(Duration::from_millis(100))
+                .with_max_extension(Duration::from_secs(1)),
+        );
+
+    let output = CommandPool::new().execute_task(&config).unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "done");
+
+    let calls = hook.calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].timeout, Duration::from_millis(300));
+    assert_eq!(calls[0].extensions, 0);
+}
+
+#[test]
+fn test_hook_proceed_kills_at_original_timeout() {
+    let hook = RecordingHook::new(TimeoutDecision::Proceed);
+    let config = shell("sleep 5")
+        .with_timeout(Duration::from_millis(300))
+        .with_timeout_hook(
+            TimeoutHookConfig::new(hook.clone()).with_lead_time(Duration::from_millis(100)),
+        );
+
+    let start = Instant::now();
+    let result = CommandPool::new().execute_task(&config);
+    let elapsed = start.elapsed();
+
+    assert!(matches!(result, Err(ExecuteError::Timeout(t)) if t == Duration::from_millis(300)));
+    assert!(elapsed < Duration::from_secs(2));
+    assert_eq!(hook.calls().len(), 1);
+}
+
+#[test]
+fn test_extension_is_bounded_by_max_extension() {
+    let hook = RecordingHook::new(TimeoutDecision::Extend(Duration::from_secs(1)));
+    let config = shell("sleep 5")
+        .with_timeout(Duration::from_millis(300))
+        .with_timeout_hook(
+            TimeoutHookConfig::new(hook.clone())
+                .with_lead_time(Duration::from_millis(100))
+                .with_max_extension(Duration::from_millis(200)),
+        );
+
+    let start = Instant::now();
+    let result = CommandPool::new().execute_task(&config);
+
+    assert!(matches!(result, Err(ExecuteError::Timeout(t)) if t == Duration::from_millis(500)));
+    assert!(start.elapsed() < Duration::from_secs(2));
+    // 额度一次就用完，不会再次调用
+    assert_eq!(hook.calls().len(), 1);
+    assert_eq!(
+        hook.calls()[0].remaining_extension,
+        Duration::from_millis(200)
+    );
+}
+
+#[test]
+fn test_hook_sees_recent_output() {
+    let hook = RecordingHook::new(TimeoutDecision::Proceed);
+    let config = shell("echo progress; echo warn >&2; sleep 5")
+        .with_timeout(Duration::from_millis(400))
+        .with_timeout_hook(
+            TimeoutHookConfig::new(hook.clone()).with_lead_time(Duration::from_millis(200)),
+        );
+
+    let _ = CommandPool::new().execute_task(&config);
+
+    let calls = hook.calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].recent_stdout, b"progress\n");
+    assert_eq!(calls[0].recent_stderr, b"warn\n");
+    assert!(calls[0].pid > 0);
+}
+
+#[test]
+fn test_hook_not_called_when_task_finishes_early() {
+    let hook = RecordingHook::new(TimeoutDecision::Proceed);
+    let config = shell("echo quick")
+        .with_timeout(Duration::from_secs(5))
+        .with_timeout_hook(TimeoutHookConfig::new(hook.clone()));
+
+    let output = CommandPool::new().execute_task(&config).unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "quick");
+    assert!(hook.calls().is_empty());
+}
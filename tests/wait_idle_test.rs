@@ -0,0 +1,64 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::sleep_task;
+use execute::{CommandPool, ExecutionConfig, TaskState};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_wait_idle_on_empty_pool_returns_immediately() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    let start = Instant::now();
+    pool.wait_idle();
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_wait_idle_waits_for_queued_and_running_tasks() {
+    let pool = CommandPool::with_config(ExecutionConfig::new().with_workers(2));
+    pool.start_executor();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| pool.push_task(sleep_task("0.2")).unwrap())
+        .collect();
+
+    let start = Instant::now();
+    pool.wait_idle();
+    // 两个工作线程执行四个任务至少需要两轮
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    assert!(pool.is_empty());
+    for handle in handles {
+        assert_eq!(handle.state(), TaskState::Completed);
+    }
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_wait_idle_timeout_expires() {
+    let pool = CommandPool::new();
+    pool.start_executor();
+
+    pool.push_task(sleep_task("1")).unwrap();
+    assert!(!pool.wait_idle_timeout(Duration::from_millis(100)));
+    assert!(pool.wait_idle_timeout(Duration::from_secs(5)));
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_wait_idle_after_clear() {
+    let pool = CommandPool::new();
+    pool.push_task(sleep_task("1")).unwrap();
+    pool.push_task(sleep_task("1")).unwrap();
+
+    // 未启动执行器时任务留在队列中
+    assert!(!pool.wait_idle_timeout(Duration::from_millis(50)));
+    assert_eq!(pool.clear(), 2);
+    assert!(pool.wait_idle_timeout(Duration::from_millis(50)));
+}
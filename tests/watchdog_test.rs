@@ -0,0 +1,182 @@
+#![cfg(unix)]
+
+use execute::{
+    CommandConfig, CommandExecutor, CommandPool, ExecuteError, PoolEvent, WatchdogConfig,
+};
+use std::os::unix::process::ExitStatusExt;
+use std::process::{ExitStatus, Output};
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 忽略超时的执行器：程序名为 "hang" 时阻塞 `hang` 时长
+struct HangingExecutor {
+    hang: Duration,
+}
+
+impl CommandExecutor for HangingExecutor {
+    fn execute(&self, config: &CommandConfig) -> Result<Output, ExecuteError> {
+        if config.program() == "hang" {
+            thread::sleep(self.hang);
+        }
+        Ok(Output {
+            status: ExitStatus::from_raw(0),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        })
+    }
+}
+
+fn watchdog() -> WatchdogConfig {
+    WatchdogConfig::new(Duration::from_millis(50)).with_check_interval(Duration::from_millis(10))
+}
+
+fn hang() -> CommandConfig {
+    CommandConfig::new("hang", vec![]).with_timeout(Duration::from_millis(50))
+}
+
+fn stuck_events(events: &Receiver<PoolEvent>) -> Vec<(usize, u64, bool)> {
+    events
+        .try_iter()
+        .filter_map(|event| match event {
+            PoolEvent::WorkerStuck {
+                worker,
+                task_id,
+                replaced,
+                ..
+            } => Some((worker, task_id, replaced)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_watchdog_reports_stuck_worker() {
+    let pool = CommandPool::builder()
+        .with_watchdog(watchdog())
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_with_executor(Arc::new(HangingExecutor {
+        hang: Duration::from_millis(400),
+    }));
+
+    let handle = pool.push_task(hang()).unwrap();
+    let task_id = handle.id();
+    handle.wait().unwrap();
+
+    assert_eq!(stuck_events(&events), vec![(0, task_id, false)]);
+    #[cfg(feature = "metrics")]
+    assert_eq!(pool.metrics().workers_stuck, 1);
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_watchdog_ignores_tasks_within_timeout() {
+    let pool = CommandPool::builder()
+        .with_watchdog(watchdog())
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_with_executor(Arc::new(HangingExecutor {
+        hang: Duration::from_millis(300),
+    }));
+
+    // 在超时加宽限期之内结束的任务不算卡住
+    pool.push_task(CommandConfig::new("hang", vec![]).with_timeout(Duration::from_millis(400)))
+        .unwrap()
+        .wait()
+        .unwrap();
+    pool.push_task(CommandConfig::new("fast", vec![]).with_timeout(Duration::from_millis(50)))
+        .unwrap()
+        .wait()
+        .unwrap();
+
+    assert!(stuck_events(&events).is_empty());
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_watchdog_replaces_stuck_worker() {
+    let pool = CommandPool::builder()
+        .with_workers(1)
+        .with_watchdog(watchdog().with_replacement(true))
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_with_executor(Arc::new(HangingExecutor {
+        hang: Duration::from_millis(1500),
+    }));
+
+    let start = Instant::now();
+    let stuck = pool.push_task(hang()).unwrap();
+    let next = pool.push_task(CommandConfig::new("fast", vec![])).unwrap();
+
+    // 唯一的工作线程卡住后由替补线程执行后续任务
+    next.wait().unwrap();
+    assert!(start.elapsed() < Duration::from_millis(1000));
+    assert_eq!(stuck_events(&events), vec![(0, stuck.id(), true)]);
+
+    // 卡住的任务最终结束后被替换的线程退出
+    stuck.wait().unwrap();
+    let after = pool.push_task(CommandConfig::new("fast", vec![])).unwrap();
+    after.wait().unwrap();
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_replacement_does_not_run_affinity_tasks_concurrently() {
+    let pool = CommandPool::builder()
+        .with_workers(1)
+        .with_watchdog(watchdog().with_replacement(true))
+        .build()
+        .unwrap();
+    pool.start_with_executor(Arc::new(HangingExecutor {
+        hang: Duration::from_millis(600),
+    }));
+
+    let start = Instant::now();
+    let stuck = pool.push_task(hang().with_affinity_key("repo")).unwrap();
+    let same_key = pool
+        .push_task(CommandConfig::new("fast", vec![]).with_affinity_key("repo"))
+        .unwrap();
+    let other = pool.push_task(CommandConfig::new("fast", vec![])).unwrap();
+
+    // 替补线程执行没有亲和键的任务，同键任务等被替换的线程结束
+    other.wait().unwrap();
+    assert!(start.elapsed() < Duration::from_millis(500));
+    same_key.wait().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(600));
+    stuck.wait().unwrap();
+
+    pool.shutdown().unwrap();
+}
+
+#[test]
+fn test_watchdog_max_runtime_caps_long_timeouts() {
+    let pool = CommandPool::builder()
+        .with_watchdog(
+            WatchdogConfig::new(Duration::ZERO)
+                .with_max_runtime(Duration::from_millis(100))
+                .with_check_interval(Duration::from_millis(10)),
+        )
+        .build()
+        .unwrap();
+    let events = pool.subscribe();
+    pool.start_executor();
+
+    // 默认超时较长，超过最长运行时间即视为卡住
+    let handle = pool
+        .push_task(CommandConfig::new("sleep", vec!["0.4".to_string()]))
+        .unwrap();
+    let task_id = handle.id();
+    handle.wait().unwrap();
+
+    assert_eq!(stuck_events(&events), vec![(0, task_id, false)]);
+
+    pool.shutdown().unwrap();
+}
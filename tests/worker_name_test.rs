@@ -0,0 +1,59 @@
+#![cfg(unix)]
+
+use execute::{CommandConfig, CommandPool, CommandPoolBuilder};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// 提交若干任务，返回执行结束回调时所在线程的名称
+fn worker_names(builder: CommandPoolBuilder) -> HashSet<String> {
+    let names = Arc::new(Mutex::new(HashSet::new()));
+    let pool = builder.build().unwrap();
+    {
+        let names = Arc::clone(&names);
+        pool.on_task_complete(move |_, _| {
+            let name = thread::current().name().unwrap_or_default().to_string();
+            names.lock().unwrap().insert(name);
+        });
+    }
+    pool.start_executor();
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            pool.push_task(CommandConfig::new("sleep", vec!["0.05".to_string()]))
+                .unwrap()
+        })
+        .collect();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    pool.shutdown().unwrap();
+    names.lock().unwrap().clone()
+}
+
+#[test]
+fn test_default_worker_names() {
+    let names = worker_names(CommandPool::builder().with_workers(2));
+    assert!(!names.is_empty());
+    for name in &names {
+        assert!(
+            name == "execute-worker-0" || name == "execute-worker-1",
+            "unexpected thread name {name:?}"
+        );
+    }
+}
+
+#[test]
+fn test_custom_worker_name_prefix() {
+    let names = worker_names(
+        CommandPool::builder()
+            .with_workers(2)
+            .with_worker_name_prefix("ingest"),
+    );
+    assert!(!names.is_empty());
+    for name in &names {
+        assert!(
+            name.starts_with("ingest-"),
+            "unexpected thread name {name:?}"
+        );
+    }
+}